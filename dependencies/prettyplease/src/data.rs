@@ -50,6 +50,7 @@ impl Printer {
     pub fn field(&mut self, field: &Field) {
         self.outer_attrs(&field.attrs);
         self.visibility(&field.vis);
+        self.data_mode(&field.mode);
         if let Some(ident) = &field.ident {
             self.ident(ident);
             self.word(": ");
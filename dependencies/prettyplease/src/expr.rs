@@ -89,9 +89,10 @@ impl Printer {
             Expr::GetField(expr) => self.expr_get_field(expr),
             Expr::Matches(m) => self.expr_matches(m),
 
-            Expr::Assume(_) | Expr::Assert(_) | Expr::AssertForall(_) | Expr::RevealHide(_) => {
-                unimplemented!("unknown Expr {:?}", expr)
-            }
+            Expr::Assume(expr) => self.expr_assume(expr),
+            Expr::Assert(expr) => self.expr_assert(expr),
+            Expr::AssertForall(expr) => self.expr_assert_forall(expr),
+            Expr::RevealHide(expr) => self.expr_reveal_hide(expr),
         }
 
         if needs_paren {
@@ -175,6 +176,81 @@ impl Printer {
         self.member(&expr.member);
     }
 
+    pub fn expr_assume(&mut self, expr: &verus_syn::Assume) {
+        self.outer_attrs(&expr.attrs);
+        self.word("assume(");
+        self.expr(&expr.expr, FixupContext::NONE);
+        self.word(")");
+    }
+
+    pub fn expr_assert(&mut self, expr: &verus_syn::Assert) {
+        self.outer_attrs(&expr.attrs);
+        self.word("assert(");
+        self.expr(&expr.expr, FixupContext::NONE);
+        self.word(")");
+        if expr.by_token.is_some() {
+            self.word(" by");
+            if let Some((_paren, prover)) = &expr.prover {
+                self.word("(");
+                self.ident(prover);
+                self.word(")");
+            }
+            if let Some(requires) = &expr.requires {
+                self.word(" requires ");
+                for req in requires.exprs.exprs.iter().delimited() {
+                    self.expr(&req, FixupContext::NONE);
+                    if !req.is_last {
+                        self.word(", ");
+                    }
+                }
+            }
+            if let Some(body) = &expr.body {
+                self.nbsp();
+                self.cbox(INDENT);
+                self.small_block(body, &[]);
+                self.end();
+            }
+        }
+    }
+
+    pub fn expr_assert_forall(&mut self, expr: &verus_syn::AssertForall) {
+        self.outer_attrs(&expr.attrs);
+        self.word("assert forall|");
+        for pat in expr.inputs.iter().delimited() {
+            self.pat(&pat);
+            if !pat.is_last {
+                self.word(", ");
+            }
+        }
+        self.word("| ");
+        self.expr(&expr.expr, FixupContext::NONE);
+        if let Some((_implies_token, rhs)) = &expr.implies {
+            self.word(" implies ");
+            self.expr(rhs, FixupContext::NONE);
+        }
+        self.word(" by ");
+        self.cbox(INDENT);
+        self.small_block(&expr.body, &[]);
+        self.end();
+    }
+
+    pub fn expr_reveal_hide(&mut self, expr: &verus_syn::RevealHide) {
+        self.outer_attrs(&expr.attrs);
+        if expr.reveal_with_fuel_token.is_some() {
+            self.word("reveal_with_fuel(");
+        } else if expr.hide_token.is_some() {
+            self.word("hide(");
+        } else {
+            self.word("reveal(");
+        }
+        self.expr_path(&expr.path);
+        if let Some((_comma, fuel)) = &expr.fuel {
+            self.word(", ");
+            self.expr(fuel, FixupContext::NONE);
+        }
+        self.word(")");
+    }
+
     pub fn expr_beginning_of_line(
         &mut self,
         expr: &Expr,
@@ -6,12 +6,14 @@ use crate::path::PathKind;
 use crate::INDENT;
 use proc_macro2::TokenStream;
 use verus_syn::{
-    Fields, FnArg, ForeignItem, ForeignItemFn, ForeignItemMacro, ForeignItemStatic,
-    ForeignItemType, ImplItem, ImplItemConst, ImplItemFn, ImplItemMacro, ImplItemType, Item,
-    ItemConst, ItemEnum, ItemExternCrate, ItemFn, ItemForeignMod, ItemImpl, ItemMacro, ItemMod,
-    ItemStatic, ItemStruct, ItemTrait, ItemTraitAlias, ItemType, ItemUnion, ItemUse, Receiver,
-    Signature, StaticMutability, TraitItem, TraitItemConst, TraitItemFn, TraitItemMacro,
-    TraitItemType, Type, UseGlob, UseGroup, UseName, UsePath, UseRename, UseTree, Variadic,
+    AssumeSpecification, BroadcastUse, DataMode, Ensures, Fields, FnArg, FnMode, ForeignItem,
+    ForeignItemFn, ForeignItemMacro, ForeignItemStatic, ForeignItemType, Global, GlobalInner,
+    ImplItem, ImplItemConst, ImplItemFn, ImplItemMacro, ImplItemType, InvariantNameSet, Item,
+    ItemBroadcastGroup, ItemConst, ItemEnum, ItemExternCrate, ItemFn, ItemForeignMod, ItemImpl,
+    ItemMacro, ItemMod, ItemStatic, ItemStruct, ItemTrait, ItemTraitAlias, ItemType, ItemUnion,
+    ItemUse, Publish, Receiver, Signature, SignatureSpec, Specification, StaticMutability,
+    TraitItem, TraitItemConst, TraitItemFn, TraitItemMacro, TraitItemType, Type, UseGlob, UseGroup,
+    UseName, UsePath, UseRename, UseTree, Variadic,
 };
 
 impl Printer {
@@ -34,6 +36,13 @@ impl Printer {
             Item::Union(item) => self.item_union(item),
             Item::Use(item) => self.item_use(item),
             Item::Verbatim(item) => self.item_verbatim(item),
+
+            // verus
+            Item::Global(item) => self.item_global(item),
+            Item::BroadcastUse(item) => self.item_broadcast_use(item),
+            Item::BroadcastGroup(item) => self.item_broadcast_group(item),
+            Item::AssumeSpecification(item) => self.item_assume_specification(item),
+
             _ => unimplemented!("unknown Item"),
         }
     }
@@ -42,18 +51,25 @@ impl Printer {
         self.outer_attrs(&item.attrs);
         self.cbox(0);
         self.visibility(&item.vis);
+        self.publish(&item.publish);
+        self.fn_mode(&item.mode);
         self.word("const ");
         self.ident(&item.ident);
         self.generics(&item.generics);
         self.word(": ");
         self.ty(&item.ty);
+        if let Some(ensures) = &item.ensures {
+            self.ensures_oneline(ensures);
+        }
         self.word(" = ");
         self.neverbreak();
-        if let Some(expr) = &item.expr {
-            self.expr(expr, FixupContext::NONE);
-        }
         if let Some(block) = &item.block {
+            self.cbox(INDENT);
             self.small_block(block, &[]);
+            self.end();
+        }
+        if let Some(expr) = &item.expr {
+            self.expr(expr, FixupContext::NONE);
         }
         self.word(";");
         self.end();
@@ -103,7 +119,7 @@ impl Printer {
             #[cfg(feature = "verbatim")]
             &verbatim::Safety::Disallowed,
         );
-        self.where_clause_for_body(&item.sig.generics.where_clause);
+        self.signature_spec_for_body(&item.sig);
         self.word("{");
         self.hardbreak_if_nonempty();
         self.inner_attrs(&item.attrs);
@@ -221,7 +237,9 @@ impl Printer {
             self.expr(expr, FixupContext::NONE);
         }
         if let Some(block) = &item.block {
+            self.cbox(INDENT);
             self.small_block(block, &[]);
+            self.end();
         }
         self.word(";");
         self.end();
@@ -990,7 +1008,7 @@ impl Printer {
             &verbatim::Safety::Disallowed,
         );
         if let Some(block) = &trait_item.default {
-            self.where_clause_for_body(&trait_item.sig.generics.where_clause);
+            self.signature_spec_for_body(&trait_item.sig);
             self.word("{");
             self.hardbreak_if_nonempty();
             self.inner_attrs(&trait_item.attrs);
@@ -1001,7 +1019,7 @@ impl Printer {
             self.end();
             self.word("}");
         } else {
-            self.where_clause_semi(&trait_item.sig.generics.where_clause);
+            self.signature_spec_semi(&trait_item.sig);
             self.end();
         }
         self.hardbreak();
@@ -1153,6 +1171,10 @@ impl Printer {
             ImplItem::Type(item) => self.impl_item_type(item),
             ImplItem::Macro(item) => self.impl_item_macro(item),
             ImplItem::Verbatim(item) => self.impl_item_verbatim(item),
+
+            // verus
+            ImplItem::BroadcastGroup(item) => self.item_broadcast_group(item),
+
             _ => unimplemented!("unknown ImplItem"),
         }
     }
@@ -1164,18 +1186,25 @@ impl Printer {
         if impl_item.defaultness.is_some() {
             self.word("default ");
         }
+        self.publish(&impl_item.publish);
+        self.fn_mode(&impl_item.mode);
         self.word("const ");
         self.ident(&impl_item.ident);
         self.generics(&impl_item.generics);
         self.word(": ");
         self.ty(&impl_item.ty);
+        if let Some(ensures) = &impl_item.ensures {
+            self.ensures_oneline(ensures);
+        }
         self.word(" = ");
         self.neverbreak();
         if let Some(expr) = &impl_item.expr {
             self.expr(expr, FixupContext::NONE);
         }
         if let Some(block) = &impl_item.block {
+            self.cbox(INDENT);
             self.small_block(block, &[]);
+            self.end();
         }
         self.word(";");
         self.end();
@@ -1194,7 +1223,7 @@ impl Printer {
             #[cfg(feature = "verbatim")]
             &verbatim::Safety::Disallowed,
         );
-        self.where_clause_for_body(&impl_item.sig.generics.where_clause);
+        self.signature_spec_for_body(&impl_item.sig);
         self.word("{");
         self.hardbreak_if_nonempty();
         self.inner_attrs(&impl_item.attrs);
@@ -1326,6 +1355,7 @@ impl Printer {
         signature: &Signature,
         #[cfg(feature = "verbatim")] safety: &verbatim::Safety,
     ) {
+        self.publish(&signature.publish);
         if signature.constness.is_some() {
             self.word("const ");
         }
@@ -1351,6 +1381,10 @@ impl Printer {
         if let Some(abi) = &signature.abi {
             self.abi(abi);
         }
+        if signature.broadcast.is_some() {
+            self.word("broadcast ");
+        }
+        self.fn_mode(&signature.mode);
         self.word("fn ");
         self.ident(&signature.ident);
         self.generics(&signature.generics);
@@ -1429,6 +1463,300 @@ impl Printer {
         self.word("...");
     }
 
+    // verus
+
+    pub(crate) fn publish(&mut self, publish: &Publish) {
+        match publish {
+            Publish::Closed(_) => self.word("closed "),
+            Publish::Open(_) => self.word("open "),
+            Publish::OpenRestricted(open) => {
+                self.word("open(");
+                if open.in_token.is_some() {
+                    self.word("in ");
+                }
+                self.path(&open.path, PathKind::Simple);
+                self.word(") ");
+            }
+            Publish::Uninterp(_) => self.word("uninterp "),
+            Publish::Default => {}
+        }
+    }
+
+    pub(crate) fn fn_mode(&mut self, mode: &FnMode) {
+        match mode {
+            FnMode::Spec(_) => self.word("spec "),
+            FnMode::SpecChecked(mode) => {
+                self.word("spec(");
+                self.ident(&mode.checked);
+                self.word(") ");
+            }
+            FnMode::Proof(_) => self.word("proof "),
+            FnMode::ProofAxiom(_) => self.word("axiom "),
+            FnMode::Exec(_) => self.word("exec "),
+            FnMode::Default => {}
+        }
+    }
+
+    pub(crate) fn data_mode(&mut self, mode: &DataMode) {
+        match mode {
+            DataMode::Ghost(_) => self.word("ghost "),
+            DataMode::Tracked(_) => self.word("tracked "),
+            DataMode::Exec(_) => self.word("exec "),
+            DataMode::Default => {}
+        }
+    }
+
+    /// Prints the where clause and signature spec, leaving the cursor at the
+    /// start of a fresh line for the caller's `{` when any spec clause is
+    /// present, and behaving exactly like `where_clause_for_body` otherwise.
+    fn signature_spec_for_body(&mut self, sig: &Signature) {
+        if signature_spec_is_empty(&sig.spec) {
+            self.where_clause_for_body(&sig.generics.where_clause);
+            return;
+        }
+        if has_where_predicates(&sig.generics.where_clause) {
+            self.where_clause_oneline(&sig.generics.where_clause);
+        }
+        self.signature_spec(&sig.spec);
+        self.hardbreak();
+        self.offset(-INDENT);
+    }
+
+    /// The bodyless variant: the `;` lands directly after the last clause.
+    fn signature_spec_semi(&mut self, sig: &Signature) {
+        if signature_spec_is_empty(&sig.spec) {
+            self.where_clause_semi(&sig.generics.where_clause);
+            return;
+        }
+        if has_where_predicates(&sig.generics.where_clause) {
+            self.where_clause_oneline(&sig.generics.where_clause);
+        }
+        self.signature_spec(&sig.spec);
+        self.word(";");
+    }
+
+    fn signature_spec(&mut self, spec: &SignatureSpec) {
+        if let Some(prover) = &spec.prover {
+            self.hardbreak();
+            self.word("by(");
+            self.ident(&prover.id);
+            self.word(")");
+        }
+        if let Some(requires) = &spec.requires {
+            self.spec_clause("requires", &requires.exprs);
+        }
+        if let Some(recommends) = &spec.recommends {
+            self.spec_clause("recommends", &recommends.exprs);
+            if let Some((_via_token, via)) = &recommends.via {
+                self.hardbreak();
+                self.word("via ");
+                self.expr(via, FixupContext::NONE);
+            }
+        }
+        if let Some(ensures) = &spec.ensures {
+            self.outer_attrs(&ensures.attrs);
+            self.spec_clause("ensures", &ensures.exprs);
+        }
+        if let Some(default_ensures) = &spec.default_ensures {
+            self.spec_clause("default_ensures", &default_ensures.exprs);
+        }
+        if let Some(returns) = &spec.returns {
+            self.spec_clause("returns", &returns.exprs);
+        }
+        if let Some(decreases) = &spec.decreases {
+            self.spec_clause("decreases", &decreases.decreases.exprs);
+            if let Some((_when_token, when)) = &decreases.when {
+                self.hardbreak();
+                self.word("when ");
+                self.expr(when, FixupContext::NONE);
+            }
+            if let Some((_via_token, via)) = &decreases.via {
+                self.hardbreak();
+                self.word("via ");
+                self.expr(via, FixupContext::NONE);
+            }
+        }
+        if let Some(invariants) = &spec.invariants {
+            self.hardbreak();
+            self.word("opens_invariants ");
+            match &invariants.set {
+                InvariantNameSet::Any(_) => self.word("any"),
+                InvariantNameSet::None(_) => self.word("none"),
+                InvariantNameSet::List(list) => {
+                    self.word("[");
+                    for expr in list.exprs.iter().delimited() {
+                        self.expr(&expr, FixupContext::NONE);
+                        if !expr.is_last {
+                            self.word(", ");
+                        }
+                    }
+                    self.word("]");
+                }
+                InvariantNameSet::Set(set) => self.expr(&set.expr, FixupContext::NONE),
+            }
+        }
+        if let Some(unwind) = &spec.unwind {
+            self.hardbreak();
+            self.word("no_unwind");
+            if let Some((_when_token, when)) = &unwind.when {
+                self.word(" when ");
+                self.expr(when, FixupContext::NONE);
+            }
+        }
+        if let Some(with) = &spec.with {
+            self.hardbreak();
+            self.word("with ");
+            for input in with.inputs.iter().delimited() {
+                self.fn_arg(&input);
+                if !input.is_last {
+                    self.word(", ");
+                }
+            }
+            if let Some((_arrow, outputs)) = &with.outputs {
+                self.word(" -> ");
+                for output in outputs.iter().delimited() {
+                    self.pat_type(&output);
+                    if !output.is_last {
+                        self.word(", ");
+                    }
+                }
+            }
+        }
+    }
+
+    fn spec_clause(&mut self, keyword: &'static str, spec: &Specification) {
+        self.hardbreak();
+        self.word(keyword);
+        self.cbox(INDENT);
+        for expr in spec.exprs.iter().delimited() {
+            self.hardbreak();
+            self.expr(&expr, FixupContext::NONE);
+            if !expr.is_last {
+                self.word(",");
+            }
+        }
+        self.end();
+    }
+
+    fn ensures_oneline(&mut self, ensures: &Ensures) {
+        self.word(" ensures ");
+        for expr in ensures.exprs.exprs.iter().delimited() {
+            self.expr(&expr, FixupContext::NONE);
+            if !expr.is_last {
+                self.word(", ");
+            }
+        }
+    }
+
+    fn item_global(&mut self, item: &Global) {
+        self.outer_attrs(&item.attrs);
+        self.word("global ");
+        match &item.inner {
+            GlobalInner::SizeOf(inner) => {
+                self.word("size_of ");
+                self.ty(&inner.type_);
+                self.word(" == ");
+                self.lit(&inner.expr_lit.lit);
+            }
+            GlobalInner::Layout(inner) => {
+                self.word("layout ");
+                self.ty(&inner.type_);
+                self.word(" is ");
+                self.ident(&inner.size.0);
+                self.word(" == ");
+                self.lit(&inner.size.2.lit);
+                if let Some((_comma, ident, _eq, lit)) = &inner.align {
+                    self.word(", ");
+                    self.ident(ident);
+                    self.word(" == ");
+                    self.lit(&lit.lit);
+                }
+            }
+        }
+        self.word(";");
+        self.hardbreak();
+    }
+
+    fn item_broadcast_use(&mut self, item: &BroadcastUse) {
+        self.outer_attrs(&item.attrs);
+        self.word("broadcast use ");
+        if item.brace_token.is_some() {
+            self.word("{");
+        }
+        for path in item.paths.iter().delimited() {
+            self.path(&path.path, PathKind::Simple);
+            if !path.is_last {
+                self.word(", ");
+            }
+        }
+        if item.brace_token.is_some() {
+            self.word("}");
+        }
+        self.word(";");
+        self.hardbreak();
+    }
+
+    fn item_broadcast_group(&mut self, item: &ItemBroadcastGroup) {
+        self.outer_attrs(&item.attrs);
+        self.cbox(INDENT);
+        self.visibility(&item.vis);
+        self.word("broadcast group ");
+        self.ident(&item.ident);
+        self.word(" {");
+        self.hardbreak_if_nonempty();
+        for path in &item.paths {
+            self.path(&path.path, PathKind::Simple);
+            self.word(",");
+            self.hardbreak();
+        }
+        self.offset(-INDENT);
+        self.end();
+        self.word("}");
+        self.hardbreak();
+    }
+
+    fn item_assume_specification(&mut self, item: &AssumeSpecification) {
+        self.outer_attrs(&item.attrs);
+        self.cbox(INDENT);
+        self.visibility(&item.vis);
+        self.word("assume_specification");
+        self.generics(&item.generics);
+        self.word("[");
+        self.qpath(&item.qself, &item.path, PathKind::Simple);
+        self.word("]");
+        if let Some((_paren, inputs)) = &item.inputs {
+            self.word("(");
+            for input in inputs.iter().delimited() {
+                self.fn_arg(&input);
+                if !input.is_last {
+                    self.word(", ");
+                }
+            }
+            self.word(")");
+        }
+        self.return_type(&item.output);
+        let spec = SignatureSpec {
+            prover: None,
+            requires: item.requires.clone(),
+            recommends: None,
+            ensures: item.ensures.clone(),
+            default_ensures: item.default_ensures.clone(),
+            returns: item.returns.clone(),
+            decreases: None,
+            invariants: item.invariants.clone(),
+            unwind: item.unwind.clone(),
+            with: None,
+        };
+        if signature_spec_is_empty(&spec) {
+            self.word(";");
+        } else {
+            self.signature_spec(&spec);
+            self.word(";");
+        }
+        self.end();
+        self.hardbreak();
+    }
+
     fn static_mutability(&mut self, mutability: &StaticMutability) {
         match mutability {
             #![cfg_attr(all(test, exhaustive), deny(non_exhaustive_omitted_patterns))]
@@ -1439,6 +1767,23 @@ impl Printer {
     }
 }
 
+fn signature_spec_is_empty(spec: &SignatureSpec) -> bool {
+    spec.prover.is_none()
+        && spec.requires.is_none()
+        && spec.recommends.is_none()
+        && spec.ensures.is_none()
+        && spec.default_ensures.is_none()
+        && spec.returns.is_none()
+        && spec.decreases.is_none()
+        && spec.invariants.is_none()
+        && spec.unwind.is_none()
+        && spec.with.is_none()
+}
+
+fn has_where_predicates(where_clause: &Option<verus_syn::WhereClause>) -> bool {
+    where_clause.as_ref().map_or(false, |where_clause| !where_clause.predicates.is_empty())
+}
+
 #[cfg(feature = "verbatim")]
 mod verbatim {
     use crate::algorithm::Printer;
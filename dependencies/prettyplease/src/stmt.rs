@@ -13,6 +13,12 @@ impl Printer {
                 self.outer_attrs(&local.attrs);
                 self.ibox(0);
                 self.word("let ");
+                if local.tracked.is_some() {
+                    self.word("tracked ");
+                }
+                if local.ghost.is_some() {
+                    self.word("ghost ");
+                }
                 self.pat(&local.pat);
                 if let Some(local_init) = &local.init {
                     self.word(" = ");
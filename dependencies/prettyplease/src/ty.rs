@@ -306,9 +306,21 @@ impl Printer {
     pub fn return_type(&mut self, ty: &ReturnType) {
         match ty {
             ReturnType::Default => {}
-            ReturnType::Type(_arrow, _, _, ty) => {
+            ReturnType::Type(_arrow, tracked, name, ty) => {
                 self.word(" -> ");
-                self.ty(ty);
+                if tracked.is_some() {
+                    self.word("tracked ");
+                }
+                if let Some(name) = name {
+                    let (_paren, pat, _colon) = &**name;
+                    self.word("(");
+                    self.pat(pat);
+                    self.word(": ");
+                    self.ty(ty);
+                    self.word(")");
+                } else {
+                    self.ty(ty);
+                }
             }
         }
     }
@@ -1490,6 +1490,21 @@ pub(crate) mod parsing {
                     is_not_token,
                     variant_ident,
                 });
+            } else if Precedence::HasIsMatches >= base
+                && input.peek(Token![!])
+                && input.peek2(Token![is])
+            {
+                let bang_token: Token![!] = input.parse()?;
+                input.parse::<Token![is]>()?;
+                let variant_ident = input.parse()?;
+                lhs = Expr::IsNot(ExprIsNot {
+                    attrs: Vec::new(),
+                    base: Box::new(lhs),
+                    is_not_token: crate::token::IsNot {
+                        span: bang_token.span,
+                    },
+                    variant_ident,
+                });
             } else if Precedence::HasIsMatches >= base && input.peek(Token![has]) {
                 let has_token: Token![has] = input.parse()?;
                 let rhs = unary_expr(input, allow_struct)?;
@@ -1508,6 +1523,21 @@ pub(crate) mod parsing {
                     has_not_token,
                     rhs: Box::new(rhs),
                 });
+            } else if Precedence::HasIsMatches >= base
+                && input.peek(Token![!])
+                && input.peek2(Token![has])
+            {
+                let bang_token: Token![!] = input.parse()?;
+                input.parse::<Token![has]>()?;
+                let rhs = unary_expr(input, allow_struct)?;
+                lhs = Expr::HasNot(ExprHasNot {
+                    attrs: Vec::new(),
+                    lhs: Box::new(lhs),
+                    has_not_token: crate::token::HasNot {
+                        span: bang_token.span,
+                    },
+                    rhs: Box::new(rhs),
+                });
             } else if Precedence::HasIsMatches >= base && input.peek(Token![matches]) {
                 lhs = crate::verus::parse_matches(input, lhs, allow_struct, false)?;
             } else {
@@ -207,7 +207,10 @@ pub(crate) mod parsing {
         let mut is_item_macro = false;
         if let Ok(path) = ahead.call(Path::parse_mod_style) {
             if ahead.peek(Token![!]) {
-                if ahead.peek2(Ident) || ahead.peek2(Token![try]) {
+                if (ahead.peek2(Ident) || ahead.peek2(Token![try]))
+                    && !ahead.peek2(Token![is])
+                    && !ahead.peek2(Token![has])
+                {
                     is_item_macro = true;
                 } else if ahead.peek2(token::Brace)
                     && !(ahead.peek3(Token![.]) && !ahead.peek3(Token![..])
@@ -257,6 +260,14 @@ pub(crate) mod parsing {
             || input.peek(Token![impl])
             || input.peek(Token![macro])
             || input.peek(Token![broadcast]) && input.peek2(Token![use])
+            || input.peek(Token![spec])
+                && (input.peek2(Token![fn])
+                    || input.peek2(token::Paren) && input.peek3(Token![fn]))
+            || input.peek(Token![proof]) && input.peek2(Token![fn])
+            || input.peek(Token![axiom]) && input.peek2(Token![fn])
+            || input.peek(Token![exec]) && input.peek2(Token![fn])
+            || (input.peek(Token![open]) || input.peek(Token![closed]) || input.peek(Token![uninterp]))
+                && input.peek2(Token![spec])
             || is_item_macro
         {
             let item = item::parsing::parse_rest_of_item(begin, attrs, input)?;
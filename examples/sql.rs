@@ -0,0 +1,233 @@
+// rust_verify/tests/example.rs
+//! A verified miniature SQL query engine.
+//!
+//! The specification layer (`sql_spec::relational_model`) gives queries a
+//! mathematical semantics over bags of rows; the executable layer
+//! (`sql_spec::executable_impl`) implements the operators and proves them
+//! correct against that semantics. Table access is guarded by tracked
+//! `ReadCap` capabilities: `execute_query` can only be called with
+//! capabilities covering every table the query reads.
+
+#![allow(unused_imports)]
+use vstd::prelude::*;
+
+mod sql_spec;
+
+use sql_spec::aggregates::*;
+use sql_spec::executable_impl::*;
+use sql_spec::physical_algebra::*;
+use sql_spec::relational_model::*;
+use sql_spec::simplifier::*;
+
+verus! {
+
+pub const EMPLOYEES: TableName = 0;
+
+pub const DEPARTMENTS: TableName = 1;
+
+/// Build a three-column row.
+fn row3(a: i64, b: i64, c: i64) -> (t: Tuple)
+    ensures
+        t@ =~= seq![a, b, c],
+{
+    let mut values: Vec<i64> = Vec::new();
+    values.push(a);
+    values.push(b);
+    values.push(c);
+    Tuple { values }
+}
+
+/// Hand-written planner for `Filter(Table, f)`: take the index-scan path
+/// when the formula is a single-column range on `col`, and the plain filter
+/// otherwise. Either way the contract is the filter semantics, so callers
+/// cannot tell which plan ran.
+fn filter_with_planner(data: &Vec<Tuple>, f: &Formula, col: usize) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == filter_bag(table_view(data@), f.atoms@),
+{
+    match extract_range(f, col) {
+        Some((lo, hi)) => {
+            let result = index_scan(data, col, lo, hi);
+            proof {
+                lemma_index_scan_matches_filter(table_view(data@), f.atoms@, col, lo, hi);
+            }
+            result
+        }
+        None => execute_filter(data, f),
+    }
+}
+
+fn main() {
+    // employees: (id, dept, salary)
+    let mut employees: Vec<Tuple> = Vec::new();
+    employees.push(row3(1, 0, 1500));
+    employees.push(row3(2, 0, 2500));
+    employees.push(row3(3, 1, 3500));
+
+    // departments: (id, cost_center, unused)
+    let mut departments: Vec<Tuple> = Vec::new();
+    departments.push(row3(0, 100, 0));
+    departments.push(row3(1, 200, 0));
+
+    let mut tables: Vec<Vec<Tuple>> = Vec::new();
+    tables.push(employees);
+    tables.push(departments);
+
+    // Capabilities are minted for exactly the two tables above.
+    let (db, caps) = database_with_caps(tables);
+
+    // SELECT * FROM employees WHERE salary > 2000
+    let mut atoms: Vec<AtomicFormula> = Vec::new();
+    atoms.push(AtomicFormula::Gt(2, 2000));
+    let query = Query::Filter(Formula { atoms }, Box::new(Query::Table(EMPLOYEES)));
+
+    proof {
+        assert(tables_of(Query::Table(EMPLOYEES)) =~= set![EMPLOYEES]);
+        assert(tables_of(query) =~= set![EMPLOYEES]);
+        assert(caps@.dom().contains(EMPLOYEES));
+        assert(tables_of(query).subset_of(caps@.dom()));
+    }
+    let result = execute_query(&db, &query, Tracked(caps.borrow()));
+    assert(table_view(result@) == eval_query(db, query));
+    println!("employees with salary > 2000: {} row(s)", result.len());
+
+    // Population variance of salaries in department 0 (salaries 1500 and
+    // 2500): mean 2000, truncated variance
+    // (1500^2 + 2500^2) / 2 - 2000^2 = 250000.
+    let mut dept0: Vec<Tuple> = Vec::new();
+    dept0.push(row3(1, 0, 1500));
+    dept0.push(row3(2, 0, 2500));
+    proof {
+        let rows = table_view(dept0@);
+        assert(rows =~= seq![seq![1i64, 0, 1500], seq![2i64, 0, 2500]]);
+        assert(rows[0].len() == 3 && rows[0][2] == 1500);
+        assert(rows[1].len() == 3 && rows[1][2] == 2500);
+        assert(bounded_bag(rows, 2, AGG_BOUND as int));
+    }
+    let variance = compute_aggregate_exec(AggOp::VarP(2), &dept0);
+    proof {
+        // Hand-evaluate the spec formula over the two concrete rows.
+        assert(var_p_column(seq![seq![1i64, 0, 1500], seq![2i64, 0, 2500]], 2) == 250000)
+            by (compute);
+    }
+    assert(variance == 250000);
+    println!("salary variance in department 0: {}", variance);
+
+    // INTERSECT and EXCEPT use set semantics, so duplicates collapse where
+    // bag semantics would keep them: table A holds (1,1,1) twice plus
+    // (2,2,2), table B holds (1,1,1) once. The bag-level overlap of A and B
+    // has two copies of (1,1,1); the set-semantics operators produce one.
+    let mut a_rows: Vec<Tuple> = Vec::new();
+    a_rows.push(row3(1, 1, 1));
+    a_rows.push(row3(1, 1, 1));
+    a_rows.push(row3(2, 2, 2));
+    let mut b_rows: Vec<Tuple> = Vec::new();
+    b_rows.push(row3(1, 1, 1));
+    let mut dup_tables: Vec<Vec<Tuple>> = Vec::new();
+    dup_tables.push(a_rows);
+    dup_tables.push(b_rows);
+    let (db2, caps2) = database_with_caps(dup_tables);
+
+    let intersect_q = Query::Intersect(Box::new(Query::Table(0)), Box::new(Query::Table(1)));
+    let except_q = Query::Except(Box::new(Query::Table(0)), Box::new(Query::Table(1)));
+    proof {
+        assert(tables_of(Query::Table(0)) =~= set![0usize]);
+        assert(tables_of(Query::Table(1)) =~= set![1usize]);
+        assert forall|n: TableName| tables_of(intersect_q).contains(n) implies caps2@.dom()
+            .contains(n) by {
+            assert(n == 0 || n == 1);
+        }
+        assert forall|n: TableName| tables_of(except_q).contains(n) implies caps2@.dom().contains(
+            n,
+        ) by {
+            assert(n == 0 || n == 1);
+        }
+        assert(tables_of(intersect_q).subset_of(caps2@.dom()));
+        assert(tables_of(except_q).subset_of(caps2@.dom()));
+    }
+    let intersection = execute_query(&db2, &intersect_q, Tracked(caps2.borrow()));
+    let difference = execute_query(&db2, &except_q, Tracked(caps2.borrow()));
+    proof {
+        assert(table_view(db2.tables@[0]@) =~= seq![
+            seq![1i64, 1, 1],
+            seq![1i64, 1, 1],
+            seq![2i64, 2, 2],
+        ]);
+        assert(table_view(db2.tables@[1]@) =~= seq![seq![1i64, 1, 1]]);
+        // Evaluate the set-semantics operators over the concrete rows.
+        assert(intersect_bag(
+            seq![seq![1i64, 1, 1], seq![1i64, 1, 1], seq![2i64, 2, 2]],
+            seq![seq![1i64, 1, 1]],
+        ).len() == 1) by (compute);
+        assert(except_bag(
+            seq![seq![1i64, 1, 1], seq![1i64, 1, 1], seq![2i64, 2, 2]],
+            seq![seq![1i64, 1, 1]],
+        ).len() == 1) by (compute);
+    }
+    assert(intersection.len() == 1);
+    assert(difference.len() == 1);
+    println!("A INTERSECT B: {} row(s)", intersection.len());
+    println!("A EXCEPT B: {} row(s)", difference.len());
+
+    // A bloated WHERE clause: no-op `True` atoms and a duplicated range
+    // condition. The simplifier folds it down, and its soundness theorem
+    // lets the verifier conclude that filtering with either formula keeps
+    // exactly the same rows.
+    let mut bloated_atoms: Vec<AtomicFormula> = Vec::new();
+    bloated_atoms.push(AtomicFormula::True);
+    bloated_atoms.push(AtomicFormula::Gt(2, 2000));
+    bloated_atoms.push(AtomicFormula::True);
+    bloated_atoms.push(AtomicFormula::Gt(2, 2000));
+    let bloated = Formula { atoms: bloated_atoms };
+    let simplified = simplify_formula(&bloated);
+    println!(
+        "simplified WHERE clause: {} atom(s), down from {}",
+        simplified.atoms.len(),
+        bloated.atoms.len(),
+    );
+
+    let mut crew: Vec<Tuple> = Vec::new();
+    crew.push(row3(1, 0, 1500));
+    crew.push(row3(2, 0, 2500));
+    crew.push(row3(3, 1, 3500));
+    let before = execute_filter(&crew, &bloated);
+    let after = execute_filter(&crew, &simplified);
+    proof {
+        lemma_filter_bag_equivalent(table_view(crew@), bloated.atoms@, simplified.atoms@);
+        assert(table_view(before@) == table_view(after@));
+    }
+    assert(before.len() == after.len());
+    println!(
+        "filter keeps {} row(s) before and after simplification",
+        before.len(),
+    );
+
+    // Range pushdown: 2000 < salary < 4000 is a pure single-column range,
+    // so the planner serves it with an index scan instead of the row-by-row
+    // filter; extract_range's ensures composed with the bridge lemma prove
+    // the two plans return the same rows.
+    let mut range_atoms: Vec<AtomicFormula> = Vec::new();
+    range_atoms.push(AtomicFormula::Gt(2, 2000));
+    range_atoms.push(AtomicFormula::Lt(2, 4000));
+    let range_f = Formula { atoms: range_atoms };
+    let mut staff: Vec<Tuple> = Vec::new();
+    staff.push(row3(1, 0, 1500));
+    staff.push(row3(2, 0, 2500));
+    staff.push(row3(3, 1, 3500));
+    let planned = filter_with_planner(&staff, &range_f, 2);
+    let direct = execute_filter(&staff, &range_f);
+    assert(planned.len() == direct.len());
+    println!(
+        "planner and direct filter agree: {} row(s) with 2000 < salary < 4000",
+        planned.len(),
+    );
+
+    // The verifier rejects access to tables outside the granted capability
+    // set. Table 2 does not exist and no capability was minted for it, so
+    // uncommenting the following fails `tables_of(*q).subset_of(caps@.dom())`
+    // (and `query_wf`):
+    //
+    // let bad = execute_query(&db, &Query::Table(2), Tracked(caps.borrow()));
+}
+
+} // verus!
@@ -0,0 +1,234 @@
+// rust_verify/tests/example.rs
+//! A verified mini benchmark for the SQL example.
+//!
+//! The dataset is generated *inside* `verus!` by a verified linear
+//! congruential generator, so the whole run is a pure function of the seed:
+//! no external data files, and rerunning reproduces the table bit for bit.
+//! A fixed suite of queries then exercises the verified operators — plain
+//! filter, simplified filter, index scan, group-by — at a scale the unit
+//! demos don't reach. Every runtime check below is implied by the
+//! operators' ensures clauses, so a firing assert would mean a verifier
+//! bug, not a data bug: the point is to watch the specs carry their weight
+//! on a larger run.
+
+#![allow(unused_imports)]
+use vstd::prelude::*;
+
+mod sql_spec;
+
+use sql_spec::executable_impl::*;
+use sql_spec::physical_algebra::*;
+use sql_spec::relational_model::*;
+use sql_spec::simplifier::*;
+
+verus! {
+
+/// MINSTD parameters: with a seed below [`LCG_M`], the state stays below it
+/// forever and the products fit comfortably in a `u64`.
+pub const LCG_M: u64 = 0x7fff_ffff;
+
+pub const LCG_A: u64 = 48271;
+
+/// One step of the recurrence, as a spec. This is the ground truth the
+/// generator is verified against, and what makes the dataset reproducible.
+pub open spec fn lcg_step(state: u64) -> u64 {
+    (((state as int) * (LCG_A as int)) % (LCG_M as int)) as u64
+}
+
+/// The value a state is turned into: a salary-sized number in `[0, 5000)`.
+pub open spec fn lcg_value(state: u64) -> i64 {
+    ((state as int) % 5000) as i64
+}
+
+/// The state after `k` steps from `seed`.
+pub open spec fn lcg_iter(seed: u64, k: nat) -> u64
+    decreases k,
+{
+    if k == 0 {
+        seed
+    } else {
+        lcg_step(lcg_iter(seed, (k - 1) as nat))
+    }
+}
+
+/// Advance the generator once, returning the new state and its value.
+pub fn lcg_next(state: u64) -> (res: (u64, i64))
+    requires
+        state < LCG_M,
+    ensures
+        res.0 == lcg_step(state),
+        res.0 < LCG_M,
+        res.1 == lcg_value(lcg_step(state)),
+        0 <= res.1 < 5000,
+{
+    let next = (state * LCG_A) % LCG_M;
+    (next, (next % 5000) as i64)
+}
+
+/// Deterministically generate `n` employee rows `(id, dept, salary)`.
+///
+/// Two generator steps per row pin the loop state to `lcg_iter(seed, 2 * i)`,
+/// so the table is a pure function of the seed. The ensures give the query
+/// suite what it relies on: row count, row width, and the ranges of the
+/// columns the queries select and group on.
+pub fn generate_employees(n: usize, seed: u64) -> (data: Vec<Tuple>)
+    requires
+        seed < LCG_M,
+        n <= 0x7fff_ffff,
+    ensures
+        data@.len() == n,
+        forall|i: int| 0 <= i < n ==> (#[trigger] data@[i])@.len() == 3,
+        forall|i: int| 0 <= i < n ==> 0 <= (#[trigger] data@[i])@[1] < 8,
+        forall|i: int| 0 <= i < n ==> 1000 <= (#[trigger] data@[i])@[2] < 6000,
+{
+    let mut data: Vec<Tuple> = Vec::new();
+    let mut state = seed;
+    let mut i: usize = 0;
+    while i < n
+        invariant
+            i <= n,
+            n <= 0x7fff_ffff,
+            data@.len() == i,
+            state < LCG_M,
+            state == lcg_iter(seed, (2 * i) as nat),
+            forall|j: int| 0 <= j < i ==> (#[trigger] data@[j])@.len() == 3,
+            forall|j: int| 0 <= j < i ==> 0 <= (#[trigger] data@[j])@[1] < 8,
+            forall|j: int| 0 <= j < i ==> 1000 <= (#[trigger] data@[j])@[2] < 6000,
+        decreases n - i,
+    {
+        let (s1, v1) = lcg_next(state);
+        let (s2, v2) = lcg_next(s1);
+        let dept = v1 % 8;
+        let salary = 1000 + v2;
+        let mut values: Vec<i64> = Vec::new();
+        values.push(i as i64);
+        values.push(dept);
+        values.push(salary);
+        let ghost row = values@;
+        data.push(Tuple { values });
+        proof {
+            assert(row =~= seq![i as i64, dept, salary]);
+            // Unfold the recurrence twice: the two `lcg_next` calls advance
+            // `lcg_iter(seed, 2 * i)` to `lcg_iter(seed, 2 * (i + 1))`.
+            assert(lcg_iter(seed, (2 * i + 1) as nat) == lcg_step(
+                lcg_iter(seed, (2 * i) as nat),
+            ));
+            assert(lcg_iter(seed, (2 * i + 2) as nat) == lcg_step(
+                lcg_iter(seed, (2 * i + 1) as nat),
+            ));
+        }
+        state = s2;
+        i += 1;
+    }
+    data
+}
+
+/// Wall-clock milliseconds, outside the verified world; used only for the
+/// coarse timings in the report.
+#[verifier::external_body]
+fn now_millis() -> u64 {
+    use std::time::{SystemTime, UNIX_EPOCH};
+    SystemTime::now().duration_since(UNIX_EPOCH).map(|d| d.as_millis() as u64).unwrap_or(0)
+}
+
+#[verifier::external_body]
+fn elapsed_millis(start: u64) -> u64 {
+    now_millis().saturating_sub(start)
+}
+
+fn main() {
+    let rows: usize = 50000;
+    let t0 = now_millis();
+    let employees = generate_employees(rows, 12345);
+    println!("generated {} employee row(s) in {} ms", employees.len(), elapsed_millis(t0));
+    assert(employees.len() == 50000);
+
+    // Query 1: WHERE salary > 4000, written with redundant atoms, run both
+    // as written and after the verified simplifier. The equal row counts
+    // are implied by the filter ensures plus the simplifier's soundness
+    // theorem.
+    let mut atoms: Vec<AtomicFormula> = Vec::new();
+    atoms.push(AtomicFormula::True);
+    atoms.push(AtomicFormula::Gt(2, 4000));
+    atoms.push(AtomicFormula::Gt(2, 4000));
+    let f = Formula { atoms };
+    let t1 = now_millis();
+    let direct = execute_filter(&employees, &f);
+    let t_direct = elapsed_millis(t1);
+    let simplified = simplify_formula(&f);
+    let t2 = now_millis();
+    let via_simplified = execute_filter(&employees, &simplified);
+    let t_simplified = elapsed_millis(t2);
+    proof {
+        lemma_filter_bag_equivalent(table_view(employees@), f.atoms@, simplified.atoms@);
+    }
+    assert(direct.len() == via_simplified.len());
+    println!(
+        "salary > 4000: {} row(s) ({} ms as written, {} ms simplified)",
+        direct.len(),
+        t_direct,
+        t_simplified,
+    );
+
+    // Query 2: the same predicate through the index-scan path; the bridge
+    // lemma pins its output to the filter semantics.
+    if let Some((lo, hi)) = extract_range(&simplified, 2) {
+        let t3 = now_millis();
+        let scanned = index_scan(&employees, 2, lo, hi);
+        let t_scan = elapsed_millis(t3);
+        proof {
+            lemma_index_scan_matches_filter(
+                table_view(employees@),
+                simplified.atoms@,
+                2,
+                lo,
+                hi,
+            );
+        }
+        assert(scanned.len() == via_simplified.len());
+        println!("index scan on salary: {} row(s) in {} ms", scanned.len(), t_scan);
+    }
+
+    // Query 3: GROUP BY dept. The group count is bounded by the input size
+    // and every key has exactly one column, both straight from the group-by
+    // ensures; the loop re-checks the key widths at runtime.
+    let mut group_cols: Vec<usize> = Vec::new();
+    group_cols.push(1);
+    let ghost gc = group_cols@;
+    proof {
+        assert(gc =~= seq![1usize]);
+        assert forall|i: int, c: int|
+            0 <= i < employees@.len() && 0 <= c < gc.len() implies gc[c] < (
+        employees@[i])@.len() by {
+            assert((employees@[i])@.len() == 3);
+        }
+    }
+    let t4 = now_millis();
+    let grouped = execute_group_by(&employees, group_cols);
+    let t_group = elapsed_millis(t4);
+    assert(grouped.groups.len() <= employees.len());
+    let mut widths_ok = true;
+    let mut k: usize = 0;
+    while k < grouped.groups.len()
+        invariant
+            k <= grouped.groups@.len(),
+            widths_ok,
+            forall|g: int|
+                0 <= g < grouped.groups@.len() ==> (#[trigger] grouped.groups@[g]).key@.len()
+                    == 1,
+        decreases grouped.groups@.len() - k,
+    {
+        if grouped.groups[k].key.len() != 1 {
+            widths_ok = false;
+        }
+        k += 1;
+    }
+    assert(widths_ok);
+    println!(
+        "GROUP BY dept: {} group(s) in {} ms; all key widths checked",
+        grouped.groups.len(),
+        t_group,
+    );
+}
+
+} // verus!
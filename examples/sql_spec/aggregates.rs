@@ -0,0 +1,217 @@
+//! Aggregate functions over table columns, with integer semantics.
+//!
+//! The spec layer defines column sums, sums of squares, and a truncating
+//! population variance; `compute_aggregate_exec` implements them in one pass
+//! over the rows, using `i128` intermediates, and is proved to match the spec
+//! formulas exactly under the `bounded_bag` precondition.
+
+#[allow(unused_imports)]
+use vstd::prelude::*;
+
+use crate::sql_spec::relational_model::*;
+
+verus! {
+
+/// Bound on column values accepted by the aggregate implementations; with
+/// values in `[0, AGG_BOUND]` and at most `AGG_BOUND` rows, both the sum
+/// (at most `AGG_BOUND^2 < 2^62`) and the sum of squares (at most
+/// `AGG_BOUND^3 < 2^93`) fit comfortably in `i128`.
+pub const AGG_BOUND: i64 = 0x7fff_ffff;
+
+/// The sum of column `col` over all rows.
+pub open spec fn sum_column(rows: Bag, col: int) -> int
+    decreases rows.len(),
+{
+    if rows.len() == 0 {
+        0
+    } else {
+        sum_column(rows.drop_last(), col) + rows.last()[col]
+    }
+}
+
+/// The sum of the squares of column `col` over all rows.
+pub open spec fn sum_sq_column(rows: Bag, col: int) -> int
+    decreases rows.len(),
+{
+    if rows.len() == 0 {
+        0
+    } else {
+        sum_sq_column(rows.drop_last(), col) + rows.last()[col] * rows.last()[col]
+    }
+}
+
+/// Population variance of column `col`, integer-truncated. The exact formula
+/// implemented is
+///
+/// ```text
+/// sum_sq_column(rows, col) / n - (sum_column(rows, col) / n)^2
+/// ```
+///
+/// with `n = rows.len()` and `/` the (Euclidean) spec division; the empty bag
+/// has variance 0. For nonnegative column values this agrees with truncating
+/// machine division, which is what the executable implementation uses.
+pub open spec fn var_p_column(rows: Bag, col: int) -> int {
+    let n = rows.len() as int;
+    if n == 0 {
+        0
+    } else {
+        sum_sq_column(rows, col) / n - (sum_column(rows, col) / n) * (sum_column(rows, col) / n)
+    }
+}
+
+/// An aggregate over a bag of rows.
+#[derive(Structural, PartialEq, Eq, Clone, Copy)]
+pub enum AggOp {
+    /// Number of rows.
+    Count,
+    /// Sum of the given column.
+    Sum(usize),
+    /// Population variance of the given column, integer-truncated.
+    VarP(usize),
+}
+
+pub open spec fn eval_agg(op: AggOp, rows: Bag) -> int {
+    match op {
+        AggOp::Count => rows.len() as int,
+        AggOp::Sum(col) => sum_column(rows, col as int),
+        AggOp::VarP(col) => var_p_column(rows, col as int),
+    }
+}
+
+/// Every row is wide enough for `col` and carries a value in `[0, bound]`
+/// there. This is the precondition under which the executable aggregates are
+/// overflow-free and agree with the spec formulas.
+pub open spec fn bounded_bag(rows: Bag, col: int, bound: int) -> bool {
+    forall|i: int|
+        0 <= i < rows.len() ==> {
+            &&& 0 <= col < (#[trigger] rows[i]).len()
+            &&& 0 <= rows[i][col] <= bound
+        }
+}
+
+/// Extending a prefix by one row adds that row's contribution to both sums.
+proof fn lemma_sum_prefix_step(rows: Bag, col: int, i: int)
+    requires
+        0 <= i < rows.len(),
+    ensures
+        sum_column(rows.take(i + 1), col) == sum_column(rows.take(i), col) + rows[i][col],
+        sum_sq_column(rows.take(i + 1), col) == sum_sq_column(rows.take(i), col) + rows[i][col]
+            * rows[i][col],
+{
+    assert(rows.take(i + 1).drop_last() =~= rows.take(i));
+    assert(rows.take(i + 1).last() == rows[i]);
+}
+
+/// Compute `eval_agg(op, table_view(data@))` in one pass, carrying sum and
+/// sum-of-squares accumulators in `i128`.
+pub fn compute_aggregate_exec(op: AggOp, data: &Vec<Tuple>) -> (r: i64)
+    requires
+        data@.len() <= AGG_BOUND,
+        match op {
+            AggOp::Count => true,
+            AggOp::Sum(col) => bounded_bag(table_view(data@), col as int, AGG_BOUND as int),
+            AggOp::VarP(col) => bounded_bag(table_view(data@), col as int, AGG_BOUND as int),
+        },
+    ensures
+        r == eval_agg(op, table_view(data@)),
+{
+    let ghost rows = table_view(data@);
+    match op {
+        AggOp::Count => data.len() as i64,
+        AggOp::Sum(col) => {
+            let mut i: usize = 0;
+            let mut sum: i128 = 0;
+            while i < data.len()
+                invariant
+                    i <= data@.len() <= AGG_BOUND,
+                    rows == table_view(data@),
+                    bounded_bag(rows, col as int, AGG_BOUND as int),
+                    0 <= sum <= (AGG_BOUND as int) * (i as int),
+                    sum == sum_column(rows.take(i as int), col as int),
+                decreases data@.len() - i,
+            {
+                proof {
+                    assert(rows[i as int] == data@[i as int]@);
+                    lemma_sum_prefix_step(rows, col as int, i as int);
+                }
+                let v = data[i].values[col];
+                sum = sum + v as i128;
+                i += 1;
+            }
+            proof {
+                assert(rows.take(data@.len() as int) =~= rows);
+            }
+            sum as i64
+        }
+        AggOp::VarP(col) => {
+            // One pass, accumulating the sum and the sum of squares together;
+            // the invariants tie both to the spec functions over the prefix.
+            let mut i: usize = 0;
+            let mut sum: i128 = 0;
+            let mut sum_sq: i128 = 0;
+            while i < data.len()
+                invariant
+                    i <= data@.len() <= AGG_BOUND,
+                    rows == table_view(data@),
+                    bounded_bag(rows, col as int, AGG_BOUND as int),
+                    0 <= sum <= (AGG_BOUND as int) * (i as int),
+                    sum == sum_column(rows.take(i as int), col as int),
+                    0 <= sum_sq <= (AGG_BOUND as int) * (AGG_BOUND as int) * (i as int),
+                    sum_sq == sum_sq_column(rows.take(i as int), col as int),
+                decreases data@.len() - i,
+            {
+                proof {
+                    assert(rows[i as int] == data@[i as int]@);
+                    lemma_sum_prefix_step(rows, col as int, i as int);
+                }
+                let v = data[i].values[col];
+                proof {
+                    assert((v as int) * (v as int) <= (AGG_BOUND as int) * (AGG_BOUND as int))
+                        by (nonlinear_arith)
+                        requires
+                            0 <= v as int <= AGG_BOUND as int,
+                    ;
+                }
+                sum = sum + v as i128;
+                sum_sq = sum_sq + (v as i128) * (v as i128);
+                i += 1;
+            }
+            proof {
+                assert(rows.take(data@.len() as int) =~= rows);
+            }
+            if data.len() == 0 {
+                return 0;
+            }
+            let n = data.len() as u128;
+            // Nonnegative operands make machine division agree with the
+            // (Euclidean) spec division in `var_p_column`.
+            proof {
+                assert((sum as int) / (n as int) <= AGG_BOUND as int) by (nonlinear_arith)
+                    requires
+                        0 < n as int,
+                        0 <= sum as int <= (AGG_BOUND as int) * (n as int),
+                ;
+                assert((sum_sq as int) / (n as int) <= (AGG_BOUND as int) * (AGG_BOUND as int))
+                    by (nonlinear_arith)
+                    requires
+                        0 < n as int,
+                        0 <= sum_sq as int <= (AGG_BOUND as int) * (AGG_BOUND as int) * (
+                        n as int),
+                ;
+            }
+            let mean = ((sum as u128) / n) as i128;
+            let sq_mean = ((sum_sq as u128) / n) as i128;
+            proof {
+                assert(0 <= (mean as int) * (mean as int) <= (AGG_BOUND as int) * (
+                AGG_BOUND as int)) by (nonlinear_arith)
+                    requires
+                        0 <= mean as int <= AGG_BOUND as int,
+                ;
+            }
+            let var = sq_mean - mean * mean;
+            var as i64
+        }
+    }
+}
+
+} // verus!
@@ -0,0 +1,541 @@
+//! Executable operators for the SQL example, each verified against the
+//! semantics in `relational_model`.
+//!
+//! Table access is mediated by tracked [`ReadCap`] capabilities: `read_table`
+//! demands a capability for the table it touches, and `execute_query`
+//! requires capabilities covering the query's whole footprint
+//! (`tables_of(query)`). The exec behavior is unchanged by the ghost
+//! arguments; the point is that the verifier rejects any query that reads a
+//! table outside the granted set.
+
+#[allow(unused_imports)]
+use vstd::prelude::*;
+
+use crate::sql_spec::relational_model::*;
+
+verus! {
+
+impl Clone for Tuple {
+    fn clone(&self) -> (res: Self)
+        ensures
+            res@ == self@,
+    {
+        let values = self.values.clone();
+        proof {
+            assert forall|i: int| 0 <= i < self.values@.len() implies values@[i]
+                == self.values@[i] by {
+                assert(cloned::<i64>(self.values@[i], values@[i]));
+            }
+            assert(values@ =~= self.values@);
+        }
+        Tuple { values }
+    }
+}
+
+pub fn eval_atomic_exec(t: &Tuple, a: &AtomicFormula) -> (b: bool)
+    ensures
+        b == eval_atomic(t@, *a),
+{
+    match a {
+        AtomicFormula::True => true,
+        AtomicFormula::Eq(col, v) => *col < t.values.len() && t.values[*col] == *v,
+        AtomicFormula::Lt(col, v) => *col < t.values.len() && t.values[*col] < *v,
+        AtomicFormula::Gt(col, v) => *col < t.values.len() && t.values[*col] > *v,
+    }
+}
+
+pub fn eval_formula_exec(t: &Tuple, f: &Formula) -> (b: bool)
+    ensures
+        b == eval_formula(t@, f.atoms@),
+{
+    let mut i: usize = 0;
+    while i < f.atoms.len()
+        invariant
+            i <= f.atoms.len(),
+            forall|j: int| 0 <= j < i ==> eval_atomic(t@, #[trigger] f.atoms@[j]),
+        decreases f.atoms.len() - i,
+    {
+        if !eval_atomic_exec(t, &f.atoms[i]) {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+proof fn lemma_filter_bag_empty(atoms: Seq<AtomicFormula>)
+    ensures
+        filter_bag(Seq::empty(), atoms) == Seq::<Seq<i64>>::empty(),
+{
+    reveal(Seq::<Seq<i64>>::filter);
+}
+
+proof fn lemma_filter_bag_push(b: Bag, atoms: Seq<AtomicFormula>, row: Seq<i64>)
+    ensures
+        filter_bag(b.push(row), atoms) == if eval_formula(row, atoms) {
+            filter_bag(b, atoms).push(row)
+        } else {
+            filter_bag(b, atoms)
+        },
+{
+    reveal(Seq::<Seq<i64>>::filter);
+    assert(b.push(row).drop_last() =~= b);
+    assert(b.push(row).last() == row);
+}
+
+/// SELECT * FROM data WHERE f
+pub fn execute_filter(data: &Vec<Tuple>, f: &Formula) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == filter_bag(table_view(data@), f.atoms@),
+{
+    let mut result: Vec<Tuple> = Vec::new();
+    proof {
+        lemma_filter_bag_empty(f.atoms@);
+        assert(table_view(result@) =~= Seq::<Seq<i64>>::empty());
+        assert(table_view(data@.subrange(0, 0)) =~= Seq::<Seq<i64>>::empty());
+    }
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            table_view(result@) == filter_bag(table_view(data@.subrange(0, i as int)), f.atoms@),
+        decreases data.len() - i,
+    {
+        let ghost prefix = data@.subrange(0, i as int);
+        let ghost result0 = result@;
+        let t = data[i].clone();
+        if eval_formula_exec(&t, f) {
+            result.push(t);
+            proof {
+                assert(table_view(result@) =~= table_view(result0).push(data@[i as int]@));
+            }
+        }
+        proof {
+            lemma_filter_bag_push(table_view(prefix), f.atoms@, data@[i as int]@);
+            assert(data@.subrange(0, i as int + 1) =~= prefix.push(data@[i as int]));
+            assert(table_view(prefix.push(data@[i as int])) =~= table_view(prefix).push(
+                data@[i as int]@,
+            ));
+        }
+        i += 1;
+    }
+    proof {
+        assert(data@.subrange(0, data.len() as int) =~= data@);
+    }
+    result
+}
+
+proof fn lemma_filter_indices_push(
+    data: Seq<Tuple>,
+    atoms: Seq<AtomicFormula>,
+    idxs: Seq<int>,
+    i: int,
+)
+    ensures
+        filter_indices(data, atoms, idxs.push(i)) == if 0 <= i < data.len() && eval_formula(
+            data[i]@,
+            atoms,
+        ) {
+            filter_indices(data, atoms, idxs).push(data[i]@)
+        } else {
+            filter_indices(data, atoms, idxs)
+        },
+{
+    assert(idxs.push(i).drop_last() =~= idxs);
+    assert(idxs.push(i).last() == i);
+}
+
+/// `execute_filter` with a ghost *history variable*. Alongside the result we
+/// maintain a ghost log of every index the loop examines, and the ensures
+/// clauses say the log is exactly `0, 1, ..., data.len() - 1` — every tuple
+/// was examined exactly once, in order — and that the result holds precisely
+/// the rows at the logged positions that satisfy the formula
+/// (`filter_indices`). The log is ghost, so this costs nothing at runtime;
+/// the point is to verify *how* the algorithm ran, not just what it
+/// returned.
+pub fn execute_filter_audited(data: &Vec<Tuple>, f: &Formula) -> (res: (Vec<Tuple>, Ghost<
+    Seq<int>,
+>))
+    ensures
+        res.1@.len() == data@.len(),
+        forall|j: int| 0 <= j < res.1@.len() ==> res.1@[j] == j,
+        table_view(res.0@) == filter_indices(data@, f.atoms@, res.1@),
+{
+    let mut result: Vec<Tuple> = Vec::new();
+    let ghost mut examined: Seq<int> = Seq::empty();
+    proof {
+        assert(table_view(result@) =~= Seq::<Seq<i64>>::empty());
+    }
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            examined.len() == i,
+            forall|j: int| 0 <= j < examined.len() ==> examined[j] == j,
+            table_view(result@) == filter_indices(data@, f.atoms@, examined),
+        decreases data.len() - i,
+    {
+        let ghost examined0 = examined;
+        let ghost result0 = result@;
+        let t = data[i].clone();
+        proof {
+            examined = examined.push(i as int);
+        }
+        if eval_formula_exec(&t, f) {
+            result.push(t);
+            proof {
+                assert(table_view(result@) =~= table_view(result0).push(data@[i as int]@));
+            }
+        }
+        proof {
+            lemma_filter_indices_push(data@, f.atoms@, examined0, i as int);
+        }
+        i += 1;
+    }
+    (result, Ghost(examined))
+}
+
+/// Copy a table's rows into a fresh result vector.
+pub fn clone_table(data: &Vec<Tuple>) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == table_view(data@),
+{
+    let mut result: Vec<Tuple> = Vec::new();
+    proof {
+        assert(table_view(result@) =~= table_view(data@.subrange(0, 0)));
+    }
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            table_view(result@) == table_view(data@.subrange(0, i as int)),
+        decreases data.len() - i,
+    {
+        let ghost result0 = result@;
+        let t = data[i].clone();
+        result.push(t);
+        proof {
+            assert(table_view(result@) =~= table_view(result0).push(data@[i as int]@));
+            assert(data@.subrange(0, i as int + 1) =~= data@.subrange(0, i as int).push(
+                data@[i as int],
+            ));
+            assert(table_view(data@.subrange(0, i as int + 1)) =~= table_view(
+                data@.subrange(0, i as int),
+            ).push(data@[i as int]@));
+        }
+        i += 1;
+    }
+    proof {
+        assert(data@.subrange(0, data.len() as int) =~= data@);
+    }
+    result
+}
+
+/// Exec equality on rows, mirroring spec-level `a@ == b@`.
+fn rows_equal(a: &Tuple, b: &Tuple) -> (eq: bool)
+    ensures
+        eq <==> a@ =~= b@,
+{
+    if a.values.len() != b.values.len() {
+        return false;
+    }
+    let mut i: usize = 0;
+    while i < a.values.len()
+        invariant
+            i <= a.values.len(),
+            a.values.len() == b.values.len(),
+            forall|j: int| 0 <= j < i ==> a@[j] == b@[j],
+        decreases a.values.len() - i,
+    {
+        if a.values[i] != b.values[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// Linear membership scan: does `data` hold a row equal to `t`?
+fn contains_row(data: &Vec<Tuple>, t: &Tuple) -> (found: bool)
+    ensures
+        found == (nb_occ(t@, table_view(data@)) > 0),
+{
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            forall|j: int| 0 <= j < i ==> table_view(data@)[j] != t@,
+        decreases data.len() - i,
+    {
+        if rows_equal(&data[i], t) {
+            proof {
+                lemma_nb_occ_contains(table_view(data@), t@);
+                assert(table_view(data@)[i as int] == t@);
+            }
+            return true;
+        }
+        i += 1;
+    }
+    proof {
+        lemma_nb_occ_contains(table_view(data@), t@);
+    }
+    false
+}
+
+proof fn lemma_membership_filter_empty(b: Bag, keep_present: bool)
+    ensures
+        membership_filter(Seq::empty(), b, keep_present) == Seq::<Seq<i64>>::empty(),
+{
+    reveal(Seq::<Seq<i64>>::filter);
+}
+
+proof fn lemma_membership_filter_push(a: Bag, b: Bag, keep_present: bool, row: Seq<i64>)
+    ensures
+        membership_filter(a.push(row), b, keep_present) == if (nb_occ(row, b) > 0)
+            == keep_present {
+            membership_filter(a, b, keep_present).push(row)
+        } else {
+            membership_filter(a, b, keep_present)
+        },
+{
+    reveal(Seq::<Seq<i64>>::filter);
+    assert(a.push(row).drop_last() =~= a);
+    assert(a.push(row).last() == row);
+}
+
+/// Keep the rows of `data` that are (for `keep_present`) or are not present
+/// in `other`: the exec counterpart of `membership_filter`.
+fn filter_by_membership(data: &Vec<Tuple>, other: &Vec<Tuple>, keep_present: bool) -> (result: Vec<
+    Tuple,
+>)
+    ensures
+        table_view(result@) == membership_filter(
+            table_view(data@),
+            table_view(other@),
+            keep_present,
+        ),
+{
+    let mut result: Vec<Tuple> = Vec::new();
+    proof {
+        lemma_membership_filter_empty(table_view(other@), keep_present);
+        assert(table_view(result@) =~= Seq::<Seq<i64>>::empty());
+        assert(table_view(data@.subrange(0, 0)) =~= Seq::<Seq<i64>>::empty());
+    }
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            table_view(result@) == membership_filter(
+                table_view(data@.subrange(0, i as int)),
+                table_view(other@),
+                keep_present,
+            ),
+        decreases data.len() - i,
+    {
+        let ghost prefix = data@.subrange(0, i as int);
+        let ghost result0 = result@;
+        let t = data[i].clone();
+        if contains_row(other, &t) == keep_present {
+            result.push(t);
+            proof {
+                assert(table_view(result@) =~= table_view(result0).push(data@[i as int]@));
+            }
+        }
+        proof {
+            lemma_membership_filter_push(
+                table_view(prefix),
+                table_view(other@),
+                keep_present,
+                data@[i as int]@,
+            );
+            assert(data@.subrange(0, i as int + 1) =~= prefix.push(data@[i as int]));
+            assert(table_view(prefix.push(data@[i as int])) =~= table_view(prefix).push(
+                data@[i as int]@,
+            ));
+        }
+        i += 1;
+    }
+    proof {
+        assert(data@.subrange(0, data.len() as int) =~= data@);
+    }
+    result
+}
+
+/// SELECT DISTINCT: keep the first copy of each row.
+pub fn execute_distinct(data: &Vec<Tuple>) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == distinct_bag(table_view(data@)),
+{
+    let mut result: Vec<Tuple> = Vec::new();
+    proof {
+        assert(table_view(result@) =~= Seq::<Seq<i64>>::empty());
+        assert(table_view(data@.subrange(0, 0)) =~= Seq::<Seq<i64>>::empty());
+    }
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            table_view(result@) == distinct_bag(table_view(data@.subrange(0, i as int))),
+        decreases data.len() - i,
+    {
+        let ghost prefix = data@.subrange(0, i as int);
+        let ghost result0 = result@;
+        let t = data[i].clone();
+        // The result so far is duplicate-free, so scanning it for `t` decides
+        // whether the whole prefix already contains this row.
+        let dup = contains_row(&result, &t);
+        proof {
+            lemma_nb_occ_distinct(table_view(prefix), data@[i as int]@);
+        }
+        if !dup {
+            result.push(t);
+            proof {
+                assert(table_view(result@) =~= table_view(result0).push(data@[i as int]@));
+            }
+        }
+        proof {
+            assert(data@.subrange(0, i as int + 1) =~= prefix.push(data@[i as int]));
+            assert(table_view(prefix.push(data@[i as int])) =~= table_view(prefix).push(
+                data@[i as int]@,
+            ));
+            assert(table_view(prefix).push(data@[i as int]@).drop_last() =~= table_view(prefix));
+        }
+        i += 1;
+    }
+    proof {
+        assert(data@.subrange(0, data.len() as int) =~= data@);
+    }
+    result
+}
+
+/// A INTERSECT B: one copy of each row present in both inputs.
+pub fn execute_intersect(a: &Vec<Tuple>, b: &Vec<Tuple>) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == intersect_bag(table_view(a@), table_view(b@)),
+{
+    let present = filter_by_membership(a, b, true);
+    execute_distinct(&present)
+}
+
+/// A EXCEPT B: one copy of each row of `a` that `b` does not contain.
+pub fn execute_except(a: &Vec<Tuple>, b: &Vec<Tuple>) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == except_bag(table_view(a@), table_view(b@)),
+{
+    let absent = filter_by_membership(a, b, false);
+    execute_distinct(&absent)
+}
+
+/// Mint one read capability per table, for tables `0 .. n`.
+proof fn tracked_caps_up_to(n: nat) -> (tracked caps: Map<TableName, ReadCap>)
+    ensures
+        forall|t: TableName| t < n <==> #[trigger] caps.dom().contains(t),
+        forall|t: TableName| t < n ==> #[trigger] caps[t].table == t,
+    decreases n,
+{
+    if n == 0 {
+        Map::tracked_empty()
+    } else {
+        let prev = (n - 1) as usize;
+        let tracked mut caps = tracked_caps_up_to((n - 1) as nat);
+        caps.tracked_insert(prev, ReadCap { table: prev });
+        caps
+    }
+}
+
+/// Package a database together with a tracked map holding a read capability
+/// for each of its tables. Clients hand (borrows of) individual capabilities
+/// to `read_table`/`execute_query`.
+pub fn database_with_caps(tables: Vec<Vec<Tuple>>) -> (res: (
+    Database,
+    Tracked<Map<TableName, ReadCap>>,
+))
+    ensures
+        res.0.tables == tables,
+        forall|n: TableName| n < res.0.tables@.len() <==> #[trigger] res.1@.dom().contains(n),
+        forall|n: TableName| n < res.0.tables@.len() ==> #[trigger] res.1@[n].table == n,
+{
+    let db = Database { tables };
+    let num_tables = db.tables.len();
+    let tracked caps = tracked_caps_up_to(num_tables as nat);
+    (db, Tracked(caps))
+}
+
+/// Reading a table requires presenting a capability for exactly that table.
+pub fn read_table<'a>(db: &'a Database, name: TableName, cap: Tracked<&ReadCap>) -> (table: &'a Vec<
+    Tuple,
+>)
+    requires
+        cap@.table == name,
+        name < db.tables@.len(),
+    ensures
+        *table == db.tables@[name as int],
+{
+    &db.tables[name]
+}
+
+/// Execute a query, presenting read capabilities covering its footprint.
+///
+/// The requires clause is the security statement: a query can only be run if
+/// `tables_of(*q)` is a subset of the granted capability set, and every table
+/// access inside goes through `read_table` with one of those capabilities.
+pub fn execute_query(db: &Database, q: &Query, caps: Tracked<&Map<TableName, ReadCap>>) -> (result:
+    Vec<Tuple>)
+    requires
+        query_wf(*q, *db),
+        tables_of(*q).subset_of(caps@.dom()),
+        forall|n: TableName| #[trigger] caps@.dom().contains(n) ==> caps@[n].table == n,
+    ensures
+        table_view(result@) == eval_query(*db, *q),
+    decreases *q,
+{
+    match q {
+        Query::Table(name) => {
+            proof {
+                assert(tables_of(*q).contains(*name));
+            }
+            let table = read_table(db, *name, Tracked(caps.borrow().tracked_borrow(*name)));
+            clone_table(table)
+        }
+        Query::Filter(f, sub) => {
+            let sub_result = execute_query(db, sub, caps);
+            execute_filter(&sub_result, f)
+        }
+        Query::Intersect(a, b) => {
+            proof {
+                assert forall|n: TableName| tables_of(**a).contains(n) implies caps@.dom().contains(
+                    n,
+                ) by {
+                    assert(tables_of(*q).contains(n));
+                }
+                assert forall|n: TableName| tables_of(**b).contains(n) implies caps@.dom().contains(
+                    n,
+                ) by {
+                    assert(tables_of(*q).contains(n));
+                }
+            }
+            let left = execute_query(db, a, caps);
+            let right = execute_query(db, b, caps);
+            execute_intersect(&left, &right)
+        }
+        Query::Except(a, b) => {
+            proof {
+                assert forall|n: TableName| tables_of(**a).contains(n) implies caps@.dom().contains(
+                    n,
+                ) by {
+                    assert(tables_of(*q).contains(n));
+                }
+                assert forall|n: TableName| tables_of(**b).contains(n) implies caps@.dom().contains(
+                    n,
+                ) by {
+                    assert(tables_of(*q).contains(n));
+                }
+            }
+            let left = execute_query(db, a, caps);
+            let right = execute_query(db, b, caps);
+            execute_except(&left, &right)
+        }
+    }
+}
+
+} // verus!
@@ -0,0 +1,5 @@
+pub mod aggregates;
+pub mod executable_impl;
+pub mod physical_algebra;
+pub mod relational_model;
+pub mod simplifier;
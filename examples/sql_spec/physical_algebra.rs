@@ -0,0 +1,555 @@
+//! Physical operators for the SQL example.
+//!
+//! The grouping machinery keeps a strict spec/exec split: [`Group`] is a
+//! ghost type used only in specifications, while [`ExecGroup`] and
+//! [`GroupByState`] hold runtime data (`Vec`s, never `Seq`s) and relate to
+//! the spec types through `View`.
+
+#[allow(unused_imports)]
+use vstd::prelude::*;
+
+use crate::sql_spec::relational_model::*;
+
+verus! {
+
+/// Spec-level group: a grouping key and the bag of rows carrying that key.
+pub ghost struct Group {
+    pub key: Seq<i64>,
+    pub tuples: Bag,
+}
+
+/// Runtime group; views to [`Group`].
+pub struct ExecGroup {
+    pub key: Vec<i64>,
+    pub tuples: Vec<Tuple>,
+}
+
+impl View for ExecGroup {
+    type V = Group;
+
+    open spec fn view(&self) -> Group {
+        Group { key: self.key@, tuples: table_view(self.tuples@) }
+    }
+}
+
+/// Compare two grouping keys element-wise.
+pub fn keys_equal(a: &Vec<i64>, b: &Vec<i64>) -> (eq: bool)
+    ensures
+        eq <==> a@ =~= b@,
+{
+    if a.len() != b.len() {
+        return false;
+    }
+    let mut i: usize = 0;
+    while i < a.len()
+        invariant
+            i <= a@.len(),
+            a@.len() == b@.len(),
+            forall|j: int| 0 <= j < i ==> a@[j] == b@[j],
+        decreases a@.len() - i,
+    {
+        if a[i] != b[i] {
+            return false;
+        }
+        i += 1;
+    }
+    proof {
+        assert(a@ =~= b@);
+    }
+    true
+}
+
+/// In-progress state of a group-by: the grouping columns and the groups
+/// accumulated so far.
+pub struct GroupByState {
+    pub group_cols: Vec<usize>,
+    pub groups: Vec<ExecGroup>,
+}
+
+impl View for GroupByState {
+    type V = Seq<Group>;
+
+    open spec fn view(&self) -> Seq<Group> {
+        self.groups@.map_values(|g: ExecGroup| g@)
+    }
+}
+
+impl GroupByState {
+    pub fn new(group_cols: Vec<usize>) -> (state: GroupByState)
+        ensures
+            state.groups@.len() == 0,
+            state.group_cols == group_cols,
+    {
+        GroupByState { group_cols, groups: Vec::new() }
+    }
+
+    /// Return the index of the group with `key`, appending a fresh empty
+    /// group if none exists yet.
+    pub fn find_or_create_group(&mut self, key: Vec<i64>) -> (idx: usize)
+        ensures
+            idx < self.groups@.len(),
+            self.groups@[idx as int]@.key =~= key@,
+            old(self).groups@.len() <= self.groups@.len() <= old(self).groups@.len() + 1,
+            forall|i: int|
+                0 <= i < old(self).groups@.len() ==> self.groups@[i] == old(self).groups@[i],
+            self.group_cols == old(self).group_cols,
+            self.groups@.len() == old(self).groups@.len() + 1 ==> idx == old(self).groups@.len()
+                && self.groups@[idx as int]@.tuples =~= Seq::<Seq<i64>>::empty(),
+    {
+        let mut i: usize = 0;
+        while i < self.groups.len()
+            invariant
+                i <= self.groups@.len(),
+                self == old(self),
+            decreases self.groups@.len() - i,
+        {
+            if keys_equal(&self.groups[i].key, &key) {
+                return i;
+            }
+            i += 1;
+        }
+        let idx = self.groups.len();
+        let group = ExecGroup { key, tuples: Vec::new() };
+        proof {
+            assert(group@.tuples =~= Seq::<Seq<i64>>::empty());
+        }
+        self.groups.push(group);
+        idx
+    }
+
+    /// Append `t` to the group at `idx`.
+    pub fn add_to_group(&mut self, idx: usize, t: Tuple)
+        requires
+            idx < old(self).groups@.len(),
+        ensures
+            self.groups@.len() == old(self).groups@.len(),
+            self.group_cols == old(self).group_cols,
+            self.groups@[idx as int]@.key == old(self).groups@[idx as int]@.key,
+            self.groups@[idx as int]@.tuples =~= old(self).groups@[idx as int]@.tuples.push(t@),
+            forall|i: int|
+                0 <= i < self.groups@.len() && i != idx ==> self.groups@[i] == old(
+                    self,
+                ).groups@[i],
+    {
+        let ghost t_view = t@;
+        let mut group = self.groups.remove(idx);
+        group.tuples.push(t);
+        proof {
+            assert(group@.tuples =~= old(self).groups@[idx as int]@.tuples.push(t_view));
+        }
+        self.groups.insert(idx, group);
+        proof {
+            assert(self.groups@ =~= old(self).groups@.update(idx as int, group));
+        }
+    }
+}
+
+/// The grouping key of a row at the spec level: the values of `group_cols`,
+/// in order.
+pub open spec fn extract_key_spec(row: Seq<i64>, group_cols: Seq<usize>) -> Seq<i64> {
+    group_cols.map_values(|c: usize| row[c as int])
+}
+
+/// The spec-level denotation of a group-by: the set of keys occurring in the
+/// input. (The per-group contents are determined by the key, so the key set
+/// is the essential content of the grouping.)
+pub open spec fn eval_group_by(rows: Bag, group_cols: Seq<usize>) -> Set<Seq<i64>> {
+    Set::new(
+        |k: Seq<i64>|
+            exists|j: int| 0 <= j < rows.len() && extract_key_spec(#[trigger] rows[j], group_cols)
+                == k,
+    )
+}
+
+/// The set of keys carried by a sequence of groups.
+pub open spec fn group_key_set(groups: Seq<ExecGroup>) -> Set<Seq<i64>> {
+    Set::new(
+        |k: Seq<i64>|
+            exists|g: int| 0 <= g < groups.len() && (#[trigger] groups[g]).key@ == k,
+    )
+}
+
+/// Extract the grouping key of `t`: the values of `group_cols`, in order.
+pub fn extract_grouping_key(t: &Tuple, group_cols: &Vec<usize>) -> (key: Vec<i64>)
+    requires
+        forall|i: int| 0 <= i < group_cols@.len() ==> #[trigger] group_cols@[i] < t@.len(),
+    ensures
+        key@.len() == group_cols@.len(),
+        forall|i: int| 0 <= i < key@.len() ==> key@[i] == t@[group_cols@[i] as int],
+        key@ == extract_key_spec(t@, group_cols@),
+{
+    let mut key: Vec<i64> = Vec::new();
+    let mut i: usize = 0;
+    while i < group_cols.len()
+        invariant
+            i <= group_cols@.len(),
+            forall|j: int| 0 <= j < group_cols@.len() ==> #[trigger] group_cols@[j] < t@.len(),
+            key@.len() == i,
+            forall|j: int| 0 <= j < i ==> key@[j] == t@[group_cols@[j] as int],
+        decreases group_cols@.len() - i,
+    {
+        key.push(t.values[group_cols[i]]);
+        i += 1;
+    }
+    proof {
+        assert(key@ =~= extract_key_spec(t@, group_cols@));
+    }
+    key
+}
+
+/// Group `data` by the values of `group_cols`.
+///
+/// Beyond the bookkeeping properties (group count, key widths), the contract
+/// states exact key membership: the groups carry precisely the keys that
+/// occur in the input, so the output groups correspond one-to-one to the
+/// input's key set `eval_group_by(table_view(data@), group_cols@)`.
+pub fn execute_group_by(data: &Vec<Tuple>, group_cols: Vec<usize>) -> (result: GroupByState)
+    requires
+        group_cols@.len() > 0,
+        forall|i: int, c: int|
+            0 <= i < data@.len() && 0 <= c < group_cols@.len() ==> #[trigger] group_cols@[c] < (
+            #[trigger] data@[i])@.len(),
+    ensures
+        result.group_cols@ == group_cols@,
+        result.groups@.len() <= data@.len(),
+        forall|g: int|
+            0 <= g < result.groups@.len() ==> (#[trigger] result.groups@[g]).key@.len()
+                == group_cols@.len(),
+        // every key is nonempty; this is what makes `group_cols.len() > 0`
+        // load-bearing
+        forall|g: int|
+            0 <= g < result.groups@.len() ==> (#[trigger] result.groups@[g]).key@.len() > 0,
+        // soundness: every group key occurs in the input
+        forall|g: int|
+            0 <= g < result.groups@.len() ==> exists|j: int|
+                0 <= j < data@.len() && extract_key_spec(#[trigger] data@[j]@, group_cols@) == (
+                #[trigger] result.groups@[g]).key@,
+        // completeness: every input key is carried by some group
+        forall|j: int|
+            0 <= j < data@.len() ==> exists|g: int|
+                0 <= g < result.groups@.len() && (#[trigger] result.groups@[g]).key@
+                    == extract_key_spec(#[trigger] data@[j]@, group_cols@),
+        // the two directions together: the output keys are exactly the
+        // input's key set
+        group_key_set(result.groups@) =~= eval_group_by(table_view(data@), group_cols@),
+{
+    let mut state = GroupByState::new(group_cols);
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data@.len(),
+            state.group_cols@ == group_cols@,
+            forall|j: int, c: int|
+                0 <= j < data@.len() && 0 <= c < group_cols@.len() ==> #[trigger] group_cols@[c]
+                    < (#[trigger] data@[j])@.len(),
+            state.groups@.len() <= i,
+            forall|g: int|
+                0 <= g < state.groups@.len() ==> (#[trigger] state.groups@[g]).key@.len()
+                    == group_cols@.len(),
+            forall|g: int|
+                0 <= g < state.groups@.len() ==> exists|j: int|
+                    0 <= j < i && extract_key_spec(#[trigger] data@[j]@, group_cols@) == (
+                    #[trigger] state.groups@[g]).key@,
+            forall|j: int|
+                0 <= j < i ==> exists|g: int|
+                    0 <= g < state.groups@.len() && (#[trigger] state.groups@[g]).key@
+                        == extract_key_spec(#[trigger] data@[j]@, group_cols@),
+        decreases data@.len() - i,
+    {
+        let ghost pre = state;
+        let t = &data[i];
+        let key = extract_grouping_key(t, &state.group_cols);
+        let idx = state.find_or_create_group(key);
+        let ghost mid = state;
+        state.add_to_group(idx, t.clone());
+        proof {
+            // `add_to_group` leaves every key in place, and `find_or_create`
+            // pinned `key` at `idx`; re-establish both membership invariants
+            // for the extended prefix.
+            assert(state.groups@.len() == mid.groups@.len());
+            assert(state.groups@[idx as int].key@ == mid.groups@[idx as int].key@);
+            assert(mid.groups@[idx as int]@.key =~= key@);
+            assert(state.groups@[idx as int].key@ == extract_key_spec(
+                data@[i as int]@,
+                group_cols@,
+            ));
+            assert forall|g: int| 0 <= g < state.groups@.len() implies exists|j: int|
+                0 <= j < i + 1 && extract_key_spec(#[trigger] data@[j]@, group_cols@) == (
+                #[trigger] state.groups@[g]).key@ by {
+                if g == idx {
+                    assert(extract_key_spec(data@[i as int]@, group_cols@)
+                        == state.groups@[g].key@);
+                } else if g < pre.groups@.len() {
+                    // an old group, untouched by both calls
+                    assert(state.groups@[g] == pre.groups@[g]);
+                    let j = choose|j: int|
+                        0 <= j < i && extract_key_spec(#[trigger] data@[j]@, group_cols@)
+                            == pre.groups@[g].key@;
+                    assert(extract_key_spec(data@[j]@, group_cols@) == state.groups@[g].key@);
+                }
+            }
+            assert forall|j: int| 0 <= j < i + 1 implies exists|g: int|
+                0 <= g < state.groups@.len() && (#[trigger] state.groups@[g]).key@
+                    == extract_key_spec(#[trigger] data@[j]@, group_cols@) by {
+                if j == i {
+                    assert(state.groups@[idx as int].key@ == extract_key_spec(
+                        data@[j]@,
+                        group_cols@,
+                    ));
+                } else {
+                    let g = choose|g: int|
+                        0 <= g < pre.groups@.len() && (#[trigger] pre.groups@[g]).key@
+                            == extract_key_spec(data@[j]@, group_cols@);
+                    assert(state.groups@[g] == pre.groups@[g]);
+                    assert(state.groups@[g].key@ == extract_key_spec(data@[j]@, group_cols@));
+                }
+            }
+        }
+        i += 1;
+    }
+    proof {
+        // Lift the two quantified invariants (now over all of `data`) to the
+        // set-level statement.
+        assert forall|k: Seq<i64>| group_key_set(state.groups@).contains(k) implies eval_group_by(
+            table_view(data@),
+            group_cols@,
+        ).contains(k) by {
+            let g = choose|g: int|
+                0 <= g < state.groups@.len() && (#[trigger] state.groups@[g]).key@ == k;
+            let j = choose|j: int|
+                0 <= j < data@.len() && extract_key_spec(#[trigger] data@[j]@, group_cols@)
+                    == state.groups@[g].key@;
+            assert(table_view(data@)[j] == data@[j]@);
+            assert(extract_key_spec(table_view(data@)[j], group_cols@) == k);
+        }
+        assert forall|k: Seq<i64>| eval_group_by(table_view(data@), group_cols@).contains(k)
+            implies group_key_set(state.groups@).contains(k) by {
+            let j = choose|j: int|
+                0 <= j < table_view(data@).len() && extract_key_spec(
+                    #[trigger] table_view(data@)[j],
+                    group_cols@,
+                ) == k;
+            assert(table_view(data@)[j] == data@[j]@);
+            let g = choose|g: int|
+                0 <= g < state.groups@.len() && (#[trigger] state.groups@[g]).key@
+                    == extract_key_spec(data@[j]@, group_cols@);
+            assert(state.groups@[g].key@ == k);
+        }
+    }
+    state
+}
+
+/// Whether `col` is in bounds for `t` and its value lies in `[lo, hi]`.
+///
+/// This is the interval domain the planner works in: a closed range on a
+/// single column, with in-boundedness folded in so it lines up with
+/// [`eval_atomic`]'s treatment of out-of-range columns.
+pub open spec fn in_range(t: Seq<i64>, col: usize, lo: i64, hi: i64) -> bool {
+    col < t.len() && lo <= t[col as int] <= hi
+}
+
+/// The rows of `b` whose `col` value lies in `[lo, hi]`: the semantics of an
+/// index scan over `col`.
+pub open spec fn range_bag(b: Bag, col: usize, lo: i64, hi: i64) -> Bag {
+    b.filter(|t: Seq<i64>| in_range(t, col, lo, hi))
+}
+
+proof fn lemma_range_bag_empty(col: usize, lo: i64, hi: i64)
+    ensures
+        range_bag(Seq::empty(), col, lo, hi) == Seq::<Seq<i64>>::empty(),
+{
+    reveal(Seq::<Seq<i64>>::filter);
+}
+
+proof fn lemma_range_bag_push(b: Bag, col: usize, lo: i64, hi: i64, row: Seq<i64>)
+    ensures
+        range_bag(b.push(row), col, lo, hi) == if in_range(row, col, lo, hi) {
+            range_bag(b, col, lo, hi).push(row)
+        } else {
+            range_bag(b, col, lo, hi)
+        },
+{
+    reveal(Seq::<Seq<i64>>::filter);
+    assert(b.push(row).drop_last() =~= b);
+    assert(b.push(row).last() == row);
+}
+
+/// An index scan over `col`, restricted to `[lo, hi]`.
+///
+/// A production engine would walk a sorted index; the example models just
+/// the operator's *contract* — what rows come back — so a sequential scan
+/// suffices and the ensures is what the planner composes against.
+pub fn index_scan(data: &Vec<Tuple>, col: usize, lo: i64, hi: i64) -> (result: Vec<Tuple>)
+    ensures
+        table_view(result@) == range_bag(table_view(data@), col, lo, hi),
+{
+    let mut result: Vec<Tuple> = Vec::new();
+    proof {
+        lemma_range_bag_empty(col, lo, hi);
+        assert(table_view(result@) =~= Seq::<Seq<i64>>::empty());
+        assert(table_view(data@.subrange(0, 0)) =~= Seq::<Seq<i64>>::empty());
+    }
+    let mut i: usize = 0;
+    while i < data.len()
+        invariant
+            i <= data.len(),
+            table_view(result@) == range_bag(
+                table_view(data@.subrange(0, i as int)),
+                col,
+                lo,
+                hi,
+            ),
+        decreases data.len() - i,
+    {
+        let ghost prefix = data@.subrange(0, i as int);
+        let ghost result0 = result@;
+        let t = data[i].clone();
+        if col < t.values.len() && lo <= t.values[col] && t.values[col] <= hi {
+            result.push(t);
+            proof {
+                assert(table_view(result@) =~= table_view(result0).push(data@[i as int]@));
+            }
+        }
+        proof {
+            lemma_range_bag_push(table_view(prefix), col, lo, hi, data@[i as int]@);
+            assert(data@.subrange(0, i as int + 1) =~= prefix.push(data@[i as int]));
+            assert(table_view(prefix.push(data@[i as int])) =~= table_view(prefix).push(
+                data@[i as int]@,
+            ));
+        }
+        i += 1;
+    }
+    proof {
+        assert(data@.subrange(0, data.len() as int) =~= data@);
+    }
+    result
+}
+
+/// Conservative range extraction: when every atom of `f` constrains `col`
+/// (and none is the vacuous `True`), the conjunction is exactly a closed
+/// interval on `col`, and the ensures says so. Any other shape returns
+/// `None`, which carries no obligation — the caller falls back to the plain
+/// filter.
+pub fn extract_range(f: &Formula, col: usize) -> (res: Option<(i64, i64)>)
+    ensures
+        res matches Some(r) ==> forall|t: Seq<i64>|
+            eval_formula(t, f.atoms@) <==> in_range(t, col, r.0, r.1),
+{
+    if f.atoms.len() == 0 {
+        // The empty conjunction accepts rows where `col` is out of bounds;
+        // no interval does.
+        return None;
+    }
+    let mut lo: i64 = i64::MIN;
+    let mut hi: i64 = i64::MAX;
+    proof {
+        assert(f.atoms@.subrange(0, 0) =~= Seq::<AtomicFormula>::empty());
+    }
+    let mut i: usize = 0;
+    while i < f.atoms.len()
+        invariant
+            i <= f.atoms.len(),
+            i > 0 ==> forall|t: Seq<i64>|
+                eval_formula(t, f.atoms@.subrange(0, i as int)) <==> in_range(t, col, lo, hi),
+        decreases f.atoms.len() - i,
+    {
+        let ghost prefix = f.atoms@.subrange(0, i as int);
+        let ghost lo0 = lo;
+        let ghost hi0 = hi;
+        let a = f.atoms[i];
+        let (new_lo, new_hi) = match a {
+            AtomicFormula::Eq(c, v) => {
+                if c != col {
+                    return None;
+                }
+                (v, v)
+            }
+            AtomicFormula::Lt(c, v) => {
+                // `t[col] < i64::MIN` is unsatisfiable but not an interval
+                // with representable bounds; leave it to the filter path.
+                if c != col || v == i64::MIN {
+                    return None;
+                }
+                (i64::MIN, v - 1)
+            }
+            AtomicFormula::Gt(c, v) => {
+                if c != col || v == i64::MAX {
+                    return None;
+                }
+                (v + 1, i64::MAX)
+            }
+            AtomicFormula::True => return None,
+        };
+        if new_lo > lo {
+            lo = new_lo;
+        }
+        if new_hi < hi {
+            hi = new_hi;
+        }
+        proof {
+            assert(f.atoms@.subrange(0, i as int + 1) =~= prefix.push(a));
+            assert forall|t: Seq<i64>|
+                eval_formula(t, f.atoms@.subrange(0, i as int + 1)) <==> in_range(
+                    t,
+                    col,
+                    lo,
+                    hi,
+                ) by {
+                crate::sql_spec::simplifier::lemma_eval_formula_push(t, prefix, a);
+                if i > 0 {
+                    assert(eval_formula(t, prefix) <==> in_range(t, col, lo0, hi0));
+                }
+            }
+        }
+        i += 1;
+    }
+    proof {
+        assert(f.atoms@.subrange(0, f.atoms.len() as int) =~= f.atoms@);
+    }
+    Some((lo, hi))
+}
+
+/// The planner's bridge: once `extract_range` certifies that `f` is exactly
+/// the interval `[lo, hi]` on `col`, scanning the index produces the same
+/// bag as filtering with `f`.
+pub proof fn lemma_index_scan_matches_filter(
+    b: Bag,
+    atoms: Seq<AtomicFormula>,
+    col: usize,
+    lo: i64,
+    hi: i64,
+)
+    requires
+        forall|t: Seq<i64>| eval_formula(t, atoms) <==> in_range(t, col, lo, hi),
+    ensures
+        range_bag(b, col, lo, hi) == filter_bag(b, atoms),
+    decreases b.len(),
+{
+    reveal(Seq::<Seq<i64>>::filter);
+    if b.len() == 0 {
+        assert(range_bag(b, col, lo, hi) =~= filter_bag(b, atoms));
+    } else {
+        lemma_index_scan_matches_filter(b.drop_last(), atoms, col, lo, hi);
+        assert(range_bag(b, col, lo, hi) =~= filter_bag(b, atoms));
+    }
+}
+
+/// Corollary in counting form: the two plans agree on the multiplicity of
+/// every row.
+pub proof fn lemma_index_scan_counts(
+    b: Bag,
+    atoms: Seq<AtomicFormula>,
+    col: usize,
+    lo: i64,
+    hi: i64,
+    t: Seq<i64>,
+)
+    requires
+        forall|u: Seq<i64>| eval_formula(u, atoms) <==> in_range(u, col, lo, hi),
+    ensures
+        nb_occ(t, range_bag(b, col, lo, hi)) == nb_occ(t, filter_bag(b, atoms)),
+{
+    lemma_index_scan_matches_filter(b, atoms, col, lo, hi);
+}
+
+} // verus!
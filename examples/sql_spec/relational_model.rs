@@ -0,0 +1,318 @@
+//! The specification layer of the SQL example: tuples, tables, queries, and
+//! their mathematical semantics.
+
+#[allow(unused_imports)]
+use vstd::prelude::*;
+
+verus! {
+
+/// Tables are identified by their index into `Database::tables`.
+pub type TableName = usize;
+
+/// A row: one `i64` value per column.
+pub struct Tuple {
+    pub values: Vec<i64>,
+}
+
+impl View for Tuple {
+    type V = Seq<i64>;
+
+    open spec fn view(&self) -> Seq<i64> {
+        self.values@
+    }
+}
+
+/// A table's contents as a bag of rows. We represent bags as sequences; all
+/// semantic statements are phrased so that row order carries no meaning.
+pub type Bag = Seq<Seq<i64>>;
+
+/// The bag of rows stored in a sequence of tuples.
+pub open spec fn table_view(data: Seq<Tuple>) -> Bag {
+    data.map_values(|t: Tuple| t@)
+}
+
+/// An atomic predicate over a single column of a row.
+#[derive(Structural, PartialEq, Eq, Clone, Copy)]
+pub enum AtomicFormula {
+    True,
+    /// `column = value`
+    Eq(usize, i64),
+    /// `column < value`
+    Lt(usize, i64),
+    /// `column > value`
+    Gt(usize, i64),
+}
+
+/// A WHERE clause: the conjunction of its atoms.
+pub struct Formula {
+    pub atoms: Vec<AtomicFormula>,
+}
+
+pub open spec fn eval_atomic(t: Seq<i64>, a: AtomicFormula) -> bool {
+    match a {
+        AtomicFormula::True => true,
+        AtomicFormula::Eq(col, v) => col < t.len() && t[col as int] == v,
+        AtomicFormula::Lt(col, v) => col < t.len() && t[col as int] < v,
+        AtomicFormula::Gt(col, v) => col < t.len() && t[col as int] > v,
+    }
+}
+
+pub open spec fn eval_formula(t: Seq<i64>, atoms: Seq<AtomicFormula>) -> bool {
+    forall|i: int| 0 <= i < atoms.len() ==> eval_atomic(t, #[trigger] atoms[i])
+}
+
+/// A (very small) query language.
+pub enum Query {
+    Table(TableName),
+    Filter(Formula, Box<Query>),
+    /// INTERSECT (set semantics): rows present in both operands, once each.
+    Intersect(Box<Query>, Box<Query>),
+    /// EXCEPT (set semantics): rows of the left operand absent from the
+    /// right, once each.
+    Except(Box<Query>, Box<Query>),
+}
+
+pub struct Database {
+    pub tables: Vec<Vec<Tuple>>,
+}
+
+/// Whether every table a query mentions exists in the database.
+pub open spec fn query_wf(q: Query, db: Database) -> bool
+    decreases q,
+{
+    match q {
+        Query::Table(name) => name < db.tables.len(),
+        Query::Filter(_, sub) => query_wf(*sub, db),
+        Query::Intersect(a, b) => query_wf(*a, db) && query_wf(*b, db),
+        Query::Except(a, b) => query_wf(*a, db) && query_wf(*b, db),
+    }
+}
+
+/// The set of tables a query reads. This is the footprint used by the
+/// capability demo in `executable_impl`: executing a query only requires
+/// read capabilities for `tables_of(query)`.
+pub open spec fn tables_of(q: Query) -> Set<TableName>
+    decreases q,
+{
+    match q {
+        Query::Table(name) => set![name],
+        Query::Filter(_, sub) => tables_of(*sub),
+        Query::Intersect(a, b) => tables_of(*a).union(tables_of(*b)),
+        Query::Except(a, b) => tables_of(*a).union(tables_of(*b)),
+    }
+}
+
+pub open spec fn filter_bag(b: Bag, atoms: Seq<AtomicFormula>) -> Bag {
+    b.filter(|t: Seq<i64>| eval_formula(t, atoms))
+}
+
+/// The rows of `data` at positions `idxs` (in order) that satisfy `atoms`.
+/// Out-of-range positions contribute nothing. This is the shape of
+/// `execute_filter_audited`'s result: the rows at the positions its ghost
+/// audit log says were examined and passed the formula.
+pub open spec fn filter_indices(data: Seq<Tuple>, atoms: Seq<AtomicFormula>, idxs: Seq<int>) -> Bag
+    decreases idxs.len(),
+{
+    if idxs.len() == 0 {
+        Seq::empty()
+    } else {
+        let prefix = filter_indices(data, atoms, idxs.drop_last());
+        let i = idxs.last();
+        if 0 <= i < data.len() && eval_formula(data[i]@, atoms) {
+            prefix.push(data[i]@)
+        } else {
+            prefix
+        }
+    }
+}
+
+/// The number of occurrences of row `t` in bag `b`: the multiplicity
+/// function that makes bag-level statements order-independent. All the
+/// set-semantics operators below are characterized through `nb_occ`.
+pub open spec fn nb_occ(t: Seq<i64>, b: Bag) -> nat
+    decreases b.len(),
+{
+    if b.len() == 0 {
+        0
+    } else {
+        nb_occ(t, b.drop_last()) + if b.last() == t {
+            1nat
+        } else {
+            0nat
+        }
+    }
+}
+
+/// UNION ALL: bag union keeps every copy from both sides.
+pub open spec fn union_all_bag(a: Bag, b: Bag) -> Bag {
+    a + b
+}
+
+/// DISTINCT: keep the first copy of each row, dropping later duplicates.
+pub open spec fn distinct_bag(b: Bag) -> Bag
+    decreases b.len(),
+{
+    if b.len() == 0 {
+        b
+    } else if nb_occ(b.last(), b.drop_last()) > 0 {
+        distinct_bag(b.drop_last())
+    } else {
+        distinct_bag(b.drop_last()).push(b.last())
+    }
+}
+
+/// The rows of `a` that are (for `keep_present`) or are not (otherwise)
+/// present in `b`, with multiplicities taken from `a`. This is the bag-level
+/// construction under both set-semantics operators.
+pub open spec fn membership_filter(a: Bag, b: Bag, keep_present: bool) -> Bag {
+    a.filter(|t: Seq<i64>| (nb_occ(t, b) > 0) == keep_present)
+}
+
+/// INTERSECT: one copy of each row present in both inputs.
+pub open spec fn intersect_bag(a: Bag, b: Bag) -> Bag {
+    distinct_bag(membership_filter(a, b, true))
+}
+
+/// EXCEPT: one copy of each row of `a` that `b` does not contain.
+pub open spec fn except_bag(a: Bag, b: Bag) -> Bag {
+    distinct_bag(membership_filter(a, b, false))
+}
+
+/// The denotation of a query: the bag of rows it produces.
+pub open spec fn eval_query(db: Database, q: Query) -> Bag
+    decreases q,
+{
+    match q {
+        Query::Table(name) => table_view(db.tables@[name as int]@),
+        Query::Filter(f, sub) => filter_bag(eval_query(db, *sub), f.atoms@),
+        Query::Intersect(a, b) => intersect_bag(eval_query(db, *a), eval_query(db, *b)),
+        Query::Except(a, b) => except_bag(eval_query(db, *a), eval_query(db, *b)),
+    }
+}
+
+// The nb_occ lemma library: how each bag operator transforms multiplicities.
+
+pub proof fn lemma_nb_occ_push(b: Bag, row: Seq<i64>, t: Seq<i64>)
+    ensures
+        nb_occ(t, b.push(row)) == nb_occ(t, b) + if row == t {
+            1nat
+        } else {
+            0nat
+        },
+{
+    assert(b.push(row).drop_last() =~= b);
+}
+
+pub proof fn lemma_nb_occ_concat(a: Bag, b: Bag, t: Seq<i64>)
+    ensures
+        nb_occ(t, union_all_bag(a, b)) == nb_occ(t, a) + nb_occ(t, b),
+    decreases b.len(),
+{
+    if b.len() == 0 {
+        assert(a + b =~= a);
+    } else {
+        lemma_nb_occ_concat(a, b.drop_last(), t);
+        assert((a + b).drop_last() =~= a + b.drop_last());
+        assert((a + b).last() == b.last());
+    }
+}
+
+/// A bag contains a row (has positive multiplicity) iff some index holds it.
+pub proof fn lemma_nb_occ_contains(b: Bag, t: Seq<i64>)
+    ensures
+        nb_occ(t, b) > 0 <==> exists|i: int| 0 <= i < b.len() && b[i] == t,
+    decreases b.len(),
+{
+    if b.len() > 0 {
+        lemma_nb_occ_contains(b.drop_last(), t);
+        if b.last() == t {
+            assert(b[b.len() - 1] == t);
+        } else {
+            assert forall|i: int| 0 <= i < b.len() && b[i] == t implies 0 <= i < b.drop_last().len()
+                && b.drop_last()[i] == t by {
+                assert(i < b.len() - 1);
+            }
+            assert forall|i: int|
+                0 <= i < b.drop_last().len() && b.drop_last()[i] == t implies b[i] == t by {}
+        }
+    }
+}
+
+/// DISTINCT keeps exactly one copy of every row the input contains.
+pub proof fn lemma_nb_occ_distinct(b: Bag, t: Seq<i64>)
+    ensures
+        nb_occ(t, distinct_bag(b)) == if nb_occ(t, b) > 0 {
+            1nat
+        } else {
+            0nat
+        },
+    decreases b.len(),
+{
+    if b.len() > 0 {
+        lemma_nb_occ_distinct(b.drop_last(), t);
+        if nb_occ(b.last(), b.drop_last()) == 0 {
+            lemma_nb_occ_push(distinct_bag(b.drop_last()), b.last(), t);
+        }
+    }
+}
+
+/// The membership filter keeps a row's full multiplicity or none of it,
+/// depending only on whether `b` contains the row.
+pub proof fn lemma_nb_occ_membership_filter(a: Bag, b: Bag, keep_present: bool, t: Seq<i64>)
+    ensures
+        nb_occ(t, membership_filter(a, b, keep_present)) == if (nb_occ(t, b) > 0) == keep_present {
+            nb_occ(t, a)
+        } else {
+            0nat
+        },
+    decreases a.len(),
+{
+    reveal(Seq::<Seq<i64>>::filter);
+    if a.len() > 0 {
+        lemma_nb_occ_membership_filter(a.drop_last(), b, keep_present, t);
+        if (nb_occ(a.last(), b) > 0) == keep_present {
+            lemma_nb_occ_push(membership_filter(a.drop_last(), b, keep_present), a.last(), t);
+        }
+    }
+}
+
+/// INTERSECT characterized by multiplicity: exactly one copy of `t` when
+/// both inputs contain it, none otherwise.
+pub proof fn lemma_intersect_characterization(a: Bag, b: Bag, t: Seq<i64>)
+    ensures
+        nb_occ(t, intersect_bag(a, b)) == if nb_occ(t, a) > 0 && nb_occ(t, b) > 0 {
+            1nat
+        } else {
+            0nat
+        },
+{
+    lemma_nb_occ_membership_filter(a, b, true, t);
+    lemma_nb_occ_distinct(membership_filter(a, b, true), t);
+}
+
+/// EXCEPT characterized by multiplicity: exactly one copy of `t` when `a`
+/// contains it and `b` does not, none otherwise.
+pub proof fn lemma_except_characterization(a: Bag, b: Bag, t: Seq<i64>)
+    ensures
+        nb_occ(t, except_bag(a, b)) == if nb_occ(t, a) > 0 && nb_occ(t, b) == 0 {
+            1nat
+        } else {
+            0nat
+        },
+{
+    lemma_nb_occ_membership_filter(a, b, false, t);
+    lemma_nb_occ_distinct(membership_filter(a, b, false), t);
+}
+
+/// A tracked capability to read one table. Holding (a reference to) a
+/// `ReadCap` for table `name` is what authorizes `read_table` below.
+///
+/// Note this is a teaching device: the constructor is public, so nothing
+/// prevents a client from minting its own capability. A real system would
+/// keep the field private and only hand out capabilities from a trusted
+/// constructor like `database_with_caps`.
+pub tracked struct ReadCap {
+    pub ghost table: TableName,
+}
+
+} // verus!
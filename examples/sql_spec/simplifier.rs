@@ -0,0 +1,244 @@
+//! A verified constant-folding simplifier for WHERE clauses.
+//!
+//! `simplify_formula` drops `True` atoms and duplicates from a conjunction,
+//! and detects contradictory atom pairs on the same column (`Eq(c, 5)` with
+//! `Lt(c, 3)`, or `Gt(c, x)` with `Lt(c, y)` when no integer fits strictly
+//! between `x` and `y`), collapsing the whole formula to a canonical
+//! always-false clause. The soundness theorem is carried in the ensures:
+//! the simplified formula evaluates exactly like the original on every row.
+
+#[allow(unused_imports)]
+use vstd::prelude::*;
+
+use crate::sql_spec::relational_model::*;
+
+verus! {
+
+/// One direction of the contradiction table; see [`atoms_contradict`].
+pub open spec fn pair_contradicts(a: AtomicFormula, b: AtomicFormula) -> bool {
+    match (a, b) {
+        (AtomicFormula::Eq(c1, v1), AtomicFormula::Eq(c2, v2)) => c1 == c2 && v1 != v2,
+        (AtomicFormula::Eq(c1, v), AtomicFormula::Lt(c2, w)) => c1 == c2 && v >= w,
+        (AtomicFormula::Eq(c1, v), AtomicFormula::Gt(c2, w)) => c1 == c2 && v <= w,
+        // `t < y && t > x` needs an integer strictly between `x` and `y`,
+        // so the pair is unsatisfiable exactly when `y <= x + 1` (the
+        // addition is mathematical, so `x == i64::MAX` needs no special
+        // case).
+        (AtomicFormula::Lt(c1, y), AtomicFormula::Gt(c2, x)) => c1 == c2 && y <= x + 1,
+        _ => false,
+    }
+}
+
+/// No row can satisfy both `a` and `b`; proven by [`lemma_contradictory_atoms`].
+pub open spec fn atoms_contradict(a: AtomicFormula, b: AtomicFormula) -> bool {
+    pair_contradicts(a, b) || pair_contradicts(b, a)
+}
+
+/// The canonical unsatisfiable conjunction: no `i64` is below `i64::MIN`.
+pub open spec fn always_false_atoms() -> Seq<AtomicFormula> {
+    seq![AtomicFormula::Lt(0, i64::MIN)]
+}
+
+proof fn lemma_always_false(t: Seq<i64>)
+    ensures
+        !eval_formula(t, always_false_atoms()),
+{
+    assert(always_false_atoms()[0] == AtomicFormula::Lt(0, i64::MIN));
+    if eval_formula(t, always_false_atoms()) {
+        assert(eval_atomic(t, always_false_atoms()[0]));
+        assert(t[0] >= i64::MIN);
+    }
+}
+
+proof fn lemma_contradictory_atoms(t: Seq<i64>, a: AtomicFormula, b: AtomicFormula)
+    requires
+        atoms_contradict(a, b),
+    ensures
+        !(eval_atomic(t, a) && eval_atomic(t, b)),
+{
+}
+
+/// Conjunction semantics of appending one atom; shared with the range
+/// extraction in `physical_algebra`.
+pub proof fn lemma_eval_formula_push(t: Seq<i64>, atoms: Seq<AtomicFormula>, a: AtomicFormula)
+    ensures
+        eval_formula(t, atoms.push(a)) <==> (eval_formula(t, atoms) && eval_atomic(t, a)),
+{
+    assert(atoms.push(a)[atoms.len() as int] == a);
+    if eval_formula(t, atoms.push(a)) {
+        assert forall|i: int| 0 <= i < atoms.len() implies eval_atomic(
+            t,
+            #[trigger] atoms[i],
+        ) by {
+            assert(eval_atomic(t, atoms.push(a)[i]));
+        }
+    }
+    if eval_formula(t, atoms) && eval_atomic(t, a) {
+        assert forall|i: int| 0 <= i < atoms.push(a).len() implies eval_atomic(
+            t,
+            #[trigger] atoms.push(a)[i],
+        ) by {
+            if i < atoms.len() {
+                assert(eval_atomic(t, atoms[i]));
+            }
+        }
+    }
+}
+
+proof fn lemma_eval_formula_prefix(t: Seq<i64>, atoms: Seq<AtomicFormula>, n: int)
+    requires
+        0 <= n <= atoms.len(),
+    ensures
+        eval_formula(t, atoms) ==> eval_formula(t, atoms.subrange(0, n)),
+{
+    if eval_formula(t, atoms) {
+        assert forall|i: int| 0 <= i < n implies eval_atomic(
+            t,
+            #[trigger] atoms.subrange(0, n)[i],
+        ) by {
+            assert(atoms.subrange(0, n)[i] == atoms[i]);
+        }
+    }
+}
+
+/// Equivalent formulas filter a bag identically.
+pub proof fn lemma_filter_bag_equivalent(b: Bag, a1: Seq<AtomicFormula>, a2: Seq<AtomicFormula>)
+    requires
+        forall|t: Seq<i64>| eval_formula(t, a1) <==> eval_formula(t, a2),
+    ensures
+        filter_bag(b, a1) == filter_bag(b, a2),
+    decreases b.len(),
+{
+    reveal(Seq::<Seq<i64>>::filter);
+    if b.len() == 0 {
+        assert(filter_bag(b, a1) =~= filter_bag(b, a2));
+    } else {
+        lemma_filter_bag_equivalent(b.drop_last(), a1, a2);
+        assert(filter_bag(b, a1) =~= filter_bag(b, a2));
+    }
+}
+
+fn pair_contradicts_exec(a: &AtomicFormula, b: &AtomicFormula) -> (c: bool)
+    ensures
+        c == pair_contradicts(*a, *b),
+{
+    match (a, b) {
+        (AtomicFormula::Eq(c1, v1), AtomicFormula::Eq(c2, v2)) => c1 == c2 && v1 != v2,
+        (AtomicFormula::Eq(c1, v), AtomicFormula::Lt(c2, w)) => c1 == c2 && v >= w,
+        (AtomicFormula::Eq(c1, v), AtomicFormula::Gt(c2, w)) => c1 == c2 && v <= w,
+        (AtomicFormula::Lt(c1, y), AtomicFormula::Gt(c2, x)) => c1 == c2 && (*y as i128) <= (*x
+            as i128) + 1,
+        _ => false,
+    }
+}
+
+fn atoms_contradict_exec(a: &AtomicFormula, b: &AtomicFormula) -> (c: bool)
+    ensures
+        c == atoms_contradict(*a, *b),
+{
+    pair_contradicts_exec(a, b) || pair_contradicts_exec(b, a)
+}
+
+/// Verified constant folding on a WHERE clause: drop `True` atoms and
+/// duplicates, and collapse to the canonical always-false clause when two
+/// atoms contradict. The ensures is the soundness theorem: simplification
+/// never changes which rows a formula accepts.
+pub fn simplify_formula(f: &Formula) -> (simplified: Formula)
+    ensures
+        forall|t: Seq<i64>|
+            eval_formula(t, simplified.atoms@) <==> eval_formula(t, f.atoms@),
+{
+    let mut out: Vec<AtomicFormula> = Vec::new();
+    proof {
+        assert(f.atoms@.subrange(0, 0) =~= Seq::<AtomicFormula>::empty());
+    }
+    let mut i: usize = 0;
+    while i < f.atoms.len()
+        invariant
+            i <= f.atoms.len(),
+            forall|t: Seq<i64>|
+                eval_formula(t, out@) <==> eval_formula(
+                    t,
+                    f.atoms@.subrange(0, i as int),
+                ),
+        decreases f.atoms.len() - i,
+    {
+        let ghost out0 = out@;
+        let ghost prefix = f.atoms@.subrange(0, i as int);
+        let a = f.atoms[i];
+
+        let mut duplicate = a == AtomicFormula::True;
+        let mut contradiction = false;
+        let mut j: usize = 0;
+        while j < out.len()
+            invariant
+                j <= out.len(),
+                out@ == out0,
+                duplicate ==> a == AtomicFormula::True || exists|k: int|
+                    0 <= k < out0.len() && out0[k] == a,
+                contradiction ==> exists|k: int|
+                    0 <= k < out0.len() && atoms_contradict(out0[k], a),
+            decreases out.len() - j,
+        {
+            if out[j] == a {
+                duplicate = true;
+            }
+            if atoms_contradict_exec(&out[j], &a) {
+                contradiction = true;
+            }
+            j += 1;
+        }
+
+        if contradiction {
+            let mut atoms: Vec<AtomicFormula> = Vec::new();
+            atoms.push(AtomicFormula::Lt(0, i64::MIN));
+            proof {
+                let k = choose|k: int| 0 <= k < out0.len() && atoms_contradict(out0[k], a);
+                assert forall|t: Seq<i64>|
+                    eval_formula(t, atoms@) <==> eval_formula(t, f.atoms@) by {
+                    assert(atoms@ =~= always_false_atoms());
+                    lemma_always_false(t);
+                    if eval_formula(t, f.atoms@) {
+                        lemma_eval_formula_prefix(t, f.atoms@, i as int);
+                        assert(eval_formula(t, out0));
+                        assert(eval_atomic(t, out0[k]));
+                        assert(eval_atomic(t, f.atoms@[i as int]));
+                        lemma_contradictory_atoms(t, out0[k], a);
+                    }
+                }
+            }
+            return Formula { atoms };
+        }
+        if !duplicate {
+            out.push(a);
+        }
+        proof {
+            let atom = f.atoms@[i as int];
+            assert(f.atoms@.subrange(0, i as int + 1) =~= prefix.push(atom));
+            assert forall|t: Seq<i64>|
+                eval_formula(t, out@) <==> eval_formula(
+                    t,
+                    f.atoms@.subrange(0, i as int + 1),
+                ) by {
+                lemma_eval_formula_push(t, prefix, atom);
+                if duplicate {
+                    // Skipped: the atom is `True` or already present, so the
+                    // surviving conjunction implies it.
+                    if eval_formula(t, out0) && a != AtomicFormula::True {
+                        let k = choose|k: int| 0 <= k < out0.len() && out0[k] == a;
+                        assert(eval_atomic(t, out0[k]));
+                    }
+                } else {
+                    lemma_eval_formula_push(t, out0, atom);
+                }
+            }
+        }
+        i += 1;
+    }
+    proof {
+        assert(f.atoms@.subrange(0, f.atoms.len() as int) =~= f.atoms@);
+    }
+    Formula { atoms: out }
+}
+
+} // verus!
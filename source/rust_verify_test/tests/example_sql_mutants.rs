@@ -0,0 +1,92 @@
+#![feature(rustc_private)]
+//! Negative verification harness for the SQL example.
+//!
+//! The example's contracts are regression-protected by curated mutants:
+//! unified diffs under `examples/sql_spec/mutation_tests/` that each delete
+//! or negate one load-bearing clause. Each patch is applied to a scratch copy
+//! of the example at test time (so the mutants cannot drift from the real
+//! sources silently) and the in-tree verifier must reject every mutant while
+//! accepting the pristine copy.
+//!
+//! Verification runs through the same `run_verus` path as the rest of this
+//! suite, so the test needs vargo but no released Verus. Applying the diffs
+//! shells out to `patch`, and its absence fails the test rather than
+//! skipping it: a silent skip would leave the mutation gate unenforced.
+
+#[macro_use]
+mod common;
+use common::*;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::{Command, Stdio};
+
+fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../examples")
+}
+
+/// Copy `examples/sql.rs` and the `sql_spec` modules into `root/examples/`,
+/// mirroring the repository layout the mutant diffs were generated against.
+fn copy_example(root: &Path) {
+    let spec_dst = root.join("examples/sql_spec");
+    fs::create_dir_all(&spec_dst).unwrap();
+    fs::copy(examples_dir().join("sql.rs"), root.join("examples/sql.rs")).unwrap();
+    for entry in fs::read_dir(examples_dir().join("sql_spec")).unwrap() {
+        let entry = entry.unwrap();
+        if entry.path().extension().is_some_and(|ext| ext == "rs") {
+            fs::copy(entry.path(), spec_dst.join(entry.file_name())).unwrap();
+        }
+    }
+}
+
+fn apply_patch(root: &Path, patch_file: &Path) {
+    let status = Command::new("patch")
+        .args(["-p1", "--silent"])
+        .stdin(Stdio::from(fs::File::open(patch_file).unwrap()))
+        .current_dir(root)
+        .status()
+        .expect("failed to spawn `patch`; it is required to apply the curated mutants");
+    assert!(
+        status.success(),
+        "patch {} no longer applies; regenerate it against the current sources",
+        patch_file.display()
+    );
+}
+
+fn verus_accepts(root: &Path) -> bool {
+    let run = run_verus(&[], root, &root.join("examples/sql.rs"), true, false);
+    run.status.success()
+}
+
+#[test]
+fn sql_example_mutants_fail_verification() {
+    let scratch =
+        std::env::temp_dir().join(format!("sql-example-mutants-{}", std::process::id()));
+
+    // The pristine example must verify, or the mutant failures below would be
+    // meaningless.
+    fs::remove_dir_all(&scratch).ok();
+    copy_example(&scratch);
+    assert!(verus_accepts(&scratch), "the unmutated SQL example failed verification");
+
+    let mut patches: Vec<PathBuf> = fs::read_dir(examples_dir().join("sql_spec/mutation_tests"))
+        .unwrap()
+        .map(|entry| entry.unwrap().path())
+        .filter(|path| path.extension().is_some_and(|ext| ext == "patch"))
+        .collect();
+    patches.sort();
+    assert!(!patches.is_empty(), "no mutant patches found");
+
+    for patch_file in &patches {
+        fs::remove_dir_all(&scratch).ok();
+        copy_example(&scratch);
+        apply_patch(&scratch, patch_file);
+        assert!(
+            !verus_accepts(&scratch),
+            "mutant {} verified successfully; its clause is no longer load-bearing",
+            patch_file.display()
+        );
+    }
+
+    fs::remove_dir_all(&scratch).ok();
+}
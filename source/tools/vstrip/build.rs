@@ -0,0 +1,15 @@
+fn main() {
+    println!("cargo:rerun-if-changed=Cargo.toml");
+    // Expose the pinned verus_syn version so --version can report which
+    // parser the binary was built against.
+    let manifest = std::fs::read_to_string("Cargo.toml").unwrap();
+    let version = manifest
+        .lines()
+        .find(|line| line.trim_start().starts_with("verus_syn"))
+        .and_then(|line| {
+            let rest = line.split("version = \"").nth(1)?;
+            Some(rest.split('"').next()?.trim_start_matches('='))
+        })
+        .unwrap_or("unknown");
+    println!("cargo:rustc-env=VSTRIP_VERUS_SYN_VERSION={}", version);
+}
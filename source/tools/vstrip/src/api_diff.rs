@@ -0,0 +1,278 @@
+//! Detect changes to the public API surface caused by stripping.
+//!
+//! Stripping is not always API-neutral: removing ghost parameters changes a
+//! function's arity, removing spec members shrinks traits, and removing ghost
+//! fields changes struct constructors. This pass collects every public item
+//! of the input and the output, renders each canonically (spec clauses and
+//! modes erased, whitespace normalized, so that cosmetic differences don't
+//! register), and classifies the differences.
+
+use std::collections::BTreeMap;
+use std::fmt;
+
+use quote::ToTokens;
+use serde::Serialize;
+use verus_syn::{File, FnMode, ImplItem, Item, Publish, Signature, TraitItem, Visibility};
+
+/// How one public item was affected by stripping.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "kebab-case")]
+pub enum ApiChangeKind {
+    RemovedItem,
+    ChangedSignature,
+    RemovedField,
+    Unchanged,
+}
+
+impl fmt::Display for ApiChangeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let s = match self {
+            ApiChangeKind::RemovedItem => "removed-item",
+            ApiChangeKind::ChangedSignature => "changed-signature",
+            ApiChangeKind::RemovedField => "removed-field",
+            ApiChangeKind::Unchanged => "unchanged",
+        };
+        f.write_str(s)
+    }
+}
+
+/// One entry of the API diff: the item's path, what happened to it, and its
+/// canonical rendering before and (if it survived) after stripping.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct ApiChange {
+    pub path: String,
+    pub kind: ApiChangeKind,
+    pub before: Option<String>,
+    pub after: Option<String>,
+}
+
+/// Compare the public API surfaces of `before` and `after`, returning one
+/// entry per public item of `before` (stripping never adds items).
+pub fn diff_public_api(before: &File, after: &File) -> Vec<ApiChange> {
+    let before_surface = collect_public_items(before);
+    let after_surface = collect_public_items(after);
+    let mut changes = Vec::new();
+    for (path, b) in &before_surface {
+        let Some(a) = after_surface.get(path) else {
+            changes.push(ApiChange {
+                path: path.clone(),
+                kind: ApiChangeKind::RemovedItem,
+                before: Some(b.rendered.clone()),
+                after: None,
+            });
+            continue;
+        };
+        let mut removed_fields = 0;
+        let mut field_changed = false;
+        if let (Some(bf), Some(af)) = (&b.fields, &a.fields) {
+            for (name, ty) in bf {
+                match af.get(name) {
+                    None => {
+                        removed_fields += 1;
+                        changes.push(ApiChange {
+                            path: format!("{}::{}", path, name),
+                            kind: ApiChangeKind::RemovedField,
+                            before: Some(ty.clone()),
+                            after: None,
+                        });
+                    }
+                    Some(aty) if aty != ty => field_changed = true,
+                    Some(_) => {}
+                }
+            }
+        }
+        if b.rendered != a.rendered || field_changed {
+            changes.push(ApiChange {
+                path: path.clone(),
+                kind: ApiChangeKind::ChangedSignature,
+                before: Some(b.rendered.clone()),
+                after: Some(a.rendered.clone()),
+            });
+        } else if removed_fields == 0 {
+            changes.push(ApiChange {
+                path: path.clone(),
+                kind: ApiChangeKind::Unchanged,
+                before: Some(b.rendered.clone()),
+                after: Some(a.rendered.clone()),
+            });
+        }
+    }
+    changes
+}
+
+/// Text report: one line per changed item, then a summary. Unchanged entries
+/// are kept in the JSON report but elided here.
+pub fn render_text(changes: &[ApiChange]) -> String {
+    let mut out = String::new();
+    let mut interesting = 0;
+    for change in changes {
+        if change.kind == ApiChangeKind::Unchanged {
+            continue;
+        }
+        interesting += 1;
+        out.push_str(&format!("api-diff: {} {}\n", change.kind, change.path));
+        if change.kind == ApiChangeKind::ChangedSignature {
+            if let (Some(before), Some(after)) = (&change.before, &change.after) {
+                out.push_str(&format!("  before: {}\n  after:  {}\n", before, after));
+            }
+        }
+    }
+    out.push_str(&format!(
+        "api-diff: {} public item(s), {} affected by stripping\n",
+        changes.len(),
+        interesting
+    ));
+    out
+}
+
+pub fn render_json(changes: &[ApiChange]) -> String {
+    serde_json::to_string_pretty(changes).expect("api diff serialization does not fail")
+}
+
+/// The canonical rendering of one public item, plus its fields when the item
+/// is a struct (fields diff at finer granularity than whole items).
+struct ItemSurface {
+    rendered: String,
+    fields: Option<BTreeMap<String, String>>,
+}
+
+fn collect_public_items(file: &File) -> BTreeMap<String, ItemSurface> {
+    let mut surface = BTreeMap::new();
+    collect_items(&file.items, &mut Vec::new(), &mut surface);
+    surface
+}
+
+fn collect_items(
+    items: &[Item],
+    module_path: &mut Vec<String>,
+    surface: &mut BTreeMap<String, ItemSurface>,
+) {
+    for item in items {
+        match item {
+            Item::Fn(func) if is_public(&func.vis) => {
+                surface.insert(
+                    key(module_path, &func.sig.ident.to_string()),
+                    ItemSurface { rendered: normalized_sig(&func.sig), fields: None },
+                );
+            }
+            Item::Struct(def) if is_public(&def.vis) => {
+                let mut fields = BTreeMap::new();
+                for (i, field) in def.fields.iter().enumerate() {
+                    let name = match &field.ident {
+                        Some(ident) => ident.to_string(),
+                        None => i.to_string(),
+                    };
+                    fields.insert(name, render(&field.ty));
+                }
+                surface.insert(
+                    key(module_path, &def.ident.to_string()),
+                    ItemSurface {
+                        rendered: format!("struct {} {}", def.ident, render(&def.generics)),
+                        fields: Some(fields),
+                    },
+                );
+            }
+            Item::Enum(def) if is_public(&def.vis) => {
+                surface.insert(
+                    key(module_path, &def.ident.to_string()),
+                    ItemSurface { rendered: render(def), fields: None },
+                );
+            }
+            Item::Trait(def) if is_public(&def.vis) => {
+                surface.insert(
+                    key(module_path, &def.ident.to_string()),
+                    ItemSurface {
+                        rendered: format!("trait {} {}", def.ident, render(&def.generics)),
+                        fields: None,
+                    },
+                );
+                module_path.push(def.ident.to_string());
+                for member in &def.items {
+                    if let TraitItem::Fn(func) = member {
+                        surface.insert(
+                            key(module_path, &func.sig.ident.to_string()),
+                            ItemSurface { rendered: normalized_sig(&func.sig), fields: None },
+                        );
+                    }
+                }
+                module_path.pop();
+            }
+            Item::Impl(imp) if imp.trait_.is_none() => {
+                module_path.push(render(&imp.self_ty));
+                for member in &imp.items {
+                    if let ImplItem::Fn(func) = member {
+                        if is_public(&func.vis) {
+                            surface.insert(
+                                key(module_path, &func.sig.ident.to_string()),
+                                ItemSurface {
+                                    rendered: normalized_sig(&func.sig),
+                                    fields: None,
+                                },
+                            );
+                        }
+                    }
+                }
+                module_path.pop();
+            }
+            Item::Const(def) if is_public(&def.vis) => {
+                surface.insert(
+                    key(module_path, &def.ident.to_string()),
+                    ItemSurface {
+                        rendered: format!("const {}: {}", def.ident, render(&def.ty)),
+                        fields: None,
+                    },
+                );
+            }
+            Item::Static(def) if is_public(&def.vis) => {
+                surface.insert(
+                    key(module_path, &def.ident.to_string()),
+                    ItemSurface {
+                        rendered: format!("static {}: {}", def.ident, render(&def.ty)),
+                        fields: None,
+                    },
+                );
+            }
+            Item::Type(def) if is_public(&def.vis) => {
+                surface.insert(
+                    key(module_path, &def.ident.to_string()),
+                    ItemSurface { rendered: render(def), fields: None },
+                );
+            }
+            Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    module_path.push(module.ident.to_string());
+                    collect_items(items, module_path, surface);
+                    module_path.pop();
+                }
+            }
+            _ => {}
+        }
+    }
+}
+
+fn is_public(vis: &Visibility) -> bool {
+    matches!(vis, Visibility::Public(_))
+}
+
+fn key(module_path: &[String], name: &str) -> String {
+    if module_path.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}::{}", module_path.join("::"), name)
+    }
+}
+
+/// Render a signature with all Verus-only parts erased, so that the input and
+/// output renderings differ only when the Rust-visible signature differs.
+fn normalized_sig(sig: &Signature) -> String {
+    let mut sig = sig.clone();
+    sig.publish = Publish::Default;
+    sig.mode = FnMode::Default;
+    sig.broadcast = None;
+    sig.spec.erase_spec_fields();
+    render(&sig)
+}
+
+fn render<T: ToTokens>(t: &T) -> String {
+    t.to_token_stream().to_string().split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,103 @@
+//! Attribute-only cleaning: remove `#[verifier::*]` attributes while leaving
+//! everything else — bodies, ghost code, spec clauses — untouched.
+//!
+//! This is a much gentler transformation than [`crate::visitor::StripVisitor`]
+//! and is aimed at codebases partway through migrating off Verus, where the
+//! ghost code is still wanted but the verifier attributes have become noise.
+
+use verus_syn::visit_mut::{self, VisitMut};
+use verus_syn::{Attribute, File, ImplItem, Item, TraitItem};
+
+/// Removes `#[verifier::*]` (and `#![verifier::*]`) attributes wherever they
+/// appear; no other part of the tree is modified.
+pub struct AttributeStripVisitor;
+
+/// True for `verifier::foo`-pathed attributes, in either outer or inner form.
+fn is_verifier_attribute(attr: &Attribute) -> bool {
+    attr.path().segments.first().is_some_and(|seg| seg.ident == "verifier")
+}
+
+fn retain_attrs(attrs: &mut Vec<Attribute>) {
+    attrs.retain(|attr| !is_verifier_attribute(attr));
+}
+
+impl VisitMut for AttributeStripVisitor {
+    fn visit_file_mut(&mut self, file: &mut File) {
+        retain_attrs(&mut file.attrs);
+        visit_mut::visit_file_mut(self, file);
+    }
+
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        match item {
+            Item::Const(i) => retain_attrs(&mut i.attrs),
+            Item::Enum(i) => retain_attrs(&mut i.attrs),
+            Item::ExternCrate(i) => retain_attrs(&mut i.attrs),
+            Item::Fn(i) => retain_attrs(&mut i.attrs),
+            Item::ForeignMod(i) => retain_attrs(&mut i.attrs),
+            Item::Impl(i) => retain_attrs(&mut i.attrs),
+            Item::Macro(i) => retain_attrs(&mut i.attrs),
+            Item::Mod(i) => retain_attrs(&mut i.attrs),
+            Item::Static(i) => retain_attrs(&mut i.attrs),
+            Item::Struct(i) => retain_attrs(&mut i.attrs),
+            Item::Trait(i) => retain_attrs(&mut i.attrs),
+            Item::TraitAlias(i) => retain_attrs(&mut i.attrs),
+            Item::Type(i) => retain_attrs(&mut i.attrs),
+            Item::Union(i) => retain_attrs(&mut i.attrs),
+            Item::Use(i) => retain_attrs(&mut i.attrs),
+            Item::Global(i) => retain_attrs(&mut i.attrs),
+            Item::BroadcastUse(i) => retain_attrs(&mut i.attrs),
+            Item::BroadcastGroup(i) => retain_attrs(&mut i.attrs),
+            Item::AssumeSpecification(i) => retain_attrs(&mut i.attrs),
+            Item::Verbatim(_) => {}
+            _ => {}
+        }
+        visit_mut::visit_item_mut(self, item);
+    }
+
+    fn visit_impl_item_mut(&mut self, item: &mut ImplItem) {
+        match item {
+            ImplItem::Const(i) => retain_attrs(&mut i.attrs),
+            ImplItem::Fn(i) => retain_attrs(&mut i.attrs),
+            ImplItem::Type(i) => retain_attrs(&mut i.attrs),
+            ImplItem::Macro(i) => retain_attrs(&mut i.attrs),
+            ImplItem::Verbatim(_) => {}
+            _ => {}
+        }
+        visit_mut::visit_impl_item_mut(self, item);
+    }
+
+    fn visit_trait_item_mut(&mut self, item: &mut TraitItem) {
+        match item {
+            TraitItem::Const(i) => retain_attrs(&mut i.attrs),
+            TraitItem::Fn(i) => retain_attrs(&mut i.attrs),
+            TraitItem::Type(i) => retain_attrs(&mut i.attrs),
+            TraitItem::Macro(i) => retain_attrs(&mut i.attrs),
+            TraitItem::Verbatim(_) => {}
+            _ => {}
+        }
+        visit_mut::visit_trait_item_mut(self, item);
+    }
+
+    fn visit_field_mut(&mut self, field: &mut verus_syn::Field) {
+        retain_attrs(&mut field.attrs);
+        visit_mut::visit_field_mut(self, field);
+    }
+
+    fn visit_variant_mut(&mut self, variant: &mut verus_syn::Variant) {
+        retain_attrs(&mut variant.attrs);
+        visit_mut::visit_variant_mut(self, variant);
+    }
+
+    fn visit_fn_arg_mut(&mut self, arg: &mut verus_syn::FnArg) {
+        match &mut arg.kind {
+            verus_syn::FnArgKind::Typed(pat_type) => retain_attrs(&mut pat_type.attrs),
+            verus_syn::FnArgKind::Receiver(receiver) => retain_attrs(&mut receiver.attrs),
+        }
+        visit_mut::visit_fn_arg_mut(self, arg);
+    }
+
+    fn visit_local_mut(&mut self, local: &mut verus_syn::Local) {
+        retain_attrs(&mut local.attrs);
+        visit_mut::visit_local_mut(self, local);
+    }
+}
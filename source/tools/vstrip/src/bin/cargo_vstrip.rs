@@ -0,0 +1,238 @@
+//! `cargo vstrip`: run vstrip over the packages of a Cargo workspace.
+//!
+//! Cargo invokes external subcommands as `cargo-vstrip vstrip <args>`, so the
+//! extra `vstrip` argv entry is dropped before parsing. The workspace is
+//! located by walking up from the current directory; by default every
+//! workspace member's sources are stripped and written under
+//! `<workspace>/target/vstrip/<package>/`, mirroring each package's layout.
+//!
+//! The stripping flags are the same as `vstrip`'s, shared via
+//! [`vstrip::cli::StripArgs`], and each package runs through the same
+//! `--package` pipeline as `vstrip --package <member>`: the declared targets,
+//! their modules, and path dependencies. A `.vstrip.toml` discovered from a
+//! package root layers under the command line, exactly as for `vstrip`.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use vstrip::cli::StripArgs;
+use vstrip::config::MessageFormat;
+use vstrip::{Config, Result, StripError};
+
+/// Strip Verus specification and proof code from a Cargo workspace.
+#[derive(Parser)]
+#[command(name = "cargo-vstrip", bin_name = "cargo vstrip", version)]
+struct Cli {
+    /// Process only the named package(s) instead of every workspace member
+    #[arg(short = 'p', long = "package", value_name = "NAME")]
+    packages: Vec<String>,
+
+    #[command(flatten)]
+    strip: StripArgs,
+}
+
+struct Package {
+    name: String,
+    root: PathBuf,
+}
+
+fn main() -> ExitCode {
+    // Drop the `vstrip` argument cargo inserts when dispatching to us, while
+    // still supporting direct `cargo-vstrip <args>` invocation.
+    let mut args: Vec<String> = std::env::args().collect();
+    if args.get(1).map(String::as_str) == Some("vstrip") {
+        args.remove(1);
+    }
+    let cli = Cli::parse_from(args);
+    let json_messages = cli.strip.message_format == Some(MessageFormat::Json);
+    match run(cli) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if json_messages {
+                eprintln!("{}", vstrip::diagnostics::Message::from_error(&e).render_line());
+            } else {
+                eprintln!("error: {}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn run(cli: Cli) -> Result<()> {
+    let cwd = std::env::current_dir()
+        .map_err(|e| StripError::IoError { path: PathBuf::from("."), source: e })?;
+    let workspace_root = find_workspace_root(&cwd)?;
+    let mut packages = discover_packages(&workspace_root)?;
+    if !cli.packages.is_empty() {
+        packages.retain(|p| cli.packages.iter().any(|sel| sel == &p.name));
+        for sel in &cli.packages {
+            if !packages.iter().any(|p| &p.name == sel) {
+                return Err(StripError::ConfigError(format!(
+                    "package `{}` not found in workspace {}",
+                    sel,
+                    workspace_root.display()
+                )));
+            }
+        }
+    }
+    // Writing under target/vstrip is the default, but only when no other
+    // destination (or a no-write mode) was asked for.
+    let default_out_dir = !(cli.strip.in_place
+        || cli.strip.check
+        || cli.strip.diff
+        || cli.strip.out_dir.is_some()
+        || cli.strip.output.is_some());
+    let shared = cli.strip.layer();
+    for package in &packages {
+        let mut layer = shared.clone();
+        layer.input = Some(package.root.clone());
+        layer.package = Some(true);
+        if default_out_dir {
+            layer.out_dir = Some(workspace_root.join("target/vstrip").join(&package.name));
+        }
+        // Per-package project files layer under the flags, as for vstrip.
+        let layered = match vstrip::config::discover_project_config(&package.root)? {
+            Some(project) => project.merge(&layer),
+            None => layer,
+        };
+        let config = layered.apply_to(&Config::default());
+        vstrip::process(&config)?;
+    }
+    Ok(())
+}
+
+/// Walk up from `start` looking for the workspace root: the first manifest
+/// with a `[workspace]` table wins; failing that, the outermost manifest.
+fn find_workspace_root(start: &Path) -> Result<PathBuf> {
+    let mut outermost: Option<PathBuf> = None;
+    for dir in start.ancestors() {
+        let manifest = dir.join("Cargo.toml");
+        if manifest.is_file() {
+            let text = fs::read_to_string(&manifest)
+                .map_err(|e| StripError::IoError { path: manifest.clone(), source: e })?;
+            if has_table(&text, "workspace") {
+                return Ok(dir.to_path_buf());
+            }
+            outermost = Some(dir.to_path_buf());
+        }
+    }
+    outermost.ok_or_else(|| {
+        StripError::ConfigError(format!(
+            "no Cargo.toml found in {} or any parent directory",
+            start.display()
+        ))
+    })
+}
+
+/// List the packages of the workspace rooted at `root`: its `[workspace]`
+/// members (with trailing `/*` globs expanded), or the root package itself.
+fn discover_packages(root: &Path) -> Result<Vec<Package>> {
+    let manifest_path = root.join("Cargo.toml");
+    let manifest = fs::read_to_string(&manifest_path)
+        .map_err(|e| StripError::IoError { path: manifest_path.clone(), source: e })?;
+    let mut packages = Vec::new();
+    if has_table(&manifest, "workspace") {
+        for member in workspace_members(&manifest) {
+            if let Some(prefix) = member.strip_suffix("/*") {
+                let dir = root.join(prefix);
+                let entries = fs::read_dir(&dir)
+                    .map_err(|e| StripError::IoError { path: dir.clone(), source: e })?;
+                for entry in entries {
+                    let entry =
+                        entry.map_err(|e| StripError::IoError { path: dir.clone(), source: e })?;
+                    add_package(&mut packages, &entry.path());
+                }
+            } else {
+                add_package(&mut packages, &root.join(&member));
+            }
+        }
+    }
+    if packages.is_empty() {
+        add_package(&mut packages, root);
+    }
+    if packages.is_empty() {
+        return Err(StripError::ConfigError(format!(
+            "no packages found under {}",
+            root.display()
+        )));
+    }
+    packages.sort_by(|a, b| a.name.cmp(&b.name));
+    Ok(packages)
+}
+
+fn add_package(packages: &mut Vec<Package>, dir: &Path) {
+    let manifest = dir.join("Cargo.toml");
+    let Ok(text) = fs::read_to_string(&manifest) else {
+        return;
+    };
+    if let Some(name) = package_name(&text) {
+        packages.push(Package { name, root: dir.to_path_buf() });
+    }
+}
+
+/// Minimal manifest scanning; enough for member/package discovery without a
+/// TOML dependency.
+fn has_table(manifest: &str, table: &str) -> bool {
+    manifest.lines().any(|line| line.trim() == format!("[{}]", table))
+}
+
+fn package_name(manifest: &str) -> Option<String> {
+    let mut in_package = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_package = line == "[package]";
+            continue;
+        }
+        if in_package {
+            if let Some(rest) = line.strip_prefix("name") {
+                let rest = rest.trim_start();
+                if let Some(value) = rest.strip_prefix('=') {
+                    return Some(value.trim().trim_matches('"').to_string());
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Extract the `members = [...]` entries of the `[workspace]` table.
+fn workspace_members(manifest: &str) -> Vec<String> {
+    let mut members = Vec::new();
+    let mut in_workspace = false;
+    let mut in_members = false;
+    for line in manifest.lines() {
+        let line = line.trim();
+        if line.starts_with('[') {
+            in_workspace = line == "[workspace]";
+            in_members = false;
+            continue;
+        }
+        if !in_workspace {
+            continue;
+        }
+        let mut rest = line;
+        if let Some(after) = line.strip_prefix("members") {
+            let after = after.trim_start();
+            let Some(after) = after.strip_prefix('=') else { continue };
+            in_members = true;
+            rest = after.trim_start().strip_prefix('[').unwrap_or(after).trim();
+        }
+        if in_members {
+            for piece in rest.split(',') {
+                let piece = piece.trim().trim_end_matches(']').trim();
+                let piece = piece.trim_matches('"');
+                if !piece.is_empty() {
+                    members.push(piece.to_string());
+                }
+            }
+            if rest.contains(']') {
+                in_members = false;
+            }
+        }
+    }
+    members
+}
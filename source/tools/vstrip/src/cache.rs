@@ -0,0 +1,123 @@
+//! Persistent caching across invocations.
+//!
+//! With `--cache FILE`, a JSON file records for every processed source its
+//! modification time and a hash of its contents; files whose entry still
+//! matches are skipped on the next run. The hash of the stripped output is
+//! also recorded so downstream tooling can detect stale outputs. The file
+//! also names the vstrip version that wrote it; a different binary discards
+//! it entirely, since the same input may now strip differently.
+
+use std::collections::HashMap;
+use std::fs;
+use std::hash::{Hash, Hasher};
+use std::path::{Path, PathBuf};
+use std::time::SystemTime;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{Result, StripError};
+
+/// What the cache remembers about one processed file.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CacheEntry {
+    pub mtime: SystemTime,
+    pub content_hash: u64,
+    pub stripped_hash: u64,
+}
+
+/// A map from source path to [`CacheEntry`], serialized as JSON.
+#[derive(Debug, Serialize, Deserialize)]
+pub struct IncrementalCache {
+    /// The vstrip version that wrote the cache. A different binary may strip
+    /// differently, so entries from another version are discarded wholesale
+    /// on load. The serde default (the empty string) never matches a real
+    /// version, which retires pre-versioning cache files too.
+    #[serde(default)]
+    version: String,
+    entries: HashMap<PathBuf, CacheEntry>,
+}
+
+impl Default for IncrementalCache {
+    fn default() -> IncrementalCache {
+        IncrementalCache {
+            version: env!("CARGO_PKG_VERSION").to_string(),
+            entries: HashMap::new(),
+        }
+    }
+}
+
+/// `DefaultHasher::new()` uses fixed keys, so these hashes are stable across
+/// processes (though not across Rust releases, which only costs a cache miss).
+fn hash_str(s: &str) -> u64 {
+    let mut hasher = std::collections::hash_map::DefaultHasher::new();
+    s.hash(&mut hasher);
+    hasher.finish()
+}
+
+impl IncrementalCache {
+    /// Load a cache from `path`; a missing file yields an empty cache, so
+    /// first runs need no special casing.
+    pub fn load(path: &Path) -> Result<IncrementalCache> {
+        let text = match fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == std::io::ErrorKind::NotFound => {
+                return Ok(IncrementalCache::default());
+            }
+            Err(e) => return Err(StripError::IoError { path: path.to_path_buf(), source: e }),
+        };
+        let cache: IncrementalCache = serde_json::from_str(&text).map_err(|e| {
+            StripError::ConfigError(format!("invalid cache file {}: {}", path.display(), e))
+        })?;
+        if cache.version != env!("CARGO_PKG_VERSION") {
+            return Ok(IncrementalCache::default());
+        }
+        Ok(cache)
+    }
+
+    pub fn save(&self, path: &Path) -> Result<()> {
+        let text = serde_json::to_string_pretty(self)
+            .expect("cache serialization does not fail");
+        fs::write(path, text)
+            .map_err(|e| StripError::IoError { path: path.to_path_buf(), source: e })
+    }
+
+    /// Whether `path` is unchanged since its cache entry was recorded: both
+    /// the mtime and the content hash must match (the hash guards against
+    /// restored mtimes, the mtime makes the common unchanged case cheap to
+    /// reason about).
+    pub fn is_current(&self, path: &Path) -> bool {
+        let Some(entry) = self.entries.get(path) else {
+            return false;
+        };
+        let Ok(mtime) = fs::metadata(path).and_then(|m| m.modified()) else {
+            return false;
+        };
+        if mtime != entry.mtime {
+            return false;
+        }
+        let Ok(content) = fs::read_to_string(path) else {
+            return false;
+        };
+        hash_str(&content) == entry.content_hash
+    }
+
+    /// Record that `path` was just stripped to `stripped`. Failure to stat or
+    /// re-read the file simply leaves no entry (the file will be reprocessed
+    /// next time).
+    pub fn update(&mut self, path: &Path, stripped: &str) {
+        let Ok(mtime) = fs::metadata(path).and_then(|m| m.modified()) else {
+            return;
+        };
+        let Ok(content) = fs::read_to_string(path) else {
+            return;
+        };
+        self.entries.insert(
+            path.to_path_buf(),
+            CacheEntry {
+                mtime,
+                content_hash: hash_str(&content),
+                stripped_hash: hash_str(stripped),
+            },
+        );
+    }
+}
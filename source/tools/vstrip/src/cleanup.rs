@@ -0,0 +1,154 @@
+//! Post-strip import cleanup: drop `use` items that only served removed
+//! spec code.
+//!
+//! Stripping deletes the spec functions and ghost items a file's imports
+//! were feeding, but the imports themselves survive [`crate::visitor`] and
+//! then trip rustc's `unused_imports` lint in the output. This pass compares
+//! identifier usage before and after stripping and removes exactly the
+//! imports stripping orphaned: a name the original never mentioned either —
+//! a trait imported only for its methods, say — is the author's business
+//! and is left alone. `use foo::*` globs hide what they provide, so they
+//! are only removed when `foo` is listed in
+//! [`Config::known_spec_modules`](crate::Config::known_spec_modules).
+
+use std::collections::HashSet;
+
+use verus_syn::visit::{self, Visit};
+use verus_syn::visit_mut::{self, VisitMut};
+use verus_syn::{File, Ident, Item, UseTree};
+
+use crate::config::Config;
+
+/// Every identifier appearing in `file` outside of `use` items.
+///
+/// Callers collect this from the original tree before stripping and hand it
+/// to [`remove_unused_imports`], which compares it against the stripped
+/// tree's own set.
+pub(crate) fn used_identifiers(file: &File) -> HashSet<String> {
+    let mut collector = IdentCollector { used: HashSet::new() };
+    collector.visit_file(file);
+    collector.used
+}
+
+/// Remove `use` imports whose names were used before stripping but appear
+/// nowhere in the stripped `file`. Group imports are pruned name by name;
+/// glob imports are removed only when their path names a module from
+/// [`Config::known_spec_modules`](crate::Config::known_spec_modules),
+/// matched against either the full `a::b` path or its final segment.
+pub fn remove_unused_imports(
+    file: &mut File,
+    previously_used: &HashSet<String>,
+    config: &Config,
+) {
+    let mut pruner = ImportPruner {
+        previously_used,
+        still_used: used_identifiers(file),
+        spec_modules: &config.known_spec_modules,
+    };
+    pruner.visit_file_mut(file);
+}
+
+struct IdentCollector {
+    used: HashSet<String>,
+}
+
+impl<'ast> Visit<'ast> for IdentCollector {
+    fn visit_item(&mut self, item: &'ast Item) {
+        // The names an import binds do not count as uses of themselves.
+        if matches!(item, Item::Use(_)) {
+            return;
+        }
+        visit::visit_item(self, item);
+    }
+
+    fn visit_ident(&mut self, ident: &'ast Ident) {
+        self.used.insert(ident.to_string());
+    }
+}
+
+struct ImportPruner<'a> {
+    previously_used: &'a HashSet<String>,
+    still_used: HashSet<String>,
+    spec_modules: &'a [String],
+}
+
+impl ImportPruner<'_> {
+    /// True for a bound name that stripping orphaned: the original tree
+    /// used it, the stripped tree does not.
+    fn orphaned(&self, ident: &Ident) -> bool {
+        let name = ident.to_string();
+        self.previously_used.contains(&name) && !self.still_used.contains(&name)
+    }
+
+    fn is_spec_module(&self, path: &[String]) -> bool {
+        let joined = path.join("::");
+        self.spec_modules
+            .iter()
+            .any(|m| *m == joined || Some(m.as_str()) == path.last().map(String::as_str))
+    }
+
+    /// The surviving part of `tree`, or `None` if nothing survives. `path`
+    /// carries the segments leading down to `tree`, for the glob check.
+    fn prune_tree(&self, tree: &UseTree, path: &mut Vec<String>) -> Option<UseTree> {
+        match tree {
+            UseTree::Path(use_path) => {
+                path.push(use_path.ident.to_string());
+                let pruned = self.prune_tree(&use_path.tree, path);
+                path.pop();
+                pruned.map(|inner| {
+                    UseTree::Path(verus_syn::UsePath {
+                        ident: use_path.ident.clone(),
+                        colon2_token: use_path.colon2_token,
+                        tree: Box::new(inner),
+                    })
+                })
+            }
+            UseTree::Name(name) => (!self.orphaned(&name.ident)).then(|| tree.clone()),
+            UseTree::Rename(rename) => (!self.orphaned(&rename.rename)).then(|| tree.clone()),
+            UseTree::Glob(_) => (!self.is_spec_module(path)).then(|| tree.clone()),
+            UseTree::Group(group) => {
+                let kept: Vec<UseTree> =
+                    group.items.iter().filter_map(|t| self.prune_tree(t, path)).collect();
+                if kept.is_empty() {
+                    return None;
+                }
+                if kept.len() == 1 {
+                    // `use a::{b};` reads as a leftover; collapse to `use a::b;`.
+                    return Some(kept.into_iter().next().expect("length checked"));
+                }
+                Some(UseTree::Group(verus_syn::UseGroup {
+                    brace_token: group.brace_token,
+                    items: kept.into_iter().collect(),
+                }))
+            }
+        }
+    }
+
+    fn retain_imports(&self, items: &mut Vec<Item>) {
+        let old = std::mem::take(items);
+        for mut item in old {
+            if let Item::Use(import) = &mut item {
+                let mut path = Vec::new();
+                match self.prune_tree(&import.tree, &mut path) {
+                    Some(tree) => import.tree = tree,
+                    None => continue,
+                }
+            }
+            items.push(item);
+        }
+    }
+}
+
+impl VisitMut for ImportPruner<'_> {
+    fn visit_file_mut(&mut self, file: &mut File) {
+        self.retain_imports(&mut file.items);
+        visit_mut::visit_file_mut(self, file);
+    }
+
+    fn visit_item_mod_mut(&mut self, module: &mut verus_syn::ItemMod) {
+        if let Some((_, items)) = &mut module.content {
+            self.retain_imports(items);
+        }
+        visit_mut::visit_item_mod_mut(self, module);
+    }
+}
@@ -0,0 +1,678 @@
+//! The command-line flag surface shared by the `vstrip` and `cargo-vstrip`
+//! binaries.
+//!
+//! Both binaries accept the same stripping and output-format options; only
+//! the way they pick their inputs differs (`vstrip` takes a path, `--package`
+//! or `--files-from`; `cargo vstrip` discovers workspace members). Defining
+//! the shared flags once as a [`StripArgs`] group, `#[command(flatten)]`ed
+//! into each binary's own parser, keeps the two in lockstep: a new option
+//! added here appears in both, with the same help text and the same
+//! [`PartialConfig`] layering via [`StripArgs::layer`].
+//!
+//! Input-selection flags (`--recursive`, `--package`, `--files-from`,
+//! `--no-ignore`) stay in the `vstrip` binary, where they mean something.
+
+use std::path::PathBuf;
+
+use crate::config::{
+    ApiDiffFormat, AssertStrategy, EmptyBodyPolicy, MessageFormat, PartialConfig, StatsFormat,
+};
+
+/// Stripping and output options common to `vstrip` and `cargo vstrip`.
+#[derive(clap::Args, Clone)]
+pub struct StripArgs {
+    /// Write output to this file instead of stdout
+    #[arg(
+        short,
+        long,
+        conflicts_with = "in_place",
+        help_heading = "Input/Output options",
+        long_help = "Write output to this file instead of stdout.\n\n\
+                     Example: vstrip src/lib.rs --output stripped.rs"
+    )]
+    pub output: Option<PathBuf>,
+
+    /// With --recursive or --package, mirror the stripped files under DIR
+    #[arg(
+        long,
+        value_name = "DIR",
+        conflicts_with_all = ["in_place", "output", "follow_includes"],
+        help_heading = "Input/Output options",
+        long_help = "Write each stripped file to the same relative path under DIR instead\n\
+                     of rewriting in place: src/foo/bar.rs becomes DIR/foo/bar.rs, with\n\
+                     intermediate directories created as needed. DIR must not lie inside\n\
+                     the input directory, or the next run would re-process its own\n\
+                     output. Only meaningful with --recursive or --package:\n\
+                     vstrip --recursive --out-dir stripped/ src/"
+    )]
+    pub out_dir: Option<PathBuf>,
+
+    /// With --out-dir, copy non-.rs files into the output tree verbatim
+    #[arg(
+        long,
+        requires = "out_dir",
+        help_heading = "Input/Output options",
+        long_help = "Copy files the recursive walk finds that are not .rs sources into\n\
+                     --out-dir unchanged, so the output tree is usable without the\n\
+                     original:\n\
+                     vstrip --recursive --out-dir stripped/ --copy-assets src/"
+    )]
+    pub copy_assets: bool,
+
+    /// Rewrite the input file in place
+    #[arg(
+        long,
+        help_heading = "Input/Output options",
+        long_help = "Rewrite the input file in place, replacing the annotated source.\n\n\
+                     Example: vstrip --in-place --recursive src/"
+    )]
+    pub in_place: bool,
+
+    /// With --in-place, first copy each original to <file>SUFFIX
+    #[arg(
+        long,
+        value_name = "SUFFIX",
+        num_args = 0..=1,
+        default_missing_value = ".orig",
+        require_equals = true,
+        requires = "in_place",
+        help_heading = "Input/Output options",
+        long_help = "Before rewriting a file in place, copy the original alongside it with\n\
+                     SUFFIX appended (default .orig). The rewrite only happens once the\n\
+                     copy succeeds. An existing backup makes the run fail unless\n\
+                     --force-backup is also given:\n\
+                     vstrip --in-place --backup src/lib.rs\n\
+                     vstrip --in-place --backup=.bak src/lib.rs"
+    )]
+    pub backup: Option<String>,
+
+    /// Overwrite existing backup files instead of failing
+    #[arg(long, requires = "backup", help_heading = "Input/Output options")]
+    pub force_backup: bool,
+
+    /// Delete files stripping leaves empty, and their mod declarations
+    #[arg(
+        long,
+        conflicts_with = "check",
+        help_heading = "Processing modes",
+        long_help = "After a --recursive or --package run that writes files (--in-place or\n\
+                     --out-dir), delete every output file stripping left without items —\n\
+                     a proofs.rs that held only spec and proof code, say — and remove the\n\
+                     `mod name;` declaration in the parent module file that pointed at\n\
+                     it. A `pub mod` declaration re-exports the module as crate API, so\n\
+                     that module is kept and a warning is printed instead:\n\
+                     vstrip --recursive --in-place --remove-empty src/"
+    )]
+    pub remove_empty: bool,
+
+    /// Only process files matching GLOB with --recursive (repeatable)
+    #[arg(
+        long,
+        value_name = "GLOB",
+        action = clap::ArgAction::Append,
+        help_heading = "Processing modes",
+        long_help = "Only process files matching GLOB during a recursive walk. Patterns\n\
+                     are matched against paths relative to the input directory; an\n\
+                     empty list means every .rs file. Repeatable:\n\
+                     vstrip --recursive --include 'src/**' ."
+    )]
+    pub include: Vec<String>,
+
+    /// Skip files matching GLOB with --recursive (repeatable)
+    #[arg(
+        long,
+        value_name = "GLOB",
+        action = clap::ArgAction::Append,
+        help_heading = "Processing modes",
+        long_help = "Skip files matching GLOB during a recursive walk; exclude wins over\n\
+                     --include. Patterns are matched against paths relative to the input\n\
+                     directory. Repeatable:\n\
+                     vstrip --recursive --exclude 'tests/**' --exclude '**/generated_*.rs' src/"
+    )]
+    pub exclude: Vec<String>,
+
+    /// Verify files are free of Verus constructs, writing nothing
+    #[arg(
+        long,
+        help_heading = "Processing modes",
+        long_help = "Parse, strip, and validate, but do not write any output. Each file is\n\
+                     reported as already clean or as would-be-stripped (compared modulo\n\
+                     formatting), and the run exits non-zero if the input does not parse,\n\
+                     if stripping would produce invalid Rust (e.g. colliding item names),\n\
+                     or if stripping would change any file. Suitable for enforcing\n\
+                     \"no Verus constructs here\" in CI:\n\
+                     vstrip --check --recursive src/"
+    )]
+    pub check: bool,
+
+    /// Print a unified diff of what stripping would change, writing nothing
+    #[arg(
+        long,
+        conflicts_with_all = ["in_place", "output", "out_dir"],
+        help_heading = "Processing modes",
+        long_help = "Instead of the stripped output, print a unified diff between each\n\
+                     source file and what stripping would turn it into. Nothing is\n\
+                     written; files that would not change print nothing. Exits non-zero\n\
+                     if any file would change, so it can gate CI like rustfmt --check;\n\
+                     adding --check is allowed and changes nothing:\n\
+                     vstrip --diff --recursive src/"
+    )]
+    pub diff: bool,
+
+    /// Also strip each output a second time and fail if the passes disagree
+    #[arg(
+        long,
+        help_heading = "Processing modes",
+        long_help = "After stripping, strip the output a second time and fail if the two\n\
+                     results differ. Output that is not a fixed point means the strip\n\
+                     logic is unstable on this input; useful as a CI guard:\n\
+                     vstrip --check --check-idempotent --recursive src/"
+    )]
+    pub check_idempotent: bool,
+
+    /// Re-parse each output as plain Rust before writing anything
+    #[arg(
+        long,
+        conflicts_with = "attributes_only",
+        help_heading = "Processing modes",
+        long_help = "Before any file is written, re-parse the stripped output with the\n\
+                     standard (non-Verus) Rust parser. If a Verus-only construct leaked\n\
+                     through, the run fails naming the offending line and the original\n\
+                     file is left untouched. Recommended with --in-place."
+    )]
+    pub verify_output: bool,
+
+    /// Fail on any stripping warning instead of printing it
+    #[arg(
+        long,
+        help_heading = "Processing modes",
+        long_help = "Treat every stripping warning (emptied file, dropped ghost parameter,\n\
+                     removed open spec fn, ...) as an error: the run fails naming the\n\
+                     warnings and nothing is written. Useful in CI alongside --check."
+    )]
+    pub deny_warnings: bool,
+
+    /// Fail on Verus constructs vstrip cannot strip instead of passing them on
+    #[arg(
+        long,
+        help_heading = "Processing modes",
+        long_help = "Fail when a file contains a Verus-only construct vstrip has no\n\
+                     handling for — a state_machine! invocation, say — instead of\n\
+                     warning and passing it through into output that will not compile.\n\
+                     Narrower than --deny-warnings, which makes every warning fatal."
+    )]
+    pub strict: bool,
+
+    /// Report spec-versus-exec density metrics instead of stripping
+    #[arg(
+        long,
+        conflicts_with_all = [
+            "check",
+            "diff",
+            "in_place",
+            "output",
+            "out_dir",
+            "stats",
+            "list_removed",
+            "json",
+        ],
+        help_heading = "Processing modes",
+        long_help = "Parse the input and report how much of it is specification and proof\n\
+                     code — spec fns, proof fns, proof blocks, and ghost declarations,\n\
+                     counted by items and by span line extents — against executable code,\n\
+                     per file and in total. Nothing is stripped or written; the report is\n\
+                     a text table on stdout, or a JSON array with\n\
+                     --message-format=json. For tracking a spec-to-exec ratio as a\n\
+                     project health metric:\n\
+                     vstrip --count --recursive src/"
+    )]
+    pub count: bool,
+
+    /// Stop at the first file that fails instead of continuing
+    #[arg(
+        long,
+        conflicts_with = "jobs",
+        help_heading = "Processing modes",
+        long_help = "Stop a --recursive or --package run at the first file that fails and\n\
+                     propagate its error, instead of processing the remaining files and\n\
+                     reporting every failure at the end. Incompatible with --jobs, whose\n\
+                     workers cannot be recalled mid-run."
+    )]
+    pub fail_fast: bool,
+
+    /// Write per-file results to stdout as a JSON array
+    #[arg(
+        long = "json",
+        help_heading = "Output format options",
+        long_help = "Write one JSON array to stdout with an entry per processed file:\n\
+                     its path, \"ok\" or \"error\" status, how many items stripping\n\
+                     removed, and any warnings. The schema is documented in the\n\
+                     diagnostics module and stable across patch releases. Needs a mode\n\
+                     where stdout is free: --check, --in-place, --output, or --out-dir.\n\
+                     Example: vstrip --check --json --recursive src/"
+    )]
+    pub json: bool,
+
+    /// With recursive stdout output, wrap each file in mod blocks
+    #[arg(
+        long,
+        conflicts_with_all = ["in_place", "out_dir", "check", "diff"],
+        help_heading = "Output format options",
+        long_help = "When --recursive or --package output goes to stdout, wrap each\n\
+                     file's items in mod blocks derived from its path (math/proofs.rs\n\
+                     becomes pub mod math { pub mod proofs { ... } }), so the\n\
+                     concatenation is one reviewable file with one definition site per\n\
+                     name. Each file is still preceded by a // ==== path ==== marker.\n\
+                     Example: vstrip --recursive --as-modules src/ > review.rs"
+    )]
+    pub as_modules: bool,
+
+    /// How errors and warnings are printed: text (default) or json
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help_heading = "Output format options",
+        long_help = "How errors and warnings are printed. text (the default) keeps the\n\
+                     human-readable error:/warning: lines; json prints one JSON object\n\
+                     per stderr line with fields level, path, line, column, message,\n\
+                     and code, for editors and other tooling. Stdout and the stripped\n\
+                     output are unaffected either way.\n\
+                     Example: vstrip --check --message-format=json --recursive src/"
+    )]
+    pub message_format: Option<MessageFormat>,
+
+    /// Print statistics on the removed spec/proof code (text or json)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        num_args = 0..=1,
+        default_missing_value = "text",
+        require_equals = true,
+        conflicts_with = "json",
+        help_heading = "Output format options",
+        long_help = "Print statistics on the removed constructs: spec and proof fns,\n\
+                     requires/ensures clauses, ghost locals/fields/params, proof blocks,\n\
+                     and assert/assume expressions, plus byte sizes before and after.\n\
+                     FORMAT is text (an aggregate table, the default) or json (an array\n\
+                     of per-file objects). Goes to stdout, so a mode where stdout is\n\
+                     free is required:\n\
+                     vstrip --check --stats --recursive src/\n\
+                     vstrip --check --stats=json --recursive src/"
+    )]
+    pub stats: Option<StatsFormat>,
+
+    /// List the removed items by name under each file name
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "After each file, print the items stripping removed — spec fn foo,\n\
+                     proof fn bar, ghost field Baz::field — with their original line\n\
+                     numbers, under the file name. Files with nothing removed print\n\
+                     nothing. Goes to stdout, so a mode where stdout is free is required:\n\
+                     vstrip --check --list-removed --recursive src/"
+    )]
+    pub list_removed: bool,
+
+    /// Keep requires/ensures clauses as doc comments on stripped functions
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "Keep requires/ensures/decreases clauses as doc comments on stripped\n\
+                     functions — and loop invariant/decreases annotations as doc comments\n\
+                     on their loops — so the specification remains readable in the output:\n\n\
+                     /// requires x < 1000\n\
+                     /// ensures r == 2 * x\n\
+                     fn double(x: u32) -> u32 { ... }"
+    )]
+    pub spec_as_comments: bool,
+
+    /// Keep impl blocks and modules that become empty after stripping
+    #[arg(long, help_heading = "Output format options")]
+    pub keep_empty_items: bool,
+
+    /// Also delete traits and trait impls that become empty after stripping
+    #[arg(
+        long,
+        conflicts_with = "keep_empty_items",
+        help_heading = "Output format options",
+        long_help = "Also delete trait definitions whose members were all spec/proof\n\
+                     declarations, and trait impls stripping emptied. Both survive by\n\
+                     default: an emptied trait is still crate API, and an empty\n\
+                     `impl Marker for T {}` is meaningful. Implementors of a deleted\n\
+                     trait elsewhere in the tree are deleted by the same rule, but\n\
+                     references from other crates are not tracked."
+    )]
+    pub remove_empty_traits: bool,
+
+    /// Keep spec fn / proof fn items instead of deleting them
+    #[arg(
+        long,
+        conflicts_with = "verify_output",
+        help_heading = "Output format options",
+        long_help = "Keep spec and proof functions instead of deleting them. The output is\n\
+                     then no longer plain Rust; meant for pipelines that re-verify the\n\
+                     result later and only want the other construct classes removed."
+    )]
+    pub keep_spec_fns: bool,
+
+    /// Keep requires/ensures/decreases clauses on surviving signatures
+    #[arg(
+        long,
+        conflicts_with_all = ["verify_output", "spec_as_comments"],
+        help_heading = "Output format options",
+        long_help = "Keep requires/ensures/decreases clauses on surviving function\n\
+                     signatures instead of removing them (the output is then no longer\n\
+                     plain Rust). Ghost parameters and mode markers are still stripped."
+    )]
+    pub keep_signature_specs: bool,
+
+    /// Keep ghost and tracked struct/enum fields
+    #[arg(
+        long,
+        conflicts_with = "verify_output",
+        help_heading = "Output format options",
+        long_help = "Keep ghost and tracked struct/enum fields, mode markers included,\n\
+                     instead of removing them (the output is then no longer plain Rust)."
+    )]
+    pub keep_ghost_fields: bool,
+
+    /// Keep proof { ... } blocks and proof-only macro invocations
+    #[arg(
+        long,
+        conflicts_with = "verify_output",
+        help_heading = "Output format options",
+        long_help = "Keep proof { ... } blocks and proof-only macro invocations (calc!,\n\
+                     reveal!, ...) verbatim, including everything inside them (the output\n\
+                     is then no longer plain Rust). Bare assert/assume expressions outside\n\
+                     proof blocks are still stripped."
+    )]
+    pub keep_proof_blocks: bool,
+
+    /// Gate removed spec/proof code behind #[cfg(feature = "FEATURE")]
+    #[arg(
+        long,
+        value_name = "FEATURE",
+        conflicts_with_all = [
+            "attributes_only",
+            "keep_spec_fns",
+            "keep_signature_specs",
+            "keep_ghost_fields",
+            "keep_proof_blocks",
+        ],
+        help_heading = "Output format options",
+        long_help = "Instead of deleting spec/proof functions, proof blocks, ghost fields,\n\
+                     and vstd imports, put #[cfg(feature = \"FEATURE\")] on them, for a\n\
+                     single source tree that both verifies and builds as plain Rust. The\n\
+                     output still has to parse without Verus, so mode keywords and\n\
+                     requires/ensures clauses are removed regardless; the clauses become\n\
+                     doc comments as with --spec-as-comments."
+    )]
+    pub cfg_gate: Option<String>,
+
+    /// What to emit for value-returning functions whose bodies were all proof
+    /// code: error, todo, or unreachable
+    #[arg(
+        long,
+        value_name = "POLICY",
+        help_heading = "Output format options",
+        long_help = "What to do when stripping removes every statement from the body of a\n\
+                     function that returns a value (the stripped body would not compile):\n\n\
+                     error        refuse, naming the function (default)\n\
+                     todo         emit todo!() as the body\n\
+                     unreachable  emit unreachable!() as the body"
+    )]
+    pub empty_body: Option<EmptyBodyPolicy>,
+
+    /// What to do with assert(...) in exec code: remove, debug-assert, or keep
+    #[arg(
+        long,
+        value_name = "STRATEGY",
+        help_heading = "Output format options",
+        long_help = "What becomes of assert(...) expressions in exec code. The condition is\n\
+                     emitted as written, so it must be executable Rust (--verify-output\n\
+                     catches ones that were not); an `assert ... by` proof body is dropped\n\
+                     either way, and assume / assert forall are always removed:\n\n\
+                     remove        drop the assertion entirely (default)\n\
+                     debug-assert  convert to debug_assert!, checked in debug builds\n\
+                     keep          convert to plain assert!, checked in every build"
+    )]
+    pub assert_strategy: Option<AssertStrategy>,
+
+    /// Drop trait method defaults whose bodies were entirely proof code
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "When a trait method's default body was entirely proof code, drop the\n\
+                     default and leave a bare declaration, instead of applying the\n\
+                     --empty-body policy to it. The trait's implementors must then\n\
+                     provide the method themselves."
+    )]
+    pub drop_empty_trait_defaults: bool,
+
+    /// Additional derive name to remove from #[derive(...)] lists (repeatable)
+    #[arg(
+        long,
+        value_name = "NAME",
+        action = clap::ArgAction::Append,
+        help_heading = "Output format options",
+        long_help = "Treat NAME as a Verus-only derive and remove it from #[derive(...)]\n\
+                     lists, alongside the built-in set (Structural). The attribute is\n\
+                     dropped entirely if its list becomes empty. Repeatable:\n\
+                     vstrip --extra-verus-derive MyView --extra-verus-derive MyGhost src/lib.rs"
+    )]
+    pub extra_verus_derive: Vec<String>,
+
+    /// Module whose use module::* glob imports may be removed (repeatable)
+    #[arg(
+        long,
+        value_name = "MODULE",
+        action = clap::ArgAction::Append,
+        help_heading = "Output format options",
+        long_help = "After stripping, use imports whose names no longer appear anywhere are\n\
+                     removed automatically, but a glob import hides what it provides. List\n\
+                     MODULE (a full a::b path or a final segment) to mark it spec-only and\n\
+                     make its glob imports eligible for removal too. Repeatable:\n\
+                     vstrip --known-spec-module spec --known-spec-module crate::ghost src/lib.rs"
+    )]
+    pub known_spec_module: Vec<String>,
+
+    /// Rewrite leftover Verus types (int, nat, Ghost<T>, ...) into plain Rust
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "After stripping, rewrite Verus-only types that survive in signatures\n\
+                     and fields into their nearest plain-Rust equivalents:\n\n\
+                     int -> i64, nat -> u64, Ghost<T> -> T, Tracked<T> -> T,\n\
+                     vstd::...::Name -> Name, and removal of View/Structural bounds.\n\n\
+                     These substitutions change semantics (i64 overflows, int does not),\n\
+                     so they are opt-in."
+    )]
+    pub aggressive_type_fixing: bool,
+
+    /// Write a .sourcemap.json sidecar mapping output positions to the input
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "Next to each written file, write <file>.sourcemap.json recording, for\n\
+                     every surviving named item, its byte offset in the output and its\n\
+                     line/column in the original source. Tools can use it to map rustc\n\
+                     diagnostics on stripped output back to the annotated file. Only\n\
+                     applies when output goes to a file (--in-place or --output)."
+    )]
+    pub emit_source_map: bool,
+
+    /// Write a JSON table mapping output lines to original lines to PATH
+    #[arg(
+        long,
+        value_name = "PATH",
+        help_heading = "Output format options",
+        long_help = "Write one JSON document to PATH mapping, for every written file, the\n\
+                     output line of each surviving item to the line its identifier stood\n\
+                     on in the annotated original, so compiler errors and panics in\n\
+                     stripped code can be traced back to the right function. Item\n\
+                     granularity: positions between items resolve to the nearest\n\
+                     preceding item. Needs a mode that writes files (--in-place,\n\
+                     --output, or --out-dir):\n\
+                     vstrip --recursive --in-place --line-map lines.json src/"
+    )]
+    pub line_map: Option<PathBuf>,
+
+    /// Also strip files pulled in via include!("literal/path.rs")
+    #[arg(
+        long,
+        help_heading = "Processing modes",
+        long_help = "Also process files pulled in by include! invocations whose argument\n\
+                     is a literal path, resolved relative to the including file.\n\
+                     Non-literal forms like include!(concat!(env!(\"OUT_DIR\"), ...)) cannot\n\
+                     be resolved and are reported as warnings. Include cycles are an error."
+    )]
+    pub follow_includes: bool,
+
+    /// Remove only #[verifier::*] attributes, keeping ghost code and specs
+    #[arg(
+        long,
+        help_heading = "Processing modes",
+        long_help = "Remove only #[verifier::*] attributes, leaving function bodies, ghost\n\
+                     code, and spec clauses intact. Useful when partially migrating a\n\
+                     codebase off Verus."
+    )]
+    pub attributes_only: bool,
+
+    /// Keep #[verifier::*] attributes in the stripped output
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "By default a full strip also removes #[verifier::*] attributes, which\n\
+                     mean nothing without Verus. Pass this flag to keep them, for tooling\n\
+                     that still reads them."
+    )]
+    pub keep_verifier_attrs: bool,
+
+    /// Follow symbolic links when walking directories
+    #[arg(long, help_heading = "Advanced options")]
+    pub follow_links: bool,
+
+    /// Process files on N threads with --recursive (0 = all available cores)
+    #[arg(
+        short,
+        long = "jobs",
+        value_name = "N",
+        conflicts_with = "cache",
+        help_heading = "Advanced options",
+        long_help = "Process the files found by --recursive in parallel on N threads;\n\
+                     0 means one per available core. Diagnostics from different files\n\
+                     may interleave. Incompatible with --cache, whose updates are\n\
+                     sequential:\n\
+                     vstrip --check --recursive --jobs 0 src/"
+    )]
+    pub jobs: Option<usize>,
+
+    /// Increase verbosity (-v: per-file progress, -vv: per-item detail)
+    #[arg(
+        short,
+        long = "verbose",
+        action = clap::ArgAction::Count,
+        help_heading = "Advanced options"
+    )]
+    pub verbose: u8,
+
+    /// Suppress warnings; only errors are printed
+    #[arg(short, long, conflicts_with = "verbose", help_heading = "Advanced options")]
+    pub quiet: bool,
+
+    /// Report public API changes caused by stripping (text or json)
+    #[arg(
+        long,
+        value_name = "FORMAT",
+        help_heading = "Output format options",
+        long_help = "After stripping, compare the public items and signatures of the input\n\
+                     and output, and print a report to stderr classifying each item as\n\
+                     removed-item, changed-signature, removed-field, or unchanged.\n\
+                     FORMAT is text or json."
+    )]
+    pub api_diff: Option<ApiDiffFormat>,
+
+    /// Fail if stripping changed any surviving public signature
+    #[arg(
+        long,
+        help_heading = "Output format options",
+        long_help = "Fail if stripping changed the signature of any surviving public item\n\
+                     (e.g. removed ghost parameters changed a function's arity). Removed\n\
+                     items are not fatal: spec and proof functions always disappear."
+    )]
+    pub fail_on_api_change: bool,
+
+    /// Cache file for skipping unchanged sources across invocations
+    #[arg(
+        long,
+        value_name = "FILE",
+        help_heading = "Advanced options",
+        long_help = "Record each processed file's mtime and content hash in FILE (JSON)\n\
+                     and skip files that are unchanged on subsequent runs. The file is\n\
+                     created on first use:\n\
+                     vstrip --in-place --recursive --cache .vstrip-cache.json src/"
+    )]
+    pub cache: Option<PathBuf>,
+}
+
+impl StripArgs {
+    /// Turn the parsed flags into one configuration layer: flags that were
+    /// not given stay `None` and inherit from the layer below (a discovered
+    /// `.vstrip.toml`, then the defaults). The input-selection fields are
+    /// left unset for the binary to fill in.
+    pub fn layer(self) -> PartialConfig {
+        PartialConfig {
+            output: self.output,
+            out_dir: self.out_dir,
+            copy_assets: self.copy_assets.then_some(true),
+            in_place: self.in_place.then_some(true),
+            backup: self.backup,
+            force_backup: self.force_backup.then_some(true),
+            remove_empty: self.remove_empty.then_some(true),
+            check: self.check.then_some(true),
+            diff: self.diff.then_some(true),
+            check_idempotent: self.check_idempotent.then_some(true),
+            verify_output: self.verify_output.then_some(true),
+            deny_warnings: self.deny_warnings.then_some(true),
+            strict: self.strict.then_some(true),
+            count: self.count.then_some(true),
+            fail_fast: self.fail_fast.then_some(true),
+            json_diagnostics: self.json.then_some(true),
+            spec_as_comments: self.spec_as_comments.then_some(true),
+            keep_empty_items: self.keep_empty_items.then_some(true),
+            remove_empty_traits: self.remove_empty_traits.then_some(true),
+            keep_spec_fns: self.keep_spec_fns.then_some(true),
+            keep_signature_specs: self.keep_signature_specs.then_some(true),
+            keep_ghost_fields: self.keep_ghost_fields.then_some(true),
+            keep_proof_blocks: self.keep_proof_blocks.then_some(true),
+            cfg_gate: self.cfg_gate,
+            follow_links: self.follow_links.then_some(true),
+            include_globs: (!self.include.is_empty()).then_some(self.include),
+            exclude_globs: (!self.exclude.is_empty()).then_some(self.exclude),
+            empty_body: self.empty_body,
+            assert_strategy: self.assert_strategy,
+            drop_empty_trait_defaults: self.drop_empty_trait_defaults.then_some(true),
+            attributes_only: self.attributes_only.then_some(true),
+            strip_verifier_attrs: self.keep_verifier_attrs.then_some(false),
+            extra_verus_derives: (!self.extra_verus_derive.is_empty())
+                .then_some(self.extra_verus_derive),
+            known_spec_modules: (!self.known_spec_module.is_empty())
+                .then_some(self.known_spec_module),
+            aggressive_type_fixing: self.aggressive_type_fixing.then_some(true),
+            emit_source_map: self.emit_source_map.then_some(true),
+            line_map: self.line_map,
+            cache: self.cache,
+            parallel_jobs: self.jobs,
+            follow_includes: self.follow_includes.then_some(true),
+            stats: self.stats,
+            list_removed: self.list_removed.then_some(true),
+            as_modules: self.as_modules.then_some(true),
+            api_diff: self.api_diff,
+            fail_on_api_change: self.fail_on_api_change.then_some(true),
+            message_format: self.message_format,
+            verbosity: (self.verbose > 0).then_some(self.verbose),
+            quiet: self.quiet.then_some(true),
+            ..PartialConfig::default()
+        }
+    }
+}
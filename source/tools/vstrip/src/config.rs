@@ -0,0 +1,1296 @@
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, StripError};
+
+/// Options controlling what `vstrip` strips and where the output goes.
+///
+/// The CLI in `main.rs` builds one of these from command-line flags; library
+/// users can construct one directly and pass it to [`crate::process`] or the
+/// `strip_*` entry points.
+#[derive(Debug, Clone)]
+pub struct Config {
+    /// File or directory to process.
+    pub input: PathBuf,
+    /// Write output to this path instead of stdout.
+    pub output: Option<PathBuf>,
+    /// In recursive mode, mirror the input directory's structure under this
+    /// directory instead of rewriting in place: `src/foo/bar.rs` is stripped
+    /// into `<out_dir>/foo/bar.rs`, with intermediate directories created as
+    /// needed. Must not lie inside the input directory.
+    pub out_dir: Option<PathBuf>,
+    /// Copy non-`.rs` files encountered by the recursive walk into `out_dir`
+    /// verbatim, so the output tree is usable without the original.
+    pub copy_assets: bool,
+    /// Rewrite input files in place.
+    pub in_place: bool,
+    /// Before an in-place rewrite, copy the original to a sibling file with
+    /// this suffix appended (the CLI default is `.orig`).
+    pub backup: Option<String>,
+    /// Overwrite an existing backup file instead of refusing to proceed.
+    pub force_backup: bool,
+    /// Recurse into directories, processing every `.rs` file.
+    pub recursive: bool,
+    /// After a recursive or package run that writes files, delete output
+    /// files stripping left without any items and drop the parent module
+    /// file's matching `mod name;` declaration. A `pub mod` declaration is
+    /// part of the crate's API, so that module is kept and flagged with a
+    /// warning instead. Requires [`Config::in_place`] or [`Config::out_dir`].
+    pub remove_empty: bool,
+    /// Treat `input` as a Cargo package root: read its `Cargo.toml` and
+    /// process exactly the sources belonging to the package — declared and
+    /// conventional targets, the module files they reach through `mod`
+    /// declarations, and path dependencies, recursively; see
+    /// [`crate::package`].
+    pub package: bool,
+    /// Read the files to process from this manifest instead of walking a
+    /// directory — one path per line, blank lines and `#` comments ignored,
+    /// relative paths resolved against the manifest's directory, `-` for
+    /// stdin. For build systems that already know the file set and pass it
+    /// as a response file. Excludes `input`, `recursive`, and `package`.
+    pub files_from: Option<PathBuf>,
+    /// Parse, strip, and validate without writing output, and fail if
+    /// stripping would change any file (compared modulo formatting).
+    pub check: bool,
+    /// Print a unified diff between each source and its stripped output
+    /// instead of the output itself, and fail if any file would change.
+    /// Combinable with [`Config::check`], which it subsumes: the diff is
+    /// shown and the run still exits non-zero on any change.
+    pub diff: bool,
+    /// Strip each output a second time and fail if the two passes disagree;
+    /// a CI guard against unstable strip logic.
+    pub check_idempotent: bool,
+    /// Re-parse each stripped output with the standard `syn` crate before
+    /// anything is written, and fail with
+    /// [`crate::StripError::InvalidOutput`] — leaving the original file
+    /// untouched — if it is not valid plain Rust.
+    pub verify_output: bool,
+    /// Treat any [`crate::warning::Warning`] as fatal: stripping fails with
+    /// [`crate::StripError::WarningsDenied`] before anything is written,
+    /// instead of printing the warnings and continuing.
+    pub deny_warnings: bool,
+    /// Fail with [`crate::StripError::UnsupportedConstructs`] when the
+    /// visitor meets a Verus-only construct it has no handling for (a
+    /// `state_machine!` invocation, say), instead of warning and passing it
+    /// through into output that will not compile. Narrower than
+    /// `deny_warnings`, which makes every warning fatal.
+    pub strict: bool,
+    /// Parse the input and report spec-versus-exec density metrics — lines
+    /// and items of specification code against executable code, per file and
+    /// in total — instead of stripping anything; see [`crate::count`]. The
+    /// report goes to stdout as a text table, or as a JSON array under a
+    /// JSON [`Config::message_format`]. Excludes every mode that writes or
+    /// occupies stdout.
+    pub count: bool,
+    /// Stop a directory or package run at the first file that fails,
+    /// propagating its error, instead of processing the remaining files and
+    /// collecting every failure into
+    /// [`crate::StripError::MultipleErrors`]. Sequential only: workers
+    /// already in flight cannot be recalled, so `parallel_jobs` excludes it.
+    pub fail_fast: bool,
+    /// Write per-file results to stdout as a JSON array in the stable
+    /// [`crate::diagnostics`] schema, instead of human-readable diagnostics.
+    pub json_diagnostics: bool,
+    /// Render stripped `requires`/`ensures`/`decreases` clauses as doc
+    /// comments on the surviving function, and loop annotations
+    /// (`invariant`, `decreases`, ...) as doc comments on the surviving
+    /// loop, instead of dropping them entirely.
+    pub spec_as_comments: bool,
+    /// Keep impl blocks and inline modules whose contents were entirely
+    /// removed, instead of deleting the empty shell.
+    pub keep_empty_items: bool,
+    /// Also delete trait definitions whose members were all spec/proof
+    /// declarations, and trait impls stripping emptied. Both are kept by
+    /// default: an emptied trait is still crate API, and an empty
+    /// `impl Marker for T {}` is meaningful.
+    pub remove_empty_traits: bool,
+    /// Keep `spec fn`s and `proof fn`s instead of deleting them. The output
+    /// is then no longer plain Rust; meant for consumers that re-verify the
+    /// result later and only want the other classes removed.
+    pub keep_spec_fns: bool,
+    /// Keep `requires`/`ensures`/`decreases` clauses on surviving function
+    /// signatures (also not plain Rust).
+    pub keep_signature_specs: bool,
+    /// Keep ghost and tracked struct/enum fields, mode markers included.
+    pub keep_ghost_fields: bool,
+    /// Keep `proof { ... }` blocks and proof-only macro invocations,
+    /// verbatim: nothing inside a kept block is stripped either.
+    pub keep_proof_blocks: bool,
+    /// Instead of deleting spec/proof functions, proof blocks, ghost fields,
+    /// and vstd imports, gate them behind `#[cfg(feature = "<name>")]`, for a
+    /// single source tree that both verifies and builds as plain Rust. The
+    /// gated code still has to parse without Verus, so mode keywords and
+    /// signature clauses are removed regardless (the clauses become doc
+    /// comments, as under [`Config::spec_as_comments`]).
+    pub cfg_gate: Option<String>,
+    /// Follow symbolic links when walking directories.
+    pub follow_links: bool,
+    /// Walk hidden directories and disregard `.gitignore`/`.ignore`/
+    /// `.verusignore` files, which recursive walks otherwise honor; see
+    /// [`crate::walk`].
+    pub no_ignore: bool,
+    /// Glob patterns a file must match (relative to the input directory) to
+    /// be processed by a recursive walk; empty means every `.rs` file.
+    pub include_globs: Vec<String>,
+    /// Glob patterns that exclude files from a recursive walk; exclude wins
+    /// over include.
+    pub exclude_globs: Vec<String>,
+    /// What to do when stripping removes every statement from the body of a
+    /// function that returns a value, which would not compile as written.
+    pub empty_body: EmptyBodyPolicy,
+    /// What becomes of `assert(...)` expressions in exec code: removed (the
+    /// default), converted to `debug_assert!`, or kept as plain `assert!`.
+    /// `assume` and `assert forall` have no runtime meaning and are always
+    /// removed.
+    pub assert_strategy: AssertStrategy,
+    /// When a trait method's default body was entirely proof code, drop the
+    /// default and leave a bare declaration instead of applying
+    /// [`Config::empty_body`] to it.
+    pub drop_empty_trait_defaults: bool,
+    /// Remove only `#[verifier::*]` attributes, leaving bodies, ghost code,
+    /// and spec clauses intact.
+    pub attributes_only: bool,
+    /// Remove `#[verifier::*]` attributes during a full strip too; they are
+    /// meaningless without Verus. On by default; turn off to keep them for
+    /// compatibility with tooling that still reads them.
+    pub strip_verifier_attrs: bool,
+    /// Derive identifiers to treat as Verus-only and remove from
+    /// `#[derive(...)]` lists, in addition to the built-in set
+    /// (`Structural`).
+    pub extra_verus_derives: Vec<String>,
+    /// Modules whose `use module::*` glob imports may be removed by the
+    /// post-strip import cleanup (see [`crate::cleanup`]); a glob hides what
+    /// it provides, so only listed modules are eligible. Each entry matches
+    /// a full `a::b` path or a final segment.
+    pub known_spec_modules: Vec<String>,
+    /// After stripping, rewrite leftover Verus types into plain-Rust
+    /// equivalents (`int` → `i64`, `Ghost<T>` → `T`, …); see
+    /// [`crate::type_fix`]. Not semantics-preserving, hence opt-in.
+    pub aggressive_type_fixing: bool,
+    /// Write a `<output>.sourcemap.json` sidecar next to each written file,
+    /// mapping output positions back to the original source; see
+    /// [`crate::sourcemap`].
+    pub emit_source_map: bool,
+    /// Write one JSON document to this path mapping, per written file,
+    /// output line numbers to the original lines of the surviving items, so
+    /// compiler errors and panics in stripped code can be traced back to the
+    /// annotated source; see [`crate::sourcemap::LineMap`]. Requires a mode
+    /// that writes files.
+    pub line_map: Option<PathBuf>,
+    /// Path of an [`crate::cache::IncrementalCache`] file; sources unchanged
+    /// since the cached run are skipped.
+    pub cache: Option<PathBuf>,
+    /// Process directory entries in parallel on this many threads; `None`
+    /// keeps processing single-threaded and `Some(0)` uses all available
+    /// cores. Only directory walks parallelize. Mutually exclusive with
+    /// `cache`, whose updates are inherently sequential.
+    pub parallel_jobs: Option<usize>,
+    /// Also process files pulled in by `include!` invocations with literal
+    /// paths, resolved relative to the including file.
+    pub follow_includes: bool,
+    /// Print per-file and aggregate statistics on the removed constructs to
+    /// stdout, in the given format; see [`crate::stats`].
+    pub stats: Option<StatsFormat>,
+    /// After each file, print the removed items by name (`spec fn foo`,
+    /// `ghost field Baz::field`, ...) to stdout under the file name; files
+    /// with nothing removed print nothing.
+    pub list_removed: bool,
+    /// With `recursive` or `package` output going to stdout, wrap each
+    /// file's items in `mod` blocks derived from its path
+    /// (`math/proofs.rs` becomes `pub mod math { pub mod proofs { ... } }`),
+    /// so a whole stripped crate concatenates into one reviewable file.
+    pub as_modules: bool,
+    /// Report how stripping changed the public API surface, in the given
+    /// format.
+    pub api_diff: Option<ApiDiffFormat>,
+    /// Fail if stripping changed any surviving public signature (removed
+    /// items are expected — spec functions always disappear — but a changed
+    /// signature means downstream callers must adapt).
+    pub fail_on_api_change: bool,
+    /// How errors and warnings are rendered: human-readable text (the
+    /// default) or one JSON object per stderr line for editor integration.
+    pub message_format: MessageFormat,
+    /// Number of `-v` flags: 0 shows errors and warnings, 1 adds per-file
+    /// progress, 2 adds per-item detail. Only consulted by the default
+    /// stderr reporter; [`crate::process_with_reporter`] ignores it.
+    pub verbosity: u8,
+    /// Suppress warnings as well; only errors reach stderr. Like
+    /// `verbosity`, only consulted by the default stderr reporter.
+    pub quiet: bool,
+}
+
+impl Default for Config {
+    fn default() -> Config {
+        Config {
+            input: PathBuf::new(),
+            output: None,
+            out_dir: None,
+            copy_assets: false,
+            in_place: false,
+            backup: None,
+            force_backup: false,
+            recursive: false,
+            remove_empty: false,
+            package: false,
+            files_from: None,
+            check: false,
+            diff: false,
+            check_idempotent: false,
+            verify_output: false,
+            deny_warnings: false,
+            strict: false,
+            count: false,
+            fail_fast: false,
+            json_diagnostics: false,
+            spec_as_comments: false,
+            keep_empty_items: false,
+            remove_empty_traits: false,
+            keep_spec_fns: false,
+            keep_signature_specs: false,
+            keep_ghost_fields: false,
+            keep_proof_blocks: false,
+            cfg_gate: None,
+            follow_links: false,
+            no_ignore: false,
+            include_globs: Vec::new(),
+            exclude_globs: Vec::new(),
+            empty_body: EmptyBodyPolicy::Error,
+            assert_strategy: AssertStrategy::Remove,
+            drop_empty_trait_defaults: false,
+            attributes_only: false,
+            strip_verifier_attrs: true,
+            extra_verus_derives: Vec::new(),
+            known_spec_modules: Vec::new(),
+            aggressive_type_fixing: false,
+            emit_source_map: false,
+            line_map: None,
+            cache: None,
+            parallel_jobs: None,
+            follow_includes: false,
+            stats: None,
+            list_removed: false,
+            as_modules: false,
+            api_diff: None,
+            fail_on_api_change: false,
+            message_format: MessageFormat::Text,
+            verbosity: 0,
+            quiet: false,
+        }
+    }
+}
+
+/// Chainable [`Config`] construction with build-time validation.
+///
+/// Filling in `Config` fields directly cannot reject contradictory
+/// combinations; the CLI relies on clap for that, and library users would
+/// otherwise have to duplicate those rules. `build` enforces them instead:
+/// an input must be set, `in_place` excludes `output`, `follow_includes`
+/// (which writes multiple files) excludes `output` too, `diff` (which
+/// writes nothing) excludes `in_place`, `output`, and `check`,
+/// `parallel_jobs` excludes `cache`, `out_dir` requires `recursive` or
+/// `package` while excluding `in_place`, `output`, and `follow_includes`,
+/// and `package` (which derives its file set from `Cargo.toml`) excludes
+/// `recursive`, `output`, and `copy_assets`.
+///
+/// `ConfigBuilder::default()` starts from exactly [`Config::default()`].
+#[derive(Debug, Clone, Default)]
+pub struct ConfigBuilder {
+    config: Config,
+}
+
+impl ConfigBuilder {
+    /// Start a builder for processing `input`.
+    pub fn new(input: impl Into<PathBuf>) -> ConfigBuilder {
+        ConfigBuilder::default().input(input)
+    }
+
+    pub fn input(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.input = path.into();
+        self
+    }
+
+    pub fn output(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.output = Some(path.into());
+        self
+    }
+
+    /// Mirror the input tree into `dir` (recursive mode only).
+    pub fn out_dir(mut self, dir: impl Into<PathBuf>) -> Self {
+        self.config.out_dir = Some(dir.into());
+        self
+    }
+
+    pub fn copy_assets(mut self) -> Self {
+        self.config.copy_assets = true;
+        self
+    }
+
+    pub fn in_place(mut self) -> Self {
+        self.config.in_place = true;
+        self
+    }
+
+    /// Keep a copy of each rewritten original under this suffix.
+    pub fn backup(mut self, suffix: impl Into<String>) -> Self {
+        self.config.backup = Some(suffix.into());
+        self
+    }
+
+    pub fn force_backup(mut self) -> Self {
+        self.config.force_backup = true;
+        self
+    }
+
+    pub fn recursive(mut self) -> Self {
+        self.config.recursive = true;
+        self
+    }
+
+    /// Delete output files stripping left empty, and the `mod` declarations
+    /// pointing at them.
+    pub fn remove_empty(mut self) -> Self {
+        self.config.remove_empty = true;
+        self
+    }
+
+    /// Treat the input as a Cargo package root; see [`crate::package`].
+    pub fn package(mut self) -> Self {
+        self.config.package = true;
+        self
+    }
+
+    /// Process exactly the files this manifest lists instead of walking a
+    /// directory; `-` reads the list from stdin.
+    pub fn files_from(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.files_from = Some(path.into());
+        self
+    }
+
+    pub fn check(mut self) -> Self {
+        self.config.check = true;
+        self
+    }
+
+    pub fn diff(mut self) -> Self {
+        self.config.diff = true;
+        self
+    }
+
+    pub fn check_idempotent(mut self) -> Self {
+        self.config.check_idempotent = true;
+        self
+    }
+
+    pub fn verify_output(mut self) -> Self {
+        self.config.verify_output = true;
+        self
+    }
+
+    pub fn deny_warnings(mut self) -> Self {
+        self.config.deny_warnings = true;
+        self
+    }
+
+    /// Fail on Verus-only constructs the visitor cannot handle.
+    pub fn strict(mut self) -> Self {
+        self.config.strict = true;
+        self
+    }
+
+    /// Report spec-versus-exec density metrics instead of stripping.
+    pub fn count(mut self) -> Self {
+        self.config.count = true;
+        self
+    }
+
+    pub fn fail_fast(mut self) -> Self {
+        self.config.fail_fast = true;
+        self
+    }
+
+    pub fn json_diagnostics(mut self) -> Self {
+        self.config.json_diagnostics = true;
+        self
+    }
+
+    pub fn spec_as_comments(mut self) -> Self {
+        self.config.spec_as_comments = true;
+        self
+    }
+
+    pub fn keep_empty_items(mut self) -> Self {
+        self.config.keep_empty_items = true;
+        self
+    }
+
+    /// Also delete emptied trait definitions and trait impls.
+    pub fn remove_empty_traits(mut self) -> Self {
+        self.config.remove_empty_traits = true;
+        self
+    }
+
+    /// Keep `spec fn`s and `proof fn`s instead of deleting them.
+    pub fn keep_spec_fns(mut self) -> Self {
+        self.config.keep_spec_fns = true;
+        self
+    }
+
+    /// Keep `requires`/`ensures`/`decreases` clauses on surviving signatures.
+    pub fn keep_signature_specs(mut self) -> Self {
+        self.config.keep_signature_specs = true;
+        self
+    }
+
+    /// Keep ghost and tracked struct/enum fields.
+    pub fn keep_ghost_fields(mut self) -> Self {
+        self.config.keep_ghost_fields = true;
+        self
+    }
+
+    /// Keep `proof { ... }` blocks and proof-only macro invocations.
+    pub fn keep_proof_blocks(mut self) -> Self {
+        self.config.keep_proof_blocks = true;
+        self
+    }
+
+    /// Gate removed spec/proof code behind `#[cfg(feature = "<feature>")]`
+    /// instead of deleting it.
+    pub fn cfg_gate(mut self, feature: impl Into<String>) -> Self {
+        self.config.cfg_gate = Some(feature.into());
+        self
+    }
+
+    pub fn follow_links(mut self) -> Self {
+        self.config.follow_links = true;
+        self
+    }
+
+    pub fn no_ignore(mut self) -> Self {
+        self.config.no_ignore = true;
+        self
+    }
+
+    /// Add one include pattern; may be called repeatedly.
+    pub fn include_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.config.include_globs.push(pattern.into());
+        self
+    }
+
+    /// Add one exclude pattern; may be called repeatedly.
+    pub fn exclude_glob(mut self, pattern: impl Into<String>) -> Self {
+        self.config.exclude_globs.push(pattern.into());
+        self
+    }
+
+    pub fn assert_strategy(mut self, strategy: AssertStrategy) -> Self {
+        self.config.assert_strategy = strategy;
+        self
+    }
+
+    pub fn empty_body(mut self, policy: EmptyBodyPolicy) -> Self {
+        self.config.empty_body = policy;
+        self
+    }
+
+    pub fn drop_empty_trait_defaults(mut self) -> Self {
+        self.config.drop_empty_trait_defaults = true;
+        self
+    }
+
+    pub fn attributes_only(mut self) -> Self {
+        self.config.attributes_only = true;
+        self
+    }
+
+    /// Whether a full strip also removes `#[verifier::*]` attributes
+    /// (the default); pass `false` to keep them.
+    pub fn strip_verifier_attrs(mut self, strip: bool) -> Self {
+        self.config.strip_verifier_attrs = strip;
+        self
+    }
+
+    /// Add one derive name to treat as Verus-only; may be called repeatedly.
+    pub fn extra_verus_derive(mut self, name: impl Into<String>) -> Self {
+        self.config.extra_verus_derives.push(name.into());
+        self
+    }
+
+    /// Add one module whose glob imports the import cleanup may remove; may
+    /// be called repeatedly.
+    pub fn known_spec_module(mut self, name: impl Into<String>) -> Self {
+        self.config.known_spec_modules.push(name.into());
+        self
+    }
+
+    pub fn aggressive_type_fixing(mut self) -> Self {
+        self.config.aggressive_type_fixing = true;
+        self
+    }
+
+    pub fn emit_source_map(mut self) -> Self {
+        self.config.emit_source_map = true;
+        self
+    }
+
+    /// Write an output-line-to-original-line table for every written file to
+    /// `path`; see [`crate::sourcemap::LineMap`].
+    pub fn line_map(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.line_map = Some(path.into());
+        self
+    }
+
+    pub fn cache(mut self, path: impl Into<PathBuf>) -> Self {
+        self.config.cache = Some(path.into());
+        self
+    }
+
+    /// Process directories on `jobs` threads; `0` means all available cores.
+    pub fn parallel_jobs(mut self, jobs: usize) -> Self {
+        self.config.parallel_jobs = Some(jobs);
+        self
+    }
+
+    pub fn follow_includes(mut self) -> Self {
+        self.config.follow_includes = true;
+        self
+    }
+
+    pub fn stats(mut self, format: StatsFormat) -> Self {
+        self.config.stats = Some(format);
+        self
+    }
+
+    pub fn as_modules(mut self) -> Self {
+        self.config.as_modules = true;
+        self
+    }
+
+    pub fn list_removed(mut self) -> Self {
+        self.config.list_removed = true;
+        self
+    }
+
+    pub fn api_diff(mut self, format: ApiDiffFormat) -> Self {
+        self.config.api_diff = Some(format);
+        self
+    }
+
+    pub fn fail_on_api_change(mut self) -> Self {
+        self.config.fail_on_api_change = true;
+        self
+    }
+
+    pub fn message_format(mut self, format: MessageFormat) -> Self {
+        self.config.message_format = format;
+        self
+    }
+
+    pub fn verbosity(mut self, verbosity: u8) -> Self {
+        self.config.verbosity = verbosity;
+        self
+    }
+
+    pub fn quiet(mut self) -> Self {
+        self.config.quiet = true;
+        self
+    }
+
+    /// Validate the combination of options and hand back the [`Config`].
+    pub fn build(self) -> Result<Config> {
+        self.config.validate()?;
+        Ok(self.config)
+    }
+}
+
+impl Config {
+    /// Check this configuration's invariants: every rule the builder
+    /// enforces, for configs assembled directly or from layered
+    /// sources. [`crate::process`] calls this at entry, so an invalid
+    /// combination fails up front instead of part-way through a tree.
+    pub fn validate(&self) -> Result<()> {
+        if self.input.as_os_str().is_empty() && self.files_from.is_none() {
+            return Err(StripError::ConfigError("no input path was set".to_string()));
+        }
+        if self.files_from.is_some() {
+            if !self.input.as_os_str().is_empty() {
+                return Err(StripError::ConfigError(
+                    "files_from supplies the file list itself; an input path does not apply"
+                        .to_string(),
+                ));
+            }
+            if self.recursive || self.package {
+                return Err(StripError::ConfigError(
+                    "files_from supplies the file list itself; recursive and package do \
+                     not apply"
+                        .to_string(),
+                ));
+            }
+            if self.output.is_some() {
+                return Err(StripError::ConfigError(
+                    "files_from processes multiple files and cannot be combined with output"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.count {
+            if self.in_place || self.output.is_some() || self.out_dir.is_some() {
+                return Err(StripError::ConfigError(
+                    "count reports metrics and writes no output; the output modes do not \
+                     apply"
+                        .to_string(),
+                ));
+            }
+            if self.check || self.diff {
+                return Err(StripError::ConfigError(
+                    "count does not strip, so check and diff have nothing to compare"
+                        .to_string(),
+                ));
+            }
+            if self.stats.is_some() || self.list_removed || self.json_diagnostics {
+                return Err(StripError::ConfigError(
+                    "count occupies stdout with its own report; stats, list_removed, and \
+                     json_diagnostics do not apply"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.in_place && self.output.is_some() {
+            return Err(StripError::ConfigError(
+                "in_place and output are mutually exclusive".to_string(),
+            ));
+        }
+        if self.follow_includes && self.output.is_some() {
+            return Err(StripError::ConfigError(
+                "follow_includes writes multiple files and cannot be combined with output \
+                 (use in_place)"
+                    .to_string(),
+            ));
+        }
+        if self.package {
+            if self.recursive {
+                return Err(StripError::ConfigError(
+                    "package discovers the package's file set itself; recursive does not apply"
+                        .to_string(),
+                ));
+            }
+            if self.output.is_some() {
+                return Err(StripError::ConfigError(
+                    "package processes multiple files and cannot be combined with output"
+                        .to_string(),
+                ));
+            }
+            if self.copy_assets {
+                return Err(StripError::ConfigError(
+                    "copy_assets only applies to recursive walks".to_string(),
+                ));
+            }
+        }
+        if self.out_dir.is_some() {
+            if !self.recursive && !self.package {
+                return Err(StripError::ConfigError(
+                    "out_dir only applies to recursive or package runs".to_string(),
+                ));
+            }
+            if self.in_place || self.output.is_some() {
+                return Err(StripError::ConfigError(
+                    "out_dir is mutually exclusive with in_place and output".to_string(),
+                ));
+            }
+            if self.follow_includes {
+                return Err(StripError::ConfigError(
+                    "follow_includes can reach files outside the input directory, which \
+                     have no place under out_dir"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.copy_assets && self.out_dir.is_none() {
+            return Err(StripError::ConfigError(
+                "copy_assets only applies with out_dir".to_string(),
+            ));
+        }
+        if self.backup.is_some() && !self.in_place {
+            return Err(StripError::ConfigError(
+                "backup only applies to in_place rewrites".to_string(),
+            ));
+        }
+        if self.remove_empty {
+            if !self.recursive && !self.package {
+                return Err(StripError::ConfigError(
+                    "remove_empty only applies to recursive or package runs".to_string(),
+                ));
+            }
+            if !self.in_place && self.out_dir.is_none() {
+                return Err(StripError::ConfigError(
+                    "remove_empty deletes written files, so it needs in_place or out_dir"
+                        .to_string(),
+                ));
+            }
+            if self.check {
+                return Err(StripError::ConfigError(
+                    "check writes nothing, so remove_empty has nothing to delete".to_string(),
+                ));
+            }
+        }
+        if self.as_modules {
+            if !self.recursive && !self.package {
+                return Err(StripError::ConfigError(
+                    "as_modules only applies to recursive or package runs".to_string(),
+                ));
+            }
+            if self.in_place || self.out_dir.is_some() || self.check || self.diff {
+                return Err(StripError::ConfigError(
+                    "as_modules wraps concatenated stdout output, so it needs a mode that \
+                     prints to stdout"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.line_map.is_some()
+            && !self.in_place
+            && self.output.is_none()
+            && self.out_dir.is_none()
+        {
+            return Err(StripError::ConfigError(
+                "line_map maps lines of written output, so it needs in_place, output, \
+                 or out_dir"
+                    .to_string(),
+            ));
+        }
+        if self.remove_empty_traits && self.keep_empty_items {
+            return Err(StripError::ConfigError(
+                "keep_empty_items keeps hollow shells and remove_empty_traits deletes \
+                 more of them; pick one"
+                    .to_string(),
+            ));
+        }
+        if self.parallel_jobs.is_some() && self.fail_fast {
+            return Err(StripError::ConfigError(
+                "fail_fast needs sequential processing; workers already in flight cannot \
+                 be recalled"
+                    .to_string(),
+            ));
+        }
+        if self.parallel_jobs.is_some() && self.cache.is_some() {
+            return Err(StripError::ConfigError(
+                "--jobs and --cache are mutually exclusive (cache updates are \
+                 sequential)"
+                    .to_string(),
+            ));
+        }
+        if self.quiet && self.verbosity > 0 {
+            return Err(StripError::ConfigError(
+                "quiet and verbosity pull in opposite directions; pick one".to_string(),
+            ));
+        }
+        if self.verify_output && self.attributes_only {
+            return Err(StripError::ConfigError(
+                "verify_output cannot be combined with attributes_only, whose output \
+                 deliberately keeps Verus-only code"
+                    .to_string(),
+            ));
+        }
+        if self.verify_output
+            && (self.keep_spec_fns
+                || self.keep_signature_specs
+                || self.keep_ghost_fields
+                || self.keep_proof_blocks)
+        {
+            return Err(StripError::ConfigError(
+                "verify_output cannot be combined with the keep_* selections, whose output \
+                 deliberately retains Verus-only syntax"
+                    .to_string(),
+            ));
+        }
+        if self.cfg_gate.is_some() {
+            if self.attributes_only {
+                return Err(StripError::ConfigError(
+                    "attributes_only leaves everything in place; cfg_gate does not apply"
+                        .to_string(),
+                ));
+            }
+            if self.keep_spec_fns
+                || self.keep_signature_specs
+                || self.keep_ghost_fields
+                || self.keep_proof_blocks
+            {
+                return Err(StripError::ConfigError(
+                    "the keep_* selections retain code unconditionally; cfg_gate already \
+                     retains it behind a feature"
+                        .to_string(),
+                ));
+            }
+        }
+        if self.spec_as_comments && self.keep_signature_specs {
+            return Err(StripError::ConfigError(
+                "spec_as_comments renders removed clauses; with keep_signature_specs \
+                 nothing is removed, so the two make no sense together"
+                    .to_string(),
+            ));
+        }
+        if self.stats.is_some() && self.json_diagnostics {
+            return Err(StripError::ConfigError(
+                "stats and json_diagnostics both write a JSON document to stdout; pick one"
+                    .to_string(),
+            ));
+        }
+        if self.diff
+            && (self.in_place
+                || self.output.is_some()
+                || self.out_dir.is_some())
+        {
+            return Err(StripError::ConfigError(
+                "diff prints what would change and writes nothing; it cannot be combined \
+                 with in_place, output, or out_dir"
+                    .to_string(),
+            ));
+        }
+        Ok(())
+    }
+}
+
+/// One layer of configuration, with every field optional.
+///
+/// Build systems assemble the effective [`Config`] from several sources —
+/// built-in defaults, a project file, the environment, the command line —
+/// and a fully-built `Config` cannot say which of its fields were chosen
+/// deliberately and which are just defaults. A `PartialConfig` records
+/// exactly the fields a layer set: `None` means "inherit from the layer
+/// below". For the fields that are themselves optional (`output`, `out_dir`,
+/// `files_from`, `cfg_gate`, `cache`, `stats`, `api_diff`) a layer can set a
+/// value but not clear one set below it.
+///
+/// This is also the schema of `.vstrip.toml` project files (see
+/// [`PartialConfig::from_toml`]): each field is a top-level key of the same
+/// name, and omitted keys inherit, so a file only states what it wants
+/// changed. Unknown keys are rejected rather than silently dropped, since a
+/// typoed option that does nothing is worse than an error.
+#[derive(Debug, Clone, Default, serde::Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PartialConfig {
+    pub input: Option<PathBuf>,
+    pub output: Option<PathBuf>,
+    pub out_dir: Option<PathBuf>,
+    pub copy_assets: Option<bool>,
+    pub in_place: Option<bool>,
+    pub backup: Option<String>,
+    pub force_backup: Option<bool>,
+    pub recursive: Option<bool>,
+    pub remove_empty: Option<bool>,
+    pub package: Option<bool>,
+    pub files_from: Option<PathBuf>,
+    pub check: Option<bool>,
+    pub diff: Option<bool>,
+    pub check_idempotent: Option<bool>,
+    pub verify_output: Option<bool>,
+    pub deny_warnings: Option<bool>,
+    pub strict: Option<bool>,
+    pub count: Option<bool>,
+    pub fail_fast: Option<bool>,
+    pub json_diagnostics: Option<bool>,
+    pub spec_as_comments: Option<bool>,
+    pub keep_empty_items: Option<bool>,
+    pub remove_empty_traits: Option<bool>,
+    pub keep_spec_fns: Option<bool>,
+    pub keep_signature_specs: Option<bool>,
+    pub keep_ghost_fields: Option<bool>,
+    pub keep_proof_blocks: Option<bool>,
+    pub cfg_gate: Option<String>,
+    pub follow_links: Option<bool>,
+    pub no_ignore: Option<bool>,
+    pub include_globs: Option<Vec<String>>,
+    pub exclude_globs: Option<Vec<String>>,
+    pub empty_body: Option<EmptyBodyPolicy>,
+    pub assert_strategy: Option<AssertStrategy>,
+    pub drop_empty_trait_defaults: Option<bool>,
+    pub attributes_only: Option<bool>,
+    pub strip_verifier_attrs: Option<bool>,
+    pub extra_verus_derives: Option<Vec<String>>,
+    pub known_spec_modules: Option<Vec<String>>,
+    pub aggressive_type_fixing: Option<bool>,
+    pub emit_source_map: Option<bool>,
+    pub line_map: Option<PathBuf>,
+    pub cache: Option<PathBuf>,
+    pub parallel_jobs: Option<usize>,
+    pub follow_includes: Option<bool>,
+    pub stats: Option<StatsFormat>,
+    pub list_removed: Option<bool>,
+    pub as_modules: Option<bool>,
+    pub api_diff: Option<ApiDiffFormat>,
+    pub fail_on_api_change: Option<bool>,
+    pub message_format: Option<MessageFormat>,
+    pub verbosity: Option<u8>,
+    pub quiet: Option<bool>,
+}
+
+impl PartialConfig {
+    /// Combine two layers; any field set in `other` wins.
+    pub fn merge(&self, other: &PartialConfig) -> PartialConfig {
+        PartialConfig {
+            input: other.input.clone().or_else(|| self.input.clone()),
+            output: other.output.clone().or_else(|| self.output.clone()),
+            out_dir: other.out_dir.clone().or_else(|| self.out_dir.clone()),
+            copy_assets: other.copy_assets.or(self.copy_assets),
+            in_place: other.in_place.or(self.in_place),
+            backup: other.backup.clone().or_else(|| self.backup.clone()),
+            force_backup: other.force_backup.or(self.force_backup),
+            recursive: other.recursive.or(self.recursive),
+            remove_empty: other.remove_empty.or(self.remove_empty),
+            package: other.package.or(self.package),
+            files_from: other.files_from.clone().or_else(|| self.files_from.clone()),
+            check: other.check.or(self.check),
+            diff: other.diff.or(self.diff),
+            check_idempotent: other.check_idempotent.or(self.check_idempotent),
+            verify_output: other.verify_output.or(self.verify_output),
+            deny_warnings: other.deny_warnings.or(self.deny_warnings),
+            strict: other.strict.or(self.strict),
+            count: other.count.or(self.count),
+            fail_fast: other.fail_fast.or(self.fail_fast),
+            json_diagnostics: other.json_diagnostics.or(self.json_diagnostics),
+            spec_as_comments: other.spec_as_comments.or(self.spec_as_comments),
+            keep_empty_items: other.keep_empty_items.or(self.keep_empty_items),
+            remove_empty_traits: other.remove_empty_traits.or(self.remove_empty_traits),
+            keep_spec_fns: other.keep_spec_fns.or(self.keep_spec_fns),
+            keep_signature_specs: other.keep_signature_specs.or(self.keep_signature_specs),
+            keep_ghost_fields: other.keep_ghost_fields.or(self.keep_ghost_fields),
+            keep_proof_blocks: other.keep_proof_blocks.or(self.keep_proof_blocks),
+            cfg_gate: other.cfg_gate.clone().or_else(|| self.cfg_gate.clone()),
+            follow_links: other.follow_links.or(self.follow_links),
+            no_ignore: other.no_ignore.or(self.no_ignore),
+            include_globs: other.include_globs.clone().or_else(|| self.include_globs.clone()),
+            exclude_globs: other.exclude_globs.clone().or_else(|| self.exclude_globs.clone()),
+            empty_body: other.empty_body.or(self.empty_body),
+            assert_strategy: other.assert_strategy.or(self.assert_strategy),
+            drop_empty_trait_defaults: other
+                .drop_empty_trait_defaults
+                .or(self.drop_empty_trait_defaults),
+            attributes_only: other.attributes_only.or(self.attributes_only),
+            strip_verifier_attrs: other.strip_verifier_attrs.or(self.strip_verifier_attrs),
+            extra_verus_derives: other
+                .extra_verus_derives
+                .clone()
+                .or_else(|| self.extra_verus_derives.clone()),
+            known_spec_modules: other
+                .known_spec_modules
+                .clone()
+                .or_else(|| self.known_spec_modules.clone()),
+            aggressive_type_fixing: other.aggressive_type_fixing.or(self.aggressive_type_fixing),
+            emit_source_map: other.emit_source_map.or(self.emit_source_map),
+            line_map: other.line_map.clone().or_else(|| self.line_map.clone()),
+            cache: other.cache.clone().or_else(|| self.cache.clone()),
+            parallel_jobs: other.parallel_jobs.or(self.parallel_jobs),
+            follow_includes: other.follow_includes.or(self.follow_includes),
+            stats: other.stats.or(self.stats),
+            list_removed: other.list_removed.or(self.list_removed),
+            as_modules: other.as_modules.or(self.as_modules),
+            api_diff: other.api_diff.or(self.api_diff),
+            fail_on_api_change: other.fail_on_api_change.or(self.fail_on_api_change),
+            message_format: other.message_format.or(self.message_format),
+            verbosity: other.verbosity.or(self.verbosity),
+            quiet: other.quiet.or(self.quiet),
+        }
+    }
+
+    /// Resolve this layer against `base`, inheriting every unset field.
+    pub fn apply_to(&self, base: &Config) -> Config {
+        Config {
+            input: self.input.clone().unwrap_or_else(|| base.input.clone()),
+            output: self.output.clone().or_else(|| base.output.clone()),
+            out_dir: self.out_dir.clone().or_else(|| base.out_dir.clone()),
+            copy_assets: self.copy_assets.unwrap_or(base.copy_assets),
+            in_place: self.in_place.unwrap_or(base.in_place),
+            backup: self.backup.clone().or_else(|| base.backup.clone()),
+            force_backup: self.force_backup.unwrap_or(base.force_backup),
+            recursive: self.recursive.unwrap_or(base.recursive),
+            remove_empty: self.remove_empty.unwrap_or(base.remove_empty),
+            package: self.package.unwrap_or(base.package),
+            files_from: self.files_from.clone().or_else(|| base.files_from.clone()),
+            check: self.check.unwrap_or(base.check),
+            diff: self.diff.unwrap_or(base.diff),
+            check_idempotent: self.check_idempotent.unwrap_or(base.check_idempotent),
+            verify_output: self.verify_output.unwrap_or(base.verify_output),
+            deny_warnings: self.deny_warnings.unwrap_or(base.deny_warnings),
+            strict: self.strict.unwrap_or(base.strict),
+            count: self.count.unwrap_or(base.count),
+            fail_fast: self.fail_fast.unwrap_or(base.fail_fast),
+            json_diagnostics: self.json_diagnostics.unwrap_or(base.json_diagnostics),
+            spec_as_comments: self.spec_as_comments.unwrap_or(base.spec_as_comments),
+            keep_empty_items: self.keep_empty_items.unwrap_or(base.keep_empty_items),
+            remove_empty_traits: self.remove_empty_traits.unwrap_or(base.remove_empty_traits),
+            keep_spec_fns: self.keep_spec_fns.unwrap_or(base.keep_spec_fns),
+            keep_signature_specs: self
+                .keep_signature_specs
+                .unwrap_or(base.keep_signature_specs),
+            keep_ghost_fields: self.keep_ghost_fields.unwrap_or(base.keep_ghost_fields),
+            keep_proof_blocks: self.keep_proof_blocks.unwrap_or(base.keep_proof_blocks),
+            cfg_gate: self.cfg_gate.clone().or_else(|| base.cfg_gate.clone()),
+            follow_links: self.follow_links.unwrap_or(base.follow_links),
+            no_ignore: self.no_ignore.unwrap_or(base.no_ignore),
+            include_globs: self
+                .include_globs
+                .clone()
+                .unwrap_or_else(|| base.include_globs.clone()),
+            exclude_globs: self
+                .exclude_globs
+                .clone()
+                .unwrap_or_else(|| base.exclude_globs.clone()),
+            empty_body: self.empty_body.unwrap_or(base.empty_body),
+            assert_strategy: self.assert_strategy.unwrap_or(base.assert_strategy),
+            drop_empty_trait_defaults: self
+                .drop_empty_trait_defaults
+                .unwrap_or(base.drop_empty_trait_defaults),
+            attributes_only: self.attributes_only.unwrap_or(base.attributes_only),
+            strip_verifier_attrs: self
+                .strip_verifier_attrs
+                .unwrap_or(base.strip_verifier_attrs),
+            extra_verus_derives: self
+                .extra_verus_derives
+                .clone()
+                .unwrap_or_else(|| base.extra_verus_derives.clone()),
+            known_spec_modules: self
+                .known_spec_modules
+                .clone()
+                .unwrap_or_else(|| base.known_spec_modules.clone()),
+            aggressive_type_fixing: self
+                .aggressive_type_fixing
+                .unwrap_or(base.aggressive_type_fixing),
+            emit_source_map: self.emit_source_map.unwrap_or(base.emit_source_map),
+            line_map: self.line_map.clone().or_else(|| base.line_map.clone()),
+            cache: self.cache.clone().or_else(|| base.cache.clone()),
+            parallel_jobs: self.parallel_jobs.or(base.parallel_jobs),
+            follow_includes: self.follow_includes.unwrap_or(base.follow_includes),
+            stats: self.stats.or(base.stats),
+            list_removed: self.list_removed.unwrap_or(base.list_removed),
+            as_modules: self.as_modules.unwrap_or(base.as_modules),
+            api_diff: self.api_diff.or(base.api_diff),
+            fail_on_api_change: self.fail_on_api_change.unwrap_or(base.fail_on_api_change),
+            message_format: self.message_format.unwrap_or(base.message_format),
+            verbosity: self.verbosity.unwrap_or(base.verbosity),
+            quiet: self.quiet.unwrap_or(base.quiet),
+        }
+    }
+}
+
+impl PartialConfig {
+    /// Parse one layer from TOML text, the format of `.vstrip.toml` files.
+    /// Keys mirror the [`Config`] field names; unknown keys are an error.
+    pub fn from_toml(text: &str) -> Result<PartialConfig> {
+        toml::from_str(text)
+            .map_err(|e| StripError::ConfigError(format!("invalid TOML configuration: {}", e)))
+    }
+}
+
+/// Merge layered configuration: every field explicitly set in `overlay`
+/// takes precedence over `base`.
+pub fn merge_config(base: &Config, overlay: &PartialConfig) -> Config {
+    overlay.apply_to(base)
+}
+
+impl Config {
+    /// Method form of [`merge_config`].
+    pub fn merge(&self, overlay: &PartialConfig) -> Config {
+        merge_config(self, overlay)
+    }
+
+    /// Read the TOML file at `path` and resolve it against the defaults.
+    ///
+    /// For layering the file under other sources (the CLI does this), parse
+    /// it with [`PartialConfig::from_toml`] instead and merge the layers.
+    pub fn from_toml_file(path: &Path) -> Result<Config> {
+        let text = std::fs::read_to_string(path)
+            .map_err(|e| StripError::IoError { path: path.to_path_buf(), source: e })?;
+        let layer = PartialConfig::from_toml(&text).map_err(|e| name_config_file(e, path))?;
+        Ok(layer.apply_to(&Config::default()))
+    }
+}
+
+/// Find the nearest `.vstrip.toml` at or above `input` and parse it.
+///
+/// `input` may be a file or a directory; the search starts at the file's
+/// directory (resolved to an absolute path, so relative inputs still climb
+/// past the working directory) and walks up to the filesystem root. `None`
+/// means no project file exists anywhere above the input.
+pub fn discover_project_config(input: &Path) -> Result<Option<PartialConfig>> {
+    let start = if input.is_dir() { input } else { input.parent().unwrap_or(Path::new(".")) };
+    let start = std::fs::canonicalize(start).unwrap_or_else(|_| start.to_path_buf());
+    for dir in start.ancestors() {
+        let candidate = dir.join(".vstrip.toml");
+        if candidate.is_file() {
+            let text = std::fs::read_to_string(&candidate)
+                .map_err(|e| StripError::IoError { path: candidate.clone(), source: e })?;
+            let layer =
+                PartialConfig::from_toml(&text).map_err(|e| name_config_file(e, &candidate))?;
+            return Ok(Some(layer));
+        }
+    }
+    Ok(None)
+}
+
+/// Prefix a [`PartialConfig::from_toml`] failure with the file it came from.
+fn name_config_file(e: StripError, path: &Path) -> StripError {
+    match e {
+        StripError::ConfigError(msg) => {
+            StripError::ConfigError(format!("{}: {}", path.display(), msg))
+        }
+        other => other,
+    }
+}
+
+/// Output format for the `--stats` report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StatsFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for StatsFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<StatsFormat, String> {
+        match s {
+            "text" => Ok(StatsFormat::Text),
+            "json" => Ok(StatsFormat::Json),
+            _ => Err(format!("unknown stats format `{}` (expected text or json)", s)),
+        }
+    }
+}
+
+// In `.vstrip.toml` the enum options are the same strings the CLI accepts,
+// so deserialization goes through `FromStr` rather than a parallel derive.
+impl<'de> serde::Deserialize<'de> for StatsFormat {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Output format for the API-diff report.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ApiDiffFormat {
+    Text,
+    Json,
+}
+
+impl std::str::FromStr for ApiDiffFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<ApiDiffFormat, String> {
+        match s {
+            "text" => Ok(ApiDiffFormat::Text),
+            "json" => Ok(ApiDiffFormat::Json),
+            _ => Err(format!("unknown api-diff format `{}` (expected text or json)", s)),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for ApiDiffFormat {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// Policy for a non-unit-returning function whose body consisted entirely of
+/// proof code: the stripped `fn f() -> u32 { }` is not valid Rust, so either
+/// refuse to emit it or plug the hole with a diverging macro.
+///
+/// Detection is per function body; branches of an `if` or `match` that become
+/// empty are not patched individually, since their expected type is not
+/// syntactically known.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum EmptyBodyPolicy {
+    /// Report an error naming the function (the default).
+    Error,
+    /// Emit `todo!()` as the sole body statement.
+    Todo,
+    /// Emit `unreachable!()` as the sole body statement.
+    Unreachable,
+}
+
+impl std::str::FromStr for EmptyBodyPolicy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<EmptyBodyPolicy, String> {
+        match s {
+            "error" => Ok(EmptyBodyPolicy::Error),
+            "todo" => Ok(EmptyBodyPolicy::Todo),
+            "unreachable" => Ok(EmptyBodyPolicy::Unreachable),
+            _ => Err(format!("unknown empty-body policy `{}` (expected error, todo, or unreachable)", s)),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for EmptyBodyPolicy {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// What becomes of `assert(...)` expressions in exec code.
+///
+/// An exec-position assertion was proven at verification time, but some
+/// codebases want it re-checked at runtime once the proofs are gone. The
+/// condition is emitted as written (after ghost sub-expressions are
+/// rewritten), so it must be executable Rust for the output to compile;
+/// `--verify-output` catches conditions that were not. An `assert ... by`
+/// proof body is dropped either way, and `assume` / `assert forall`, which
+/// have no runtime meaning, are always removed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AssertStrategy {
+    /// Remove the assertion entirely (the default).
+    Remove,
+    /// Convert to `debug_assert!`, checked in debug builds only.
+    DebugAssert,
+    /// Convert to plain `assert!`, checked in every build.
+    Keep,
+}
+
+impl std::str::FromStr for AssertStrategy {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<AssertStrategy, String> {
+        match s {
+            "remove" => Ok(AssertStrategy::Remove),
+            "debug-assert" => Ok(AssertStrategy::DebugAssert),
+            "keep" => Ok(AssertStrategy::Keep),
+            _ => Err(format!(
+                "unknown assert strategy `{}` (expected remove, debug-assert, or keep)",
+                s
+            )),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for AssertStrategy {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+/// How errors and warnings are rendered, for `--message-format`.
+///
+/// This only affects diagnostics; the stripped source itself is emitted the
+/// same way under either format.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum MessageFormat {
+    /// Human-readable `error:`/`warning:` lines on stderr (the default).
+    Text,
+    /// One [`crate::diagnostics::Message`] JSON object per stderr line, for
+    /// editors and other tooling.
+    Json,
+}
+
+impl std::str::FromStr for MessageFormat {
+    type Err = String;
+
+    fn from_str(s: &str) -> std::result::Result<MessageFormat, String> {
+        match s {
+            "text" => Ok(MessageFormat::Text),
+            "json" => Ok(MessageFormat::Json),
+            _ => Err(format!("unknown message format `{}` (expected text or json)", s)),
+        }
+    }
+}
+
+impl<'de> serde::Deserialize<'de> for MessageFormat {
+    fn deserialize<D: serde::Deserializer<'de>>(d: D) -> std::result::Result<Self, D::Error> {
+        String::deserialize(d)?.parse().map_err(serde::de::Error::custom)
+    }
+}
@@ -0,0 +1,234 @@
+//! Spec-density metrics: how much of the input is specification and proof
+//! code versus executable code.
+//!
+//! `--count` walks the input like a stripping run but writes no output;
+//! instead each file is parsed and a [`CountVisitor`] tallies the
+//! verification-only constructs — spec and proof fns, proof blocks, ghost
+//! declarations — by their span line extents, using the same classification
+//! helpers the strip pass uses. [`crate::process`] prints the per-file and
+//! total counts as a text table, or as a JSON array under
+//! `--message-format=json`, for tracking a spec-to-exec ratio over time.
+
+use std::path::Path;
+
+use verus_syn::spanned::Spanned;
+use verus_syn::visit::{self, Visit};
+use verus_syn::{Expr, FnMode, Stmt};
+
+use crate::error::{Result, StripError};
+use crate::preprocess;
+use crate::visitor::{
+    is_ghost_field, is_ghost_wrapper_pat, is_proof_expr, is_proof_macro, is_spec_or_proof_fn,
+};
+
+/// Counters for one file's verification-only constructs, by item and by
+/// line extent.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct SpecCounts {
+    /// `spec fn`s, open or closed.
+    pub spec_fns: usize,
+    /// `proof fn`s, including axioms.
+    pub proof_fns: usize,
+    /// Statement-position proof code: `proof { ... }` blocks, bare
+    /// `assert`/`assume` expressions, and proof-only macro invocations.
+    pub proof_blocks: usize,
+    /// Ghost declarations: `ghost`/`tracked` locals, `Ghost(x)`/`Tracked(x)`
+    /// bindings, and ghost struct/enum fields.
+    pub ghost_decls: usize,
+    /// Executable (exec- or default-mode) functions, for the item ratio.
+    pub exec_fns: usize,
+    /// Lines covered by the spec constructs above, by span extent; nested
+    /// proof code inside a spec fn is not counted twice.
+    pub spec_lines: usize,
+    /// The file's remaining lines, spec subtracted from the total.
+    pub exec_lines: usize,
+}
+
+impl SpecCounts {
+    /// Fold `other` into these counters, for per-run aggregation.
+    pub fn add(&mut self, other: &SpecCounts) {
+        self.spec_fns += other.spec_fns;
+        self.proof_fns += other.proof_fns;
+        self.proof_blocks += other.proof_blocks;
+        self.ghost_decls += other.ghost_decls;
+        self.exec_fns += other.exec_fns;
+        self.spec_lines += other.spec_lines;
+        self.exec_lines += other.exec_lines;
+    }
+
+    fn spec_items(&self) -> usize {
+        self.spec_fns + self.proof_fns + self.proof_blocks + self.ghost_decls
+    }
+}
+
+/// One file's [`SpecCounts`], named for the report.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FileCounts {
+    pub path: String,
+    pub counts: SpecCounts,
+}
+
+impl FileCounts {
+    pub fn new(path: &Path, counts: SpecCounts) -> FileCounts {
+        FileCounts { path: path.display().to_string(), counts }
+    }
+}
+
+/// Parse `source` and tally its spec-versus-exec counts; `path` names the
+/// file in parse errors.
+pub fn count_source(source: &str, path: &Path) -> Result<SpecCounts> {
+    let unwrapped = preprocess::unwrap_verus_macros(source);
+    let file = verus_syn::parse_file(&unwrapped)
+        .map_err(|e| StripError::ParseError { path: path.to_path_buf(), source: e })?;
+    let mut visitor = CountVisitor::default();
+    visitor.visit_file(&file);
+    let mut counts = visitor.counts;
+    // The preprocessor preserves line structure, so spans measured on the
+    // unwrapped file count lines of the original source.
+    counts.exec_lines = source.lines().count().saturating_sub(counts.spec_lines);
+    Ok(counts)
+}
+
+/// How many source lines a node's span covers.
+fn line_extent<T: Spanned>(node: &T) -> usize {
+    let span = node.span();
+    span.end().line.saturating_sub(span.start().line) + 1
+}
+
+/// A read-only sibling of [`crate::visitor::StripVisitor`]: where the strip
+/// pass removes a construct, this pass adds its span extent to the spec
+/// tally and moves on.
+#[derive(Default)]
+struct CountVisitor {
+    counts: SpecCounts,
+}
+
+impl CountVisitor {
+    fn record_fn(&mut self, mode: &FnMode, lines: usize) {
+        match mode {
+            FnMode::Spec(_) | FnMode::SpecChecked(_) => self.counts.spec_fns += 1,
+            _ => self.counts.proof_fns += 1,
+        }
+        self.counts.spec_lines += lines;
+    }
+}
+
+impl<'ast> Visit<'ast> for CountVisitor {
+    fn visit_item_fn(&mut self, func: &'ast verus_syn::ItemFn) {
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            // The whole function is spec code; nothing inside counts again.
+            self.record_fn(&func.sig.mode, line_extent(func));
+        } else {
+            self.counts.exec_fns += 1;
+            visit::visit_item_fn(self, func);
+        }
+    }
+
+    fn visit_impl_item_fn(&mut self, func: &'ast verus_syn::ImplItemFn) {
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            self.record_fn(&func.sig.mode, line_extent(func));
+        } else {
+            self.counts.exec_fns += 1;
+            visit::visit_impl_item_fn(self, func);
+        }
+    }
+
+    fn visit_trait_item_fn(&mut self, func: &'ast verus_syn::TraitItemFn) {
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            self.record_fn(&func.sig.mode, line_extent(func));
+        } else {
+            self.counts.exec_fns += 1;
+            visit::visit_trait_item_fn(self, func);
+        }
+    }
+
+    fn visit_stmt(&mut self, stmt: &'ast Stmt) {
+        match stmt {
+            Stmt::Expr(expr, _) if is_proof_expr(expr) => {
+                self.counts.proof_blocks += 1;
+                self.counts.spec_lines += line_extent(stmt);
+            }
+            Stmt::Macro(mac) if is_proof_macro(&mac.mac.path) => {
+                self.counts.proof_blocks += 1;
+                self.counts.spec_lines += line_extent(stmt);
+            }
+            Stmt::Local(local)
+                if local.ghost.is_some()
+                    || local.tracked.is_some()
+                    || is_ghost_wrapper_pat(&local.pat) =>
+            {
+                self.counts.ghost_decls += 1;
+                self.counts.spec_lines += line_extent(stmt);
+            }
+            _ => visit::visit_stmt(self, stmt),
+        }
+    }
+
+    fn visit_expr(&mut self, expr: &'ast Expr) {
+        // Proof expressions in non-statement positions (`assert(...)` as a
+        // trailing expression, say) still count.
+        if is_proof_expr(expr) {
+            self.counts.proof_blocks += 1;
+            self.counts.spec_lines += line_extent(expr);
+        } else {
+            visit::visit_expr(self, expr);
+        }
+    }
+
+    fn visit_field(&mut self, field: &'ast verus_syn::Field) {
+        if is_ghost_field(field) {
+            self.counts.ghost_decls += 1;
+            self.counts.spec_lines += line_extent(field);
+        } else {
+            visit::visit_field(self, field);
+        }
+    }
+}
+
+/// Render per-file counts as a JSON array, one object per file.
+pub fn render_json(files: &[FileCounts]) -> String {
+    serde_json::to_string_pretty(files).expect("count serialization cannot fail")
+}
+
+/// Render the counts as a human-readable table: one row per file, a totals
+/// row, and the spec-to-exec line ratio the metric exists for.
+pub fn render_text(files: &[FileCounts]) -> String {
+    let mut totals = SpecCounts::default();
+    for file in files {
+        totals.add(&file.counts);
+    }
+    let width = files
+        .iter()
+        .map(|file| file.path.len())
+        .chain(std::iter::once("total".len()))
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    out.push_str(&format!(
+        "{:<width$}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+        "path", "spec lines", "exec lines", "spec items", "exec items",
+        width = width
+    ));
+    let mut row = |label: &str, counts: &SpecCounts| {
+        out.push_str(&format!(
+            "{:<width$}  {:>10}  {:>10}  {:>10}  {:>10}\n",
+            label,
+            counts.spec_lines,
+            counts.exec_lines,
+            counts.spec_items(),
+            counts.exec_fns,
+            width = width
+        ));
+    };
+    for file in files {
+        row(&file.path, &file.counts);
+    }
+    row("total", &totals);
+    if totals.exec_lines > 0 {
+        out.push_str(&format!(
+            "spec-to-exec line ratio: {:.2}\n",
+            totals.spec_lines as f64 / totals.exec_lines as f64
+        ));
+    }
+    out
+}
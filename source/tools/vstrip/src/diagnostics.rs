@@ -0,0 +1,143 @@
+//! Machine-readable per-file results for the `--json` flag, and the
+//! per-message line schema behind `--message-format json`.
+//!
+//! The `--json` schema is part of the tool's interface and stays stable
+//! across patch releases: the output is one JSON array on stdout, one
+//! object per processed file, with fields
+//!
+//! ```text
+//! file      the file's path as walked (string)
+//! status    "ok" or "error"
+//! stripped  how many items were removed entirely (number)
+//! warnings  array of { "kind", "message" }
+//! error     the failure message; present only when status is "error"
+//! ```
+//!
+//! Under `--message-format json` each error and warning is instead one
+//! [`Message`] object per stderr line, with fields `level`, `path`, `line`,
+//! `column`, `message`, and `code` — `path`/`line`/`column` are null when
+//! the message is not about one source location. New optional fields may
+//! appear in minor releases; existing fields keep their names and meaning.
+
+use std::path::Path;
+
+use serde::Serialize;
+
+use crate::error::StripError;
+use crate::warning::Warning;
+
+/// The outcome of processing one file, in the stable `--json` schema.
+#[derive(Debug, Clone, Serialize)]
+pub struct FileDiagnostic {
+    pub file: String,
+    pub status: Status,
+    pub stripped: usize,
+    pub warnings: Vec<Warning>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub error: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "lowercase")]
+pub enum Status {
+    Ok,
+    Error,
+}
+
+impl FileDiagnostic {
+    pub fn ok(path: &Path, stripped: usize, warnings: Vec<Warning>) -> FileDiagnostic {
+        FileDiagnostic {
+            file: path.display().to_string(),
+            status: Status::Ok,
+            stripped,
+            warnings,
+            error: None,
+        }
+    }
+
+    pub fn error(path: &Path, message: String) -> FileDiagnostic {
+        FileDiagnostic {
+            file: path.display().to_string(),
+            status: Status::Error,
+            stripped: 0,
+            warnings: Vec::new(),
+            error: Some(message),
+        }
+    }
+}
+
+pub fn render(diagnostics: &[FileDiagnostic]) -> String {
+    serde_json::to_string_pretty(diagnostics).expect("diagnostic serialization does not fail")
+}
+
+/// One error or warning in the `--message-format json` line schema.
+///
+/// `line` and `column` are 1-based, as editors count them.
+#[derive(Debug, Clone, Serialize)]
+pub struct Message {
+    pub level: &'static str,
+    pub path: Option<String>,
+    pub line: Option<usize>,
+    pub column: Option<usize>,
+    pub message: String,
+    pub code: &'static str,
+}
+
+impl Message {
+    pub fn from_warning(path: &Path, warning: &Warning) -> Message {
+        Message {
+            level: "warning",
+            path: Some(path.display().to_string()),
+            line: warning.line(),
+            column: warning.column(),
+            message: warning.to_string(),
+            code: warning.kind(),
+        }
+    }
+
+    pub fn from_error(error: &StripError) -> Message {
+        // Errors that point at one source location surface it in the
+        // structured fields; the rendered message then drops the textual
+        // `path:line:column:` prefix so editors do not show it twice.
+        let (path, line, column, message) = match error {
+            StripError::IoError { path, source } => {
+                (Some(path), None, None, source.to_string())
+            }
+            StripError::ParseError { path, source } => {
+                let start = source.span().start();
+                (
+                    Some(path),
+                    Some(start.line),
+                    Some(start.column + 1),
+                    format!("parse error: {}", source),
+                )
+            }
+            StripError::InvalidOutput { path, source, snippet } => {
+                // The span here points into the stripped output, not the
+                // source file, so it stays in the message text rather than
+                // the structured fields an editor would jump to.
+                let rendered = format!(
+                    "stripped output is not valid Rust: {} (output line {}: `{}`)",
+                    source,
+                    source.span().start().line,
+                    snippet
+                );
+                (Some(path), None, None, rendered)
+            }
+            other => (None, None, None, other.to_string()),
+        };
+        Message {
+            level: "error",
+            path: path.map(|p| p.display().to_string()),
+            line,
+            column,
+            message,
+            code: error.code(),
+        }
+    }
+
+    /// The message as one line of JSON, ready to print.
+    pub fn render_line(&self) -> String {
+        serde_json::to_string(self).expect("message serialization does not fail")
+    }
+}
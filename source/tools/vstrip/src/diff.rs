@@ -0,0 +1,169 @@
+//! Unified-diff rendering between an original source and its stripped output.
+//!
+//! `--diff` mode shows exactly what stripping would change without writing
+//! anything, in the familiar `---`/`+++`/`@@` format so the output can be
+//! read (or applied with `patch`) like any other diff. The line-level edit
+//! script comes from a plain Myers diff; no attempt is made at word-level
+//! refinement, since stripped output is reformatted wholesale anyway.
+
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// Lines of surrounding context included in each hunk.
+const CONTEXT: usize = 3;
+
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum Op {
+    Keep,
+    Delete,
+    Insert,
+}
+
+/// Render a unified diff from `original` to `stripped`, labelled with `path`.
+///
+/// Returns `None` when the two texts are identical, so callers can stay
+/// silent for files that stripping would leave untouched.
+pub fn unified_diff(original: &str, stripped: &str, path: &Path) -> Option<String> {
+    if original == stripped {
+        return None;
+    }
+    let old: Vec<&str> = original.lines().collect();
+    let new: Vec<&str> = stripped.lines().collect();
+    let ops = edit_script(&old, &new);
+    let mut out = String::new();
+    writeln!(out, "--- {}", path.display()).unwrap();
+    writeln!(out, "+++ {} (stripped)", path.display()).unwrap();
+    render_hunks(&mut out, &ops, &old, &new);
+    Some(out)
+}
+
+/// Compute a minimal line-level edit script turning `old` into `new`, using
+/// the greedy Myers algorithm. `Keep` consumes one line from both sides,
+/// `Delete` one from `old`, `Insert` one from `new`.
+fn edit_script(old: &[&str], new: &[&str]) -> Vec<Op> {
+    let n = old.len() as isize;
+    let m = new.len() as isize;
+    let max = n + m;
+    let offset = max;
+    let idx = |k: isize| (offset + k) as usize;
+    // v[idx(k)] is the furthest x reached on diagonal k = x - y; trace keeps
+    // one snapshot per edit distance for backtracking.
+    let mut v = vec![0isize; (2 * max + 2) as usize];
+    let mut trace: Vec<Vec<isize>> = Vec::new();
+    'search: for d in 0..=max {
+        trace.push(v.clone());
+        let mut k = -d;
+        while k <= d {
+            let mut x = if k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]) {
+                v[idx(k + 1)]
+            } else {
+                v[idx(k - 1)] + 1
+            };
+            let mut y = x - k;
+            while x < n && y < m && old[x as usize] == new[y as usize] {
+                x += 1;
+                y += 1;
+            }
+            v[idx(k)] = x;
+            if x >= n && y >= m {
+                break 'search;
+            }
+            k += 2;
+        }
+    }
+    let mut ops = Vec::new();
+    let (mut x, mut y) = (n, m);
+    for (d, v) in trace.iter().enumerate().skip(1).rev() {
+        let d = d as isize;
+        let k = x - y;
+        let went_down = k == -d || (k != d && v[idx(k - 1)] < v[idx(k + 1)]);
+        let prev_k = if went_down { k + 1 } else { k - 1 };
+        let prev_x = v[idx(prev_k)];
+        let prev_y = prev_x - prev_k;
+        while x > prev_x && y > prev_y {
+            ops.push(Op::Keep);
+            x -= 1;
+            y -= 1;
+        }
+        if went_down {
+            ops.push(Op::Insert);
+            y -= 1;
+        } else {
+            ops.push(Op::Delete);
+            x -= 1;
+        }
+    }
+    // Edit distance 0: whatever remains is the leading common snake.
+    while x > 0 {
+        ops.push(Op::Keep);
+        x -= 1;
+        y -= 1;
+    }
+    debug_assert_eq!(y, 0);
+    ops.reverse();
+    ops
+}
+
+/// Group the edit script into hunks — runs of changes plus [`CONTEXT`] lines
+/// on either side, with nearby runs merged — and render each with its
+/// `@@ -start,len +start,len @@` header.
+fn render_hunks(out: &mut String, ops: &[Op], old: &[&str], new: &[&str]) {
+    // For each op, the old/new line index it starts at.
+    let mut positions = Vec::with_capacity(ops.len());
+    let (mut x, mut y) = (0usize, 0usize);
+    for op in ops {
+        positions.push((x, y));
+        match op {
+            Op::Keep => {
+                x += 1;
+                y += 1;
+            }
+            Op::Delete => x += 1,
+            Op::Insert => y += 1,
+        }
+    }
+    let mut i = 0;
+    while i < ops.len() {
+        if ops[i] == Op::Keep {
+            i += 1;
+            continue;
+        }
+        // A hunk: from CONTEXT before this change to CONTEXT after the last
+        // change reachable without a gap of more than 2 * CONTEXT keeps.
+        let mut end = i + 1;
+        let mut keeps = 0;
+        for (j, op) in ops.iter().enumerate().skip(i + 1) {
+            if *op == Op::Keep {
+                keeps += 1;
+                if keeps > 2 * CONTEXT {
+                    break;
+                }
+            } else {
+                keeps = 0;
+                end = j + 1;
+            }
+        }
+        let start = i.saturating_sub(CONTEXT);
+        let stop = (end + CONTEXT).min(ops.len());
+        let (old_start, new_start) = positions[start];
+        let old_len = ops[start..stop].iter().filter(|op| **op != Op::Insert).count();
+        let new_len = ops[start..stop].iter().filter(|op| **op != Op::Delete).count();
+        writeln!(
+            out,
+            "@@ -{},{} +{},{} @@",
+            if old_len == 0 { old_start } else { old_start + 1 },
+            old_len,
+            if new_len == 0 { new_start } else { new_start + 1 },
+            new_len,
+        )
+        .unwrap();
+        for (op, &(px, py)) in ops[start..stop].iter().zip(&positions[start..stop]) {
+            match op {
+                Op::Keep => writeln!(out, " {}", old[px]).unwrap(),
+                Op::Delete => writeln!(out, "-{}", old[px]).unwrap(),
+                Op::Insert => writeln!(out, "+{}", new[py]).unwrap(),
+            }
+        }
+        i = stop;
+    }
+}
@@ -0,0 +1,220 @@
+use std::fmt;
+use std::path::PathBuf;
+
+use crate::api_diff::ApiChange;
+use crate::validate::DuplicateItem;
+use crate::warning::Warning;
+
+pub type Result<T> = std::result::Result<T, StripError>;
+
+/// Errors produced while stripping Verus constructs from a source tree.
+#[derive(Debug)]
+pub enum StripError {
+    /// A file could not be read or written.
+    IoError { path: PathBuf, source: std::io::Error },
+    /// The (preprocessed) source failed to parse as Verus-extended Rust.
+    ParseError { path: PathBuf, source: verus_syn::Error },
+    /// The requested option combination is invalid.
+    ConfigError(String),
+    /// Stripping left two surviving items with the same name in the same
+    /// namespace; writing the output would produce uncompilable Rust.
+    DuplicateItems(Vec<DuplicateItem>),
+    /// Stripping removed every statement from the body of a value-returning
+    /// function, and the configured [`crate::config::EmptyBodyPolicy`] is to
+    /// refuse rather than patch the body.
+    EmptyBodies(Vec<String>),
+    /// Stripping changed public signatures and `fail_on_api_change` is set.
+    ApiChanged(Vec<ApiChange>),
+    /// Following `include!`s revisited a file already on the include chain.
+    IncludeCycle(Vec<PathBuf>),
+    /// `--diff` mode found files whose stripped output differs from the
+    /// source; the diffs themselves were already printed to stdout.
+    DiffsFound(Vec<PathBuf>),
+    /// Under `verify_output`, the stripped text failed to re-parse as plain
+    /// Rust; nothing was written. `snippet` is the offending output line.
+    InvalidOutput { path: PathBuf, source: syn::Error, snippet: String },
+    /// Under `check_idempotent`, stripping the stripped output again changed
+    /// it: the strip logic is unstable on this input.
+    IdempotencyError { first: String, second: String },
+    /// Under `deny_warnings`, stripping raised warnings; nothing was
+    /// written. The warnings themselves are carried along for display.
+    WarningsDenied(Vec<Warning>),
+    /// Under `strict`, the visitor met Verus-only constructs it has no
+    /// handling for; their invocations would survive into output that does
+    /// not compile as plain Rust. Nothing was written.
+    UnsupportedConstructs(Vec<Warning>),
+    /// A directory or package run saw these files fail; the other files
+    /// were still processed. Under [`crate::config::Config::fail_fast`] the
+    /// first failure propagates bare instead.
+    MultipleErrors(Vec<(PathBuf, StripError)>),
+}
+
+impl StripError {
+    /// Stable machine-readable category, e.g. `parse-error`; the `code`
+    /// field of [`crate::diagnostics::Message`].
+    pub fn code(&self) -> &'static str {
+        match self {
+            StripError::IoError { .. } => "io-error",
+            StripError::ParseError { .. } => "parse-error",
+            StripError::ConfigError(_) => "config-error",
+            StripError::DuplicateItems(_) => "duplicate-items",
+            StripError::EmptyBodies(_) => "empty-bodies",
+            StripError::ApiChanged(_) => "api-changed",
+            StripError::IncludeCycle(_) => "include-cycle",
+            StripError::DiffsFound(_) => "diffs-found",
+            StripError::InvalidOutput { .. } => "invalid-output",
+            StripError::IdempotencyError { .. } => "idempotency-error",
+            StripError::WarningsDenied(_) => "warnings-denied",
+            StripError::UnsupportedConstructs(_) => "unsupported-constructs",
+            StripError::MultipleErrors(_) => "multiple-errors",
+        }
+    }
+}
+
+impl fmt::Display for StripError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StripError::IoError { path, source } => {
+                write!(f, "{}: {}", path.display(), source)
+            }
+            StripError::ParseError { path, source } => {
+                let start = source.span().start();
+                write!(
+                    f,
+                    "{}:{}:{}: parse error: {}",
+                    path.display(),
+                    start.line,
+                    start.column + 1,
+                    source
+                )
+            }
+            StripError::ConfigError(msg) => write!(f, "invalid configuration: {}", msg),
+            StripError::DuplicateItems(dups) => {
+                writeln!(f, "stripping produced {} duplicate definition(s):", dups.len())?;
+                for dup in dups {
+                    writeln!(f, "  {}", dup)?;
+                }
+                write!(
+                    f,
+                    "the colliding items were likely distinguished by ghost code or cfg \
+                     attributes that stripping removed"
+                )
+            }
+            StripError::EmptyBodies(functions) => {
+                writeln!(
+                    f,
+                    "stripping emptied the body of {} value-returning function(s):",
+                    functions.len()
+                )?;
+                for name in functions {
+                    writeln!(f, "  {}", name)?;
+                }
+                write!(
+                    f,
+                    "these bodies were entirely proof code; rerun with \
+                     --empty-body=todo or --empty-body=unreachable to emit a \
+                     placeholder body instead"
+                )
+            }
+            StripError::ApiChanged(changes) => {
+                writeln!(f, "stripping changed {} public signature(s):", changes.len())?;
+                for change in changes {
+                    writeln!(
+                        f,
+                        "  {}: {} -> {}",
+                        change.path,
+                        change.before.as_deref().unwrap_or("<none>"),
+                        change.after.as_deref().unwrap_or("<none>"),
+                    )?;
+                }
+                write!(f, "remove the ghost parameters/fields or drop --fail-on-api-change")
+            }
+            StripError::IncludeCycle(chain) => {
+                let rendered: Vec<String> =
+                    chain.iter().map(|p| p.display().to_string()).collect();
+                write!(f, "include! cycle detected: {}", rendered.join(" -> "))
+            }
+            StripError::DiffsFound(paths) => {
+                write!(f, "{} file(s) would be changed by stripping", paths.len())
+            }
+            StripError::InvalidOutput { path, source, snippet } => {
+                write!(
+                    f,
+                    "{}: stripped output is not valid Rust: {} (output line {}: `{}`)",
+                    path.display(),
+                    source,
+                    source.span().start().line,
+                    snippet
+                )
+            }
+            StripError::IdempotencyError { first, second } => {
+                let diverges = first
+                    .lines()
+                    .zip(second.lines())
+                    .position(|(a, b)| a != b)
+                    .map_or(first.lines().count().min(second.lines().count()), |n| n)
+                    + 1;
+                write!(
+                    f,
+                    "stripping is not idempotent: a second pass changed the output \
+                     (first divergence at output line {})",
+                    diverges
+                )
+            }
+            StripError::WarningsDenied(warnings) => {
+                writeln!(f, "stripping raised {} warning(s):", warnings.len())?;
+                for warning in warnings {
+                    writeln!(f, "  {}", warning)?;
+                }
+                write!(f, "warnings are fatal under --deny-warnings")
+            }
+            StripError::UnsupportedConstructs(constructs) => {
+                writeln!(
+                    f,
+                    "stripping met {} construct(s) it cannot handle:",
+                    constructs.len()
+                )?;
+                for construct in constructs {
+                    match (construct.line(), construct.column()) {
+                        (Some(line), Some(column)) => {
+                            writeln!(f, "  {}:{}: {}", line, column, construct)?
+                        }
+                        _ => writeln!(f, "  {}", construct)?,
+                    }
+                }
+                write!(f, "these survive into uncompilable output; rerun without --strict \
+                           to pass them through anyway")
+            }
+            StripError::MultipleErrors(failures) => {
+                writeln!(f, "{} file(s) failed:", failures.len())?;
+                for (i, (path, error)) in failures.iter().enumerate() {
+                    if i > 0 {
+                        writeln!(f)?;
+                    }
+                    write!(f, "  {}: {}", path.display(), error)?;
+                }
+                Ok(())
+            }
+        }
+    }
+}
+
+impl std::error::Error for StripError {
+    fn source(&self) -> Option<&(dyn std::error::Error + 'static)> {
+        match self {
+            StripError::IoError { source, .. } => Some(source),
+            StripError::ParseError { source, .. } => Some(source),
+            StripError::InvalidOutput { source, .. } => Some(source),
+            StripError::ConfigError(_)
+            | StripError::DuplicateItems(_)
+            | StripError::EmptyBodies(_)
+            | StripError::ApiChanged(_)
+            | StripError::IncludeCycle(_)
+            | StripError::DiffsFound(_)
+            | StripError::IdempotencyError { .. }
+            | StripError::WarningsDenied(_)
+            | StripError::UnsupportedConstructs(_)
+            | StripError::MultipleErrors(_) => None,
+        }
+    }
+}
@@ -0,0 +1,59 @@
+//! Include/exclude glob filtering for recursive directory walks.
+//!
+//! Patterns come from `--include`/`--exclude` and are matched against each
+//! candidate's path *relative to the input directory*, so `tests/**` means
+//! the `tests` directory under the input, wherever the input itself lives.
+//! Exclude always wins over include, and an empty include list means
+//! "everything" — the common case of only excluding generated code needs no
+//! include patterns.
+
+use std::path::Path;
+
+use globset::{Glob, GlobSet, GlobSetBuilder};
+
+use crate::error::{Result, StripError};
+
+/// Compiled `--include`/`--exclude` patterns.
+pub struct PathFilters {
+    include: Option<GlobSet>,
+    exclude: Option<GlobSet>,
+}
+
+impl PathFilters {
+    /// Compile the pattern lists; an empty list compiles to "no constraint".
+    pub fn new(include: &[String], exclude: &[String]) -> Result<PathFilters> {
+        Ok(PathFilters { include: compile(include)?, exclude: compile(exclude)? })
+    }
+
+    /// Whether the file at `relative` (to the input directory) should be
+    /// processed: not excluded, and matching an include pattern if any were
+    /// given.
+    pub fn admits(&self, relative: &Path) -> bool {
+        if let Some(exclude) = &self.exclude {
+            if exclude.is_match(relative) {
+                return false;
+            }
+        }
+        match &self.include {
+            Some(include) => include.is_match(relative),
+            None => true,
+        }
+    }
+}
+
+fn compile(patterns: &[String]) -> Result<Option<GlobSet>> {
+    if patterns.is_empty() {
+        return Ok(None);
+    }
+    let mut builder = GlobSetBuilder::new();
+    for pattern in patterns {
+        let glob = Glob::new(pattern).map_err(|e| {
+            StripError::ConfigError(format!("invalid glob pattern `{}`: {}", pattern, e))
+        })?;
+        builder.add(glob);
+    }
+    let set = builder
+        .build()
+        .map_err(|e| StripError::ConfigError(format!("cannot compile glob patterns: {}", e)))?;
+    Ok(Some(set))
+}
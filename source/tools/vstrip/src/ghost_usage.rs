@@ -0,0 +1,215 @@
+//! Diagnostic pass reporting where ghost types appear in exec positions.
+//!
+//! Before stripping a codebase, it helps to know which executable code
+//! mentions verification-only types: an exec function taking `Ghost<int>`
+//! will lose a parameter, a struct field of type `Seq<T>` will disappear,
+//! and so on. [`collect_ghost_type_usages`] walks the parsed file and
+//! records every such reference without modifying anything, so the
+//! migration can be planned before `vstrip` rewrites the tree.
+
+use std::fmt;
+
+use verus_syn::visit::{self, Visit};
+use verus_syn::{DataMode, Fields, FnArgKind, Pat, ReturnType, Type};
+
+use crate::error::{Result, StripError};
+use crate::preprocess;
+use crate::visitor::is_spec_or_proof_fn;
+
+/// Which verification-only type was found.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum GhostTypeKind {
+    /// The mathematical integer type `int`.
+    Int,
+    /// The natural-number type `nat`.
+    Nat,
+    /// The `Ghost<T>` wrapper.
+    Ghost,
+    /// The `Tracked<T>` wrapper.
+    Tracked,
+    /// The spec sequence type `Seq<T>`.
+    Seq,
+    /// The spec map type `Map<K, V>`.
+    Map,
+}
+
+impl fmt::Display for GhostTypeKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            GhostTypeKind::Int => "int",
+            GhostTypeKind::Nat => "nat",
+            GhostTypeKind::Ghost => "Ghost",
+            GhostTypeKind::Tracked => "Tracked",
+            GhostTypeKind::Seq => "Seq",
+            GhostTypeKind::Map => "Map",
+        };
+        f.write_str(name)
+    }
+}
+
+/// Every ghost-type reference found in an exec position, as
+/// `(description, line, kind)` tuples in source order.
+///
+/// Line numbers refer to the preprocessed source; since
+/// [`preprocess::unwrap_verus_macros`] blanks the wrapper tokens in place
+/// instead of deleting them, they always match the original file.
+#[derive(Debug, Clone)]
+pub struct GhostTypeUsageReport {
+    pub locations: Vec<(String, usize, GhostTypeKind)>,
+}
+
+/// Scan `source` for ghost types (`int`, `nat`, `Ghost<T>`, `Tracked<T>`,
+/// `Seq<T>`, `Map<K, V>`) referenced from exec positions: non-spec-mode
+/// function signatures, struct and enum fields, and annotated `let`
+/// bindings inside exec functions.
+///
+/// Positions that strip cleanly on their own are not reported: spec and
+/// proof functions are skipped entirely, as are `ghost`/`tracked` fields
+/// and locals — those vanish wholesale rather than leaving a hole in
+/// surviving exec code. `let` bindings are only examined through their
+/// explicit type annotation; inferred ghost types are invisible here.
+pub fn collect_ghost_type_usages(source: &str) -> Result<GhostTypeUsageReport> {
+    let unwrapped = preprocess::unwrap_verus_macros(source);
+    let file = verus_syn::parse_file(&unwrapped).map_err(|e| StripError::ParseError {
+        path: std::path::PathBuf::from("<source>"),
+        source: e,
+    })?;
+    let mut collector = UsageCollector { locations: Vec::new(), current_fn: None };
+    collector.visit_file(&file);
+    Ok(GhostTypeUsageReport { locations: collector.locations })
+}
+
+struct UsageCollector {
+    locations: Vec<(String, usize, GhostTypeKind)>,
+    /// Name of the exec function whose body is being visited, for labelling
+    /// `let` bindings.
+    current_fn: Option<String>,
+}
+
+impl UsageCollector {
+    /// Record every ghost type mentioned anywhere inside `ty` (including
+    /// nested uses like `Vec<Ghost<int>>`, which yields two entries).
+    fn scan_type(&mut self, context: &str, ty: &Type) {
+        let mut scanner = TypeScanner { found: Vec::new() };
+        scanner.visit_type(ty);
+        for (line, kind) in scanner.found {
+            self.locations.push((context.to_string(), line, kind));
+        }
+    }
+
+    fn scan_signature(&mut self, sig: &verus_syn::Signature) {
+        let name = sig.ident.to_string();
+        for arg in &sig.inputs {
+            if let FnArgKind::Typed(pat_type) = &arg.kind {
+                let label = match &*pat_type.pat {
+                    Pat::Ident(ident) => {
+                        format!("fn {}: parameter {}", name, ident.ident)
+                    }
+                    _ => format!("fn {}: parameter", name),
+                };
+                self.scan_type(&label, &pat_type.ty);
+            }
+        }
+        if let ReturnType::Type(_, _, _, ty) = &sig.output {
+            self.scan_type(&format!("fn {}: return type", name), ty);
+        }
+    }
+
+    fn scan_fields(&mut self, owner: &str, fields: &Fields) {
+        for (index, field) in fields.iter().enumerate() {
+            if matches!(field.mode, DataMode::Ghost(_) | DataMode::Tracked(_)) {
+                continue;
+            }
+            let label = match &field.ident {
+                Some(ident) => format!("{}: field {}", owner, ident),
+                None => format!("{}: field {}", owner, index),
+            };
+            self.scan_type(&label, &field.ty);
+        }
+    }
+}
+
+impl<'ast> Visit<'ast> for UsageCollector {
+    fn visit_item_fn(&mut self, func: &'ast verus_syn::ItemFn) {
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            return;
+        }
+        self.scan_signature(&func.sig);
+        let saved = self.current_fn.replace(func.sig.ident.to_string());
+        visit::visit_item_fn(self, func);
+        self.current_fn = saved;
+    }
+
+    fn visit_impl_item_fn(&mut self, func: &'ast verus_syn::ImplItemFn) {
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            return;
+        }
+        self.scan_signature(&func.sig);
+        let saved = self.current_fn.replace(func.sig.ident.to_string());
+        visit::visit_impl_item_fn(self, func);
+        self.current_fn = saved;
+    }
+
+    fn visit_trait_item_fn(&mut self, func: &'ast verus_syn::TraitItemFn) {
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            return;
+        }
+        self.scan_signature(&func.sig);
+        let saved = self.current_fn.replace(func.sig.ident.to_string());
+        visit::visit_trait_item_fn(self, func);
+        self.current_fn = saved;
+    }
+
+    fn visit_item_struct(&mut self, item: &'ast verus_syn::ItemStruct) {
+        self.scan_fields(&format!("struct {}", item.ident), &item.fields);
+    }
+
+    fn visit_item_enum(&mut self, item: &'ast verus_syn::ItemEnum) {
+        for variant in &item.variants {
+            self.scan_fields(
+                &format!("enum {}::{}", item.ident, variant.ident),
+                &variant.fields,
+            );
+        }
+    }
+
+    fn visit_local(&mut self, local: &'ast verus_syn::Local) {
+        if local.ghost.is_none() && local.tracked.is_none() {
+            if let Pat::Type(pat_type) = &local.pat {
+                let label = match (&self.current_fn, &*pat_type.pat) {
+                    (Some(name), Pat::Ident(ident)) => {
+                        format!("fn {}: let {}", name, ident.ident)
+                    }
+                    (Some(name), _) => format!("fn {}: let binding", name),
+                    (None, _) => "let binding".to_string(),
+                };
+                self.scan_type(&label, &pat_type.ty);
+            }
+        }
+        visit::visit_local(self, local);
+    }
+}
+
+struct TypeScanner {
+    found: Vec<(usize, GhostTypeKind)>,
+}
+
+impl<'ast> Visit<'ast> for TypeScanner {
+    fn visit_type_path(&mut self, type_path: &'ast verus_syn::TypePath) {
+        if let Some(last) = type_path.path.segments.last() {
+            let kind = match last.ident.to_string().as_str() {
+                "int" => Some(GhostTypeKind::Int),
+                "nat" => Some(GhostTypeKind::Nat),
+                "Ghost" => Some(GhostTypeKind::Ghost),
+                "Tracked" => Some(GhostTypeKind::Tracked),
+                "Seq" => Some(GhostTypeKind::Seq),
+                "Map" => Some(GhostTypeKind::Map),
+                _ => None,
+            };
+            if let Some(kind) = kind {
+                self.found.push((last.ident.span().start().line, kind));
+            }
+        }
+        visit::visit_type_path(self, type_path);
+    }
+}
@@ -0,0 +1,55 @@
+//! Discovery of `include!`-ed source files.
+//!
+//! Crates occasionally assemble a module from pieces with
+//! `include!("part.rs")`; stripping only the including file then leaves Verus
+//! syntax in the composed crate. With `--follow-includes`, every `include!`
+//! whose argument is a literal relative path is resolved against the
+//! including file and processed as well. `include!(concat!(...))` and other
+//! non-literal forms cannot be resolved without expanding macros; they are
+//! reported, not followed.
+
+use std::path::{Path, PathBuf};
+
+use verus_syn::visit::{self, Visit};
+
+use crate::error::{Result, StripError};
+
+/// The `include!` arguments found in one file, split into paths we can
+/// follow and token forms we cannot.
+pub struct IncludeScan {
+    pub resolved: Vec<PathBuf>,
+    pub unresolved: Vec<String>,
+}
+
+/// Scan `source` (the text of `including_file`) for `include!` invocations.
+pub fn scan_includes(source: &str, including_file: &Path) -> Result<IncludeScan> {
+    let unwrapped = crate::preprocess::unwrap_verus_macros(source);
+    let file = verus_syn::parse_file(&unwrapped).map_err(|e| StripError::ParseError {
+        path: including_file.to_path_buf(),
+        source: e,
+    })?;
+    let base = including_file.parent().unwrap_or_else(|| Path::new(".")).to_path_buf();
+    let mut collector =
+        IncludeCollector { scan: IncludeScan { resolved: Vec::new(), unresolved: Vec::new() }, base };
+    collector.visit_file(&file);
+    Ok(collector.scan)
+}
+
+struct IncludeCollector {
+    scan: IncludeScan,
+    base: PathBuf,
+}
+
+impl<'ast> Visit<'ast> for IncludeCollector {
+    fn visit_macro(&mut self, mac: &'ast verus_syn::Macro) {
+        // `include!` only; `include_str!`/`include_bytes!` embed data, not
+        // code, and need no stripping.
+        if mac.path.segments.last().is_some_and(|seg| seg.ident == "include") {
+            match verus_syn::parse2::<verus_syn::LitStr>(mac.tokens.clone()) {
+                Ok(lit) => self.scan.resolved.push(self.base.join(lit.value())),
+                Err(_) => self.scan.unresolved.push(mac.tokens.to_string()),
+            }
+        }
+        visit::visit_macro(self, mac);
+    }
+}
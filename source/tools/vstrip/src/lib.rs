@@ -0,0 +1,1361 @@
+//! `vstrip`: strip Verus specification and proof code from Rust source,
+//! leaving the compilable executable subset.
+//!
+//! The pipeline is: [`preprocess::unwrap_verus_macros`] textually removes
+//! `verus! { ... }` wrappers, `verus_syn` parses the result as Verus-extended
+//! Rust, [`visitor::StripVisitor`] deletes ghost code and spec annotations,
+//! [`validate::check_duplicates`] rejects output where stripping made distinct
+//! items collide, and `verus_prettyplease` renders the surviving tree.
+
+pub mod api_diff;
+pub mod attributes;
+pub mod cache;
+pub mod cleanup;
+pub mod cli;
+pub mod config;
+pub mod count;
+pub mod diagnostics;
+pub mod diff;
+pub mod error;
+pub mod filters;
+pub mod ghost_usage;
+pub mod includes;
+pub mod package;
+pub mod preprocess;
+pub mod prune;
+pub mod reporter;
+pub mod rewrap;
+pub mod sourcemap;
+pub mod stats;
+pub mod type_fix;
+pub mod validate;
+pub mod visitor;
+pub mod walk;
+pub mod warning;
+
+pub use config::{Config, ConfigBuilder};
+pub use error::{Result, StripError};
+pub use reporter::{Level, Reporter};
+pub use visitor::{
+    strip_block, strip_expr, strip_item, StripDisposition, StripReport, StrippedItem,
+    StrippedItemKind,
+};
+pub use warning::Warning;
+
+use std::fs;
+use std::path::Path;
+
+use quote::ToTokens;
+use verus_syn::visit_mut::VisitMut;
+
+use reporter::EventContext;
+use visitor::StripVisitor;
+
+/// Everything one strip of one source text produced, beyond the output
+/// itself: which items disappeared and what the visitor found suspicious.
+#[derive(Debug, Clone)]
+pub struct StripResult {
+    /// The stripped, reformatted source.
+    pub output: String,
+    /// Every named item that was removed entirely, in visit order.
+    pub stripped_items: Vec<StrippedItem>,
+    /// Suspicious situations noticed while stripping; nothing fatal.
+    pub warnings: Vec<Warning>,
+    /// Aggregate counts over `stripped_items`, as rendered by the
+    /// `strip-report` progress event.
+    pub report: StripReport,
+    /// Anchors mapping output positions back to original lines; written to a
+    /// sidecar file under [`Config::emit_source_map`].
+    pub source_map: sourcemap::SourceMap,
+    /// Per-category counters of removed constructs — spec and proof fns,
+    /// ghost params and fields, asserts, loop clauses, ... — as printed
+    /// under `--stats`; see [`stats::StripStats`] for the categories.
+    pub stats: stats::StripStats,
+}
+
+/// Strip all Verus constructs from `source`, returning plain Rust.
+pub fn strip_source(source: &str, config: &Config) -> Result<String> {
+    Ok(strip_source_detailed(source, config)?.output)
+}
+
+/// Like [`strip_source`], but return the full [`StripResult`]: the output
+/// plus the removed items and any warnings.
+pub fn strip_source_detailed(source: &str, config: &Config) -> Result<StripResult> {
+    strip_source_at(source, config, Path::new("<source>"))
+}
+
+/// Like [`strip_source`], but also return a [`StripReport`] tallying what
+/// was removed (e.g. how many open vs. closed spec functions the file had).
+pub fn strip_source_with_report(source: &str, config: &Config) -> Result<(String, StripReport)> {
+    let result = strip_source_detailed(source, config)?;
+    Ok((result.output, result.report))
+}
+
+/// Strip the file at `path`, returning plain Rust.
+pub fn strip_file(path: &Path, config: &Config) -> Result<String> {
+    Ok(strip_file_detailed(path, config)?.output)
+}
+
+/// Like [`strip_file`], but return the full [`StripResult`].
+pub fn strip_file_detailed(path: &Path, config: &Config) -> Result<StripResult> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| StripError::IoError { path: path.to_path_buf(), source: e })?;
+    strip_source_at(&source, config, path)
+}
+
+/// Strip several files as one unit: every file's signatures are indexed
+/// before any rewriting, so a ghost parameter (or ghost return-tuple
+/// element) dropped from a function in one file also has its call sites
+/// rewritten in the others. [`strip_file`] on its own only sees callees
+/// defined in the same file. Results come back in the order of `paths`.
+pub fn strip_files(paths: &[std::path::PathBuf], config: &Config) -> Result<Vec<StripResult>> {
+    let mut index = visitor::GhostSignatureIndex::default();
+    let mut sources = Vec::with_capacity(paths.len());
+    for path in paths {
+        let source = fs::read_to_string(path)
+            .map_err(|e| StripError::IoError { path: path.clone(), source: e })?;
+        let unwrapped = preprocess::unwrap_verus_macros(&source);
+        let file = verus_syn::parse_file(&unwrapped)
+            .map_err(|e| StripError::ParseError { path: path.clone(), source: e })?;
+        index.add_file(&file);
+        sources.push(source);
+    }
+    paths
+        .iter()
+        .zip(sources)
+        .map(|(path, source)| strip_source_at_seeded(&source, config, path, Some(&index)))
+        .collect()
+}
+
+/// Strip Verus constructs from already-tokenized source, for build scripts
+/// and proc macros that hold a `TokenStream` (from `quote!` or a macro body)
+/// rather than text. The tokens are parsed directly as a file — no
+/// `verus! { ... }` wrapper is expected, since such callers hold the macro
+/// body itself — the usual visitor passes run, and the survivors come back
+/// as tokens instead of pretty-printed text. Parse failures map to
+/// [`StripError::ParseError`] with the placeholder path `<tokens>`.
+///
+/// Streams built with `quote!` lose the `Joint` punct spacing that composite
+/// Verus operators such as `==>` rely on; it is restored before parsing (see
+/// `preprocess::rejoin_verus_operators`).
+pub fn strip_tokens(
+    tokens: proc_macro2::TokenStream,
+    config: &Config,
+) -> Result<proc_macro2::TokenStream> {
+    let tokens = preprocess::rejoin_verus_operators(tokens);
+    let mut file: verus_syn::File = verus_syn::parse2(tokens).map_err(|e| StripError::ParseError {
+        path: std::path::PathBuf::from("<tokens>"),
+        source: e,
+    })?;
+    if config.attributes_only {
+        attributes::AttributeStripVisitor.visit_file_mut(&mut file);
+        return Ok(file.into_token_stream());
+    }
+    let previously_used = cleanup::used_identifiers(&file);
+    let mut visitor = StripVisitor::new(config);
+    visitor.visit_file_mut(&mut file);
+    cleanup::remove_unused_imports(&mut file, &previously_used, config);
+    if config.strip_verifier_attrs {
+        attributes::AttributeStripVisitor.visit_file_mut(&mut file);
+    }
+    if !visitor.empty_bodies.is_empty() {
+        return Err(StripError::EmptyBodies(visitor.empty_bodies));
+    }
+    let duplicates = validate::check_duplicates(&file);
+    if !duplicates.is_empty() {
+        return Err(StripError::DuplicateItems(duplicates));
+    }
+    if config.aggressive_type_fixing {
+        type_fix::TypeFixVisitor.visit_file_mut(&mut file);
+    }
+    Ok(file.into_token_stream())
+}
+
+fn strip_source_at(source: &str, config: &Config, path: &Path) -> Result<StripResult> {
+    strip_source_at_seeded(source, config, path, None)
+}
+
+fn strip_source_at_seeded(
+    source: &str,
+    config: &Config,
+    path: &Path,
+    seed: Option<&visitor::GhostSignatureIndex>,
+) -> Result<StripResult> {
+    let result = strip_source_once(source, config, path, seed)?;
+    if config.check_idempotent {
+        // A stable pipeline must be a fixed point after one pass: parsing its
+        // own output and stripping again has nothing left to remove. The
+        // api-diff report is silenced for the verification pass, or it would
+        // print twice.
+        let second = {
+            let mut quiet = config.clone();
+            quiet.api_diff = None;
+            strip_source_once(&result.output, &quiet, path, seed)?
+        };
+        if second.output != result.output {
+            return Err(StripError::IdempotencyError {
+                first: result.output,
+                second: second.output,
+            });
+        }
+    }
+    if config.strict {
+        let unsupported: Vec<Warning> = result
+            .warnings
+            .iter()
+            .filter(|w| matches!(w, Warning::UnknownVerusConstruct { .. }))
+            .cloned()
+            .collect();
+        if !unsupported.is_empty() {
+            return Err(StripError::UnsupportedConstructs(unsupported));
+        }
+    }
+    if config.deny_warnings && !result.warnings.is_empty() {
+        // The warnings ride along in the error so the caller sees what
+        // tripped the denial; nothing downstream gets written.
+        return Err(StripError::WarningsDenied(result.warnings));
+    }
+    Ok(result)
+}
+
+fn strip_source_once(
+    source: &str,
+    config: &Config,
+    path: &Path,
+    seed: Option<&visitor::GhostSignatureIndex>,
+) -> Result<StripResult> {
+    if config.attributes_only {
+        // The attribute pass removes no items, so its result is bare output.
+        return Ok(StripResult {
+            output: strip_attributes_at(source, path)?,
+            stripped_items: Vec::new(),
+            warnings: Vec::new(),
+            report: StripReport::default(),
+            source_map: sourcemap::SourceMap::default(),
+            stats: stats::StripStats::default(),
+        });
+    }
+    let unwrapped = preprocess::unwrap_verus_macros(source);
+    let mut file = verus_syn::parse_file(&unwrapped)
+        .map_err(|e| StripError::ParseError { path: path.to_path_buf(), source: e })?;
+    let pre_strip = if config.api_diff.is_some() || config.fail_on_api_change {
+        Some(file.clone())
+    } else {
+        None
+    };
+    let had_items = !file.items.is_empty();
+    let previously_used = cleanup::used_identifiers(&file);
+    let mut visitor = StripVisitor::new(config);
+    if let Some(seed) = seed {
+        visitor.seed_signature_index(seed.clone());
+    }
+    visitor.visit_file_mut(&mut file);
+    cleanup::remove_unused_imports(&mut file, &previously_used, config);
+    if config.strip_verifier_attrs {
+        // `#[verifier::*]` attributes mean nothing without Verus; reuse the
+        // attribute pass, which knows every position they can appear in,
+        // instead of teaching StripVisitor about attributes too.
+        attributes::AttributeStripVisitor.visit_file_mut(&mut file);
+    }
+    if had_items && file.items.is_empty() {
+        visitor.warnings.push(Warning::OnlySpecCode { path: path.display().to_string() });
+    }
+    if !visitor.empty_bodies.is_empty() {
+        return Err(StripError::EmptyBodies(std::mem::take(&mut visitor.empty_bodies)));
+    }
+    let duplicates = validate::check_duplicates(&file);
+    if !duplicates.is_empty() {
+        return Err(StripError::DuplicateItems(duplicates));
+    }
+    visitor
+        .warnings
+        .extend(visitor::dangling_call_warnings(&file, &visitor.stripped_items));
+    if let Some(pre_strip) = pre_strip {
+        let changes = api_diff::diff_public_api(&pre_strip, &file);
+        match config.api_diff {
+            Some(config::ApiDiffFormat::Text) => eprint!("{}", api_diff::render_text(&changes)),
+            Some(config::ApiDiffFormat::Json) => eprintln!("{}", api_diff::render_json(&changes)),
+            None => {}
+        }
+        if config.fail_on_api_change {
+            let changed: Vec<_> = changes
+                .into_iter()
+                .filter(|c| c.kind == api_diff::ApiChangeKind::ChangedSignature)
+                .collect();
+            if !changed.is_empty() {
+                return Err(StripError::ApiChanged(changed));
+            }
+        }
+    }
+    if config.aggressive_type_fixing {
+        type_fix::TypeFixVisitor.visit_file_mut(&mut file);
+    }
+    let output = verus_prettyplease::unparse(&file);
+    if config.verify_output {
+        // A failure here is a bug in the visitor or a construct it cannot
+        // fully translate; the error fires before any caller writes, so the
+        // original file is left untouched.
+        if let Err(e) = syn::parse_file(&output) {
+            let line = e.span().start().line;
+            let snippet = output
+                .lines()
+                .nth(line.saturating_sub(1))
+                .unwrap_or("")
+                .trim()
+                .to_string();
+            return Err(StripError::InvalidOutput {
+                path: path.to_path_buf(),
+                source: e,
+                snippet,
+            });
+        }
+    }
+    let source_map = sourcemap::build_source_map(&file, &output);
+    Ok(StripResult {
+        output,
+        stripped_items: visitor.stripped_items,
+        warnings: visitor.warnings,
+        report: visitor.report,
+        source_map,
+        stats: visitor.stats,
+    })
+}
+
+/// Remove only `#[verifier::*]` attributes from `source`, leaving bodies,
+/// ghost code, and spec clauses intact.
+///
+/// This is the gentle sibling of [`strip_source`], for codebases partially
+/// migrating off Verus. It shares the preprocessing and rendering pipeline,
+/// so `verus! { ... }` wrappers are unwrapped and the output is reformatted
+/// by `verus_prettyplease`, but no code is added or removed beyond the
+/// attributes themselves.
+pub fn strip_verus_attributes_only(source: &str, _config: &Config) -> Result<String> {
+    strip_attributes_at(source, Path::new("<source>"))
+}
+
+fn strip_attributes_at(source: &str, path: &Path) -> Result<String> {
+    let unwrapped = preprocess::unwrap_verus_macros(source);
+    let mut file = verus_syn::parse_file(&unwrapped)
+        .map_err(|e| StripError::ParseError { path: path.to_path_buf(), source: e })?;
+    attributes::AttributeStripVisitor.visit_file_mut(&mut file);
+    Ok(verus_prettyplease::unparse(&file))
+}
+
+/// Strip from an arbitrary reader to an arbitrary writer.
+///
+/// The input is still buffered in full — `verus_syn` needs the whole source
+/// to parse — but the output is written to `writer` in fixed-size chunks
+/// rather than handed back as one large `String`, which keeps the peak
+/// footprint down for very large (usually generated) files.
+pub fn strip_source_streaming<R: std::io::Read, W: std::io::Write>(
+    mut reader: R,
+    mut writer: W,
+    config: &Config,
+) -> Result<()> {
+    let stream_path = Path::new("<stream>");
+    let io_err = |e| StripError::IoError { path: stream_path.to_path_buf(), source: e };
+    let mut source = String::new();
+    reader.read_to_string(&mut source).map_err(io_err)?;
+    let stripped = strip_source_at(&source, config, stream_path)?.output;
+    drop(source);
+    write_in_chunks(&mut writer, &stripped, stream_path)
+}
+
+/// Write `text` to `writer` in fixed-size chunks, attributing I/O errors to
+/// `path`.
+fn write_in_chunks<W: std::io::Write>(writer: &mut W, text: &str, path: &Path) -> Result<()> {
+    let io_err = |e| StripError::IoError { path: path.to_path_buf(), source: e };
+    const CHUNK: usize = 64 * 1024;
+    for chunk in text.as_bytes().chunks(CHUNK) {
+        writer.write_all(chunk).map_err(io_err)?;
+    }
+    writer.flush().map_err(io_err)
+}
+
+/// Strip `source` and write the output to `writer` instead of returning it.
+///
+/// The renderer still produces the output as one string, but it is written
+/// out and dropped here rather than handed back, so the caller never holds
+/// the source and the stripped text at the same time.
+pub fn strip_source_to_writer<W: std::io::Write>(
+    source: &str,
+    config: &Config,
+    writer: &mut W,
+) -> Result<()> {
+    let stripped = strip_source_at(source, config, Path::new("<source>"))?.output;
+    write_in_chunks(writer, &stripped, Path::new("<writer>"))
+}
+
+/// [`strip_source_to_writer`] reading from the file at `path`; errors (both
+/// parse and I/O) are attributed to that path.
+pub fn strip_file_to_writer<W: std::io::Write>(
+    path: &Path,
+    config: &Config,
+    writer: &mut W,
+) -> Result<()> {
+    let source = fs::read_to_string(path)
+        .map_err(|e| StripError::IoError { path: path.to_path_buf(), source: e })?;
+    let stripped = strip_source_at(&source, config, path)?.output;
+    drop(source);
+    write_in_chunks(writer, &stripped, path)
+}
+
+/// [`strip_source_streaming`] between two files, with buffered I/O.
+pub fn strip_file_streaming(input: &Path, output: &Path, config: &Config) -> Result<()> {
+    let reader = std::io::BufReader::new(
+        fs::File::open(input)
+            .map_err(|e| StripError::IoError { path: input.to_path_buf(), source: e })?,
+    );
+    let writer = std::io::BufWriter::new(
+        fs::File::create(output)
+            .map_err(|e| StripError::IoError { path: output.to_path_buf(), source: e })?,
+    );
+    strip_source_streaming(reader, writer, config)
+}
+
+/// Strip `source` and re-parse the output with the standard `syn` crate, for
+/// downstream tools that work in the `syn` type hierarchy rather than
+/// `verus_syn`.
+///
+/// Stripped output is always supposed to be plain Rust, so a parse failure
+/// here indicates a bug in the visitor; it is reported as
+/// [`StripError::ParseError`] against the stripped text.
+pub fn strip_source_to_syn_file(source: &str, config: &Config) -> Result<syn::File> {
+    let stripped = strip_source(source, config)?;
+    syn::parse_file(&stripped).map_err(|e| StripError::ParseError {
+        path: std::path::PathBuf::from("<stripped output>"),
+        source: verus_syn::Error::new(e.span(), e.to_string()),
+    })
+}
+
+/// Process `config.input` according to the configured mode: a single file,
+/// with `recursive` every `.rs` file under a directory, or with `package` the
+/// sources belonging to a Cargo package.
+///
+/// Progress and diagnostics go to a [`reporter::StderrReporter`] at
+/// [`Config::verbosity`] — or, under [`Config::message_format`] json, as one
+/// [`diagnostics::Message`] JSON object per stderr line — and
+/// [`process_with_reporter`] can capture, silence, or restructure them.
+///
+/// Multi-file runs process (and report) files in lexicographic path order,
+/// whatever order the filesystem hands them back in, so two runs over the
+/// same tree produce byte-identical stdout and list failures identically —
+/// stripped artifacts built by concatenation are reproducible. The only
+/// exception is diagnostic interleaving under [`Config::parallel_jobs`].
+pub fn process(config: &Config) -> Result<()> {
+    if config.message_format == config::MessageFormat::Json {
+        return process_with_reporter(config, &reporter::MessageReporter);
+    }
+    let stderr = if config.quiet {
+        reporter::StderrReporter::errors_only()
+    } else {
+        reporter::StderrReporter::new(config.verbosity)
+    };
+    process_with_reporter(config, &stderr)
+}
+
+/// [`process`] with every progress and diagnostic message routed through
+/// `reporter` instead of printed to stderr.
+pub fn process_with_reporter(config: &Config, reporter: &dyn Reporter) -> Result<()> {
+    // Configs built by [`ConfigBuilder`] were validated already, but ones
+    // assembled directly or from layered sources were not.
+    config.validate()?;
+    // `diff` occupies stdout even alongside `check`, so it never frees it.
+    if config.json_diagnostics
+        && (config.diff
+            || (!config.check
+                && !config.in_place
+                && config.output.is_none()
+                && config.out_dir.is_none()))
+    {
+        return Err(StripError::ConfigError(
+            "--json writes diagnostics to stdout, which would interleave with stripped \
+             source (use --check, --in-place, --output, or --out-dir)"
+                .to_string(),
+        ));
+    }
+    if config.stats.is_some()
+        && (config.diff
+            || (!config.check
+                && !config.in_place
+                && config.output.is_none()
+                && config.out_dir.is_none()))
+    {
+        return Err(StripError::ConfigError(
+            "--stats writes its report to stdout, which would interleave with stripped \
+             source (use --check, --in-place, --output, or --out-dir)"
+                .to_string(),
+        ));
+    }
+    if config.list_removed
+        && (config.diff
+            || (!config.check
+                && !config.in_place
+                && config.output.is_none()
+                && config.out_dir.is_none()))
+    {
+        return Err(StripError::ConfigError(
+            "--list-removed writes its report to stdout, which would interleave with \
+             stripped source (use --check, --in-place, --output, or --out-dir)"
+                .to_string(),
+        ));
+    }
+    if config.count {
+        return process_count(config, reporter);
+    }
+    if config.input == Path::new("-") {
+        return process_stdin(config);
+    }
+    let mut cache = match &config.cache {
+        Some(path) => Some(cache::IncrementalCache::load(path)?),
+        None => None,
+    };
+    let outcome = if let Some(manifest) = &config.files_from {
+        process_manifest(manifest, config, cache.as_mut(), reporter)
+    } else if config.package {
+        process_package(config, cache.as_mut(), reporter)
+    } else if config.input.is_dir() {
+        if !config.recursive {
+            return Err(StripError::ConfigError(format!(
+                "{} is a directory (pass --recursive to process it)",
+                config.input.display()
+            )));
+        }
+        process_directory(config, cache.as_mut(), reporter)
+    } else {
+        let input = config.input.clone();
+        match process_file(&input, config, cache.as_mut(), reporter) {
+            Ok(outcome) => {
+                if config.json_diagnostics {
+                    println!("{}", diagnostics::render(&[outcome.diagnostic]));
+                }
+                if let Some(format) = config.stats {
+                    print_stats(format, std::slice::from_ref(&outcome.stats));
+                }
+                if let Some(map_path) = &config.line_map {
+                    let tables: Vec<_> = outcome.line_map.iter().cloned().collect();
+                    fs::write(map_path, sourcemap::render_line_maps(&tables))
+                        .map_err(|e| StripError::IoError { path: map_path.clone(), source: e })?;
+                }
+                if outcome.changed {
+                    Err(StripError::DiffsFound(vec![input]))
+                } else {
+                    Ok(())
+                }
+            }
+            Err(e) => {
+                if config.json_diagnostics {
+                    let diagnostic = diagnostics::FileDiagnostic::error(&input, e.to_string());
+                    println!("{}", diagnostics::render(&[diagnostic]));
+                }
+                Err(e)
+            }
+        }
+    };
+    if let (Some(cache), Some(path)) = (&cache, &config.cache) {
+        // Persist what was processed even if some files failed.
+        cache.save(path)?;
+    }
+    outcome
+}
+
+/// `INPUT` of `-`: read the whole source from stdin, strip it, and write to
+/// stdout (or `--output`). Made for editor filters like `:%!vstrip -`; modes
+/// that need a real file or directory are rejected up front.
+fn process_stdin(config: &Config) -> Result<()> {
+    if config.in_place {
+        return Err(StripError::ConfigError(
+            "--in-place cannot be used when reading from stdin".to_string(),
+        ));
+    }
+    if config.recursive {
+        return Err(StripError::ConfigError(
+            "--recursive cannot be used when reading from stdin".to_string(),
+        ));
+    }
+    if config.package {
+        return Err(StripError::ConfigError(
+            "--package cannot be used when reading from stdin".to_string(),
+        ));
+    }
+    let stdin_path = Path::new("<stdin>");
+    let mut source = String::new();
+    std::io::Read::read_to_string(&mut std::io::stdin(), &mut source)
+        .map_err(|e| StripError::IoError { path: stdin_path.to_path_buf(), source: e })?;
+    let stripped = strip_source_at(&source, config, stdin_path)?.output;
+    if config.diff {
+        return match diff::unified_diff(&source, &stripped, stdin_path) {
+            Some(rendered) => {
+                print!("{}", rendered);
+                Err(StripError::DiffsFound(vec![stdin_path.to_path_buf()]))
+            }
+            None => Ok(()),
+        };
+    }
+    if config.check {
+        return if strip_is_clean(&source, &stripped, stdin_path)? {
+            Ok(())
+        } else {
+            Err(StripError::DiffsFound(vec![stdin_path.to_path_buf()]))
+        };
+    }
+    match &config.output {
+        Some(output) => fs::write(output, &stripped)
+            .map_err(|e| StripError::IoError { path: output.clone(), source: e })?,
+        // Chunked writes keep very large editor-filter outputs from being
+        // duplicated by the formatting machinery in `print!`.
+        None => write_in_chunks(&mut std::io::stdout().lock(), &stripped, stdin_path)?,
+    }
+    Ok(())
+}
+
+/// `--count`: parse every input file and report spec-versus-exec density
+/// metrics instead of stripping. Shares the input discovery of the other
+/// modes — single file, stdin, recursive walk, package, or manifest — but
+/// none of the writing machinery, since the report is the only output.
+fn process_count(config: &Config, reporter: &dyn Reporter) -> Result<()> {
+    if config.input == Path::new("-") {
+        let stdin_path = Path::new("<stdin>");
+        let mut source = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut source)
+            .map_err(|e| StripError::IoError { path: stdin_path.to_path_buf(), source: e })?;
+        let counts = count::count_source(&source, stdin_path)?;
+        print_counts(config, &[count::FileCounts::new(stdin_path, counts)]);
+        return Ok(());
+    }
+    let mut entries = Vec::new();
+    if let Some(manifest) = &config.files_from {
+        entries = manifest_entries(manifest)?;
+    } else if config.package {
+        let path_filters = filters::PathFilters::new(&config.include_globs, &config.exclude_globs)?;
+        for pkg in &package::discover(&config.input)? {
+            for path in &pkg.files {
+                let relative = path.strip_prefix(&pkg.root).unwrap_or(path);
+                if path_filters.admits(relative) {
+                    entries.push(path.clone());
+                }
+            }
+        }
+    } else if config.input.is_dir() {
+        if !config.recursive {
+            return Err(StripError::ConfigError(format!(
+                "{} is a directory (pass --recursive to process it)",
+                config.input.display()
+            )));
+        }
+        let path_filters = filters::PathFilters::new(&config.include_globs, &config.exclude_globs)?;
+        for path in walk::walk(&config.input, config.follow_links, config.no_ignore)?.files {
+            if path.extension().is_some_and(|ext| ext == "rs") {
+                let relative = path.strip_prefix(&config.input).unwrap_or(&path);
+                if path_filters.admits(relative) {
+                    entries.push(path);
+                }
+            }
+        }
+    } else {
+        entries.push(config.input.clone());
+    }
+    entries.sort();
+    entries.dedup();
+    let mut files = Vec::new();
+    let mut file_errors: Vec<(std::path::PathBuf, StripError)> = Vec::new();
+    for path in entries {
+        let outcome = fs::read_to_string(&path)
+            .map_err(|e| StripError::IoError { path: path.clone(), source: e })
+            .and_then(|source| count::count_source(&source, &path));
+        match outcome {
+            Ok(counts) => files.push(count::FileCounts::new(&path, counts)),
+            Err(e) => {
+                if config.fail_fast {
+                    return Err(e);
+                }
+                reporter.event(
+                    Level::Error,
+                    &e.to_string(),
+                    &EventContext::for_path("file-error", &path),
+                );
+                file_errors.push((path, e));
+            }
+        }
+    }
+    // The report prints even when some files failed: the counted files'
+    // rows are still valid.
+    print_counts(config, &files);
+    if file_errors.is_empty() {
+        Ok(())
+    } else {
+        Err(StripError::MultipleErrors(file_errors))
+    }
+}
+
+/// Print the `--count` report in the format `message_format` picks.
+fn print_counts(config: &Config, files: &[count::FileCounts]) {
+    if config.message_format == config::MessageFormat::Json {
+        println!("{}", count::render_json(files));
+    } else {
+        print!("{}", count::render_text(files));
+    }
+}
+
+/// What processing one file produced, for the caller's bookkeeping.
+struct FileOutcome {
+    /// The `--diff`/`--check` verdict: `true` if stripping would change this
+    /// file (or one reached through its `include!`s); always `false` in the
+    /// modes that write output.
+    changed: bool,
+    /// The file's entry for the `--json` diagnostic array.
+    diagnostic: diagnostics::FileDiagnostic,
+    /// The file's entry for the `--stats` report.
+    stats: stats::FileStats,
+    /// Whether stripping removed every item the file had, making it a
+    /// candidate for [`Config::remove_empty`].
+    emptied: bool,
+    /// The file's line table for the [`Config::line_map`] document, keyed by
+    /// the path the output was written to; `None` unless a table was
+    /// requested and output was written.
+    line_map: Option<(std::path::PathBuf, sourcemap::LineMap)>,
+}
+
+fn process_file(
+    path: &Path,
+    config: &Config,
+    cache: Option<&mut cache::IncrementalCache>,
+    reporter: &dyn Reporter,
+) -> Result<FileOutcome> {
+    process_file_rec(path, config, cache, reporter, &mut Vec::new())
+}
+
+/// `include_stack` holds the (canonicalized) chain of files currently being
+/// followed, for cycle detection under `follow_includes`.
+fn process_file_rec(
+    path: &Path,
+    config: &Config,
+    mut cache: Option<&mut cache::IncrementalCache>,
+    reporter: &dyn Reporter,
+    include_stack: &mut Vec<std::path::PathBuf>,
+) -> Result<FileOutcome> {
+    if let Some(cache) = &cache {
+        if cache.is_current(path) {
+            return Ok(FileOutcome {
+                changed: false,
+                diagnostic: diagnostics::FileDiagnostic::ok(path, 0, Vec::new()),
+                stats: stats::FileStats::new(path, stats::StripStats::default(), 0, 0),
+                emptied: false,
+                line_map: None,
+            });
+        }
+    }
+    reporter.event(
+        Level::Info,
+        &format!("processing {}", path.display()),
+        &EventContext::for_path("file-start", path),
+    );
+    let source = fs::read_to_string(path)
+        .map_err(|e| StripError::IoError { path: path.to_path_buf(), source: e })?;
+    let result = strip_source_at(&source, config, path)?;
+    let diagnostic =
+        diagnostics::FileDiagnostic::ok(path, result.stripped_items.len(), result.warnings.clone());
+    // The strip pass already diagnosed total emptiness; its warning doubles
+    // as the `remove_empty` signal, so a file the author left empty — which
+    // gets no warning — is never a removal candidate.
+    let emptied =
+        result.warnings.iter().any(|w| matches!(w, Warning::OnlySpecCode { .. }));
+    let stripped = result.output;
+    let file_stats = stats::FileStats::new(path, result.stats, source.len(), stripped.len());
+    reporter.event(
+        Level::Debug,
+        &format!("{}: {}", path.display(), result.report),
+        &EventContext::for_path("strip-report", path),
+    );
+    for item in &result.stripped_items {
+        reporter.event(
+            Level::Debug,
+            &format!("{}:{}: removed {}", path.display(), item.line, item),
+            &EventContext::for_path("stripped-item", path),
+        );
+    }
+    for warning in &result.warnings {
+        if config.message_format == config::MessageFormat::Json {
+            // Editors parse these lines; they bypass the reporter's
+            // verbosity filtering the same way `--json` bypasses stdout.
+            eprintln!("{}", diagnostics::Message::from_warning(path, warning).render_line());
+        } else {
+            reporter.event(
+                Level::Warn,
+                &format!("{}: {}", path.display(), warning),
+                &EventContext::for_path("warning", path),
+            );
+        }
+    }
+    if config.list_removed && !result.stripped_items.is_empty() {
+        // Unlike the per-item Debug events above, this is the file's review
+        // record: printed to stdout whatever the verbosity.
+        println!("{}:", path.display());
+        for item in &result.stripped_items {
+            println!("  {} (line {})", item, item.line);
+        }
+    }
+    let mut changed = false;
+    if config.follow_includes {
+        let scan = includes::scan_includes(&source, path)?;
+        for unresolved in &scan.unresolved {
+            reporter.event(
+                Level::Warn,
+                &format!(
+                    "{}: cannot follow include!({}); only literal paths are resolvable",
+                    path.display(),
+                    unresolved
+                ),
+                &EventContext::for_path("unresolved-include", path),
+            );
+        }
+        let canonical = fs::canonicalize(path).unwrap_or_else(|_| path.to_path_buf());
+        include_stack.push(canonical);
+        for included in &scan.resolved {
+            let included_canonical =
+                fs::canonicalize(included).unwrap_or_else(|_| included.clone());
+            if include_stack.contains(&included_canonical) {
+                let mut chain = include_stack.clone();
+                chain.push(included_canonical);
+                return Err(StripError::IncludeCycle(chain));
+            }
+            changed |= process_file_rec(included, config, cache.as_deref_mut(), reporter, include_stack)?
+                .changed;
+        }
+        include_stack.pop();
+    }
+    if config.diff {
+        // Show what stripping would change; nothing is written and the cache
+        // is left alone so the next run reports the same files. When `check`
+        // is also set this branch subsumes it: the verdict is the diff's,
+        // which (unlike bare check) counts formatting-only changes too.
+        if let Some(rendered) = diff::unified_diff(&source, &stripped, path) {
+            print!("{}", rendered);
+            changed = true;
+        }
+        return Ok(FileOutcome {
+            changed,
+            diagnostic,
+            stats: file_stats,
+            emptied: false,
+            line_map: None,
+        });
+    }
+    if config.check {
+        // Parsing, stripping, and validation succeeded; nothing is written.
+        // Whether stripping would change the file is decided modulo
+        // formatting: both sides are compared as token streams, so only
+        // removed constructs count as a difference.
+        let clean = strip_is_clean(&source, &stripped, path)?;
+        if clean {
+            reporter.event(
+                Level::Info,
+                &format!("{}: already clean", path.display()),
+                &EventContext::for_path("check-clean", path),
+            );
+            if let Some(cache) = cache {
+                cache.update(path, &stripped);
+            }
+        } else {
+            // Deliberately left out of the cache: the next run must flag
+            // this file again.
+            reporter.event(
+                Level::Warn,
+                &format!("{}: would be stripped", path.display()),
+                &EventContext::for_path("check-would-strip", path),
+            );
+            changed = true;
+        }
+        return Ok(FileOutcome {
+            changed,
+            diagnostic,
+            stats: file_stats,
+            emptied: false,
+            line_map: None,
+        });
+    }
+    if config.in_place {
+        if let Some(suffix) = &config.backup {
+            // The rewrite must not start until the original is safe.
+            backup_original(path, suffix, config.force_backup)?;
+        }
+    }
+    let mirrored;
+    let target = if config.in_place {
+        Some(path)
+    } else if let Some(out_dir) = &config.out_dir {
+        mirrored = mirror_destination(out_dir, &config.input, path)?;
+        Some(mirrored.as_path())
+    } else {
+        config.output.as_deref()
+    };
+    match target {
+        Some(target) => write_atomic(target, &stripped)?,
+        None if config.recursive || config.package || config.files_from.is_some() => {
+            // Concatenated stdout output needs a seam per file, or the
+            // reader cannot tell where one module ends and the next begins.
+            let relative = path.strip_prefix(&config.input).unwrap_or(path);
+            println!("// ==== {} ====", relative.display());
+            if config.as_modules {
+                print!("{}", wrap_as_modules(&stripped, relative, path)?);
+            } else {
+                print!("{}", stripped);
+            }
+        }
+        None => print!("{}", stripped),
+    }
+    if config.emit_source_map {
+        if let Some(target) = target {
+            // The sidecar travels with the written output; stdout output has
+            // no stable path for one, so nothing is emitted there.
+            let sidecar = sourcemap::sidecar_path(target);
+            fs::write(&sidecar, result.source_map.to_json())
+                .map_err(|e| StripError::IoError { path: sidecar.clone(), source: e })?;
+        }
+    }
+    // Recorded after any in-place rewrite, so the entry reflects the state
+    // the file was left in.
+    if let Some(cache) = cache {
+        cache.update(path, &stripped);
+    }
+    // The table is keyed by the written file — the one whose lines compiler
+    // diagnostics will actually name.
+    let line_map = match (&config.line_map, target) {
+        (Some(_), Some(target)) => {
+            Some((target.to_path_buf(), result.source_map.to_line_map(&stripped)))
+        }
+        _ => None,
+    };
+    Ok(FileOutcome { changed: false, diagnostic, stats: file_stats, emptied, line_map })
+}
+
+/// Re-render `stripped` with its items nested in `mod` blocks derived from
+/// `relative`: `math/proofs.rs` becomes `pub mod math { pub mod proofs {
+/// ... } }`, so a concatenated crate still has one definition site per
+/// name. `mod.rs`, `lib.rs`, and `main.rs` contribute their directories
+/// only, and separators that are not valid in identifiers become `_`.
+fn wrap_as_modules(stripped: &str, relative: &Path, path: &Path) -> Result<String> {
+    let mut segments: Vec<String> = relative
+        .components()
+        .map(|c| c.as_os_str().to_string_lossy().into_owned())
+        .collect();
+    if let Some(last) = segments.last_mut() {
+        *last = last.trim_end_matches(".rs").to_string();
+        if matches!(last.as_str(), "mod" | "lib" | "main") {
+            segments.pop();
+        }
+    }
+    // The stripped output is plain Rust and known to parse; reusing the
+    // Verus parser and printer keeps the formatting identical to the
+    // unwrapped rendering.
+    let mut file = verus_syn::parse_file(stripped)
+        .map_err(|e| StripError::ParseError { path: path.to_path_buf(), source: e })?;
+    for segment in segments.iter().rev() {
+        let ident =
+            proc_macro2::Ident::new(&segment.replace('-', "_"), proc_macro2::Span::call_site());
+        let items = std::mem::take(&mut file.items);
+        file.items = vec![verus_syn::parse_quote!(pub mod #ident { #(#items)* })];
+    }
+    Ok(verus_prettyplease::unparse(&file))
+}
+
+/// Write `contents` to `path` through a temporary sibling file renamed over
+/// the target, so an interrupted or failed write (disk full, for instance)
+/// can never leave a truncated file behind — under `in_place` the target is
+/// the only copy of the annotated source. The temporary lives next to the
+/// target to keep the rename on one filesystem; on any failure it is
+/// removed and the original is untouched.
+fn write_atomic(path: &Path, contents: &str) -> Result<()> {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(format!(".vstrip-tmp.{}", std::process::id()));
+    let tmp = std::path::PathBuf::from(name);
+    let write = || -> std::io::Result<()> {
+        use std::io::Write;
+        let mut file = fs::File::create(&tmp)?;
+        file.write_all(contents.as_bytes())?;
+        // Flushed to disk before the rename makes it visible: a crash can
+        // cost the temporary, never the target.
+        file.sync_all()
+    };
+    if let Err(e) = write() {
+        let _ = fs::remove_file(&tmp);
+        return Err(StripError::IoError { path: tmp, source: e });
+    }
+    fs::rename(&tmp, path).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        StripError::IoError { path: path.to_path_buf(), source: e }
+    })
+}
+
+/// Copy `path` to `path` + `suffix` ahead of an in-place rewrite. An existing
+/// backup is only overwritten under `force`; otherwise it is treated as a
+/// leftover from an earlier run that the user has not looked at yet.
+///
+/// The copy goes through a temporary sibling and a rename, like
+/// [`write_atomic`]: the backup exists to survive failures, so a
+/// half-written one must never be mistaken for a good copy.
+fn backup_original(path: &Path, suffix: &str, force: bool) -> Result<()> {
+    let mut name = path.as_os_str().to_os_string();
+    name.push(suffix);
+    let backup = std::path::PathBuf::from(name);
+    if backup.exists() && !force {
+        return Err(StripError::IoError {
+            path: backup,
+            source: std::io::Error::new(
+                std::io::ErrorKind::AlreadyExists,
+                "backup file already exists (pass --force-backup to overwrite)",
+            ),
+        });
+    }
+    let mut tmp_name = backup.as_os_str().to_os_string();
+    tmp_name.push(format!(".vstrip-tmp.{}", std::process::id()));
+    let tmp = std::path::PathBuf::from(tmp_name);
+    if let Err(e) = fs::copy(path, &tmp) {
+        let _ = fs::remove_file(&tmp);
+        return Err(StripError::IoError { path: tmp, source: e });
+    }
+    fs::rename(&tmp, &backup).map_err(|e| {
+        let _ = fs::remove_file(&tmp);
+        StripError::IoError { path: backup.clone(), source: e }
+    })
+}
+
+/// Decide whether stripping left `source` semantically unchanged, for
+/// `--check`. Both sides are parsed and compared as token streams: that makes
+/// the comparison insensitive to formatting without ever sending the original
+/// — which still contains every Verus construct — through the printer.
+fn strip_is_clean(source: &str, stripped: &str, path: &Path) -> Result<bool> {
+    use quote::ToTokens;
+    let unwrapped = preprocess::unwrap_verus_macros(source);
+    let original = verus_syn::parse_file(&unwrapped)
+        .map_err(|e| StripError::ParseError { path: path.to_path_buf(), source: e })?;
+    let reparsed = verus_syn::parse_file(stripped).map_err(|e| {
+        // The original parsed, so stripped text that does not is a bug in
+        // the strip pipeline, not in the input; a `ParseError` here would
+        // point its coordinates at untouched original lines.
+        let line = e.span().start().line;
+        let snippet =
+            stripped.lines().nth(line.saturating_sub(1)).unwrap_or("").trim().to_string();
+        StripError::InvalidOutput {
+            path: path.to_path_buf(),
+            source: syn::Error::new(e.span(), e.to_string()),
+            snippet,
+        }
+    })?;
+    Ok(original.into_token_stream().to_string() == reparsed.into_token_stream().to_string())
+}
+
+/// Print the `--stats` report for `files` to stdout in `format`: the raw
+/// per-file array as JSON, or totals as a text table.
+fn print_stats(format: config::StatsFormat, files: &[stats::FileStats]) {
+    match format {
+        config::StatsFormat::Json => println!("{}", stats::render_json(files)),
+        config::StatsFormat::Text => print!("{}", stats::render_text(files)),
+    }
+}
+
+/// Map `path` (normally inside `input`) to its mirror under `out_dir`,
+/// creating the intermediate directories on the way.
+pub(crate) fn mirror_destination(
+    out_dir: &Path,
+    input: &Path,
+    path: &Path,
+) -> Result<std::path::PathBuf> {
+    let relative = path.strip_prefix(input).unwrap_or(path);
+    let destination = out_dir.join(relative);
+    if let Some(parent) = destination.parent() {
+        fs::create_dir_all(parent)
+            .map_err(|e| StripError::IoError { path: parent.to_path_buf(), source: e })?;
+    }
+    Ok(destination)
+}
+
+fn process_directory(
+    config: &Config,
+    cache: Option<&mut cache::IncrementalCache>,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    if let Some(out_dir) = &config.out_dir {
+        fs::create_dir_all(out_dir)
+            .map_err(|e| StripError::IoError { path: out_dir.clone(), source: e })?;
+        // Canonicalize both sides so `src` vs. `./src/../src/out` cannot
+        // smuggle the output tree into the walk: stripped files would be
+        // re-stripped on the next run.
+        let out_canonical = fs::canonicalize(out_dir)
+            .map_err(|e| StripError::IoError { path: out_dir.clone(), source: e })?;
+        let input_canonical = fs::canonicalize(&config.input)
+            .map_err(|e| StripError::IoError { path: config.input.clone(), source: e })?;
+        if out_canonical.starts_with(&input_canonical) {
+            return Err(StripError::ConfigError(format!(
+                "--out-dir {} is inside the input directory",
+                out_dir.display()
+            )));
+        }
+    }
+    let path_filters = filters::PathFilters::new(&config.include_globs, &config.exclude_globs)?;
+    let walked = walk::walk(&config.input, config.follow_links, config.no_ignore)?;
+    let ignored = walked.ignored;
+    let mut entries = Vec::new();
+    let mut skipped = 0usize;
+    for path in walked.files {
+        if path.extension().is_some_and(|ext| ext == "rs") {
+            // Patterns see paths relative to the walk root, so `tests/**`
+            // means the same thing whatever directory was passed in.
+            let relative = path.strip_prefix(&config.input).unwrap_or(&path);
+            if path_filters.admits(relative) {
+                entries.push(path);
+            } else {
+                skipped += 1;
+            }
+        } else if config.copy_assets {
+            // The output tree should be usable on its own, so non-Rust files
+            // (build scripts' inputs, fixtures, ...) travel along unchanged.
+            if let Some(out_dir) = &config.out_dir {
+                let destination = mirror_destination(out_dir, &config.input, &path)?;
+                fs::copy(&path, &destination)
+                    .map_err(|e| StripError::IoError { path: destination, source: e })?;
+                reporter.event(
+                    Level::Debug,
+                    &format!("copied {}", path.display()),
+                    &EventContext::for_path("asset-copy", &path),
+                );
+            }
+        }
+    }
+    process_entries(entries, skipped, ignored, config, cache, reporter)
+}
+
+/// Process the Cargo package at `config.input`: strip exactly the files
+/// [`package::discover`] attributes to it and its path dependencies, under
+/// the same modes a recursive walk supports.
+fn process_package(
+    config: &Config,
+    cache: Option<&mut cache::IncrementalCache>,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    let packages = package::discover(&config.input)?;
+    let path_filters = filters::PathFilters::new(&config.include_globs, &config.exclude_globs)?;
+    let mut entries = Vec::new();
+    let mut skipped = 0usize;
+    for pkg in &packages {
+        for path in &pkg.files {
+            // Patterns see paths relative to each package's own root, so
+            // `tests/**` means the same thing in the package and in its path
+            // dependencies.
+            let relative = path.strip_prefix(&pkg.root).unwrap_or(path);
+            if path_filters.admits(relative) {
+                entries.push(path.clone());
+            } else {
+                skipped += 1;
+            }
+        }
+    }
+    entries.sort();
+    entries.dedup();
+    // `--out-dir` mirrors relative to the requested package's root; when
+    // path dependencies lie outside it, everything mirrors from the closest
+    // directory containing all the packages instead, so `../util/src/lib.rs`
+    // cannot escape the output tree.
+    let base = packages
+        .iter()
+        .skip(1)
+        .fold(packages[0].root.clone(), |base, pkg| common_ancestor(&base, &pkg.root));
+    let effective = Config { input: base, ..config.clone() };
+    process_entries(entries, skipped, 0, &effective, cache, reporter)
+}
+
+/// Process exactly the files `manifest` lists (`--files-from`), bypassing
+/// the directory walk: one path per line, blank lines and `#` comments
+/// ignored. For build systems that already know the file set and pass it as
+/// a response file.
+fn process_manifest(
+    manifest: &Path,
+    config: &Config,
+    cache: Option<&mut cache::IncrementalCache>,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    let entries = manifest_entries(manifest)?;
+    // A listed file that is missing surfaces as that file's `IoError` inside
+    // `process_entries` — accumulated with the other failures, or fatal at
+    // once under `fail_fast`, like any other per-file error.
+    process_entries(entries, 0, 0, config, cache, reporter)
+}
+
+/// Read the file list out of a `--files-from` manifest (`-` for stdin),
+/// sorted and deduplicated.
+fn manifest_entries(manifest: &Path) -> Result<Vec<std::path::PathBuf>> {
+    let (contents, base) = if manifest == Path::new("-") {
+        let mut text = String::new();
+        std::io::Read::read_to_string(&mut std::io::stdin(), &mut text)
+            .map_err(|e| StripError::IoError { path: manifest.to_path_buf(), source: e })?;
+        // A list read from stdin has no manifest directory to anchor to, so
+        // relative entries resolve against the working directory.
+        (text, std::path::PathBuf::new())
+    } else {
+        let text = fs::read_to_string(manifest)
+            .map_err(|e| StripError::IoError { path: manifest.to_path_buf(), source: e })?;
+        (text, manifest.parent().unwrap_or(Path::new("")).to_path_buf())
+    };
+    let mut entries = Vec::new();
+    for line in contents.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let path = Path::new(line);
+        // Relative entries mean "relative to the manifest", so a response
+        // file keeps working wherever vstrip is invoked from.
+        entries.push(if path.is_absolute() { path.to_path_buf() } else { base.join(path) });
+    }
+    entries.sort();
+    entries.dedup();
+    Ok(entries)
+}
+
+/// The longest shared prefix of two absolute paths.
+fn common_ancestor(a: &Path, b: &Path) -> std::path::PathBuf {
+    a.components().zip(b.components()).take_while(|(x, y)| x == y).map(|(x, _)| x).collect()
+}
+
+/// Strip `entries` — sequentially or on the configured thread pool — then
+/// report per-file outcomes and the run summary. The shared back half of
+/// directory and package processing.
+fn process_entries(
+    mut entries: Vec<std::path::PathBuf>,
+    mut skipped: usize,
+    ignored: usize,
+    config: &Config,
+    mut cache: Option<&mut cache::IncrementalCache>,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    // Deterministic, lexicographic order: concatenated stdout output
+    // promises it, and sorted diagnostics are easier to compare across runs.
+    entries.sort();
+    // Files the incremental cache marks as current are skipped up front; the
+    // per-file check inside `process_file_rec` then only matters for files
+    // reached through `include!`s.
+    if let Some(cache) = &cache {
+        entries.retain(|path| {
+            let current = cache.is_current(path);
+            skipped += current as usize;
+            !current
+        });
+    }
+    let outcomes: Vec<(std::path::PathBuf, Result<FileOutcome>)> = match config.parallel_jobs {
+        Some(jobs) => {
+            use rayon::prelude::*;
+            // `num_threads(0)` is rayon's "pick the core count".
+            let pool = rayon::ThreadPoolBuilder::new()
+                .num_threads(jobs)
+                .build()
+                .map_err(|e| {
+                    StripError::ConfigError(format!("cannot build thread pool: {}", e))
+                })?;
+            // The cache is rejected alongside `parallel_jobs` at config
+            // validation, so workers can run without one.
+            pool.install(|| {
+                entries
+                    .par_iter()
+                    .map(|path| (path.clone(), process_file(path, config, None, reporter)))
+                    .collect()
+            })
+        }
+        None => {
+            let mut sequential = Vec::new();
+            for path in &entries {
+                let outcome = process_file(path, config, cache.as_deref_mut(), reporter);
+                let failed = outcome.is_err();
+                sequential.push((path.clone(), outcome));
+                // Under `fail_fast` the remaining entries are never touched.
+                if failed && config.fail_fast {
+                    break;
+                }
+            }
+            sequential
+        }
+    };
+    let mut processed = 0usize;
+    let mut file_errors: Vec<(std::path::PathBuf, StripError)> = Vec::new();
+    let mut would_change = Vec::new();
+    let mut emptied = Vec::new();
+    let mut file_diagnostics = Vec::new();
+    let mut file_stats = Vec::new();
+    let mut line_maps = Vec::new();
+    for (path, outcome) in outcomes {
+        match outcome {
+            Ok(outcome) => {
+                processed += 1;
+                file_diagnostics.push(outcome.diagnostic);
+                file_stats.push(outcome.stats);
+                if let Some(entry) = outcome.line_map {
+                    line_maps.push(entry);
+                }
+                if outcome.emptied && config.remove_empty {
+                    emptied.push(path.clone());
+                }
+                if outcome.changed {
+                    would_change.push(path);
+                }
+            }
+            Err(e) => {
+                if config.message_format == config::MessageFormat::Json {
+                    let mut message = diagnostics::Message::from_error(&e);
+                    // Errors with no location of their own still concern
+                    // this file; name it so the editor can anchor them.
+                    message.path.get_or_insert_with(|| path.display().to_string());
+                    eprintln!("{}", message.render_line());
+                } else {
+                    reporter.event(
+                        Level::Error,
+                        &e.to_string(),
+                        &EventContext::for_path("file-error", &path),
+                    );
+                }
+                file_diagnostics.push(diagnostics::FileDiagnostic::error(&path, e.to_string()));
+                file_errors.push((path, e));
+            }
+        }
+    }
+    // Deletion waits until every file is written: the declaring module
+    // files being edited here are themselves outputs of the loop above.
+    for path in &emptied {
+        prune::remove_empty_file(path, config, reporter)?;
+    }
+    if config.json_diagnostics {
+        println!("{}", diagnostics::render(&file_diagnostics));
+    }
+    if let Some(format) = config.stats {
+        print_stats(format, &file_stats);
+    }
+    if let Some(path) = &config.line_map {
+        // Written even when some files failed: the tables for the files that
+        // were written are still valid.
+        fs::write(path, sourcemap::render_line_maps(&line_maps))
+            .map_err(|e| StripError::IoError { path: path.clone(), source: e })?;
+    }
+    reporter.event(
+        Level::Info,
+        &format!(
+            "{} file(s) processed, {} error(s), {} skipped, {} ignored",
+            processed,
+            file_errors.len(),
+            skipped,
+            ignored
+        ),
+        &EventContext::new("summary"),
+    );
+    if !file_errors.is_empty() {
+        if config.fail_fast {
+            // The run stopped at this error; hand it back bare, as a
+            // single-file run would.
+            let (_, error) = file_errors.remove(0);
+            Err(error)
+        } else {
+            Err(StripError::MultipleErrors(file_errors))
+        }
+    } else if !would_change.is_empty() {
+        Err(StripError::DiffsFound(would_change))
+    } else {
+        Ok(())
+    }
+}
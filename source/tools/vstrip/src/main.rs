@@ -0,0 +1,169 @@
+use std::path::PathBuf;
+use std::process::ExitCode;
+
+use clap::Parser;
+
+use vstrip::cli::StripArgs;
+use vstrip::config::{MessageFormat, PartialConfig};
+use vstrip::Config;
+
+/// Built at compile time so clap can borrow it; the runtime `String` form
+/// would need clap's `string` feature.
+const VERSION: &str =
+    concat!(env!("CARGO_PKG_VERSION"), " (verus_syn ", env!("VSTRIP_VERUS_SYN_VERSION"), ")");
+
+/// Strip Verus specification and proof code from Rust source files.
+///
+/// Settings can also be stored in a .vstrip.toml in the input directory or
+/// any ancestor, with snake_case keys mirroring the library Config fields
+/// (e.g. spec_as_comments = true); flags given on the command line override
+/// the file.
+///
+/// Run with `--help` (rather than `-h`) for extended descriptions and
+/// examples for each option.
+#[derive(Parser)]
+#[command(name = "vstrip", version = VERSION, subcommand_negates_reqs = true)]
+struct Cli {
+    #[command(subcommand)]
+    command: Option<Command>,
+
+    /// File or directory to process, or - to read from stdin
+    #[arg(required_unless_present_any = ["package", "files_from"])]
+    input: Option<PathBuf>,
+
+    /// Recurse into directories, processing every .rs file
+    #[arg(short, long, help_heading = "Processing modes")]
+    recursive: bool,
+
+    /// Strip the Cargo package at PATH: its targets, their modules, and
+    /// path dependencies
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["input", "recursive", "output", "copy_assets"],
+        help_heading = "Processing modes",
+        long_help = "Treat PATH as a Cargo package root: read its Cargo.toml and process\n\
+                     exactly the sources that belong to the package — the declared\n\
+                     lib/bin/example/test targets plus the conventional src/, examples/,\n\
+                     and tests/ locations, every module file they reach through mod\n\
+                     declarations (even outside src/ via #[path]), and path dependencies,\n\
+                     recursively. Errors if Cargo.toml is missing or malformed. Respects\n\
+                     --out-dir and --in-place:\n\
+                     vstrip --package mycrate/ --out-dir stripped/"
+    )]
+    package: Option<PathBuf>,
+
+    /// Process exactly the files listed in PATH, one per line (- for stdin)
+    #[arg(
+        long,
+        value_name = "PATH",
+        conflicts_with_all = ["input", "recursive", "package"],
+        help_heading = "Processing modes",
+        long_help = "Read the list of files to process from PATH instead of walking a\n\
+                     directory: one path per line, blank lines and # comments ignored,\n\
+                     relative paths resolved against PATH's directory. PATH of - reads\n\
+                     the list from stdin. For build systems that already know the file\n\
+                     set and pass it as a response file; a listed file that is missing\n\
+                     is reported without stopping the rest unless --fail-fast is given:\n\
+                     vstrip --files-from stripped-files.txt --in-place"
+    )]
+    files_from: Option<PathBuf>,
+
+    /// Walk hidden directories and files listed in ignore files
+    #[arg(
+        long,
+        requires = "recursive",
+        help_heading = "Advanced options",
+        long_help = "Recursive walks normally prune hidden directories and honor\n\
+                     .gitignore/.ignore files — plus .verusignore, vstrip's own file\n\
+                     for exclusions committed to the repository — so running at a\n\
+                     repository root does not descend into target/ or .git/.\n\
+                     --no-ignore walks everything."
+    )]
+    no_ignore: bool,
+
+    #[command(flatten)]
+    strip: StripArgs,
+}
+
+#[derive(clap::Subcommand)]
+enum Command {
+    /// Re-wrap a stripped file in a verus! block, ordered like its original,
+    /// so the two diff cleanly
+    Rewrap {
+        /// Previously stripped file
+        stripped: PathBuf,
+
+        /// The original (annotated) file whose layout to mirror
+        #[arg(long)]
+        like: PathBuf,
+
+        /// Write output to this file instead of stdout
+        #[arg(short, long)]
+        output: Option<PathBuf>,
+    },
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    if let Some(Command::Rewrap { stripped, like, output }) = cli.command {
+        return match vstrip::rewrap::rewrap_file(&stripped, &like) {
+            Ok(rewrapped) => match output {
+                Some(path) => match std::fs::write(&path, rewrapped) {
+                    Ok(()) => ExitCode::SUCCESS,
+                    Err(e) => {
+                        eprintln!("error: {}: {}", path.display(), e);
+                        ExitCode::FAILURE
+                    }
+                },
+                None => {
+                    print!("{}", rewrapped);
+                    ExitCode::SUCCESS
+                }
+            },
+            Err(e) => {
+                eprintln!("error: {}", e);
+                ExitCode::FAILURE
+            }
+        };
+    }
+    // The command line is one layer: flags that were not given stay `None`
+    // and inherit from a discovered `.vstrip.toml`, if any, which in turn
+    // inherits from the defaults. The shared flags are layered by StripArgs;
+    // the input-selection flags are this binary's own.
+    let cli_layer = PartialConfig {
+        input: cli.package.clone().or(cli.input),
+        package: cli.package.is_some().then_some(true),
+        files_from: cli.files_from,
+        recursive: cli.recursive.then_some(true),
+        no_ignore: cli.no_ignore.then_some(true),
+        ..cli.strip.layer()
+    };
+    // Project-file discovery starts from the input, or from the manifest's
+    // directory when --files-from replaces the input argument.
+    let discovery_root = cli_layer
+        .input
+        .clone()
+        .or_else(|| cli_layer.files_from.clone())
+        .expect("clap requires an input, --package, or --files-from");
+    let layered = match vstrip::config::discover_project_config(&discovery_root) {
+        Ok(Some(project)) => project.merge(&cli_layer),
+        Ok(None) => cli_layer,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            return ExitCode::FAILURE;
+        }
+    };
+    let config = layered.apply_to(&Config::default());
+    match vstrip::process(&config) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            if config.message_format == MessageFormat::Json {
+                eprintln!("{}", vstrip::diagnostics::Message::from_error(&e).render_line());
+            } else {
+                eprintln!("error: {}", e);
+            }
+            ExitCode::FAILURE
+        }
+    }
+}
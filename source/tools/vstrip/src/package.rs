@@ -0,0 +1,235 @@
+//! Cargo package discovery for `--package` mode.
+//!
+//! [`discover`] reads a package's `Cargo.toml` and resolves exactly the
+//! sources that belong to it: every target Cargo would compile (the declared
+//! `lib`/`bin`/`example`/`test` entries plus the conventional autodiscovered
+//! locations), the module files those targets reach through `mod`
+//! declarations — including `#[path]`-redirected ones outside `src/` — and,
+//! recursively, any path dependencies, so a workspace-local helper crate is
+//! stripped along with its user. Registry and git dependencies have no local
+//! sources and are ignored.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::error::{Result, StripError};
+use crate::preprocess;
+
+/// One discovered package: its manifest directory and the source files that
+/// belong to it.
+#[derive(Debug)]
+pub struct PackageSources {
+    /// The `[package] name` from the manifest.
+    pub name: String,
+    /// Canonicalized directory containing the `Cargo.toml`.
+    pub root: PathBuf,
+    /// The package's `.rs` files, sorted and deduplicated.
+    pub files: Vec<PathBuf>,
+}
+
+/// Discover the package rooted at `dir` (a directory containing a
+/// `Cargo.toml`, or the manifest path itself) and its path dependencies; the
+/// requested package comes first. A missing or malformed manifest is an
+/// error.
+pub fn discover(dir: &Path) -> Result<Vec<PackageSources>> {
+    let mut packages = Vec::new();
+    let mut visited = Vec::new();
+    discover_into(dir, &mut packages, &mut visited)?;
+    Ok(packages)
+}
+
+fn discover_into(
+    dir: &Path,
+    packages: &mut Vec<PackageSources>,
+    visited: &mut Vec<PathBuf>,
+) -> Result<()> {
+    let dir = if dir.file_name().is_some_and(|n| n == "Cargo.toml") {
+        dir.parent().unwrap_or(Path::new(".")).to_path_buf()
+    } else {
+        dir.to_path_buf()
+    };
+    let root = fs::canonicalize(&dir)
+        .map_err(|e| StripError::IoError { path: dir.clone(), source: e })?;
+    // Path dependencies may form cycles through dev-dependencies; each
+    // package is discovered once.
+    if visited.contains(&root) {
+        return Ok(());
+    }
+    visited.push(root.clone());
+    let manifest_path = root.join("Cargo.toml");
+    if !manifest_path.is_file() {
+        return Err(StripError::ConfigError(format!(
+            "{} has no Cargo.toml; --package needs a package root",
+            root.display()
+        )));
+    }
+    let text = fs::read_to_string(&manifest_path)
+        .map_err(|e| StripError::IoError { path: manifest_path.clone(), source: e })?;
+    let manifest: toml::Value = text.parse().map_err(|e| {
+        StripError::ConfigError(format!(
+            "{}: malformed manifest: {}",
+            manifest_path.display(),
+            e
+        ))
+    })?;
+    let name = manifest
+        .get("package")
+        .and_then(|p| p.get("name"))
+        .and_then(toml::Value::as_str)
+        .ok_or_else(|| {
+            StripError::ConfigError(format!(
+                "{}: no [package] name (is this a bare workspace root?)",
+                manifest_path.display()
+            ))
+        })?
+        .to_string();
+    let mut files = Vec::new();
+    for entry in entry_points(&manifest, &root)? {
+        collect_modules(&entry, true, &mut files);
+    }
+    files.sort();
+    files.dedup();
+    packages.push(PackageSources { name, root: root.clone(), files });
+    for table in ["dependencies", "dev-dependencies", "build-dependencies"] {
+        let Some(deps) = manifest.get(table).and_then(toml::Value::as_table) else {
+            continue;
+        };
+        for dep in deps.values() {
+            if let Some(path) = dep.get("path").and_then(toml::Value::as_str) {
+                discover_into(&root.join(path), packages, visited)?;
+            }
+        }
+    }
+    Ok(())
+}
+
+/// Every target root Cargo would compile for this package: declared `[lib]`/
+/// `[[bin]]`/`[[example]]`/`[[test]]` paths plus the conventional
+/// autodiscovered locations. A declared `path` that does not exist is an
+/// error; a missing conventional file simply is not a target.
+fn entry_points(manifest: &toml::Value, root: &Path) -> Result<Vec<PathBuf>> {
+    let mut entries = Vec::new();
+    if let Some(path) =
+        manifest.get("lib").and_then(|l| l.get("path")).and_then(toml::Value::as_str)
+    {
+        entries.push(declared_target(root, path)?);
+    }
+    for (table, default_dir) in [("bin", "src/bin"), ("example", "examples"), ("test", "tests")] {
+        for target in manifest.get(table).and_then(toml::Value::as_array).into_iter().flatten() {
+            if let Some(path) = target.get("path").and_then(toml::Value::as_str) {
+                entries.push(declared_target(root, path)?);
+            } else if let Some(name) = target.get("name").and_then(toml::Value::as_str) {
+                let conventional = root.join(default_dir).join(format!("{}.rs", name));
+                if conventional.is_file() {
+                    entries.push(conventional);
+                }
+            }
+        }
+    }
+    for conventional in ["src/lib.rs", "src/main.rs"] {
+        let path = root.join(conventional);
+        if path.is_file() {
+            entries.push(path);
+        }
+    }
+    for dir in ["src/bin", "examples", "tests"] {
+        let Ok(read) = fs::read_dir(root.join(dir)) else {
+            continue;
+        };
+        for entry in read.flatten() {
+            let path = entry.path();
+            if path.is_file() && path.extension().is_some_and(|ext| ext == "rs") {
+                entries.push(path);
+            } else if path.is_dir() && path.join("main.rs").is_file() {
+                // Directory targets (`src/bin/tool/main.rs`).
+                entries.push(path.join("main.rs"));
+            }
+        }
+    }
+    Ok(entries)
+}
+
+fn declared_target(root: &Path, path: &str) -> Result<PathBuf> {
+    let full = root.join(path);
+    if full.is_file() {
+        Ok(full)
+    } else {
+        Err(StripError::ConfigError(format!(
+            "{}: declared target source {} does not exist",
+            root.join("Cargo.toml").display(),
+            path
+        )))
+    }
+}
+
+/// Add `file` and every module file reachable from it through `mod`
+/// declarations. A file that does not parse is still included — stripping it
+/// later produces the real error — but cannot be followed further.
+fn collect_modules(file: &Path, crate_root: bool, files: &mut Vec<PathBuf>) {
+    if files.iter().any(|f| f == file) {
+        return;
+    }
+    files.push(file.to_path_buf());
+    let Ok(source) = fs::read_to_string(file) else {
+        return;
+    };
+    let unwrapped = preprocess::unwrap_verus_macros(&source);
+    let Ok(parsed) = verus_syn::parse_file(&unwrapped) else {
+        return;
+    };
+    // Target roots and `mod.rs` files look their children up next to
+    // themselves; any other file owns the directory named after it.
+    let parent = file.parent().unwrap_or(Path::new("."));
+    let base = if crate_root || file.file_name().is_some_and(|n| n == "mod.rs") {
+        parent.to_path_buf()
+    } else {
+        parent.join(file.file_stem().unwrap_or_default())
+    };
+    collect_mod_items(&parsed.items, &base, files);
+}
+
+fn collect_mod_items(items: &[verus_syn::Item], base: &Path, files: &mut Vec<PathBuf>) {
+    for item in items {
+        let verus_syn::Item::Mod(module) = item else {
+            continue;
+        };
+        match &module.content {
+            // Inline modules extend the directory their children look in.
+            Some((_, items)) => {
+                collect_mod_items(items, &base.join(module.ident.to_string()), files)
+            }
+            None => {
+                let candidates = match path_attribute(&module.attrs) {
+                    Some(path) => vec![base.join(path)],
+                    None => vec![
+                        base.join(format!("{}.rs", module.ident)),
+                        base.join(module.ident.to_string()).join("mod.rs"),
+                    ],
+                };
+                // A module with no file behind it is most likely cfg-gated
+                // for another platform; skipping it beats failing the run.
+                if let Some(found) = candidates.into_iter().find(|c| c.is_file()) {
+                    collect_modules(&found, false, files);
+                }
+            }
+        }
+    }
+}
+
+/// The value of a `#[path = "..."]` attribute, if present.
+fn path_attribute(attrs: &[verus_syn::Attribute]) -> Option<String> {
+    for attr in attrs {
+        let verus_syn::Meta::NameValue(nv) = &attr.meta else {
+            continue;
+        };
+        if !nv.path.is_ident("path") {
+            continue;
+        }
+        if let verus_syn::Expr::Lit(lit) = &nv.value {
+            if let verus_syn::Lit::Str(s) = &lit.lit {
+                return Some(s.value());
+            }
+        }
+    }
+    None
+}
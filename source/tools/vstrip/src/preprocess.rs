@@ -0,0 +1,299 @@
+//! Text-level preprocessing that runs before parsing.
+//!
+//! `verus_syn` understands Verus syntax for items, but source files wrap that
+//! syntax in `verus! { ... }` macro invocations, inside which the parser would
+//! only see an opaque token stream. Rather than parsing and re-splicing token
+//! trees, we unwrap the macro textually: every `verus! { ... }` invocation is
+//! replaced by the text between its braces, and the result is parsed as a
+//! single Verus-extended Rust file.
+//!
+//! The unwrapped text is position-preserving: the removed wrapper tokens are
+//! blanked with spaces rather than deleted, so every surviving byte keeps its
+//! original line *and* column. Spans the parser reports against the unwrapped
+//! text — parse errors, warning locations, source-map anchors — therefore
+//! point into the original file with no mapping step.
+
+/// Replace every `verus! { ... }` invocation in `source` with the text it
+/// wraps, at any nesting depth (e.g. inside `mod` bodies). The wrapper tokens
+/// are overwritten with spaces, not removed, so positions in the result equal
+/// positions in `source` (see the module docs).
+///
+/// The scan is token-aware just enough to be safe: `verus!` occurrences inside
+/// string literals (raw ones included), character literals, and comments are
+/// left alone.
+pub fn unwrap_verus_macros(source: &str) -> String {
+    let bytes = source.as_bytes();
+    let mut out = String::with_capacity(source.len());
+    let mut last_copied = 0;
+    // Closing-brace positions of `verus!` blocks we are currently inside; the
+    // brace itself must be dropped from the output when we reach it.
+    let mut pending_closes: Vec<usize> = Vec::new();
+    let mut i = 0;
+    while i < bytes.len() {
+        if pending_closes.last() == Some(&i) {
+            pending_closes.pop();
+            out.push_str(&source[last_copied..i]);
+            out.push(' ');
+            last_copied = i + 1;
+            i += 1;
+            // Some files terminate the invocation `verus! { ... };` — the
+            // macro statement's semicolon must go with the braces, or it
+            // would survive as a stray `;` in the output.
+            let mut j = i;
+            while bytes.get(j).is_some_and(|b| b.is_ascii_whitespace()) {
+                j += 1;
+            }
+            if bytes.get(j) == Some(&b';') {
+                out.push_str(&source[last_copied..j]);
+                out.push(' ');
+                last_copied = j + 1;
+                i = j + 1;
+            }
+            continue;
+        }
+        match bytes[i] {
+            b'/' if bytes.get(i + 1) == Some(&b'/') => i = skip_line_comment(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b'"' => i = skip_string(bytes, i),
+            b'r' | b'b' if is_ident_boundary(bytes, i) => match skip_raw_string(bytes, i) {
+                Some(end) => i = end,
+                None => i += 1,
+            },
+            b'\'' => i = skip_char_or_lifetime(bytes, i),
+            b'v' if is_ident_boundary(bytes, i) && source[i..].starts_with("verus") => {
+                match verus_invocation_open_brace(bytes, i + "verus".len()) {
+                    Some(open) => match find_matching_brace(bytes, open) {
+                        Some(close) => {
+                            out.push_str(&source[last_copied..i]);
+                            // Blank the `verus! {` tokens in place, keeping
+                            // any newlines between them, so the text after
+                            // the brace stays at its original column.
+                            for c in source[i..=open].chars() {
+                                out.push(if c.is_whitespace() { c } else { ' ' });
+                            }
+                            last_copied = open + 1;
+                            pending_closes.push(close);
+                            i = open + 1;
+                        }
+                        // Unbalanced braces; leave the text alone and let the
+                        // parser report the error with a proper span.
+                        None => i += 1,
+                    },
+                    None => i += 1,
+                }
+            }
+            _ => i += 1,
+        }
+    }
+    out.push_str(&source[last_copied..]);
+    out
+}
+
+/// If the text after the `verus` identifier (starting at `i`) is `! {`, with
+/// optional whitespace, return the position of the opening brace.
+fn verus_invocation_open_brace(bytes: &[u8], mut i: usize) -> Option<usize> {
+    if bytes.get(i) != Some(&b'!') {
+        return None;
+    }
+    i += 1;
+    while bytes.get(i).is_some_and(|b| b.is_ascii_whitespace()) {
+        i += 1;
+    }
+    (bytes.get(i) == Some(&b'{')).then_some(i)
+}
+
+/// True if `i` starts a new identifier, i.e. the previous byte cannot be part
+/// of one (guards against matching the tail of `my_verus`, etc.).
+fn is_ident_boundary(bytes: &[u8], i: usize) -> bool {
+    i == 0 || !(bytes[i - 1].is_ascii_alphanumeric() || bytes[i - 1] == b'_')
+}
+
+/// Given the position of an opening `{`, return the position of the matching
+/// `}`, skipping braces inside strings, character literals, and comments.
+pub(crate) fn find_matching_brace(bytes: &[u8], open: usize) -> Option<usize> {
+    debug_assert_eq!(bytes[open], b'{');
+    let mut depth = 0usize;
+    let mut i = open;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'{' => {
+                depth += 1;
+                i += 1;
+            }
+            b'}' => {
+                depth -= 1;
+                if depth == 0 {
+                    return Some(i);
+                }
+                i += 1;
+            }
+            b'/' if bytes.get(i + 1) == Some(&b'/') => i = skip_line_comment(bytes, i),
+            b'/' if bytes.get(i + 1) == Some(&b'*') => i = skip_block_comment(bytes, i),
+            b'"' => i = skip_string(bytes, i),
+            b'r' | b'b' if is_ident_boundary(bytes, i) => match skip_raw_string(bytes, i) {
+                Some(end) => i = end,
+                None => i += 1,
+            },
+            b'\'' => i = skip_char_or_lifetime(bytes, i),
+            _ => i += 1,
+        }
+    }
+    None
+}
+
+fn skip_line_comment(bytes: &[u8], mut i: usize) -> usize {
+    while i < bytes.len() && bytes[i] != b'\n' {
+        i += 1;
+    }
+    i
+}
+
+fn skip_block_comment(bytes: &[u8], mut i: usize) -> usize {
+    // Block comments nest in Rust.
+    let mut depth = 0usize;
+    while i < bytes.len() {
+        if bytes[i] == b'/' && bytes.get(i + 1) == Some(&b'*') {
+            depth += 1;
+            i += 2;
+        } else if bytes[i] == b'*' && bytes.get(i + 1) == Some(&b'/') {
+            depth -= 1;
+            i += 2;
+            if depth == 0 {
+                return i;
+            }
+        } else {
+            i += 1;
+        }
+    }
+    i
+}
+
+/// Skip a string literal starting at the `"` at position `i`.
+fn skip_string(bytes: &[u8], mut i: usize) -> usize {
+    debug_assert_eq!(bytes[i], b'"');
+    i += 1;
+    while i < bytes.len() {
+        match bytes[i] {
+            b'\\' => i += 2,
+            b'"' => return i + 1,
+            _ => i += 1,
+        }
+    }
+    i
+}
+
+/// Skip a raw string literal (`r"..."`, `r#"..."#`, `br##"..."##`) whose
+/// first prefix byte sits at position `i`, returning the position after the
+/// closing quote and its hashes. `None` means `i` does not start a raw
+/// string (a plain identifier beginning with `r`, a `b'x'` byte literal, a
+/// `b"..."` byte string — the ordinary skippers handle those).
+fn skip_raw_string(bytes: &[u8], i: usize) -> Option<usize> {
+    let mut j = i;
+    if bytes.get(j) == Some(&b'b') {
+        j += 1;
+    }
+    if bytes.get(j) != Some(&b'r') {
+        return None;
+    }
+    j += 1;
+    let mut hashes = 0usize;
+    while bytes.get(j) == Some(&b'#') {
+        hashes += 1;
+        j += 1;
+    }
+    if bytes.get(j) != Some(&b'"') {
+        return None;
+    }
+    j += 1;
+    // No escapes inside a raw string: the literal ends at the first `"`
+    // followed by as many `#` as opened it.
+    while j < bytes.len() {
+        if bytes[j] == b'"'
+            && bytes.len() > j + hashes
+            && bytes[j + 1..=j + hashes].iter().all(|&b| b == b'#')
+        {
+            return Some(j + 1 + hashes);
+        }
+        j += 1;
+    }
+    Some(j)
+}
+
+/// Skip a character literal (`'a'`, `'\n'`); a lone `'` introducing a lifetime
+/// is stepped over without consuming anything else.
+fn skip_char_or_lifetime(bytes: &[u8], i: usize) -> usize {
+    debug_assert_eq!(bytes[i], b'\'');
+    // `'\x'` escape form
+    if bytes.get(i + 1) == Some(&b'\\') {
+        let mut j = i + 2;
+        while j < bytes.len() && bytes[j] != b'\'' {
+            j += 1;
+        }
+        return j + 1;
+    }
+    // `'c'` simple form (anything else is a lifetime)
+    if bytes.get(i + 2) == Some(&b'\'') {
+        return i + 3;
+    }
+    i + 1
+}
+
+/// The token-level counterpart of [`unwrap_verus_macros`], for
+/// [`strip_tokens`](crate::strip_tokens) input. Verus operators like `==>`
+/// are not single Rust tokens: the lexer splits `==>` into `==` and `>`, and
+/// `verus_syn` reassembles them only when the boundary punct carries
+/// `Spacing::Joint`. Streams built with `quote!` lose that spacing — the
+/// macro pushes each Rust token separately, with an `Alone` boundary — so
+/// the parser sees `== >` and reports "expected an expression". This pass
+/// restores `Joint` spacing wherever consecutive puncts spell a composite
+/// Verus operator whose split reading is not valid Rust, which makes the
+/// rejoining unambiguous.
+///
+/// `&&&` and `|||` are deliberately left alone: their split readings
+/// (`a && &b`, `|| |x| ...`) are valid code, so a stream without spacing
+/// information genuinely cannot distinguish the two.
+pub(crate) fn rejoin_verus_operators(tokens: proc_macro2::TokenStream) -> proc_macro2::TokenStream {
+    use proc_macro2::{Group, Punct, Spacing, TokenTree};
+
+    // Longest first, so `<==>` wins over `<==` and `=~~=` over `=~=`.
+    const OPERATORS: &[&str] =
+        &["<==>", "=~~=", "!~~=", "===", "!==", "==>", "<==", "=~=", "!~="];
+
+    let mut out: Vec<TokenTree> = Vec::new();
+    for tt in tokens {
+        match tt {
+            TokenTree::Group(group) => {
+                let inner = rejoin_verus_operators(group.stream());
+                let mut rebuilt = Group::new(group.delimiter(), inner);
+                rebuilt.set_span(group.span());
+                out.push(TokenTree::Group(rebuilt));
+            }
+            other => out.push(other),
+        }
+    }
+    let mut i = 0;
+    while i < out.len() {
+        let matched = OPERATORS.iter().find(|op| {
+            op.chars().enumerate().all(|(k, ch)| {
+                matches!(out.get(i + k), Some(TokenTree::Punct(p)) if p.as_char() == ch)
+            })
+        });
+        if let Some(op) = matched {
+            // Mark every char but the last `Joint`; the last keeps its own
+            // spacing so the operator does not fuse with whatever follows.
+            for k in 0..op.len() - 1 {
+                if let TokenTree::Punct(p) = &mut out[i + k] {
+                    if p.spacing() != Spacing::Joint {
+                        let mut joined = Punct::new(p.as_char(), Spacing::Joint);
+                        joined.set_span(p.span());
+                        *p = joined;
+                    }
+                }
+            }
+            i += op.len();
+        } else {
+            i += 1;
+        }
+    }
+    out.into_iter().collect()
+}
@@ -0,0 +1,167 @@
+//! Post-run removal of files stripping left empty, under
+//! [`Config::remove_empty`](crate::Config::remove_empty).
+//!
+//! A module like `proofs.rs` that held only spec and proof code strips down
+//! to an empty file, and the parent's `mod proofs;` then points at dead
+//! weight. This pass runs after a recursive or package walk has written its
+//! outputs: it deletes each emptied output file and edits the file that
+//! declared the module — the sibling `name.rs`, or `mod.rs`/`lib.rs`/
+//! `main.rs` in the declaring directory — to drop the matching `mod name;`.
+//! A `pub mod` declaration re-exports the module as part of the crate's API,
+//! so that file is kept and a warning is emitted instead; so is a file whose
+//! declaration cannot be found, and a crate root, which no `mod` declares.
+
+use std::fs;
+use std::path::{Path, PathBuf};
+
+use crate::config::Config;
+use crate::error::{Result, StripError};
+use crate::reporter::{EventContext, Level, Reporter};
+use crate::sourcemap;
+
+/// Remove the emptied output file corresponding to the source file `path`,
+/// along with the `mod` declaration pointing at it. In `out_dir` mode the
+/// output file is the mirror of `path`; in `in_place` mode it is `path`
+/// itself. The file is kept — with a warning — when its declaration is
+/// `pub` or cannot be found.
+pub fn remove_empty_file(
+    path: &Path,
+    config: &Config,
+    reporter: &dyn Reporter,
+) -> Result<()> {
+    let target = match &config.out_dir {
+        Some(out_dir) => crate::mirror_destination(out_dir, &config.input, path)?,
+        None => path.to_path_buf(),
+    };
+    let Some((name, declaring_dir)) = module_identity(&target) else {
+        reporter.event(
+            Level::Warn,
+            &format!(
+                "{}: stripped empty, but a crate root cannot be removed; left in place",
+                target.display()
+            ),
+            &EventContext::for_path("empty-file-kept", &target),
+        );
+        return Ok(());
+    };
+    for candidate in declaring_candidates(&declaring_dir) {
+        if candidate == target || !candidate.is_file() {
+            continue;
+        }
+        match remove_mod_decl(&candidate, &name)? {
+            DeclOutcome::Removed => {
+                fs::remove_file(&target)
+                    .map_err(|e| StripError::IoError { path: target.clone(), source: e })?;
+                if config.emit_source_map {
+                    // The sidecar travelled with the output; it goes too.
+                    fs::remove_file(sourcemap::sidecar_path(&target)).ok();
+                }
+                reporter.event(
+                    Level::Info,
+                    &format!("removed {} (stripped empty)", target.display()),
+                    &EventContext::for_path("empty-file-removed", &target),
+                );
+                reporter.event(
+                    Level::Debug,
+                    &format!("{}: removed `mod {};`", candidate.display(), name),
+                    &EventContext::for_path("mod-decl-removed", &candidate),
+                );
+                return Ok(());
+            }
+            DeclOutcome::Public => {
+                reporter.event(
+                    Level::Warn,
+                    &format!(
+                        "{}: `pub mod {};` re-exports a module stripping emptied; \
+                         left in place",
+                        candidate.display(),
+                        name
+                    ),
+                    &EventContext::for_path("empty-file-kept", &target),
+                );
+                return Ok(());
+            }
+            DeclOutcome::NotDeclared => {}
+        }
+    }
+    reporter.event(
+        Level::Warn,
+        &format!(
+            "{}: stripped empty, but no `mod {};` declaration was found to update; \
+             left in place",
+            target.display(),
+            name
+        ),
+        &EventContext::for_path("empty-file-kept", &target),
+    );
+    Ok(())
+}
+
+/// The module name `target` provides and the directory whose owning module
+/// file declares it, or `None` for a crate root (`lib.rs`/`main.rs`), which
+/// no `mod` declaration reaches.
+fn module_identity(target: &Path) -> Option<(String, PathBuf)> {
+    let stem = target.file_stem()?.to_str()?;
+    let dir = target.parent()?;
+    if stem == "mod" {
+        // `a/b/mod.rs` is module `b`, declared from directory `a`.
+        let name = dir.file_name()?.to_str()?.to_string();
+        Some((name, dir.parent()?.to_path_buf()))
+    } else if stem == "lib" || stem == "main" {
+        None
+    } else {
+        Some((stem.to_string(), dir.to_path_buf()))
+    }
+}
+
+/// The files that could declare a module living in `dir`, in the order they
+/// are searched: the sibling `dir.rs` form first, then the files a module
+/// rooted in `dir` itself would use.
+fn declaring_candidates(dir: &Path) -> Vec<PathBuf> {
+    let mut candidates = Vec::new();
+    if let (Some(parent), Some(name)) = (dir.parent(), dir.file_name()) {
+        let mut sibling = name.to_os_string();
+        sibling.push(".rs");
+        candidates.push(parent.join(sibling));
+    }
+    candidates.push(dir.join("mod.rs"));
+    candidates.push(dir.join("lib.rs"));
+    candidates.push(dir.join("main.rs"));
+    candidates
+}
+
+enum DeclOutcome {
+    /// The declaration was found, removed, and the file rewritten.
+    Removed,
+    /// The declaration is `pub` (or otherwise visible) and was left alone.
+    Public,
+    /// This file does not declare the module.
+    NotDeclared,
+}
+
+/// Drop `mod name;` from the file at `candidate` if it declares it
+/// privately. The file is one this run already stripped and wrote, so it
+/// parses as plain Rust and rewriting it through the same printer changes
+/// nothing but the removed declaration.
+fn remove_mod_decl(candidate: &Path, name: &str) -> Result<DeclOutcome> {
+    let source = fs::read_to_string(candidate)
+        .map_err(|e| StripError::IoError { path: candidate.to_path_buf(), source: e })?;
+    let mut file = verus_syn::parse_file(&source)
+        .map_err(|e| StripError::ParseError { path: candidate.to_path_buf(), source: e })?;
+    let Some(position) = file.items.iter().position(|item| {
+        matches!(item, verus_syn::Item::Mod(module)
+            if module.ident == name && module.content.is_none())
+    }) else {
+        return Ok(DeclOutcome::NotDeclared);
+    };
+    let verus_syn::Item::Mod(module) = &file.items[position] else {
+        unreachable!("position found by the match above");
+    };
+    if !matches!(module.vis, verus_syn::Visibility::Inherited) {
+        return Ok(DeclOutcome::Public);
+    }
+    file.items.remove(position);
+    fs::write(candidate, verus_prettyplease::unparse(&file))
+        .map_err(|e| StripError::IoError { path: candidate.to_path_buf(), source: e })?;
+    Ok(DeclOutcome::Removed)
+}
@@ -0,0 +1,176 @@
+//! Structured progress and diagnostic reporting.
+//!
+//! Historically `process` and friends printed directly to stderr, which
+//! could not be silenced, captured, or machine-parsed. Every message is now
+//! an *event* with a severity [`Level`] and a stable kind string, routed
+//! through a [`Reporter`]: human-readable stderr for the CLI (with `-v`/`-vv`
+//! opening up per-file progress and per-item detail), [`SilentReporter`] for
+//! embedders, and [`JsonReporter`] and [`MessageReporter`] for tooling that
+//! wants one JSON object per line.
+
+use std::path::Path;
+
+/// Severity of an event. [`Level::Error`] and [`Level::Warn`] are always
+/// shown by [`StderrReporter`]; [`Level::Info`] needs `-v` and
+/// [`Level::Debug`] needs `-vv`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    Error,
+    Warn,
+    Info,
+    Debug,
+}
+
+impl std::fmt::Display for Level {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Level::Error => write!(f, "error"),
+            Level::Warn => write!(f, "warning"),
+            Level::Info => write!(f, "info"),
+            Level::Debug => write!(f, "debug"),
+        }
+    }
+}
+
+/// What an event is about, beyond its human-readable message.
+///
+/// `kind` is a stable, machine-matchable identifier; the set currently
+/// emitted by [`crate::process`] is: `file-start`, `strip-report`,
+/// `stripped-item`, `warning`, `check-clean`, `check-would-strip`,
+/// `unresolved-include`, `asset-copy`, `empty-file-removed`,
+/// `mod-decl-removed`, `empty-file-kept`, `file-error`, and `summary`.
+pub struct EventContext<'a> {
+    pub kind: &'static str,
+    pub path: Option<&'a Path>,
+}
+
+impl<'a> EventContext<'a> {
+    pub fn new(kind: &'static str) -> EventContext<'a> {
+        EventContext { kind, path: None }
+    }
+
+    pub fn for_path(kind: &'static str, path: &'a Path) -> EventContext<'a> {
+        EventContext { kind, path: Some(path) }
+    }
+}
+
+/// Sink for processing events. Implementations decide which levels to show
+/// and how to render them; emitters fire every event unconditionally.
+///
+/// `Sync` is required because parallel directory processing (see
+/// [`crate::config::Config::parallel_jobs`]) fires events from worker
+/// threads; implementations that accumulate state need interior
+/// synchronization, e.g. a `Mutex` around the buffer.
+pub trait Reporter: Sync {
+    fn event(&self, level: Level, message: &str, context: &EventContext<'_>);
+}
+
+/// Human-readable reporting on stderr, the CLI default.
+///
+/// `verbosity` is the number of `-v` flags: 0 shows errors and warnings,
+/// 1 adds per-file progress, 2 adds per-item detail. [`errors_only`]
+/// (`--quiet`) drops warnings too; errors cannot be silenced.
+///
+/// [`errors_only`]: StderrReporter::errors_only
+pub struct StderrReporter {
+    verbosity: u8,
+    quiet: bool,
+}
+
+impl StderrReporter {
+    pub fn new(verbosity: u8) -> StderrReporter {
+        StderrReporter { verbosity, quiet: false }
+    }
+
+    /// A reporter that shows nothing but errors, for scripted runs.
+    pub fn errors_only() -> StderrReporter {
+        StderrReporter { verbosity: 0, quiet: true }
+    }
+
+    fn shown(&self, level: Level) -> bool {
+        match level {
+            Level::Error => true,
+            Level::Warn => !self.quiet,
+            Level::Info => self.verbosity >= 1,
+            Level::Debug => self.verbosity >= 2,
+        }
+    }
+}
+
+impl Reporter for StderrReporter {
+    fn event(&self, level: Level, message: &str, _context: &EventContext<'_>) {
+        if !self.shown(level) {
+            return;
+        }
+        match level {
+            // Matches the historical output format for errors and warnings.
+            Level::Error => eprintln!("error: {}", message),
+            Level::Warn => eprintln!("warning: {}", message),
+            Level::Info | Level::Debug => eprintln!("{}", message),
+        }
+    }
+}
+
+/// Discards every event; for embedders that surface errors themselves via
+/// the returned [`crate::StripError`].
+pub struct SilentReporter;
+
+impl Reporter for SilentReporter {
+    fn event(&self, _level: Level, _message: &str, _context: &EventContext<'_>) {}
+}
+
+/// One JSON object per event, on stderr, regardless of verbosity; consumers
+/// filter by `level` and `kind` themselves.
+pub struct JsonReporter;
+
+#[derive(serde::Serialize)]
+struct JsonEvent<'a> {
+    level: String,
+    kind: &'a str,
+    message: &'a str,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    path: Option<&'a Path>,
+}
+
+impl Reporter for JsonReporter {
+    fn event(&self, level: Level, message: &str, context: &EventContext<'_>) {
+        let event = JsonEvent {
+            level: level.to_string(),
+            kind: context.kind,
+            message,
+            path: context.path,
+        };
+        eprintln!(
+            "{}",
+            serde_json::to_string(&event).expect("event serialization does not fail")
+        );
+    }
+}
+
+/// Events in the `--message-format json` line schema (see
+/// [`crate::diagnostics::Message`]); the event kind becomes the `code`
+/// field. Events have no span of their own, so `line` and `column` are
+/// null — warnings and errors with locations are emitted with them filled
+/// in by [`crate::process`] directly, bypassing the reporter. Like
+/// [`JsonReporter`], nothing is filtered by verbosity; consumers filter by
+/// `level` themselves.
+pub struct MessageReporter;
+
+impl Reporter for MessageReporter {
+    fn event(&self, level: Level, message: &str, context: &EventContext<'_>) {
+        let message = crate::diagnostics::Message {
+            level: match level {
+                Level::Error => "error",
+                Level::Warn => "warning",
+                Level::Info => "info",
+                Level::Debug => "debug",
+            },
+            path: context.path.map(|p| p.display().to_string()),
+            line: None,
+            column: None,
+            message: message.to_string(),
+            code: context.kind,
+        };
+        eprintln!("{}", message.render_line());
+    }
+}
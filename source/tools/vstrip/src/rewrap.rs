@@ -0,0 +1,143 @@
+//! Re-wrap a stripped file so it diffs cleanly against its original.
+//!
+//! Review tooling wants to compare a stripped file with the annotated source
+//! it came from, but stripping unwraps `verus! { ... }` and reformats, so a
+//! plain diff shows the whole file as changed. `rewrap` rebuilds the
+//! structural alignment: the stripped items are reordered to follow the
+//! original's item order, the items that lived inside the original's
+//! `verus!` block are wrapped in one again, and items with no counterpart in
+//! the original are appended at the end under a marker comment.
+
+use std::collections::HashMap;
+use std::fs;
+use std::path::Path;
+
+use quote::ToTokens;
+use verus_syn::spanned::Spanned;
+use verus_syn::{File, Item};
+
+use crate::error::{Result, StripError};
+use crate::preprocess;
+
+/// Marker emitted above items that exist in the stripped file but not in the
+/// original.
+pub const UNMATCHED_MARKER: &str = "// vstrip-rewrap: items below have no counterpart in the original";
+
+/// Re-wrap the contents of `stripped` to mirror `original`'s layout.
+pub fn rewrap_file(stripped: &Path, original: &Path) -> Result<String> {
+    let read = |path: &Path| {
+        fs::read_to_string(path)
+            .map_err(|e| StripError::IoError { path: path.to_path_buf(), source: e })
+    };
+    rewrap_source_at(&read(stripped)?, stripped, &read(original)?, original)
+}
+
+/// Re-wrap `stripped` (plain Rust) to mirror `original` (annotated Verus).
+pub fn rewrap_source(stripped: &str, original: &str) -> Result<String> {
+    rewrap_source_at(stripped, Path::new("<stripped>"), original, Path::new("<original>"))
+}
+
+fn rewrap_source_at(
+    stripped: &str,
+    stripped_path: &Path,
+    original: &str,
+    original_path: &Path,
+) -> Result<String> {
+    let parse = |source: &str, path: &Path| {
+        verus_syn::parse_file(&preprocess::unwrap_verus_macros(source))
+            .map_err(|e| StripError::ParseError { path: path.to_path_buf(), source: e })
+    };
+    let stripped_file = parse(stripped, stripped_path)?;
+    let original_file = parse(original, original_path)?;
+
+    // The preprocessor deletes only the wrapper tokens and keeps every
+    // newline, so original item spans still give the original line layout;
+    // items before the first `verus!` line were outside the block.
+    let wrapper_line = original
+        .lines()
+        .position(|line| line.trim_start().starts_with("verus!"))
+        .map(|i| i + 1);
+
+    // First-come pairing: each original slot is matched at most once, so a
+    // stripped file with two impl blocks for one type lines up positionally.
+    let mut slots: HashMap<String, Vec<usize>> = HashMap::new();
+    for (position, item) in original_file.items.iter().enumerate().rev() {
+        slots.entry(item_key(item)).or_default().push(position);
+    }
+
+    let mut prefix: Vec<(usize, Item)> = Vec::new();
+    let mut wrapped: Vec<(usize, Item)> = Vec::new();
+    let mut appendix: Vec<Item> = Vec::new();
+    for item in stripped_file.items {
+        match slots.get_mut(&item_key(&item)).and_then(Vec::pop) {
+            Some(position) => {
+                let line = original_file.items[position].span().start().line;
+                if wrapper_line.is_some_and(|wrapper| line >= wrapper) {
+                    wrapped.push((position, item));
+                } else {
+                    prefix.push((position, item));
+                }
+            }
+            None => appendix.push(item),
+        }
+    }
+    prefix.sort_by_key(|(position, _)| *position);
+    wrapped.sort_by_key(|(position, _)| *position);
+
+    let render = |items: Vec<Item>, attrs| {
+        verus_prettyplease::unparse(&File { shebang: None, attrs, items })
+    };
+    let mut out = render(
+        prefix.into_iter().map(|(_, item)| item).collect(),
+        stripped_file.attrs,
+    );
+    if wrapper_line.is_some() {
+        if !out.is_empty() {
+            out.push('\n');
+        }
+        out.push_str("verus! {\n\n");
+        out.push_str(&render(wrapped.into_iter().map(|(_, item)| item).collect(), Vec::new()));
+        out.push_str("\n} // verus!\n");
+    } else {
+        out.push_str(&render(wrapped.into_iter().map(|(_, item)| item).collect(), Vec::new()));
+    }
+    if !appendix.is_empty() {
+        out.push('\n');
+        out.push_str(UNMATCHED_MARKER);
+        out.push('\n');
+        out.push_str(&render(appendix, Vec::new()));
+    }
+    Ok(out)
+}
+
+/// A name for matching a top-level item between the two files. Items are
+/// keyed by namespace and identifier where they have one, and by their
+/// collapsed token rendering otherwise (`use` trees, macro invocations).
+fn item_key(item: &Item) -> String {
+    let collapsed = |tokens: &dyn ToTokens| {
+        tokens.to_token_stream().to_string().split_whitespace().collect::<Vec<_>>().join(" ")
+    };
+    match item {
+        Item::Fn(func) => format!("fn {}", func.sig.ident),
+        Item::Struct(item) => format!("struct {}", item.ident),
+        Item::Enum(item) => format!("enum {}", item.ident),
+        Item::Union(item) => format!("union {}", item.ident),
+        Item::Trait(item) => format!("trait {}", item.ident),
+        Item::TraitAlias(item) => format!("trait {}", item.ident),
+        Item::Type(item) => format!("type {}", item.ident),
+        Item::Const(item) => format!("const {}", item.ident),
+        Item::Static(item) => format!("static {}", item.ident),
+        Item::Mod(item) => format!("mod {}", item.ident),
+        Item::ExternCrate(item) => format!("extern crate {}", item.ident),
+        Item::Impl(imp) => {
+            let trait_part = match &imp.trait_ {
+                Some((_, path, _)) => format!("{} for ", collapsed(path)),
+                None => String::new(),
+            };
+            format!("impl {}{}", trait_part, collapsed(&imp.self_ty))
+        }
+        Item::Use(item) => format!("use {}", collapsed(&item.tree)),
+        Item::Macro(item) => format!("macro {}", collapsed(&item.mac)),
+        other => format!("item {}", collapsed(other)),
+    }
+}
@@ -0,0 +1,168 @@
+//! Mapping positions in stripped output back to the original source.
+//!
+//! `rustc` diagnostics on stripped output point at lines the author never
+//! wrote. A [`SourceMap`] anchors each surviving named item: where its
+//! identifier landed in the output (as a byte offset) and where it stood in
+//! the original annotated file. Positions between anchors resolve to the
+//! nearest preceding item, which is coarse but enough to jump to the right
+//! function. A [`LineMap`] is the same table collapsed to whole lines, the
+//! unit compiler diagnostics and panic messages actually report.
+//!
+//! Lines are reliable because [`crate::preprocess`] removes only the
+//! `verus! {` / `}` tokens, never a newline; columns can drift on the line
+//! that held the opener itself.
+
+use std::path::{Path, PathBuf};
+
+use proc_macro2::LineColumn;
+use serde::Serialize;
+use verus_syn::{File, Item};
+
+/// Position anchors for one stripped file, ordered by output offset.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SourceMap {
+    pub entries: Vec<SourceMapEntry>,
+}
+
+/// One anchor: a surviving item's identifier in the output, and where that
+/// identifier was in the original source.
+#[derive(Debug, Clone, Serialize)]
+pub struct SourceMapEntry {
+    /// Byte offset of the identifier in the stripped output.
+    pub output_byte_offset: usize,
+    /// 1-based line of the identifier in the original source.
+    pub original_line: usize,
+    /// 0-based column of the identifier in the original source.
+    pub original_col: usize,
+}
+
+impl SourceMap {
+    /// The original position of the nearest anchor at or before `offset` in
+    /// the output, as `(line, col)`; `None` before the first anchor.
+    pub fn original_position_for(&self, offset: usize) -> Option<(usize, usize)> {
+        let upper = self.entries.partition_point(|e| e.output_byte_offset <= offset);
+        let entry = &self.entries[upper.checked_sub(1)?];
+        Some((entry.original_line, entry.original_col))
+    }
+
+    pub fn to_json(&self) -> String {
+        serde_json::to_string_pretty(self).expect("source map serialization does not fail")
+    }
+
+    /// Collapse the byte-offset anchors into a line table for `output`, for
+    /// [`Config::line_map`](crate::Config::line_map).
+    pub fn to_line_map(&self, output: &str) -> LineMap {
+        // Byte offset at which each output line begins, in order, so an
+        // anchor's line is the count of starts at or before its offset.
+        let mut line_starts = vec![0usize];
+        for (i, b) in output.bytes().enumerate() {
+            if b == b'\n' {
+                line_starts.push(i + 1);
+            }
+        }
+        let entries = self
+            .entries
+            .iter()
+            .map(|entry| LineMapEntry {
+                output_line: line_starts.partition_point(|&s| s <= entry.output_byte_offset),
+                original_line: entry.original_line,
+            })
+            .collect();
+        LineMap { entries }
+    }
+}
+
+/// Item-granularity line table for one output file: each surviving item's
+/// line in the output and in the original source. Coarser than a
+/// [`SourceMap`] — positions between items resolve to the nearest preceding
+/// item — but directly usable against compiler output, which reports lines.
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct LineMap {
+    pub entries: Vec<LineMapEntry>,
+}
+
+/// One row: where a surviving item's identifier landed in the output and
+/// where it stood in the original source, both as 1-based lines.
+#[derive(Debug, Clone, Serialize)]
+pub struct LineMapEntry {
+    pub output_line: usize,
+    pub original_line: usize,
+}
+
+/// Render the per-file tables as one JSON document keyed by file path, in
+/// sorted key order.
+pub fn render_line_maps(maps: &[(PathBuf, LineMap)]) -> String {
+    let object: serde_json::Map<String, serde_json::Value> = maps
+        .iter()
+        .map(|(path, map)| {
+            let value =
+                serde_json::to_value(map).expect("line map serialization does not fail");
+            (path.display().to_string(), value)
+        })
+        .collect();
+    serde_json::to_string_pretty(&serde_json::Value::Object(object))
+        .expect("line map serialization does not fail")
+}
+
+/// Where the sidecar for output written to `output` goes:
+/// `foo.rs` → `foo.rs.sourcemap.json`.
+pub fn sidecar_path(output: &Path) -> PathBuf {
+    let mut name = output.as_os_str().to_os_string();
+    name.push(".sourcemap.json");
+    PathBuf::from(name)
+}
+
+/// Build the map for `stripped` (the surviving tree, whose spans still point
+/// into the original source) as rendered into `output`.
+///
+/// Identifiers are located in the output by a forward text scan — the
+/// printer emits items in tree order, so each anchor is searched for after
+/// the previous one. An identifier that also appears in earlier prose (say,
+/// a doc comment) can anchor slightly early; the mapping is best-effort by
+/// design.
+pub fn build_source_map(stripped: &File, output: &str) -> SourceMap {
+    let mut anchors = Vec::new();
+    collect_anchors(&stripped.items, &mut anchors);
+    let mut entries = Vec::new();
+    let mut cursor = 0;
+    for (name, start) in anchors {
+        if let Some(found) = output[cursor..].find(&name) {
+            let offset = cursor + found;
+            cursor = offset + name.len();
+            entries.push(SourceMapEntry {
+                output_byte_offset: offset,
+                original_line: start.line,
+                original_col: start.column,
+            });
+        }
+    }
+    SourceMap { entries }
+}
+
+/// Collect `(identifier, original position)` for every named item, in tree
+/// order, descending into inline modules.
+fn collect_anchors(items: &[Item], anchors: &mut Vec<(String, LineColumn)>) {
+    for item in items {
+        let ident = match item {
+            Item::Fn(item) => Some(&item.sig.ident),
+            Item::Struct(item) => Some(&item.ident),
+            Item::Enum(item) => Some(&item.ident),
+            Item::Union(item) => Some(&item.ident),
+            Item::Trait(item) => Some(&item.ident),
+            Item::Const(item) => Some(&item.ident),
+            Item::Static(item) => Some(&item.ident),
+            Item::Type(item) => Some(&item.ident),
+            Item::Mod(item) => Some(&item.ident),
+            // Impls, uses, and macros have no single anchoring identifier.
+            _ => None,
+        };
+        if let Some(ident) = ident {
+            anchors.push((ident.to_string(), ident.span().start()));
+        }
+        if let Item::Mod(module) = item {
+            if let Some((_, items)) = &module.content {
+                collect_anchors(items, anchors);
+            }
+        }
+    }
+}
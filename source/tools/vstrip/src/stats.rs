@@ -0,0 +1,114 @@
+//! Statistics on how much specification and proof code stripping removed.
+//!
+//! [`crate::visitor::StripVisitor`] tallies a [`StripStats`] while it walks;
+//! [`crate::process`] wraps one per file in a [`FileStats`] (adding byte
+//! sizes) and prints them under `--stats`, either as a JSON array or as an
+//! aggregated text table. The counters cover the constructs removed from
+//! surviving code plus whole removed functions; clauses inside a deleted
+//! `spec fn` are not counted again on top of the function itself.
+
+use std::path::Path;
+
+/// Counters for every category of removed construct, kept by the visitor and
+/// readable through [`crate::visitor::StripVisitor::stats`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, serde::Serialize)]
+pub struct StripStats {
+    /// `spec fn`s removed, open or closed.
+    pub spec_fns: usize,
+    /// `proof fn`s removed, including axioms.
+    pub proof_fns: usize,
+    /// `requires` clauses stripped from surviving functions.
+    pub requires_clauses: usize,
+    /// `ensures` clauses stripped from surviving functions.
+    pub ensures_clauses: usize,
+    /// `ghost`/`tracked` local bindings removed from bodies.
+    pub ghost_locals: usize,
+    /// Ghost struct/enum fields removed.
+    pub ghost_fields: usize,
+    /// Verification-only types removed: `ghost`/`tracked` structs and enums,
+    /// and `type` aliases over spec-only types.
+    pub ghost_types: usize,
+    /// Statement-position `proof { ... }` blocks and proof-only macro
+    /// invocations (`calc!`, `reveal!`, ...) removed.
+    pub proof_blocks: usize,
+    /// `assert`/`assume`/`assert forall` expressions removed.
+    pub assert_assume_exprs: usize,
+    /// Ghost parameters (`tracked` args, `Ghost<T>`/`Tracked<T>` args)
+    /// removed from surviving signatures.
+    pub ghost_params: usize,
+    /// `invariant`/`ensures`/`decreases` clause expressions stripped from
+    /// `while`, `loop`, and `for` loops.
+    pub loop_clauses: usize,
+}
+
+impl StripStats {
+    /// Fold `other` into these counters, for per-run aggregation.
+    pub fn add(&mut self, other: &StripStats) {
+        self.spec_fns += other.spec_fns;
+        self.proof_fns += other.proof_fns;
+        self.requires_clauses += other.requires_clauses;
+        self.ensures_clauses += other.ensures_clauses;
+        self.ghost_locals += other.ghost_locals;
+        self.ghost_fields += other.ghost_fields;
+        self.ghost_types += other.ghost_types;
+        self.proof_blocks += other.proof_blocks;
+        self.assert_assume_exprs += other.assert_assume_exprs;
+        self.ghost_params += other.ghost_params;
+        self.loop_clauses += other.loop_clauses;
+    }
+}
+
+/// One file's [`StripStats`] plus how much smaller stripping made it.
+#[derive(Debug, Clone, PartialEq, Eq, serde::Serialize)]
+pub struct FileStats {
+    pub path: String,
+    pub counts: StripStats,
+    pub bytes_before: usize,
+    pub bytes_after: usize,
+}
+
+impl FileStats {
+    pub fn new(path: &Path, counts: StripStats, bytes_before: usize, bytes_after: usize) -> FileStats {
+        FileStats { path: path.display().to_string(), counts, bytes_before, bytes_after }
+    }
+}
+
+/// Render per-file statistics as a JSON array, one object per file.
+pub fn render_json(files: &[FileStats]) -> String {
+    serde_json::to_string_pretty(files).expect("statistics serialization cannot fail")
+}
+
+/// Render aggregate statistics as a human-readable table: one row per
+/// counter, totalled over every file, plus the byte sizes.
+pub fn render_text(files: &[FileStats]) -> String {
+    let mut totals = StripStats::default();
+    let mut bytes_before = 0usize;
+    let mut bytes_after = 0usize;
+    for file in files {
+        totals.add(&file.counts);
+        bytes_before += file.bytes_before;
+        bytes_after += file.bytes_after;
+    }
+    let rows: &[(&str, usize)] = &[
+        ("files", files.len()),
+        ("spec fns", totals.spec_fns),
+        ("proof fns", totals.proof_fns),
+        ("requires clauses", totals.requires_clauses),
+        ("ensures clauses", totals.ensures_clauses),
+        ("ghost locals", totals.ghost_locals),
+        ("ghost fields", totals.ghost_fields),
+        ("ghost types", totals.ghost_types),
+        ("proof blocks", totals.proof_blocks),
+        ("assert/assume exprs", totals.assert_assume_exprs),
+        ("ghost params", totals.ghost_params),
+        ("loop clauses", totals.loop_clauses),
+        ("bytes before", bytes_before),
+        ("bytes after", bytes_after),
+    ];
+    let width = rows.iter().map(|(label, _)| label.len()).max().unwrap_or(0);
+    let mut out = String::new();
+    for (label, value) in rows {
+        out.push_str(&format!("{:<width$}  {}\n", label, value, width = width));
+    }
+    out
+}
@@ -0,0 +1,151 @@
+//! Aggressive post-strip type repair: rewrite leftover Verus types into their
+//! nearest plain-Rust equivalents.
+//!
+//! Stripping removes ghost *items*, but signatures and fields of surviving
+//! executable code can still mention Verus-only types — `int`, `nat`,
+//! `Ghost<T>`, `Tracked<T>`, `vstd::`-pathed names, `View`/`Structural`
+//! bounds — and the output then fails to compile. This pass applies a fixed
+//! set of substitutions that are *usually* what the author meant, but unlike
+//! the main strip pass they are not semantics-preserving (`int` has no
+//! overflow, `i64` does), so the whole pass is opt-in via
+//! [`Config::aggressive_type_fixing`].
+
+use verus_syn::punctuated::Punctuated;
+use verus_syn::visit_mut::{self, VisitMut};
+use verus_syn::{
+    GenericArgument, Generics, Ident, PathArguments, Token, Type, TypeParam, TypeParamBound,
+    TypePath, WherePredicate,
+};
+
+use crate::config::Config;
+use crate::error::{Result, StripError};
+
+/// Apply the known-safe-enough type substitutions to `source`:
+/// `int` → `i64`, `nat` → `u64`, `Ghost<T>` → `T`, `Tracked<T>` → `T`,
+/// `vstd::…::Name` → `Name`, and removal of `View` and `Structural` bounds
+/// from generics and where clauses.
+///
+/// With [`Config::aggressive_type_fixing`] unset, the source is returned
+/// unchanged (modulo nothing: not even reformatted).
+pub fn auto_fix_type_errors(source: &str, config: &Config) -> Result<String> {
+    if !config.aggressive_type_fixing {
+        return Ok(source.to_string());
+    }
+    let unwrapped = crate::preprocess::unwrap_verus_macros(source);
+    let mut file = verus_syn::parse_file(&unwrapped).map_err(|e| StripError::ParseError {
+        path: std::path::PathBuf::from("<source>"),
+        source: e,
+    })?;
+    TypeFixVisitor.visit_file_mut(&mut file);
+    Ok(verus_prettyplease::unparse(&file))
+}
+
+/// Rewrites Verus-only types in place; see [`auto_fix_type_errors`] for the
+/// substitution table.
+pub struct TypeFixVisitor;
+
+/// The inner `T` of a `Ghost<T>` or `Tracked<T>` type, if `ty` is one.
+pub(crate) fn ghost_wrapper_inner(type_path: &TypePath) -> Option<Type> {
+    let segment = type_path.path.segments.last()?;
+    if segment.ident != "Ghost" && segment.ident != "Tracked" {
+        return None;
+    }
+    if let PathArguments::AngleBracketed(args) = &segment.arguments {
+        if args.args.len() == 1 {
+            if let GenericArgument::Type(inner) = &args.args[0] {
+                return Some(inner.clone());
+            }
+        }
+    }
+    None
+}
+
+/// True for bounds that only make sense under the verifier.
+fn is_verus_bound(bound: &TypeParamBound) -> bool {
+    match bound {
+        TypeParamBound::Trait(t) => t
+            .path
+            .segments
+            .last()
+            .is_some_and(|seg| seg.ident == "View" || seg.ident == "Structural"),
+        _ => false,
+    }
+}
+
+fn filter_bounds(
+    bounds: &Punctuated<TypeParamBound, Token![+]>,
+) -> Punctuated<TypeParamBound, Token![+]> {
+    bounds.iter().filter(|b| !is_verus_bound(b)).cloned().collect()
+}
+
+impl VisitMut for TypeFixVisitor {
+    fn visit_type_mut(&mut self, ty: &mut Type) {
+        // Unwrap Ghost<T>/Tracked<T> before descending, then re-visit the
+        // replacement: the inner type may itself need fixing (Ghost<int>).
+        if let Type::Path(type_path) = ty {
+            if type_path.qself.is_none() {
+                if let Some(inner) = ghost_wrapper_inner(type_path) {
+                    *ty = inner;
+                    self.visit_type_mut(ty);
+                    return;
+                }
+            }
+        }
+        visit_mut::visit_type_mut(self, ty);
+        if let Type::Path(type_path) = ty {
+            if type_path.qself.is_none() && type_path.path.segments.len() == 1 {
+                let segment = &mut type_path.path.segments[0];
+                if segment.arguments.is_none() {
+                    if segment.ident == "int" {
+                        segment.ident = Ident::new("i64", segment.ident.span());
+                    } else if segment.ident == "nat" {
+                        segment.ident = Ident::new("u64", segment.ident.span());
+                    }
+                }
+            }
+        }
+    }
+
+    fn visit_path_mut(&mut self, path: &mut verus_syn::Path) {
+        // `vstd::seq::Seq` → `Seq`: the vstd crate is not a dependency of the
+        // stripped output, so keep only the final name and hope it resolves
+        // (or at least produces a clearer error) locally.
+        if path.segments.len() > 1 && path.segments[0].ident == "vstd" {
+            let last = path.segments.last().cloned().expect("len > 1");
+            path.leading_colon = None;
+            path.segments.clear();
+            path.segments.push(last);
+        }
+        visit_mut::visit_path_mut(self, path);
+    }
+
+    fn visit_type_param_mut(&mut self, param: &mut TypeParam) {
+        param.bounds = filter_bounds(&param.bounds);
+        visit_mut::visit_type_param_mut(self, param);
+    }
+
+    fn visit_generics_mut(&mut self, generics: &mut Generics) {
+        if let Some(where_clause) = &mut generics.where_clause {
+            where_clause.predicates = where_clause
+                .predicates
+                .iter()
+                .cloned()
+                .filter_map(|predicate| match predicate {
+                    WherePredicate::Type(mut pt) => {
+                        pt.bounds = filter_bounds(&pt.bounds);
+                        if pt.bounds.is_empty() {
+                            None
+                        } else {
+                            Some(WherePredicate::Type(pt))
+                        }
+                    }
+                    other => Some(other),
+                })
+                .collect();
+            if where_clause.predicates.is_empty() {
+                generics.where_clause = None;
+            }
+        }
+        visit_mut::visit_generics_mut(self, generics);
+    }
+}
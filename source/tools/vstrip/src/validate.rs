@@ -0,0 +1,198 @@
+//! Post-strip validation of the surviving items.
+//!
+//! Removing ghost code can make previously-distinct items collide: e.g. two
+//! impl blocks for the same type that were distinguished by stripped
+//! attributes or ghost members can merge into one namespace with a duplicate
+//! method. Catching this before writing output gives the user a `vstrip`
+//! diagnostic pointing at the original constructs, instead of a downstream
+//! `rustc` error pointing into generated text.
+
+use std::collections::HashMap;
+use std::fmt;
+
+use quote::ToTokens;
+use verus_syn::spanned::Spanned;
+use verus_syn::{File, ImplItem, Item};
+
+/// Two surviving definitions that share a namespace and a name.
+#[derive(Debug)]
+pub struct DuplicateItem {
+    /// Enclosing module path, outermost first; empty for the file root.
+    pub module_path: Vec<String>,
+    /// Rendered self type if the definitions live in impl blocks.
+    pub self_ty: Option<String>,
+    pub name: String,
+    /// 1-based line of the first definition (in the preprocessed source).
+    pub first_line: usize,
+    /// 1-based line of the colliding definition.
+    pub second_line: usize,
+}
+
+impl fmt::Display for DuplicateItem {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let module = if self.module_path.is_empty() {
+            "the crate root".to_string()
+        } else {
+            format!("module `{}`", self.module_path.join("::"))
+        };
+        match &self.self_ty {
+            Some(self_ty) => write!(
+                f,
+                "duplicate definition of `{}` for `{}` in {} (lines {} and {})",
+                self.name, self_ty, module, self.first_line, self.second_line
+            ),
+            None => write!(
+                f,
+                "duplicate definition of `{}` in {} (lines {} and {})",
+                self.name, module, self.first_line, self.second_line
+            ),
+        }
+    }
+}
+
+/// Which Rust namespace a name lives in; `fn f` and `struct f` do not clash.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+enum Namespace {
+    Value,
+    Type,
+    Macro,
+}
+
+/// The scope a definition must be unique within.
+#[derive(Clone, PartialEq, Eq, Hash)]
+struct Key {
+    module_path: Vec<String>,
+    /// For impl members: the impl header (generics + self type), rendered
+    /// without whitespace. Two textually identical headers definitely share a
+    /// namespace; differing headers are conservatively assumed distinct.
+    self_ty: Option<String>,
+    namespace: Namespace,
+    name: String,
+}
+
+/// Scan the stripped file for items that now collide. Returns one entry per
+/// colliding pair, in source order.
+pub fn check_duplicates(file: &File) -> Vec<DuplicateItem> {
+    let mut checker = DuplicateChecker::default();
+    checker.collect_items(&file.items);
+    checker.duplicates
+}
+
+#[derive(Default)]
+struct DuplicateChecker {
+    module_path: Vec<String>,
+    seen: HashMap<Key, usize>,
+    duplicates: Vec<DuplicateItem>,
+}
+
+impl DuplicateChecker {
+    fn collect_items(&mut self, items: &[Item]) {
+        for item in items {
+            self.collect_item(item);
+        }
+    }
+
+    fn collect_item(&mut self, item: &Item) {
+        let line = item.span().start().line;
+        match item {
+            Item::Fn(func) => {
+                self.record(None, Namespace::Value, func.sig.ident.to_string(), line)
+            }
+            Item::Const(item_const) => {
+                self.record(None, Namespace::Value, item_const.ident.to_string(), line)
+            }
+            Item::Static(item_static) => {
+                self.record(None, Namespace::Value, item_static.ident.to_string(), line)
+            }
+            Item::Struct(item_struct) => {
+                // Unit and tuple structs also claim the value namespace, but
+                // flagging the type name is enough to report the collision.
+                self.record(None, Namespace::Type, item_struct.ident.to_string(), line)
+            }
+            Item::Enum(item_enum) => {
+                self.record(None, Namespace::Type, item_enum.ident.to_string(), line)
+            }
+            Item::Union(item_union) => {
+                self.record(None, Namespace::Type, item_union.ident.to_string(), line)
+            }
+            Item::Trait(item_trait) => {
+                self.record(None, Namespace::Type, item_trait.ident.to_string(), line)
+            }
+            Item::TraitAlias(alias) => {
+                self.record(None, Namespace::Type, alias.ident.to_string(), line)
+            }
+            Item::Type(item_type) => {
+                self.record(None, Namespace::Type, item_type.ident.to_string(), line)
+            }
+            Item::Impl(imp) => {
+                // Only inherent impls put members in a shared namespace we can
+                // check syntactically; trait impl collisions are reported by
+                // rustc as coherence errors on the original source anyway.
+                if imp.trait_.is_some() {
+                    return;
+                }
+                let header = format!(
+                    "{}{}",
+                    imp.generics.to_token_stream(),
+                    imp.self_ty.to_token_stream()
+                )
+                .split_whitespace()
+                .collect::<String>();
+                for impl_item in &imp.items {
+                    let line = impl_item.span().start().line;
+                    match impl_item {
+                        ImplItem::Fn(func) => self.record(
+                            Some(header.clone()),
+                            Namespace::Value,
+                            func.sig.ident.to_string(),
+                            line,
+                        ),
+                        ImplItem::Const(item_const) => self.record(
+                            Some(header.clone()),
+                            Namespace::Value,
+                            item_const.ident.to_string(),
+                            line,
+                        ),
+                        ImplItem::Type(item_type) => self.record(
+                            Some(header.clone()),
+                            Namespace::Type,
+                            item_type.ident.to_string(),
+                            line,
+                        ),
+                        _ => {}
+                    }
+                }
+            }
+            Item::Mod(module) => {
+                if let Some((_, items)) = &module.content {
+                    self.module_path.push(module.ident.to_string());
+                    self.collect_items(items);
+                    self.module_path.pop();
+                }
+            }
+            // Only `macro_rules!` definitions introduce a name.
+            Item::Macro(mac) if mac.mac.path.is_ident("macro_rules") => {
+                if let Some(ident) = &mac.ident {
+                    self.record(None, Namespace::Macro, ident.to_string(), line);
+                }
+            }
+            _ => {}
+        }
+    }
+
+    fn record(&mut self, self_ty: Option<String>, namespace: Namespace, name: String, line: usize) {
+        let key = Key { module_path: self.module_path.clone(), self_ty, namespace, name };
+        match self.seen.get(&key) {
+            Some(&first_line) => self.duplicates.push(DuplicateItem {
+                module_path: key.module_path,
+                self_ty: key.self_ty,
+                name: key.name,
+                first_line,
+                second_line: line,
+            }),
+            None => {
+                self.seen.insert(key, line);
+            }
+        }
+    }
+}
@@ -0,0 +1,1782 @@
+//! The AST pass that removes Verus-specific constructs.
+//!
+//! [`StripVisitor`] walks a parsed (and already unwrapped, see
+//! [`crate::preprocess`]) file and rewrites it into plain Rust: spec and proof
+//! functions are deleted, signatures lose their `requires`/`ensures`/mode
+//! annotations, ghost parameters, fields, and locals are dropped, loops lose
+//! their `invariant`/`ensures`/`decreases` clauses, and proof-only statements
+//! and expressions disappear from function bodies. Under [`Config::cfg_gate`]
+//! the deletable classes are gated behind a feature attribute instead of
+//! removed; the output must still parse without Verus, so signatures are
+//! sanitized either way.
+
+use verus_syn::punctuated::Punctuated;
+use verus_syn::spanned::Spanned;
+use verus_syn::visit_mut::{self, VisitMut};
+use verus_syn::{
+    Attribute, Block, DataMode, Expr, Fields, File, FnArg, FnArgKind, FnMode, ImplItem, Item,
+    ItemImpl, ItemTrait, Member, Meta, Pat, Path, Publish, Signature, Specification, Stmt,
+    Token, TraitItem, Type, UnOp,
+};
+
+use crate::config::{AssertStrategy, Config, EmptyBodyPolicy};
+use crate::stats::StripStats;
+use crate::warning::Warning;
+
+/// Counts of what stripping removed, for reporting back to the user.
+///
+/// Open and closed spec functions are distinguished so callers can see how
+/// much of a file's specification surface was opaque: `open`/`closed` is
+/// carried by the `publish` marker, not the function mode, and a spec
+/// function with no marker has an invisible body, so everything that is not
+/// explicitly `open` counts as closed here.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct StripReport {
+    pub open_spec_fns_removed: usize,
+    pub closed_spec_fns_removed: usize,
+}
+
+impl std::fmt::Display for StripReport {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "removed {} open and {} closed spec fn(s)",
+            self.open_spec_fns_removed, self.closed_spec_fns_removed
+        )
+    }
+}
+
+/// One named item that stripping removed entirely, recorded in visit order.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct StrippedItem {
+    pub kind: StrippedItemKind,
+    /// The item's identifier. Functions carry no path qualification; fields
+    /// are qualified by their containing type.
+    pub name: String,
+    /// 1-based line of the item's name in the original source. Unwrapping
+    /// `verus! { ... }` keeps line numbering intact, so parse spans map
+    /// straight back to the file.
+    pub line: usize,
+}
+
+/// What sort of item a [`StrippedItem`] was.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StrippedItemKind {
+    /// A `spec fn` or `spec(checked) fn`, open or closed.
+    SpecFn,
+    /// A `proof fn`, including axioms.
+    ProofFn,
+    /// A `ghost` or `tracked` field, named `Type::field` (or
+    /// `Enum::Variant::field`).
+    GhostField,
+    /// A verification-only type: a `ghost`/`tracked` struct or enum, or a
+    /// `type` alias defined over spec-only types.
+    GhostType,
+}
+
+impl std::fmt::Display for StrippedItem {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        let kind = match self.kind {
+            StrippedItemKind::SpecFn => "spec fn",
+            StrippedItemKind::ProofFn => "proof fn",
+            StrippedItemKind::GhostField => "ghost field",
+            StrippedItemKind::GhostType => "ghost type",
+        };
+        write!(f, "{} {}", kind, self.name)
+    }
+}
+
+pub struct StripVisitor<'a> {
+    config: &'a Config,
+    /// Suspicious situations noticed while stripping; handed back to callers
+    /// through [`crate::StripResult::warnings`].
+    pub(crate) warnings: Vec<Warning>,
+    /// Value-returning functions whose bodies stripping emptied entirely,
+    /// recorded under [`EmptyBodyPolicy::Error`].
+    pub(crate) empty_bodies: Vec<String>,
+    /// Tally of removed constructs, handed back alongside the output.
+    pub(crate) report: StripReport,
+    /// Each removed item individually, for [`crate::StripResult`].
+    pub(crate) stripped_items: Vec<StrippedItem>,
+    /// Per-category counters of removed constructs, for `--stats`.
+    pub(crate) stats: StripStats,
+    /// Where ghost parameters and ghost return-tuple elements sat in the
+    /// signatures being stripped, collected before any rewriting so call
+    /// sites can be fixed up; seeded across files by [`crate::strip_files`].
+    ghost_param_positions: GhostSignatureIndex,
+}
+
+impl<'a> StripVisitor<'a> {
+    pub fn new(config: &'a Config) -> StripVisitor<'a> {
+        StripVisitor {
+            config,
+            warnings: Vec::new(),
+            empty_bodies: Vec::new(),
+            report: StripReport::default(),
+            stripped_items: Vec::new(),
+            stats: StripStats::default(),
+            ghost_param_positions: GhostSignatureIndex::default(),
+        }
+    }
+
+    /// Seed the call-site rewrites with signatures gathered from the other
+    /// files of a multi-file strip; see [`GhostSignatureIndex`]. The file
+    /// being stripped is indexed on top of the seed when the walk starts.
+    pub(crate) fn seed_signature_index(&mut self, index: GhostSignatureIndex) {
+        self.ghost_param_positions = index;
+    }
+
+    pub fn warnings(&self) -> &[Warning] {
+        &self.warnings
+    }
+
+    pub fn report(&self) -> StripReport {
+        self.report
+    }
+
+    /// Per-category counts of what this visitor has removed so far.
+    pub fn stats(&self) -> StripStats {
+        self.stats
+    }
+
+    /// Tally a function that the retain passes are about to remove.
+    fn record_removed_fn(&mut self, sig: &Signature) {
+        match sig.mode {
+            FnMode::Spec(_) | FnMode::SpecChecked(_) => {
+                self.stats.spec_fns += 1;
+                if matches!(sig.publish, Publish::Open(_)) {
+                    self.report.open_spec_fns_removed += 1;
+                    let start = sig.ident.span().start();
+                    self.warnings.push(Warning::SpecFnDropped {
+                        fn_name: sig.ident.to_string(),
+                        line: start.line,
+                        column: start.column + 1,
+                    });
+                } else {
+                    self.report.closed_spec_fns_removed += 1;
+                }
+                self.stripped_items.push(StrippedItem {
+                    kind: StrippedItemKind::SpecFn,
+                    name: sig.ident.to_string(),
+                    line: sig.ident.span().start().line,
+                });
+            }
+            FnMode::Proof(_) | FnMode::ProofAxiom(_) => {
+                self.stats.proof_fns += 1;
+                self.stripped_items.push(StrippedItem {
+                    kind: StrippedItemKind::ProofFn,
+                    name: sig.ident.to_string(),
+                    line: sig.ident.span().start().line,
+                });
+            }
+            FnMode::Exec(_) | FnMode::Default => {}
+        }
+    }
+
+    /// Tally an item the retain passes are about to remove, for the kinds
+    /// that [`crate::StripResult::stripped_items`] reports by name. Trait
+    /// impls have no single name to report and are only reflected in the
+    /// surviving output.
+    fn record_removed_item(&mut self, item: &Item) {
+        match item {
+            Item::Fn(func) => self.record_removed_fn(&func.sig),
+            Item::Struct(strukt) => self.record_removed_ghost_type(&strukt.ident),
+            Item::Enum(enm) => self.record_removed_ghost_type(&enm.ident),
+            Item::Type(alias) => self.record_removed_ghost_type(&alias.ident),
+            _ => {}
+        }
+    }
+
+    fn record_removed_ghost_type(&mut self, ident: &proc_macro2::Ident) {
+        self.stats.ghost_types += 1;
+        self.stripped_items.push(StrippedItem {
+            kind: StrippedItemKind::GhostType,
+            name: ident.to_string(),
+            line: ident.span().start().line,
+        });
+    }
+
+    /// Record the ghost fields of `owner` that the `visit_fields_mut` pass
+    /// is about to drop, so they appear in
+    /// [`crate::StripResult::stripped_items`] by qualified name. Under
+    /// `keep_ghost_fields` or `cfg_gate` the fields survive and nothing is
+    /// recorded; tuple fields have no name to report and are only counted
+    /// in the statistics.
+    fn record_removed_ghost_fields(&mut self, owner: &str, fields: &Fields) {
+        if self.config.keep_ghost_fields || self.config.cfg_gate.is_some() {
+            return;
+        }
+        if let Fields::Named(named) = fields {
+            for field in &named.named {
+                if !is_ghost_field(field) {
+                    continue;
+                }
+                let Some(ident) = &field.ident else { continue };
+                self.stripped_items.push(StrippedItem {
+                    kind: StrippedItemKind::GhostField,
+                    name: format!("{}::{}", owner, ident),
+                    line: ident.span().start().line,
+                });
+            }
+        }
+    }
+
+    /// Remove Verus annotations from a function signature: mode and publish
+    /// markers, `requires`/`ensures`/`decreases` clauses, and ghost/tracked
+    /// parameters. When `spec_as_comments` is set, the removed clauses are
+    /// first rendered as doc comments and appended to `attrs`.
+    fn strip_signature(&mut self, attrs: &mut Vec<Attribute>, sig: &mut Signature) {
+        if self.comments_removed_specs() {
+            attrs.extend(create_spec_comment_attrs(sig));
+        }
+        sig.publish = Publish::Default;
+        sig.mode = FnMode::Default;
+        sig.broadcast = None;
+        if !self.config.keep_signature_specs {
+            if let Some(requires) = &sig.spec.requires {
+                self.stats.requires_clauses += requires.exprs.exprs.len();
+            }
+            if let Some(ensures) = &sig.spec.ensures {
+                self.stats.ensures_clauses += ensures.exprs.exprs.len();
+            }
+            sig.spec.erase_spec_fields();
+        }
+        let inputs = std::mem::take(&mut sig.inputs);
+        for arg in inputs {
+            if is_ghost_param(&arg) {
+                self.stats.ghost_params += 1;
+                let start = arg.span().start();
+                self.warnings.push(Warning::GhostParamDropped {
+                    fn_name: sig.ident.to_string(),
+                    param_name: param_name(&arg),
+                    line: start.line,
+                    column: start.column + 1,
+                });
+            } else {
+                sig.inputs.push(arg);
+            }
+        }
+        if let verus_syn::ReturnType::Type(_, tracked, name, ty) = &mut sig.output {
+            // The return position has no ghost marker to strip like a
+            // parameter does; unwrap `Ghost<T>`/`Tracked<T>` so the survivor
+            // returns only the executable part. The body's return values
+            // lose their wrappers to match, in `visit_expr_mut` and
+            // `unwrap_trailing_ghost_value`.
+            unwrap_ghost_return_type(ty);
+            if !self.config.keep_signature_specs {
+                // The `-> (r: u32)` name only exists for `ensures` clauses to
+                // refer to; without them it is Verus-only syntax.
+                *tracked = None;
+                *name = None;
+            }
+        }
+    }
+
+    /// The call-site counterpart of the ghost-parameter drop in
+    /// [`Self::strip_signature`]: when `name` matches a signature that lost
+    /// ghost parameters, delete the arguments those parameters received.
+    /// Wrapper constructor calls (`Tracked(x)`) are caught structurally by
+    /// [`remove_ghost_ctor_args`] even for callees outside this file; this
+    /// pass additionally handles arguments forwarded as plain expressions
+    /// (`execute_query(db, q, caps)` where `caps` was itself a dropped
+    /// `Tracked<..>` parameter). Matching is by unqualified name, like
+    /// [`dangling_call_warnings`]; requiring the argument count to equal the
+    /// recorded arity keeps unrelated same-named calls untouched.
+    fn remove_dropped_param_args(
+        &self,
+        name: &str,
+        args: &mut Punctuated<Expr, Token![,]>,
+        method_call: bool,
+    ) {
+        let Some(positions) = self.ghost_param_positions.get(name) else {
+            return;
+        };
+        if method_call && !positions.has_receiver {
+            return;
+        }
+        // A method call's receiver sits outside the argument list; shift the
+        // recorded input positions to match.
+        let offset = usize::from(method_call && positions.has_receiver);
+        if args.len() + offset != positions.arity {
+            return;
+        }
+        *args = args
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !positions.ghost.contains(&(i + offset)))
+            .map(|(_, arg)| arg.clone())
+            .collect();
+    }
+
+    /// The binding-side counterpart of the tuple collapse in
+    /// [`unwrap_ghost_return_type`]: a caller destructuring a tuple return
+    /// whose ghost elements were dropped
+    /// (`let (db, caps) = database_with_caps(...)`) loses the matching
+    /// pattern elements. Matched by callee name and original pattern arity,
+    /// like [`Self::remove_dropped_param_args`].
+    fn unwrap_ghost_binding_pattern(&self, local: &mut verus_syn::Local) {
+        let Some(init) = &local.init else {
+            return;
+        };
+        let Expr::Call(call) = &*init.expr else {
+            return;
+        };
+        let Expr::Path(func) = &*call.func else {
+            return;
+        };
+        let Some(last) = func.path.segments.last() else {
+            return;
+        };
+        let Some(positions) = self.ghost_param_positions.get(&last.ident.to_string()) else {
+            return;
+        };
+        if positions.ghost_returns.is_empty() {
+            return;
+        }
+        let Pat::Tuple(tuple) = &mut local.pat else {
+            return;
+        };
+        if tuple.elems.len() != positions.ret_arity {
+            return;
+        }
+        let kept: Vec<Pat> = tuple
+            .elems
+            .iter()
+            .enumerate()
+            .filter(|(i, _)| !positions.ghost_returns.contains(i))
+            .map(|(_, pat)| pat.clone())
+            .collect();
+        if kept.len() == 1 {
+            local.pat = kept.into_iter().next().expect("length checked");
+        } else {
+            tuple.elems = kept.into_iter().collect();
+        }
+    }
+
+    /// The const sibling of [`Self::strip_signature`]: an `exec const` can
+    /// carry an `ensures` clause, which must go with the other signature
+    /// specs for the item to parse as plain Rust.
+    fn strip_const_ensures(
+        &mut self,
+        attrs: &mut Vec<Attribute>,
+        ensures: &mut Option<verus_syn::Ensures>,
+    ) {
+        if self.config.keep_signature_specs {
+            return;
+        }
+        let Some(ensures) = ensures.take() else {
+            return;
+        };
+        if self.comments_removed_specs() {
+            attrs.extend(create_ensures_comment_attrs(&ensures));
+        }
+        self.stats.ensures_clauses += ensures.exprs.exprs.len();
+    }
+
+    /// Apply the configured [`EmptyBodyPolicy`] to a function whose body was
+    /// entirely proof code: `fn f() -> u32 { }` would not compile, so either
+    /// record the function for an error or fill in a diverging placeholder.
+    fn patch_empty_body(&mut self, sig: &Signature, block: &mut Block) {
+        if !block.stmts.is_empty() || !returns_value(sig) {
+            return;
+        }
+        match self.config.empty_body {
+            EmptyBodyPolicy::Error => {
+                self.empty_bodies.push(sig.ident.to_string());
+            }
+            EmptyBodyPolicy::Todo => {
+                block.stmts.push(verus_syn::parse_quote!(todo!();));
+            }
+            EmptyBodyPolicy::Unreachable => {
+                block.stmts.push(verus_syn::parse_quote!(unreachable!();));
+            }
+        }
+    }
+
+    /// [`keep_item`] with this configuration's `keep_*` selections applied:
+    /// spec and proof functions survive under [`Config::keep_spec_fns`], and
+    /// proof-only macro items under [`Config::keep_proof_blocks`]. A
+    /// [`Config::cfg_gate`] keeps all of those plus vstd imports; they pick
+    /// up the gate attribute in [`Self::visit_item_mut`].
+    fn keeps_item(&self, item: &Item) -> bool {
+        let gated = self.config.cfg_gate.is_some();
+        match item {
+            Item::Fn(func) if self.config.keep_spec_fns || gated => {
+                is_spec_or_proof_fn(&func.sig.mode)
+                    || is_broadcast_forall(&func.attrs)
+                    || keep_item(item)
+            }
+            Item::Macro(mac) if self.config.keep_proof_blocks || gated => {
+                is_proof_macro(&mac.mac.path) || keep_item(item)
+            }
+            // Ghost types are the item-level siblings of ghost fields and
+            // follow their keep flag; view impls and spec type aliases
+            // survive under a gate to be feature-gated in place.
+            Item::Struct(_) | Item::Enum(_) if self.config.keep_ghost_fields || gated => true,
+            Item::Impl(_) | Item::Type(_) if gated => true,
+            Item::Use(_) if gated => true,
+            _ => keep_item(item),
+        }
+    }
+
+    /// The `#[cfg(feature = "...")]` attribute gated code receives, when
+    /// [`Config::cfg_gate`] is set.
+    fn cfg_gate_attr(&self) -> Option<Attribute> {
+        let feature = self.config.cfg_gate.as_deref()?;
+        Some(verus_syn::parse_quote!(#[cfg(feature = #feature)]))
+    }
+
+    /// Whether removed specification clauses should be rendered as doc
+    /// comments: explicitly requested, or implied by `cfg_gate`, whose
+    /// output keeps the code but cannot keep Verus clause syntax.
+    fn comments_removed_specs(&self) -> bool {
+        self.config.spec_as_comments || self.config.cfg_gate.is_some()
+    }
+
+    /// Tally loop specification clauses that the caller has just detached
+    /// from a loop expression. Each entry is one optional clause's expression
+    /// list (`invariant`, `ensures`, `decreases`, ...), counted per
+    /// expression like the function-level clause counters.
+    fn count_loop_clauses<const N: usize>(&mut self, removed: [Option<Specification>; N]) {
+        for spec in removed.into_iter().flatten() {
+            self.stats.loop_clauses += spec.exprs.len();
+        }
+    }
+
+    /// Whether `path` names a derive that only Verus's builtin macros
+    /// provide: `Structural` always, plus anything the user listed in
+    /// [`Config::extra_verus_derives`]. Only bare identifiers match — a
+    /// qualified `some_crate::Structural` is somebody else's derive.
+    fn path_is_verus_derive(&self, path: &Path) -> bool {
+        let Some(ident) = path.get_ident() else {
+            return false;
+        };
+        BUILTIN_VERUS_DERIVES.iter().any(|d| ident == d)
+            || self.config.extra_verus_derives.iter().any(|d| ident == d.as_str())
+    }
+
+    /// Remove Verus-only identifiers from `#[derive(...)]` lists (dropping
+    /// the attribute entirely if nothing is left), including derives wrapped
+    /// in `#[cfg_attr(pred, derive(...))]`. Attributes that are not derive
+    /// lists, or whose arguments do not parse as one, pass through verbatim.
+    fn filter_derives(&mut self, attrs: &mut Vec<Attribute>) {
+        let old = std::mem::take(attrs);
+        for attr in old {
+            if let Some(kept) = self.rewrite_derive_attr(attr) {
+                attrs.push(kept);
+            }
+        }
+    }
+
+    fn rewrite_derive_attr(&self, attr: Attribute) -> Option<Attribute> {
+        if attr.path().is_ident("derive") {
+            let Ok(paths) =
+                attr.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+            else {
+                return Some(attr);
+            };
+            let total = paths.len();
+            let kept: Vec<Path> =
+                paths.into_iter().filter(|p| !self.path_is_verus_derive(p)).collect();
+            if kept.len() == total {
+                // Nothing removed: keep the original token stream untouched.
+                return Some(attr);
+            }
+            if kept.is_empty() {
+                return None;
+            }
+            return Some(verus_syn::parse_quote!(#[derive(#(#kept),*)]));
+        }
+        if attr.path().is_ident("cfg_attr") {
+            let Ok(metas) =
+                attr.parse_args_with(Punctuated::<Meta, Token![,]>::parse_terminated)
+            else {
+                return Some(attr);
+            };
+            if metas.len() < 2 {
+                return Some(attr);
+            }
+            let mut iter = metas.into_iter();
+            let predicate = iter.next().expect("length checked above");
+            let mut changed = false;
+            let mut payload: Vec<Meta> = Vec::new();
+            for meta in iter {
+                let Meta::List(list) = &meta else {
+                    payload.push(meta);
+                    continue;
+                };
+                if !list.path.is_ident("derive") {
+                    payload.push(meta);
+                    continue;
+                }
+                let Ok(paths) =
+                    list.parse_args_with(Punctuated::<Path, Token![,]>::parse_terminated)
+                else {
+                    payload.push(meta);
+                    continue;
+                };
+                let total = paths.len();
+                let kept: Vec<Path> =
+                    paths.into_iter().filter(|p| !self.path_is_verus_derive(p)).collect();
+                if kept.len() == total {
+                    payload.push(meta);
+                    continue;
+                }
+                changed = true;
+                if !kept.is_empty() {
+                    payload.push(verus_syn::parse_quote!(derive(#(#kept),*)));
+                }
+            }
+            if !changed {
+                return Some(attr);
+            }
+            if payload.is_empty() {
+                return None;
+            }
+            return Some(verus_syn::parse_quote!(#[cfg_attr(#predicate, #(#payload),*)]));
+        }
+        Some(attr)
+    }
+}
+
+/// Derives provided by Verus's builtin macros and nothing else; stripped
+/// output would fail to resolve them.
+const BUILTIN_VERUS_DERIVES: &[&str] = &["Structural"];
+
+impl VisitMut for StripVisitor<'_> {
+    fn visit_file_mut(&mut self, file: &mut File) {
+        // Signatures are rewritten as the walk reaches them, so the ghost
+        // parameter positions must be read off the pristine file first.
+        self.ghost_param_positions.add_file(file);
+        for item in &file.items {
+            if !self.keeps_item(item) {
+                self.record_removed_item(item);
+            }
+        }
+        file.items.retain(|item| self.keeps_item(item));
+        for item in &mut file.items {
+            self.visit_item_mut(item);
+        }
+        if !self.config.keep_empty_items {
+            file.items.retain(|item| !is_empty_shell(item, self.config.remove_empty_traits));
+        }
+    }
+
+    fn visit_item_mut(&mut self, item: &mut Item) {
+        if let Some(attr) = self.cfg_gate_attr() {
+            match item {
+                // A gated function's body is compiled out when the feature
+                // is off and rewriting it would destroy the proof it
+                // preserves, so only the signature is sanitized for the
+                // plain parse.
+                Item::Fn(func)
+                    if is_spec_or_proof_fn(&func.sig.mode)
+                        || is_broadcast_forall(&func.attrs) =>
+                {
+                    func.attrs.push(attr);
+                    self.strip_signature(&mut func.attrs, &mut func.sig);
+                    return;
+                }
+                // Macro tokens are opaque to the parser either way; the
+                // attribute alone is enough.
+                Item::Macro(mac) if is_proof_macro(&mac.mac.path) => {
+                    mac.attrs.push(attr);
+                    return;
+                }
+                // Gated code still names vstd types, so its imports stay,
+                // gated alongside it.
+                Item::Use(import) if is_verus_support_import(&import.tree) => {
+                    import.attrs.push(attr);
+                    return;
+                }
+                _ => {}
+            }
+        } else if let Item::Fn(func) = item {
+            // Spec/proof functions are deleted by the parent's retain pass
+            // (or kept verbatim under `keep_spec_fns`); either way their
+            // contents are not rewritten.
+            if is_spec_or_proof_fn(&func.sig.mode) {
+                return;
+            }
+        }
+        visit_mut::visit_item_mut(self, item);
+    }
+
+    fn visit_item_fn_mut(&mut self, func: &mut verus_syn::ItemFn) {
+        self.strip_signature(&mut func.attrs, &mut func.sig);
+        visit_mut::visit_item_fn_mut(self, func);
+        unwrap_trailing_ghost_value(&mut func.block);
+        self.patch_empty_body(&func.sig, &mut func.block);
+    }
+
+    fn visit_impl_item_fn_mut(&mut self, func: &mut verus_syn::ImplItemFn) {
+        // Spec/proof methods are filtered out by `visit_item_impl_mut`'s
+        // retain pass (or gated in place); don't visit their bodies.
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            if let Some(attr) = self.cfg_gate_attr() {
+                func.attrs.push(attr);
+                self.strip_signature(&mut func.attrs, &mut func.sig);
+            }
+            return;
+        }
+        self.strip_signature(&mut func.attrs, &mut func.sig);
+        visit_mut::visit_impl_item_fn_mut(self, func);
+        unwrap_trailing_ghost_value(&mut func.block);
+        self.patch_empty_body(&func.sig, &mut func.block);
+    }
+
+    fn visit_trait_item_fn_mut(&mut self, func: &mut verus_syn::TraitItemFn) {
+        // Only reachable for spec/proof methods under `keep_spec_fns` (left
+        // untouched) or `cfg_gate` (gated in place).
+        if is_spec_or_proof_fn(&func.sig.mode) {
+            if let Some(attr) = self.cfg_gate_attr() {
+                func.attrs.push(attr);
+                self.strip_signature(&mut func.attrs, &mut func.sig);
+            }
+            return;
+        }
+        self.strip_signature(&mut func.attrs, &mut func.sig);
+        visit_mut::visit_trait_item_fn_mut(self, func);
+        if let Some(block) = &mut func.default {
+            unwrap_trailing_ghost_value(block);
+            if block.stmts.is_empty() && self.config.drop_empty_trait_defaults {
+                // The default body was entirely proof code; unlike a free
+                // function, a trait method can simply lose its default and
+                // revert to a bare declaration.
+                func.default = None;
+                func.semi_token = Some(Default::default());
+            } else {
+                self.patch_empty_body(&func.sig, block);
+            }
+        }
+    }
+
+    fn visit_item_impl_mut(&mut self, imp: &mut ItemImpl) {
+        visit_mut::visit_item_impl_mut(self, imp);
+        if self.config.keep_spec_fns || self.config.cfg_gate.is_some() {
+            return;
+        }
+        for item in &imp.items {
+            if let ImplItem::Fn(func) = item {
+                if is_spec_or_proof_fn(&func.sig.mode) {
+                    self.record_removed_fn(&func.sig);
+                }
+            }
+        }
+        imp.items.retain(|item| match item {
+            ImplItem::Fn(func) => {
+                !is_spec_or_proof_fn(&func.sig.mode) && !is_broadcast_forall(&func.attrs)
+            }
+            _ => true,
+        });
+    }
+
+    fn visit_item_trait_mut(&mut self, trait_def: &mut ItemTrait) {
+        // Filter before descending: `visit_trait_item_fn_mut` erases modes,
+        // so deciding afterwards would see every method as `Default`.
+        if !self.config.keep_spec_fns && self.config.cfg_gate.is_none() {
+            for item in &trait_def.items {
+                if let TraitItem::Fn(func) = item {
+                    if is_spec_or_proof_fn(&func.sig.mode) {
+                        self.record_removed_fn(&func.sig);
+                    }
+                }
+            }
+            trait_def.items.retain(|item| match item {
+                TraitItem::Fn(func) => {
+                    !is_spec_or_proof_fn(&func.sig.mode) && !is_broadcast_forall(&func.attrs)
+                }
+                _ => true,
+            });
+        }
+        visit_mut::visit_item_trait_mut(self, trait_def);
+    }
+
+    fn visit_item_mod_mut(&mut self, module: &mut verus_syn::ItemMod) {
+        // Inline modules get the same passes as the file root — count, retain,
+        // descend, then drop empty shells — so removal behaves identically at
+        // every nesting depth. Without this, nested spec fns would survive
+        // with mangled signatures: the retain pass used to run only on
+        // `File::items`.
+        if let Some((_, items)) = &mut module.content {
+            for item in items.iter() {
+                if !self.keeps_item(item) {
+                    self.record_removed_item(item);
+                }
+            }
+            items.retain(|item| self.keeps_item(item));
+        }
+        visit_mut::visit_item_mod_mut(self, module);
+        if !self.config.keep_empty_items {
+            if let Some((_, items)) = &mut module.content {
+                items.retain(|item| !is_empty_shell(item, self.config.remove_empty_traits));
+            }
+        }
+    }
+
+    fn visit_item_const_mut(&mut self, item: &mut verus_syn::ItemConst) {
+        // Spec/proof consts are deleted by the retain passes; a surviving
+        // `exec const` needs its Verus-only markers cleared and, like a
+        // function signature, loses any `ensures` clause.
+        item.publish = Publish::Default;
+        item.mode = FnMode::Default;
+        self.strip_const_ensures(&mut item.attrs, &mut item.ensures);
+        visit_mut::visit_item_const_mut(self, item);
+    }
+
+    fn visit_impl_item_const_mut(&mut self, item: &mut verus_syn::ImplItemConst) {
+        item.publish = Publish::Default;
+        item.mode = FnMode::Default;
+        self.strip_const_ensures(&mut item.attrs, &mut item.ensures);
+        visit_mut::visit_impl_item_const_mut(self, item);
+    }
+
+    fn visit_item_struct_mut(&mut self, item: &mut verus_syn::ItemStruct) {
+        // Only non-ghost structs reach this point — `ghost struct` is
+        // deleted whole by the retain passes — but under `keep_ghost_fields`
+        // or a gate the mode marker still has to go for the plain parse.
+        item.mode = DataMode::Default;
+        self.filter_derives(&mut item.attrs);
+        self.record_removed_ghost_fields(&item.ident.to_string(), &item.fields);
+        visit_mut::visit_item_struct_mut(self, item);
+    }
+
+    fn visit_item_enum_mut(&mut self, item: &mut verus_syn::ItemEnum) {
+        item.mode = DataMode::Default;
+        self.filter_derives(&mut item.attrs);
+        for variant in &item.variants {
+            self.record_removed_ghost_fields(
+                &format!("{}::{}", item.ident, variant.ident),
+                &variant.fields,
+            );
+        }
+        visit_mut::visit_item_enum_mut(self, item);
+    }
+
+    fn visit_item_union_mut(&mut self, item: &mut verus_syn::ItemUnion) {
+        self.filter_derives(&mut item.attrs);
+        visit_mut::visit_item_union_mut(self, item);
+    }
+
+    fn visit_fields_mut(&mut self, fields: &mut Fields) {
+        if let Some(attr) = self.cfg_gate_attr() {
+            // The field survives behind the gate; only its Verus-only mode
+            // marker has to go for the file to parse without Verus.
+            for field in fields.iter_mut() {
+                if is_ghost_field(field) {
+                    field.attrs.push(attr.clone());
+                }
+                field.mode = DataMode::Default;
+            }
+            visit_mut::visit_fields_mut(self, fields);
+            return;
+        }
+        if self.config.keep_ghost_fields {
+            // Mode markers and all; the consumer asked for the
+            // verification-time fields to survive.
+            visit_mut::visit_fields_mut(self, fields);
+            return;
+        }
+        match fields {
+            Fields::Named(named) => {
+                let all = std::mem::take(&mut named.named);
+                let total = all.len();
+                named.named = all.into_iter().filter(|f| !is_ghost_field(f)).collect();
+                self.stats.ghost_fields += total - named.named.len();
+            }
+            Fields::Unnamed(unnamed) => {
+                let all = std::mem::take(&mut unnamed.unnamed);
+                let total = all.len();
+                unnamed.unnamed = all.into_iter().filter(|f| !is_ghost_field(f)).collect();
+                self.stats.ghost_fields += total - unnamed.unnamed.len();
+            }
+            Fields::Unit => {}
+        }
+        for field in fields.iter_mut() {
+            field.mode = DataMode::Default;
+        }
+        visit_mut::visit_fields_mut(self, fields);
+    }
+
+    fn visit_macro_mut(&mut self, mac: &mut verus_syn::Macro) {
+        // Reached for every surviving invocation, whatever position it
+        // stood in; proof macros were removed (or deliberately kept) before
+        // descent, so anything arriving here with a Verus-only name is a
+        // construct stripping has no handling for.
+        if is_unhandled_verus_macro(&mac.path) {
+            let last = mac.path.segments.last().expect("matched on the last segment");
+            let start = last.ident.span().start();
+            self.warnings.push(Warning::UnknownVerusConstruct {
+                item_name: format!("macro invocation `{}!`", last.ident),
+                suggestion: "its contents cannot be stripped; remove or port the \
+                             invocation by hand"
+                    .to_string(),
+                line: start.line,
+                column: start.column + 1,
+            });
+        }
+        visit_mut::visit_macro_mut(self, mac);
+    }
+
+    fn visit_local_mut(&mut self, local: &mut verus_syn::Local) {
+        self.unwrap_ghost_binding_pattern(local);
+        visit_mut::visit_local_mut(self, local);
+    }
+
+    fn visit_block_mut(&mut self, block: &mut Block) {
+        for stmt in &block.stmts {
+            if let Stmt::Item(item) = stmt {
+                if !self.keeps_item(item) {
+                    self.record_removed_item(item);
+                }
+            }
+        }
+        block.stmts.retain(|stmt| match stmt {
+            // `ghost` and `tracked` are the only locals-only modifiers in
+            // verus_syn; there is no `proof let` form (`Local` has no `proof`
+            // field, and `proof let x = ...;` is rejected by the parser).
+            // `let Ghost(x) = ...` / `let Tracked(x) = ...` bind ghost values
+            // without either keyword, so the pattern is checked too.
+            Stmt::Local(local) => {
+                let keep = local.ghost.is_none()
+                    && local.tracked.is_none()
+                    && !is_ghost_wrapper_pat(&local.pat);
+                if !keep {
+                    self.stats.ghost_locals += 1;
+                }
+                keep
+            }
+            Stmt::Expr(expr, _) => {
+                if !is_proof_expr(expr) {
+                    return true;
+                }
+                match expr {
+                    // A plain assert the strategy keeps is converted (not
+                    // dropped) when `visit_expr_mut` reaches it.
+                    Expr::Assert(_)
+                        if self.config.assert_strategy != AssertStrategy::Remove =>
+                    {
+                        return true;
+                    }
+                    Expr::Assert(_) | Expr::Assume(_) | Expr::AssertForall(_) => {
+                        self.stats.assert_assume_exprs += 1;
+                    }
+                    Expr::Unary(_) => {
+                        if self.config.keep_proof_blocks || self.config.cfg_gate.is_some() {
+                            return true;
+                        }
+                        self.stats.proof_blocks += 1;
+                    }
+                    // `reveal`/`hide` directives: removed but not a category
+                    // of their own in the statistics.
+                    _ => {}
+                }
+                false
+            }
+            Stmt::Macro(mac) => {
+                let keep = self.config.keep_proof_blocks
+                    || self.config.cfg_gate.is_some()
+                    || !is_proof_macro(&mac.mac.path);
+                if !keep {
+                    self.stats.proof_blocks += 1;
+                }
+                keep
+            }
+            Stmt::Item(item) => self.keeps_item(item),
+        });
+        visit_mut::visit_block_mut(self, block);
+        if !self.config.keep_empty_items {
+            // Items can nest inside function bodies too; an impl whose
+            // methods were all spec/proof becomes an empty shell only after
+            // the descent above, so the sweep has to run here as well.
+            block.stmts.retain(|stmt| match stmt {
+                Stmt::Item(item) => !is_empty_shell(item, self.config.remove_empty_traits),
+                _ => true,
+            });
+        }
+    }
+
+    fn visit_stmt_mut(&mut self, stmt: &mut Stmt) {
+        if let Some(attr) = self.cfg_gate_attr() {
+            // Statement-position proof code, which `visit_block_mut`'s
+            // retain pass spared for us, becomes a gated plain block (the
+            // `proof` keyword would not parse) or a gated macro invocation.
+            // Neither is descended into: the contents are compiled out when
+            // the feature is off, and rewriting them would destroy the
+            // proof being preserved.
+            if let Stmt::Expr(Expr::Unary(unary), _) = stmt {
+                if matches!(unary.op, UnOp::Proof(_)) {
+                    let mut body =
+                        std::mem::replace(&mut *unary.expr, Expr::Verbatim(Default::default()));
+                    if let Expr::Block(block) = &mut body {
+                        block.attrs.push(attr);
+                    }
+                    *stmt = Stmt::Expr(body, None);
+                    return;
+                }
+            }
+            if let Stmt::Macro(mac) = stmt {
+                if is_proof_macro(&mac.mac.path) {
+                    mac.attrs.push(attr);
+                    return;
+                }
+            }
+        }
+        visit_mut::visit_stmt_mut(self, stmt);
+    }
+
+    fn visit_expr_mut(&mut self, expr: &mut Expr) {
+        // A kept proof block is kept verbatim, statement or expression
+        // position alike: descending would strip the asserts inside it.
+        if self.config.keep_proof_blocks {
+            if let Expr::Unary(unary) = expr {
+                if matches!(unary.op, UnOp::Proof(_)) {
+                    return;
+                }
+            }
+        }
+        // Children first, so a ghost expression nested inside another (e.g.
+        // a proof block in a tuple element) is already rewritten by the time
+        // the parent is considered.
+        visit_mut::visit_expr_mut(self, expr);
+        match expr {
+            // `x@` is sugar for calling vstd's view; in exec position the
+            // closest plain-Rust meaning is the value itself.
+            Expr::View(view) => {
+                *expr = std::mem::replace(
+                    &mut *view.expr,
+                    Expr::Verbatim(Default::default()),
+                );
+            }
+            // Ghost-only operators in value position; their result can only
+            // have fed other ghost code, so `()` is an adequate stand-in.
+            Expr::Unary(unary)
+                if matches!(
+                    unary.op,
+                    UnOp::Proof(_) | UnOp::Forall(_) | UnOp::Exists(_) | UnOp::Choose(_)
+                ) =>
+            {
+                if matches!(unary.op, UnOp::Proof(_)) {
+                    self.stats.proof_blocks += 1;
+                }
+                *expr = unit_expr();
+            }
+            // A proof macro in value position (`if` condition, `let`
+            // initializer, ...); the statement filter in `visit_block_mut`
+            // only sees `Stmt::Macro`, so these collapse here. `calc!` and
+            // friends evaluate to `()` in Verus too, so the stand-in types.
+            Expr::Macro(mac)
+                if is_proof_macro(&mac.mac.path) && !self.config.keep_proof_blocks =>
+            {
+                self.stats.proof_blocks += 1;
+                *expr = unit_expr();
+            }
+            // Under a converting [`AssertStrategy`] the assertion becomes a
+            // runtime check on its condition (children-first visiting has
+            // already rewritten any ghost sub-expressions in it); an
+            // `assert ... by` proof body is verification-time work and is
+            // dropped either way. `assume` and `assert forall` have no
+            // runtime meaning and always collapse.
+            Expr::Assert(assert)
+                if self.config.assert_strategy != AssertStrategy::Remove =>
+            {
+                let condition =
+                    std::mem::replace(&mut *assert.expr, Expr::Verbatim(Default::default()));
+                *expr = match self.config.assert_strategy {
+                    AssertStrategy::DebugAssert => {
+                        verus_syn::parse_quote!(debug_assert!(#condition))
+                    }
+                    _ => verus_syn::parse_quote!(assert!(#condition)),
+                };
+            }
+            Expr::Assert(_) | Expr::Assume(_) | Expr::AssertForall(_) => {
+                // Statement-position forms are dropped by `visit_block_mut`
+                // before descending; this handles the expression positions.
+                self.stats.assert_assume_exprs += 1;
+                *expr = unit_expr();
+            }
+            // `(proof { ... }, value).1` is a Verus idiom for smuggling a
+            // proof into an expression; once the ghost elements above have
+            // collapsed to `()`, the projection can collapse too. Only
+            // all-unit siblings are folded away, so real side effects are
+            // never discarded.
+            Expr::Field(field) => {
+                let Expr::Tuple(tuple) = &mut *field.base else {
+                    return;
+                };
+                let Member::Unnamed(index) = &field.member else {
+                    return;
+                };
+                let index = index.index as usize;
+                if index >= tuple.elems.len()
+                    || tuple.elems.iter().enumerate().any(|(i, e)| i != index && !is_unit_expr(e))
+                {
+                    return;
+                }
+                let element =
+                    std::mem::replace(&mut tuple.elems[index], Expr::Verbatim(Default::default()));
+                *expr = element;
+            }
+            // Loop specifications (`invariant`, `invariant_except_break`,
+            // `invariant_ensures`, `ensures`, `decreases`) have no plain-Rust
+            // rendering; the loop itself survives untouched (under
+            // `spec_as_comments` the clauses become doc comments on it).
+            Expr::While(while_expr) => {
+                let removed = [
+                    (
+                        "invariant_except_break",
+                        while_expr.invariant_except_break.take().map(|c| c.exprs),
+                    ),
+                    ("invariant", while_expr.invariant.take().map(|c| c.exprs)),
+                    (
+                        "invariant_ensures",
+                        while_expr.invariant_ensures.take().map(|c| c.exprs),
+                    ),
+                    ("ensures", while_expr.ensures.take().map(|c| c.exprs)),
+                    ("decreases", while_expr.decreases.take().map(|c| c.exprs)),
+                ];
+                if self.comments_removed_specs() {
+                    while_expr.attrs.extend(create_loop_annotation_attrs(&removed));
+                }
+                self.count_loop_clauses(removed.map(|(_, spec)| spec));
+            }
+            Expr::Loop(loop_expr) => {
+                let removed = [
+                    (
+                        "invariant_except_break",
+                        loop_expr.invariant_except_break.take().map(|c| c.exprs),
+                    ),
+                    ("invariant", loop_expr.invariant.take().map(|c| c.exprs)),
+                    (
+                        "invariant_ensures",
+                        loop_expr.invariant_ensures.take().map(|c| c.exprs),
+                    ),
+                    ("ensures", loop_expr.ensures.take().map(|c| c.exprs)),
+                    ("decreases", loop_expr.decreases.take().map(|c| c.exprs)),
+                ];
+                if self.comments_removed_specs() {
+                    loop_expr.attrs.extend(create_loop_annotation_attrs(&removed));
+                }
+                self.count_loop_clauses(removed.map(|(_, spec)| spec));
+            }
+            // `return Ghost(x)` pairs with the unwrapped return type; the
+            // value loses its wrapper too, and returned tuples drop their
+            // wrapper elements to mirror the type rewrite.
+            Expr::Return(ret) => {
+                if let Some(value) = &mut ret.expr {
+                    unwrap_ghost_return_value(value);
+                }
+            }
+            // A ghost parameter dropped from a signature takes its call-site
+            // arguments with it: `read(k, Tracked(cap))` becomes `read(k)`.
+            // A bare constructor call keeps its argument, so the return-value
+            // rewrite above can unwrap it instead of emptying it.
+            Expr::Call(call) if !is_ghost_ctor(&call.func) => {
+                if let Expr::Path(func) = &*call.func {
+                    if let Some(last) = func.path.segments.last() {
+                        let name = last.ident.to_string();
+                        self.remove_dropped_param_args(&name, &mut call.args, false);
+                    }
+                }
+                remove_ghost_ctor_args(&mut call.args);
+            }
+            Expr::MethodCall(call) => {
+                let name = call.method.to_string();
+                self.remove_dropped_param_args(&name, &mut call.args, true);
+                remove_ghost_ctor_args(&mut call.args);
+            }
+            Expr::ForLoop(for_expr) => {
+                // `for pat in name: iter` names the iterator for use in the
+                // clauses; the name goes out with them.
+                for_expr.expr_name = None;
+                let removed = [
+                    ("invariant", for_expr.invariant.take().map(|c| c.exprs)),
+                    ("decreases", for_expr.decreases.take().map(|c| c.exprs)),
+                ];
+                if self.comments_removed_specs() {
+                    for_expr.attrs.extend(create_loop_annotation_attrs(&removed));
+                }
+                self.count_loop_clauses(removed.map(|(_, spec)| spec));
+            }
+            _ => {}
+        }
+    }
+}
+
+/// What a granular entry point decided about its node: rewritten in place
+/// and worth keeping, or pure verification code the caller must delete.
+/// The functions here cannot delete the node themselves — they only hold a
+/// `&mut` to it, not to its container.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum StripDisposition {
+    /// The node survives; any Verus constructs inside it have been stripped.
+    Keep,
+    /// The node is verification-only (a spec fn, a proof block, ...);
+    /// remove it from its container.
+    Remove,
+}
+
+/// Strip a single item in place, applying exactly what the file visitor
+/// would: [`StripDisposition::Remove`] for items the file pass deletes
+/// (spec/proof fns, broadcast lemmas, proof macros, vstd imports), otherwise
+/// the item is rewritten and kept. For tooling that operates on one node at
+/// a time; whole files should go through [`crate::strip_source`], which also
+/// runs the cleanup and validation passes.
+pub fn strip_item(item: &mut Item, config: &Config) -> StripDisposition {
+    let mut visitor = StripVisitor::new(config);
+    if !visitor.keeps_item(item) {
+        return StripDisposition::Remove;
+    }
+    visitor.visit_item_mut(item);
+    StripDisposition::Keep
+}
+
+/// Strip a block in place: proof statements and ghost locals are dropped
+/// from it, nested expressions rewritten. A block itself is never
+/// verification-only, so this always returns [`StripDisposition::Keep`];
+/// the uniform return type keeps the three entry points interchangeable.
+pub fn strip_block(block: &mut Block, config: &Config) -> StripDisposition {
+    let mut visitor = StripVisitor::new(config);
+    visitor.visit_block_mut(block);
+    StripDisposition::Keep
+}
+
+/// The ambient `Expr` grammar reads `assert(x < 10)` as a plain call to a
+/// function named `assert` — the Verus keyword forms only win in statement
+/// context — so an expression parsed in isolation hides its proof nature
+/// from [`is_proof_expr`]. Reparse such calls through the statement grammar
+/// to recover the keyword form.
+fn reparse_proof_keyword_call(expr: &Expr) -> Option<Expr> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    let Expr::Path(func) = &*call.func else {
+        return None;
+    };
+    let ident = func.path.get_ident()?;
+    if ident != "assert" && ident != "assume" {
+        return None;
+    }
+    // The statement grammar wants a terminated statement.
+    let tokens = quote::ToTokens::to_token_stream(expr);
+    match verus_syn::parse2::<Stmt>(quote::quote!(#tokens;)) {
+        Ok(Stmt::Expr(reparsed @ (Expr::Assert(_) | Expr::Assume(_)), _)) => Some(reparsed),
+        _ => None,
+    }
+}
+
+/// Strip one expression in place. An expression that is pure proof code on
+/// its own — `assert`/`assume`, a `proof { ... }` block, a proof macro —
+/// comes back [`StripDisposition::Remove`] under the same configuration
+/// rules the statement filter uses; the caller decides whether deletion or
+/// a `()` stand-in suits the position it holds. Anything else is rewritten
+/// (views collapsed, nested ghost code removed) and kept.
+pub fn strip_expr(expr: &mut Expr, config: &Config) -> StripDisposition {
+    if let Some(keyword_form) = reparse_proof_keyword_call(expr) {
+        *expr = keyword_form;
+    }
+    if is_proof_expr(expr) {
+        let kept = match expr {
+            // Converted to a runtime check rather than dropped.
+            Expr::Assert(_) => config.assert_strategy != AssertStrategy::Remove,
+            // A proof block, kept verbatim or gated on request.
+            Expr::Unary(_) => config.keep_proof_blocks || config.cfg_gate.is_some(),
+            _ => false,
+        };
+        if !kept {
+            return StripDisposition::Remove;
+        }
+    }
+    if let Expr::Macro(mac) = expr {
+        if is_proof_macro(&mac.mac.path)
+            && !config.keep_proof_blocks
+            && config.cfg_gate.is_none()
+        {
+            return StripDisposition::Remove;
+        }
+    }
+    let mut visitor = StripVisitor::new(config);
+    visitor.visit_expr_mut(expr);
+    StripDisposition::Keep
+}
+
+/// The unit expression `()`, the stand-in for removed ghost values.
+fn unit_expr() -> Expr {
+    verus_syn::parse_quote!(())
+}
+
+fn is_unit_expr(expr: &Expr) -> bool {
+    matches!(expr, Expr::Tuple(tuple) if tuple.elems.is_empty())
+}
+
+/// True for functions that exist only for verification and must be removed
+/// entirely from the output.
+pub(crate) fn is_spec_or_proof_fn(mode: &FnMode) -> bool {
+    match mode {
+        FnMode::Spec(_) | FnMode::SpecChecked(_) | FnMode::Proof(_) | FnMode::ProofAxiom(_) => true,
+        FnMode::Exec(_) | FnMode::Default => false,
+    }
+}
+
+/// True for items carrying `#[verifier::broadcast_forall]` (or the older
+/// `#[verifier(broadcast_forall)]` list form), the attribute spelling of a
+/// global lemma that predates the `broadcast proof fn` syntax. The declared
+/// mode of such a function is not reliable — legacy axioms were plain `fn`s
+/// with an `unimplemented!()` body — so the attribute alone marks the item
+/// verification-only.
+pub(crate) fn is_broadcast_forall(attrs: &[Attribute]) -> bool {
+    attrs.iter().any(|attr| {
+        if attr.path().segments.first().is_none_or(|seg| seg.ident != "verifier") {
+            return false;
+        }
+        match &attr.meta {
+            Meta::Path(path) => {
+                path.segments.last().is_some_and(|seg| seg.ident == "broadcast_forall")
+            }
+            Meta::List(list) => {
+                list.parse_args::<Path>().is_ok_and(|arg| arg.is_ident("broadcast_forall"))
+            }
+            Meta::NameValue(_) => false,
+        }
+    })
+}
+
+/// Decide whether a top-level (or module-level) item survives stripping at
+/// all; items rejected here are deleted rather than rewritten.
+pub(crate) fn keep_item(item: &Item) -> bool {
+    match item {
+        Item::Fn(func) => {
+            !is_spec_or_proof_fn(&func.sig.mode) && !is_broadcast_forall(&func.attrs)
+        }
+        // `spec const` / `proof const` are verification-only values; consts
+        // share the function mode machinery in verus_syn.
+        Item::Const(item) => !is_spec_or_proof_fn(&item.mode),
+        // `ghost struct` / `tracked struct` (and enum) declare types that
+        // exist only at verification time; unlike a plain struct with some
+        // ghost fields, nothing executable can remain of them.
+        Item::Struct(item) => !matches!(item.mode, DataMode::Ghost(_) | DataMode::Tracked(_)),
+        Item::Enum(item) => !matches!(item.mode, DataMode::Ghost(_) | DataMode::Tracked(_)),
+        // An `impl View`/`impl DeepView` block implements vstd's view traits,
+        // whose members are all spec mode: stripping would leave a shell that
+        // implements an unknown trait. Matching is by unqualified name, the
+        // same best effort as elsewhere in this file.
+        Item::Impl(imp) => !is_view_impl(imp),
+        // A `type` alias defined in terms of vstd's spec-only types
+        // (`type Bag = Seq<Seq<i64>>`) can never name a compilable type.
+        Item::Type(alias) => !mentions_spec_only_type(&alias.ty),
+        // Verus-only item forms have no plain-Rust counterpart.
+        Item::Global(_)
+        | Item::BroadcastUse(_)
+        | Item::BroadcastGroup(_)
+        | Item::AssumeSpecification(_) => false,
+        Item::Macro(mac) => !is_proof_macro(&mac.mac.path),
+        // Imports of the verification support crates dangle once ghost code
+        // is gone; stripped output must compile without vstd on the path.
+        Item::Use(import) => !is_verus_support_import(&import.tree),
+        _ => true,
+    }
+}
+
+/// True for trait impls of vstd's `View`/`DeepView`.
+fn is_view_impl(imp: &ItemImpl) -> bool {
+    let Some((_, path, _)) = &imp.trait_ else {
+        return false;
+    };
+    let Some(last) = path.segments.last() else {
+        return false;
+    };
+    matches!(last.ident.to_string().as_str(), "View" | "DeepView")
+}
+
+/// Idents of vstd types that exist only at verification time. Deliberately
+/// shorter than what [`crate::type_fix`] rewrites: that pass substitutes
+/// *usually-right* exec equivalents under an opt-in flag, while this list
+/// only contains types with no executable counterpart at all, used to delete
+/// `type` aliases that could never compile.
+const SPEC_ONLY_TYPES: &[&str] =
+    &["int", "nat", "Seq", "Map", "Set", "Multiset", "FnSpec", "SpecFn"];
+
+/// True when `ty` mentions one of [`SPEC_ONLY_TYPES`] anywhere inside it.
+fn mentions_spec_only_type(ty: &Type) -> bool {
+    struct Finder {
+        found: bool,
+    }
+    impl<'ast> verus_syn::visit::Visit<'ast> for Finder {
+        fn visit_path_segment(&mut self, segment: &'ast verus_syn::PathSegment) {
+            if SPEC_ONLY_TYPES.iter().any(|name| segment.ident == name) {
+                self.found = true;
+            }
+            verus_syn::visit::visit_path_segment(self, segment);
+        }
+    }
+    let mut finder = Finder { found: false };
+    verus_syn::visit::Visit::visit_type(&mut finder, ty);
+    finder.found
+}
+
+/// True for `use` trees rooted at a verification support crate (`vstd`,
+/// `builtin`, `builtin_macros`).
+fn is_verus_support_import(tree: &verus_syn::UseTree) -> bool {
+    let ident = match tree {
+        verus_syn::UseTree::Path(path) => &path.ident,
+        verus_syn::UseTree::Name(name) => &name.ident,
+        _ => return false,
+    };
+    matches!(ident.to_string().as_str(), "vstd" | "builtin" | "builtin_macros")
+}
+
+/// True for statement-position expressions that are pure proof code.
+pub(crate) fn is_proof_expr(expr: &Expr) -> bool {
+    match expr {
+        Expr::Assert(_) | Expr::Assume(_) | Expr::AssertForall(_) | Expr::RevealHide(_) => true,
+        Expr::Unary(unary) => matches!(unary.op, UnOp::Proof(_)),
+        _ => false,
+    }
+}
+
+/// True for macros whose whole invocation is proof code (`calc!`,
+/// `reveal!`, ...); note that plain `assert!` is real Rust and is kept.
+pub(crate) fn is_proof_macro(path: &Path) -> bool {
+    let Some(last) = path.segments.last() else {
+        return false;
+    };
+    matches!(
+        last.ident.to_string().as_str(),
+        "calc"
+            | "reveal"
+            | "reveal_with_fuel"
+            | "hide"
+            | "assert_by_contradiction"
+            | "assert_seqs_equal"
+            | "assert_sets_equal"
+            | "assert_maps_equal"
+            | "assert_multisets_equal"
+            | "assert_bitvector_by"
+            | "assert_nonlinear_by"
+    )
+}
+
+/// True for macros that expand to Verus-only code but are not proof code the
+/// strip passes remove — their invocations survive into the output, where
+/// they will not compile as plain Rust.
+fn is_unhandled_verus_macro(path: &Path) -> bool {
+    let Some(last) = path.segments.last() else {
+        return false;
+    };
+    matches!(
+        last.ident.to_string().as_str(),
+        "state_machine" | "tokenized_state_machine" | "atomic_with_ghost"
+    )
+}
+
+/// True for parameters that only exist at verification time: `tracked` args
+/// and args of type `Ghost<T>` / `Tracked<T>`.
+fn is_ghost_param(arg: &FnArg) -> bool {
+    if arg.tracked.is_some() {
+        return true;
+    }
+    match &arg.kind {
+        FnArgKind::Typed(pat_type) => is_ghost_wrapper_type(&pat_type.ty),
+        FnArgKind::Receiver(_) => false,
+    }
+}
+
+/// Best-effort name of a parameter, for warning messages; patterns more
+/// complex than a plain binding render as `_`.
+fn param_name(arg: &FnArg) -> String {
+    match &arg.kind {
+        FnArgKind::Typed(pat_type) => match &*pat_type.pat {
+            verus_syn::Pat::Ident(pat) => pat.ident.to_string(),
+            _ => "_".to_string(),
+        },
+        FnArgKind::Receiver(_) => "self".to_string(),
+    }
+}
+
+/// True for struct/enum fields that only exist at verification time.
+pub(crate) fn is_ghost_field(field: &verus_syn::Field) -> bool {
+    match field.mode {
+        DataMode::Ghost(_) | DataMode::Tracked(_) => true,
+        DataMode::Exec(_) | DataMode::Default => is_ghost_wrapper_type(&field.ty),
+    }
+}
+
+/// True if the function's declared return type is anything other than `()`.
+fn returns_value(sig: &Signature) -> bool {
+    match &sig.output {
+        verus_syn::ReturnType::Default => false,
+        verus_syn::ReturnType::Type(_, _, _, ty) => {
+            !matches!(&**ty, Type::Tuple(tup) if tup.elems.is_empty())
+        }
+    }
+}
+
+/// Rewrite a return type so the stripped function returns only its
+/// executable part: `Ghost<T>`/`Tracked<T>` becomes `T`, and wrapper
+/// elements of a returned tuple are removed, collapsing `(Ghost<A>, B)` to
+/// `B`. Wrappers nested any deeper are left alone.
+fn unwrap_ghost_return_type(ty: &mut Type) {
+    if let Type::Path(type_path) = &*ty {
+        if type_path.qself.is_none() {
+            if let Some(inner) = crate::type_fix::ghost_wrapper_inner(type_path) {
+                *ty = inner;
+                // `Ghost<Ghost<T>>` is legal, if pointless; keep unwrapping.
+                unwrap_ghost_return_type(ty);
+                return;
+            }
+        }
+    }
+    if let Type::Tuple(tuple) = ty {
+        let total = tuple.elems.len();
+        let kept: Vec<Type> =
+            tuple.elems.iter().filter(|e| !is_ghost_wrapper_type(e)).cloned().collect();
+        if kept.len() == total {
+            return;
+        }
+        if kept.len() == 1 {
+            *ty = kept.into_iter().next().expect("length checked");
+        } else {
+            tuple.elems = kept.into_iter().collect();
+        }
+    }
+}
+
+/// Whether `func` is a path to the `Ghost`/`Tracked` wrapper constructor.
+fn is_ghost_ctor(func: &Expr) -> bool {
+    let Expr::Path(func) = func else {
+        return false;
+    };
+    func.path
+        .segments
+        .last()
+        .is_some_and(|last| last.ident == "Ghost" || last.ident == "Tracked")
+}
+
+/// The argument of a `Ghost(x)`/`Tracked(x)` constructor call, if `expr` is
+/// one.
+fn ghost_ctor_arg(expr: &Expr) -> Option<Expr> {
+    let Expr::Call(call) = expr else {
+        return None;
+    };
+    if !is_ghost_ctor(&call.func) || call.args.len() != 1 {
+        return None;
+    }
+    Some(call.args[0].clone())
+}
+
+/// Delete `Ghost(..)`/`Tracked(..)` constructor-call arguments from a call's
+/// argument list: they can only have fed ghost parameters, and those were
+/// dropped from the callee's signature.
+fn remove_ghost_ctor_args(args: &mut Punctuated<Expr, Token![,]>) {
+    if args.iter().any(|arg| ghost_ctor_arg(arg).is_some()) {
+        *args = args.iter().filter(|arg| ghost_ctor_arg(arg).is_none()).cloned().collect();
+    }
+}
+
+/// Mirror of [`unwrap_ghost_return_type`] on a return value: `Ghost(x)`
+/// becomes `x`, and constructor-call elements of a returned tuple are
+/// removed with the same collapsing rules.
+fn unwrap_ghost_return_value(expr: &mut Expr) {
+    if let Some(inner) = ghost_ctor_arg(expr) {
+        *expr = inner;
+        unwrap_ghost_return_value(expr);
+        return;
+    }
+    if let Expr::Tuple(tuple) = expr {
+        let total = tuple.elems.len();
+        let kept: Vec<Expr> =
+            tuple.elems.iter().filter(|e| ghost_ctor_arg(e).is_none()).cloned().collect();
+        if kept.len() == total {
+            return;
+        }
+        if kept.len() == 1 {
+            *expr = kept.into_iter().next().expect("length checked");
+        } else {
+            tuple.elems = kept.into_iter().collect();
+        }
+    }
+}
+
+/// Apply [`unwrap_ghost_return_value`] to a body's trailing expression, the
+/// `return`-less way most functions produce their value.
+fn unwrap_trailing_ghost_value(block: &mut Block) {
+    if let Some(Stmt::Expr(expr, None)) = block.stmts.last_mut() {
+        unwrap_ghost_return_value(expr);
+    }
+}
+
+/// The shape of a signature that will lose ghost parameters or ghost
+/// return-tuple elements, keyed by function name in a
+/// [`GhostSignatureIndex`]. `ghost` and `arity` index and count
+/// `Signature::inputs` (receiver included), so a method call's arguments
+/// compare against them at an offset of one; `ghost_returns` and
+/// `ret_arity` do the same for the elements of a tuple return type.
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct GhostParamPositions {
+    arity: usize,
+    has_receiver: bool,
+    ghost: Vec<usize>,
+    ret_arity: usize,
+    ghost_returns: Vec<usize>,
+}
+
+/// Index of exec signatures that lose ghost parameters or ghost
+/// return-tuple elements to stripping, so `visit_expr_mut` and
+/// `visit_local_mut` can rewrite the matching call sites. The visitor
+/// indexes each file before rewriting it; [`crate::strip_files`] seeds one
+/// index with every file in a set, making the rewrites work across files.
+/// Two same-named signatures of different shapes cancel each other out:
+/// with only unqualified names to match on, guessing could delete a live
+/// argument, and leaving the call for rustc to reject is the lesser
+/// failure.
+#[derive(Debug, Default, Clone)]
+pub(crate) struct GhostSignatureIndex {
+    map: std::collections::HashMap<String, GhostParamPositions>,
+    conflicted: std::collections::HashSet<String>,
+}
+
+impl GhostSignatureIndex {
+    /// Record every relevant signature in `file`. Spec and proof fns are
+    /// skipped — they are removed whole, callers and all.
+    pub(crate) fn add_file(&mut self, file: &File) {
+        struct Collector<'a> {
+            index: &'a mut GhostSignatureIndex,
+        }
+        impl<'ast> verus_syn::visit::Visit<'ast> for Collector<'_> {
+            fn visit_signature(&mut self, sig: &'ast Signature) {
+                self.index.add_signature(sig);
+                verus_syn::visit::visit_signature(self, sig);
+            }
+        }
+        verus_syn::visit::Visit::visit_file(&mut Collector { index: self }, file);
+    }
+
+    fn add_signature(&mut self, sig: &Signature) {
+        if !matches!(sig.mode, FnMode::Exec(_) | FnMode::Default) {
+            return;
+        }
+        let ghost: Vec<usize> = sig
+            .inputs
+            .iter()
+            .enumerate()
+            .filter(|(_, arg)| is_ghost_param(arg))
+            .map(|(i, _)| i)
+            .collect();
+        let (ret_arity, ghost_returns) = ghost_return_positions(&sig.output);
+        if ghost.is_empty() && ghost_returns.is_empty() {
+            return;
+        }
+        let name = sig.ident.to_string();
+        let entry = GhostParamPositions {
+            arity: sig.inputs.len(),
+            has_receiver: matches!(
+                sig.inputs.first().map(|arg| &arg.kind),
+                Some(FnArgKind::Receiver(_))
+            ),
+            ghost,
+            ret_arity,
+            ghost_returns,
+        };
+        if self.conflicted.contains(&name) {
+            // Already ruled out.
+        } else if self.map.get(&name).is_some_and(|seen| *seen != entry) {
+            self.map.remove(&name);
+            self.conflicted.insert(name);
+        } else {
+            self.map.insert(name, entry);
+        }
+    }
+
+    fn get(&self, name: &str) -> Option<&GhostParamPositions> {
+        self.map.get(name)
+    }
+}
+
+/// The arity of a tuple return type and the positions of its
+/// `Ghost<T>`/`Tracked<T>` elements — the ones
+/// [`unwrap_ghost_return_type`] deletes. `(0, [])` for non-tuple returns.
+fn ghost_return_positions(output: &verus_syn::ReturnType) -> (usize, Vec<usize>) {
+    let verus_syn::ReturnType::Type(_, _, _, ty) = output else {
+        return (0, Vec::new());
+    };
+    let Type::Tuple(tuple) = &**ty else {
+        return (0, Vec::new());
+    };
+    let ghost = tuple
+        .elems
+        .iter()
+        .enumerate()
+        .filter(|(_, elem)| is_ghost_wrapper_type(elem))
+        .map(|(i, _)| i)
+        .collect();
+    (tuple.elems.len(), ghost)
+}
+
+/// Scan the stripped `file` for surviving calls to functions in `removed`
+/// and produce a [`Warning::DanglingCall`] for each. A removed spec or proof
+/// fn can legitimately disappear along with all of its callers, but a call
+/// from surviving exec code means the output will not compile; matching is by
+/// unqualified name (the last path segment, or the method name), which can
+/// over-report across modules but never misses a genuine dangler.
+///
+/// Positions come from the surviving AST, whose nodes keep their original
+/// spans — the preprocessor blanks the `verus!` wrapper in place — so line
+/// and column point into the input file.
+pub(crate) fn dangling_call_warnings(file: &File, removed: &[StrippedItem]) -> Vec<Warning> {
+    let removed_fns: std::collections::HashSet<&str> = removed
+        .iter()
+        .filter(|item| {
+            matches!(item.kind, StrippedItemKind::SpecFn | StrippedItemKind::ProofFn)
+        })
+        .map(|item| item.name.as_str())
+        .collect();
+    if removed_fns.is_empty() {
+        return Vec::new();
+    }
+    let mut finder = DanglingCallFinder { removed_fns, warnings: Vec::new() };
+    verus_syn::visit::Visit::visit_file(&mut finder, file);
+    finder.warnings
+}
+
+struct DanglingCallFinder<'a> {
+    removed_fns: std::collections::HashSet<&'a str>,
+    warnings: Vec<Warning>,
+}
+
+impl<'a> DanglingCallFinder<'a> {
+    fn check(&mut self, ident: &proc_macro2::Ident) {
+        let name = ident.to_string();
+        if self.removed_fns.contains(name.as_str()) {
+            let start = ident.span().start();
+            self.warnings.push(Warning::DanglingCall {
+                fn_name: name,
+                line: start.line,
+                column: start.column + 1,
+            });
+        }
+    }
+}
+
+impl<'ast> verus_syn::visit::Visit<'ast> for DanglingCallFinder<'_> {
+    fn visit_expr_call(&mut self, call: &'ast verus_syn::ExprCall) {
+        if let Expr::Path(func) = &*call.func {
+            if let Some(last) = func.path.segments.last() {
+                self.check(&last.ident);
+            }
+        }
+        verus_syn::visit::visit_expr_call(self, call);
+    }
+
+    fn visit_expr_method_call(&mut self, call: &'ast verus_syn::ExprMethodCall) {
+        self.check(&call.method);
+        verus_syn::visit::visit_expr_method_call(self, call);
+    }
+}
+
+/// True for `Ghost(x)` / `Tracked(x)` destructuring patterns, which bind the
+/// verification-time contents of a wrapper value; the pattern sibling of
+/// [`is_ghost_wrapper_type`].
+pub(crate) fn is_ghost_wrapper_pat(pat: &Pat) -> bool {
+    let Pat::TupleStruct(tuple) = pat else {
+        return false;
+    };
+    let Some(last) = tuple.path.segments.last() else {
+        return false;
+    };
+    matches!(last.ident.to_string().as_str(), "Ghost" | "Tracked")
+}
+
+fn is_ghost_wrapper_type(ty: &Type) -> bool {
+    let Type::Path(type_path) = ty else {
+        return false;
+    };
+    let Some(last) = type_path.path.segments.last() else {
+        return false;
+    };
+    matches!(last.ident.to_string().as_str(), "Ghost" | "Tracked")
+}
+
+/// True for items that stripping has hollowed out completely and that carry no
+/// meaning on their own (an inherent impl with no remaining members, or an
+/// inline module whose body became empty). Trait impls are normally kept even
+/// when empty — `impl Marker for T {}` is meaningful — as are emptied trait
+/// definitions, which remain crate API; `remove_empty_traits` opts both in.
+fn is_empty_shell(item: &Item, remove_empty_traits: bool) -> bool {
+    match item {
+        Item::Impl(imp) => {
+            imp.items.is_empty() && (imp.trait_.is_none() || remove_empty_traits)
+        }
+        Item::Trait(trait_def) => remove_empty_traits && trait_def.items.is_empty(),
+        Item::Mod(module) => {
+            matches!(&module.content, Some((_, items)) if items.is_empty())
+        }
+        _ => false,
+    }
+}
+
+/// Render the spec clauses of `sig` as `///`-style doc attributes, one line
+/// per clause, e.g. `/// requires self.inv()`.
+fn create_spec_comment_attrs(sig: &Signature) -> Vec<Attribute> {
+    let mut lines: Vec<String> = Vec::new();
+    if let Some(requires) = &sig.spec.requires {
+        for expr in &requires.exprs.exprs {
+            lines.push(format!("requires {}", render_expr(expr)));
+        }
+    }
+    if let Some(ensures) = &sig.spec.ensures {
+        for expr in &ensures.exprs.exprs {
+            lines.push(format!("ensures {}", render_expr(expr)));
+        }
+    }
+    if let Some(decreases) = &sig.spec.decreases {
+        for expr in &decreases.decreases.exprs.exprs {
+            lines.push(format!("decreases {}", render_expr(expr)));
+        }
+    }
+    lines
+        .into_iter()
+        .map(|line| {
+            let text = format!(" {}", line);
+            verus_syn::parse_quote!(#[doc = #text])
+        })
+        .collect()
+}
+
+/// Render a removed const `ensures` clause as doc attributes, one line per
+/// clause expression, matching [`create_spec_comment_attrs`] for functions.
+fn create_ensures_comment_attrs(ensures: &verus_syn::Ensures) -> Vec<Attribute> {
+    ensures
+        .exprs
+        .exprs
+        .iter()
+        .map(|expr| {
+            let text = format!(" ensures {}", render_expr(expr));
+            verus_syn::parse_quote!(#[doc = #text])
+        })
+        .collect()
+}
+
+/// Render removed loop annotations as doc attributes on the loop itself,
+/// one line per clause expression (`/// invariant i <= n`), in the order the
+/// clauses appeared. The comment-only sibling of [`create_spec_comment_attrs`]
+/// for the clause lists [`count_loop_clauses`] tallies.
+///
+/// [`count_loop_clauses`]: StripVisitor::count_loop_clauses
+fn create_loop_annotation_attrs(removed: &[(&str, Option<Specification>)]) -> Vec<Attribute> {
+    let mut attrs: Vec<Attribute> = Vec::new();
+    for (label, spec) in removed {
+        let Some(spec) = spec else { continue };
+        for expr in &spec.exprs {
+            let text = format!(" {} {}", label, render_expr(expr));
+            attrs.push(verus_syn::parse_quote!(#[doc = #text]));
+        }
+    }
+    attrs
+}
+
+fn render_expr(expr: &Expr) -> String {
+    // Single-line rendering is fine for comments; collapse prettyplease's
+    // layout decisions.
+    let pretty = verus_prettyplease::unparse_expr(expr);
+    pretty.split_whitespace().collect::<Vec<_>>().join(" ")
+}
@@ -0,0 +1,109 @@
+//! Structured warnings about suspicious stripping outcomes.
+//!
+//! Nothing here is fatal — the output is still valid Rust — but each variant
+//! marks a place where a human should look at the result. Warnings travel in
+//! [`crate::StripResult::warnings`], are printed to stderr by the CLI, and
+//! appear in the `--json` diagnostics, where they serialize as
+//! `{ "kind", "message" }` with a stable kind per variant.
+
+/// One suspicious situation noticed while stripping.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Warning {
+    /// Every item in the file was spec or proof code; the stripped output is
+    /// an empty file. The path is carried for programmatic consumers;
+    /// `Display` leaves it to the surrounding context, which already names
+    /// the file.
+    OnlySpecCode { path: String },
+    /// A Verus-only construct survives in the output and will not compile or
+    /// resolve as plain Rust (e.g. a `Ghost<T>` return type).
+    UnknownVerusConstruct { item_name: String, suggestion: String, line: usize, column: usize },
+    /// A ghost parameter was removed from a surviving function, changing its
+    /// arity for every caller.
+    GhostParamDropped { fn_name: String, param_name: String, line: usize, column: usize },
+    /// An `open` spec function was removed. Closed spec fns are opaque
+    /// implementation detail and disappear silently, but an open one was
+    /// published specification surface.
+    SpecFnDropped { fn_name: String, line: usize, column: usize },
+    /// Surviving exec code still calls a spec or proof function the strip
+    /// pass removed; the output looks like plain Rust but will not compile
+    /// until the caller is rewritten by hand.
+    DanglingCall { fn_name: String, line: usize, column: usize },
+}
+
+impl Warning {
+    /// Stable machine-readable category, e.g. `ghost-param-dropped`.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            Warning::OnlySpecCode { .. } => "only-spec-code",
+            Warning::UnknownVerusConstruct { .. } => "unknown-verus-construct",
+            Warning::GhostParamDropped { .. } => "ghost-param-dropped",
+            Warning::SpecFnDropped { .. } => "spec-fn-dropped",
+            Warning::DanglingCall { .. } => "dangling-call",
+        }
+    }
+
+    /// 1-based source line of the offending construct, when the warning is
+    /// about one spot rather than the whole file.
+    pub fn line(&self) -> Option<usize> {
+        match self {
+            Warning::OnlySpecCode { .. } => None,
+            Warning::UnknownVerusConstruct { line, .. }
+            | Warning::GhostParamDropped { line, .. }
+            | Warning::SpecFnDropped { line, .. }
+            | Warning::DanglingCall { line, .. } => Some(*line),
+        }
+    }
+
+    /// 1-based source column, paired with [`Warning::line`].
+    pub fn column(&self) -> Option<usize> {
+        match self {
+            Warning::OnlySpecCode { .. } => None,
+            Warning::UnknownVerusConstruct { column, .. }
+            | Warning::GhostParamDropped { column, .. }
+            | Warning::SpecFnDropped { column, .. }
+            | Warning::DanglingCall { column, .. } => Some(*column),
+        }
+    }
+}
+
+impl std::fmt::Display for Warning {
+    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
+        match self {
+            Warning::OnlySpecCode { path: _ } => {
+                write!(f, "nothing but spec/proof code; the stripped file is empty")
+            }
+            Warning::UnknownVerusConstruct { item_name, suggestion, .. } => {
+                write!(f, "{}: a Verus-only construct survives stripping; {}", item_name, suggestion)
+            }
+            Warning::GhostParamDropped { fn_name, param_name, .. } => {
+                write!(
+                    f,
+                    "{}: dropped ghost parameter `{}`, changing the function's arity",
+                    fn_name, param_name
+                )
+            }
+            Warning::SpecFnDropped { fn_name, .. } => {
+                write!(f, "removed open spec fn `{}` from the published specification surface", fn_name)
+            }
+            Warning::DanglingCall { fn_name, .. } => {
+                write!(
+                    f,
+                    "surviving code calls `{}`, which was removed with the spec/proof code",
+                    fn_name
+                )
+            }
+        }
+    }
+}
+
+// Hand-written so the `--json` schema stays `{ "kind", "message" }` however
+// the variants' fields evolve.
+impl serde::Serialize for Warning {
+    fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        use serde::ser::SerializeStruct;
+        let mut object = serializer.serialize_struct("Warning", 2)?;
+        object.serialize_field("kind", self.kind())?;
+        object.serialize_field("message", &self.to_string())?;
+        object.end()
+    }
+}
@@ -0,0 +1,54 @@
+use vstrip::api_diff::{diff_public_api, ApiChangeKind};
+use vstrip::{preprocess, strip_source, Config, StripError};
+
+/// Parse the fixture before and after stripping and diff the API surfaces.
+fn diff_fixture(source: &str) -> Vec<(String, ApiChangeKind)> {
+    let before = verus_syn::parse_file(&preprocess::unwrap_verus_macros(source)).unwrap();
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    let after = verus_syn::parse_file(&stripped).unwrap();
+    diff_public_api(&before, &after)
+        .into_iter()
+        .map(|change| (change.path, change.kind))
+        .collect()
+}
+
+#[test]
+fn ghost_params_change_arity() {
+    let source = include_str!("fixtures/ghost_params.rs");
+    let changes = diff_fixture(source);
+    assert_eq!(
+        changes,
+        [
+            ("incr".to_string(), ApiChangeKind::ChangedSignature),
+            ("plain".to_string(), ApiChangeKind::Unchanged),
+        ]
+    );
+}
+
+#[test]
+fn ghost_fields_are_reported_per_field() {
+    let source = include_str!("fixtures/ghost_fields.rs");
+    let changes = diff_fixture(source);
+    assert_eq!(changes, [("Account::history".to_string(), ApiChangeKind::RemovedField)]);
+}
+
+#[test]
+fn fail_on_api_change_rejects_changed_signatures() {
+    let source = include_str!("fixtures/ghost_params.rs");
+    let config = Config { fail_on_api_change: true, ..Config::default() };
+    match strip_source(source, &config) {
+        Err(StripError::ApiChanged(changes)) => {
+            assert_eq!(changes.len(), 1);
+            assert_eq!(changes[0].path, "incr");
+        }
+        other => panic!("expected ApiChanged, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn fail_on_api_change_accepts_removed_spec_items() {
+    // Spec functions always disappear; that alone is not an API "change".
+    let source = "verus! {\n\npub open spec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+    let config = Config { fail_on_api_change: true, ..Config::default() };
+    strip_source(source, &config).unwrap();
+}
@@ -0,0 +1,66 @@
+use vstrip::config::AssertStrategy;
+use vstrip::{strip_source, Config};
+
+const SOURCE: &str = r#"
+verus! {
+
+fn bump(x: u32) -> u32
+    requires
+        x < 100,
+{
+    assert(x < 100);
+    proof {
+        assert((x as int) < 100int);
+    }
+    x + 1
+}
+
+} // verus!
+"#;
+
+#[test]
+fn asserts_are_removed_by_default() {
+    let stripped = strip_source(SOURCE, &Config::default()).unwrap();
+    assert!(!stripped.contains("assert"), "{}", stripped);
+}
+
+#[test]
+fn debug_assert_strategy_converts_exec_asserts() {
+    let config =
+        Config { assert_strategy: AssertStrategy::DebugAssert, ..Config::default() };
+    let stripped = strip_source(SOURCE, &config).unwrap();
+    assert!(stripped.contains("debug_assert!(x < 100);"), "{}", stripped);
+    // Asserts inside proof blocks go down with the block; only exec-position
+    // ones are converted.
+    assert!(!stripped.contains("as int"), "{}", stripped);
+}
+
+#[test]
+fn keep_strategy_renders_plain_asserts() {
+    let config = Config { assert_strategy: AssertStrategy::Keep, ..Config::default() };
+    let stripped = strip_source(SOURCE, &config).unwrap();
+    assert!(stripped.contains("assert!(x < 100);"), "{}", stripped);
+    assert!(!stripped.contains("debug_assert"), "{}", stripped);
+}
+
+#[test]
+fn by_proofs_are_dropped_from_converted_asserts() {
+    let source = r#"
+verus! {
+
+fn scale(x: u32) -> u32
+    requires
+        x < 100,
+{
+    assert(x * 2 < 200) by (nonlinear_arith);
+    x * 2
+}
+
+} // verus!
+"#;
+    let config =
+        Config { assert_strategy: AssertStrategy::DebugAssert, ..Config::default() };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(stripped.contains("debug_assert!(x * 2 < 200);"), "{}", stripped);
+    assert!(!stripped.contains("by"), "{}", stripped);
+}
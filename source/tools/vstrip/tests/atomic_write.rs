@@ -0,0 +1,54 @@
+//! In-place rewrites go through a temporary sibling file and a rename, so a
+//! failed write can never truncate the only copy of the annotated source.
+
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+
+use vstrip::{process, Config};
+
+const SOURCE: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+fn temp_residue(dir: &PathBuf) -> Vec<String> {
+    fs::read_dir(dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.contains("vstrip-tmp"))
+        .collect()
+}
+
+#[test]
+fn successful_rewrites_leave_no_temporary_behind() {
+    let dir = scratch("atomic-clean");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    process(&Config { input: path.clone(), in_place: true, ..Config::default() }).unwrap();
+
+    assert!(!fs::read_to_string(&path).unwrap().contains("spec fn"));
+    assert_eq!(temp_residue(&dir), Vec::<String>::new());
+}
+
+#[test]
+fn failed_writes_leave_the_original_untouched() {
+    let dir = scratch("atomic-blocked");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+    // Occupy the deterministic temporary name with a directory, so creating
+    // the temporary file fails whatever the test's privileges (a read-only
+    // parent does not stop root). The original must survive the failure
+    // byte for byte.
+    let blocker = dir.join(format!("lib.rs.vstrip-tmp.{}", std::process::id()));
+    fs::create_dir(&blocker).unwrap();
+
+    let result = process(&Config { input: path.clone(), in_place: true, ..Config::default() });
+
+    result.unwrap_err();
+    assert_eq!(fs::read_to_string(&path).unwrap(), SOURCE);
+    // Still an empty directory: nothing was written through the blocked
+    // name, and once it is gone no other temporary remains either.
+    fs::remove_dir(&blocker).unwrap();
+    assert_eq!(temp_residue(&dir), Vec::<String>::new());
+}
@@ -0,0 +1,81 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+
+use vstrip::{process, Config};
+
+const SOURCE: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+fn in_place_with_backup(input: PathBuf, suffix: &str) -> Config {
+    Config {
+        input,
+        in_place: true,
+        backup: Some(suffix.to_string()),
+        ..Config::default()
+    }
+}
+
+#[test]
+fn in_place_with_backup_keeps_the_original_alongside() {
+    let dir = scratch("backup-keeps");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    process(&in_place_with_backup(path.clone(), ".orig")).unwrap();
+
+    let stripped = fs::read_to_string(&path).unwrap();
+    assert!(!stripped.contains("spec fn"));
+    assert!(stripped.contains("pub fn f()"));
+    assert_eq!(fs::read_to_string(dir.join("lib.rs.orig")).unwrap(), SOURCE);
+}
+
+#[test]
+fn existing_backups_are_refused_without_force() {
+    let dir = scratch("backup-refuse");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+    fs::write(dir.join("lib.rs.orig"), "earlier backup\n").unwrap();
+
+    let err = process(&in_place_with_backup(path.clone(), ".orig")).unwrap_err();
+    assert!(err.to_string().contains("--force-backup"));
+    // Refusal happens before any write: both files are untouched.
+    assert_eq!(fs::read_to_string(&path).unwrap(), SOURCE);
+    assert_eq!(fs::read_to_string(dir.join("lib.rs.orig")).unwrap(), "earlier backup\n");
+}
+
+#[test]
+fn force_backup_overwrites_the_old_copy() {
+    let dir = scratch("backup-force");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+    fs::write(dir.join("lib.rs.bak"), "earlier backup\n").unwrap();
+
+    let config = Config {
+        force_backup: true,
+        ..in_place_with_backup(path.clone(), ".bak")
+    };
+    process(&config).unwrap();
+    assert!(!fs::read_to_string(&path).unwrap().contains("spec fn"));
+    assert_eq!(fs::read_to_string(dir.join("lib.rs.bak")).unwrap(), SOURCE);
+}
+
+#[test]
+fn backups_leave_no_temporary_behind() {
+    let dir = scratch("backup-no-residue");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    process(&in_place_with_backup(path.clone(), ".bak")).unwrap();
+
+    // The backup is copied to a temporary sibling and renamed into place,
+    // so the directory holds only the rewrite and the finished backup.
+    assert_eq!(fs::read_to_string(dir.join("lib.rs.bak")).unwrap(), SOURCE);
+    let residue: Vec<String> = fs::read_dir(&dir)
+        .unwrap()
+        .map(|entry| entry.unwrap().file_name().to_string_lossy().into_owned())
+        .filter(|name| name.contains("vstrip-tmp"))
+        .collect();
+    assert_eq!(residue, Vec::<String>::new());
+}
@@ -0,0 +1,52 @@
+//! Coarse timing checks, ignored by default. Run with:
+//!
+//! ```
+//! cargo test --release -p vstrip -- --ignored --nocapture bench
+//! ```
+
+use std::fmt::Write;
+use std::time::Instant;
+
+use vstrip::{strip_source, Config};
+
+/// Generate an impl with `n` proof methods (each with a sizeable body) and one
+/// exec method, wrapped in a `verus!` block.
+fn synthetic_impl(n: usize) -> String {
+    let mut source = String::from(
+        "use vstd::prelude::*;\n\
+         verus! {\n\
+         pub struct S { pub x: u64 }\n\
+         impl S {\n",
+    );
+    for i in 0..n {
+        write!(
+            source,
+            "proof fn lemma_{i}(a: int, b: int)\n    requires a <= b,\n    ensures a <= b + {i},\n{{\n",
+        )
+        .unwrap();
+        for j in 0..20 {
+            writeln!(source, "    assert(a <= b + {j});").unwrap();
+        }
+        source.push_str("}\n");
+    }
+    source.push_str("pub fn get(&self) -> u64 { self.x }\n}\n} // verus!\n");
+    source
+}
+
+/// Stripping an impl that is mostly proof methods should spend its time on
+/// parsing, not on visiting bodies that are about to be deleted.
+#[test]
+#[ignore]
+fn bench_impl_with_many_proof_methods() {
+    let source = synthetic_impl(500);
+    let config = Config::default();
+    // Warm up (parse caches, allocator).
+    strip_source(&source, &config).unwrap();
+    let start = Instant::now();
+    let iterations = 20;
+    for _ in 0..iterations {
+        strip_source(&source, &config).unwrap();
+    }
+    let elapsed = start.elapsed();
+    println!("stripped {} proof methods x{}: {:?}/iter", 500, iterations, elapsed / iterations);
+}
@@ -0,0 +1,58 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+
+use vstrip::cache::IncrementalCache;
+
+#[test]
+fn cache_round_trips_and_detects_changes() {
+    let dir = scratch("cache-test");
+    let source = dir.join("input.rs");
+    let cache_path = dir.join("cache.json");
+    fs::write(&source, "fn f() {}\n").unwrap();
+
+    let mut cache = IncrementalCache::default();
+    assert!(!cache.is_current(&source));
+    cache.update(&source, "fn f() {}\n");
+    assert!(cache.is_current(&source));
+
+    // Entries survive a save/load round trip.
+    cache.save(&cache_path).unwrap();
+    let reloaded = IncrementalCache::load(&cache_path).unwrap();
+    assert!(reloaded.is_current(&source));
+
+    // Changing the contents invalidates the entry even if the mtime is
+    // restored by the filesystem's timestamp granularity.
+    fs::write(&source, "fn g() {}\n").unwrap();
+    assert!(!reloaded.is_current(&source));
+
+    // A missing cache file loads as empty.
+    let empty = IncrementalCache::load(&dir.join("absent.json")).unwrap();
+    assert!(!empty.is_current(&source));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn cache_written_by_another_version_is_discarded() {
+    let dir = scratch("cache-version");
+    let source = dir.join("input.rs");
+    let cache_path = dir.join("cache.json");
+    fs::write(&source, "fn f() {}\n").unwrap();
+
+    let mut cache = IncrementalCache::default();
+    cache.update(&source, "fn f() {}\n");
+    cache.save(&cache_path).unwrap();
+
+    // Rewrite the version marker as if an older vstrip had written the file;
+    // loading must start over rather than trust its entries.
+    let text = fs::read_to_string(&cache_path).unwrap();
+    let doctored = text.replace(env!("CARGO_PKG_VERSION"), "0.0.0-previous");
+    assert_ne!(text, doctored, "fixture must contain the version marker");
+    fs::write(&cache_path, doctored).unwrap();
+    let reloaded = IncrementalCache::load(&cache_path).unwrap();
+    assert!(!reloaded.is_current(&source));
+
+    fs::remove_dir_all(&dir).ok();
+}
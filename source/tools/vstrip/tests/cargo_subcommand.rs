@@ -0,0 +1,89 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+fn write(path: &Path, content: &str) {
+    fs::create_dir_all(path.parent().unwrap()).unwrap();
+    fs::write(path, content).unwrap();
+}
+
+fn make_workspace(root: &Path) {
+    write(&root.join("Cargo.toml"), "[workspace]\nmembers = [\"a\", \"b\"]\n");
+    for name in ["a", "b"] {
+        write(
+            &root.join(name).join("Cargo.toml"),
+            &format!("[package]\nname = \"{}\"\nversion = \"0.1.0\"\n", name),
+        );
+        write(
+            &root.join(name).join("src/lib.rs"),
+            "use vstd::prelude::*;\n\nverus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n",
+        );
+    }
+}
+
+#[test]
+fn cargo_subcommand_selects_package_and_writes_to_target_dir() {
+    let root = scratch("cargo-test");
+    make_workspace(&root);
+
+    // Fabricate the argv cargo uses for external subcommands (extra "vstrip").
+    let status = Command::new(env!("CARGO_BIN_EXE_cargo-vstrip"))
+        .args(["vstrip", "-p", "a"])
+        .current_dir(&root)
+        .status()
+        .unwrap();
+    assert!(status.success());
+
+    // Output defaults to <workspace>/target/vstrip/<package>/, mirroring the
+    // package layout; only the selected package is processed.
+    let out = root.join("target/vstrip/a/src/lib.rs");
+    let stripped = fs::read_to_string(&out).unwrap();
+    assert!(!stripped.contains("spec fn"));
+    assert!(stripped.contains("pub fn f()"));
+    assert!(!root.join("target/vstrip/b").exists());
+
+    // Unselected sources are untouched.
+    let original = fs::read_to_string(root.join("a/src/lib.rs")).unwrap();
+    assert!(original.contains("verus!"));
+
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn cargo_subcommand_accepts_the_shared_vstrip_flags() {
+    let root = scratch("cargo-flags");
+    make_workspace(&root);
+
+    // --keep-spec-fns is a vstrip flag; it reaches the shared Config through
+    // the flattened StripArgs rather than a reimplementation.
+    let status = Command::new(env!("CARGO_BIN_EXE_cargo-vstrip"))
+        .args(["vstrip", "-p", "a", "--keep-spec-fns"])
+        .current_dir(&root)
+        .status()
+        .unwrap();
+    assert!(status.success());
+    let stripped = fs::read_to_string(root.join("target/vstrip/a/src/lib.rs")).unwrap();
+    assert!(stripped.contains("spec fn"), "{}", stripped);
+
+    fs::remove_dir_all(&root).ok();
+}
+
+#[test]
+fn cargo_subcommand_rejects_unknown_package() {
+    let root = scratch("cargo-unknown");
+    make_workspace(&root);
+
+    let output = Command::new(env!("CARGO_BIN_EXE_cargo-vstrip"))
+        .args(["vstrip", "-p", "nonexistent"])
+        .current_dir(&root)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("nonexistent"));
+
+    fs::remove_dir_all(&root).ok();
+}
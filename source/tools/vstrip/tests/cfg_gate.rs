@@ -0,0 +1,46 @@
+use vstrip::{strip_source_detailed, Config, ConfigBuilder};
+
+const FIXTURE: &str = include_str!("fixtures/cfg_gate.rs");
+
+#[test]
+fn cfg_gate_retains_proof_code_behind_the_feature() {
+    let config = Config { cfg_gate: Some("verus".to_string()), ..Config::default() };
+    let result = strip_source_detailed(FIXTURE, &config).unwrap();
+    let gate = "#[cfg(feature = \"verus\")]";
+    assert!(result.output.contains(gate), "{}", result.output);
+    // Spec and proof items survive under the gate, with their mode keywords
+    // removed so the file parses without Verus.
+    assert!(result.output.contains("fn cap_spec"), "{}", result.output);
+    assert!(result.output.contains("fn lemma_cap"), "{}", result.output);
+    assert!(!result.output.contains("spec fn"));
+    assert!(!result.output.contains("proof fn"));
+    // The proof block becomes a gated plain block with its assert intact;
+    // the bare assert outside any proof block is still stripped.
+    assert!(result.output.contains("assert(old(m).used < 1000)"), "{}", result.output);
+    assert!(!result.output.contains("amount < 1000"), "{}", result.output);
+    // The ghost field survives gated, its marker gone; signature clauses
+    // become comments; the vstd import stays for the gated code to use.
+    assert!(result.output.contains("budget: int"), "{}", result.output);
+    assert!(!result.output.contains("ghost "));
+    assert!(result.output.contains("requires"), "{}", result.output);
+    assert!(result.output.contains("use vstd::prelude::*;"), "{}", result.output);
+    // Nothing gated is reported as removed; only the bare assert was.
+    assert!(result.stripped_items.is_empty());
+    assert_eq!(result.stats.spec_fns, 0);
+    assert_eq!(result.stats.proof_fns, 0);
+    assert_eq!(result.stats.proof_blocks, 0);
+    assert_eq!(result.stats.ghost_fields, 0);
+    assert_eq!(result.stats.assert_assume_exprs, 1);
+    // The whole point of the mode: the gated output is valid plain Rust.
+    syn::parse_file(&result.output).unwrap();
+}
+
+#[test]
+fn cfg_gate_conflicts_with_the_keep_selections() {
+    let err = ConfigBuilder::new("src/lib.rs")
+        .cfg_gate("verus")
+        .keep_proof_blocks()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("cfg_gate"), "{}", err);
+}
@@ -0,0 +1,79 @@
+use vstrip::{strip_source, Config};
+
+#[test]
+fn imports_orphaned_by_stripping_are_removed() {
+    let source = r#"
+use std::io::Write;
+use crate::spec_defs::max_spec;
+
+verus! {
+
+spec fn bound(x: u32) -> bool {
+    x < max_spec()
+}
+
+fn emit(out: &mut Vec<u8>, x: u32) -> usize
+    requires
+        bound(x),
+{
+    out.write_all(&[1]).unwrap();
+    1
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // `max_spec` was only called from the removed spec fn.
+    assert!(!stripped.contains("max_spec"), "{}", stripped);
+    // `Write` is named nowhere in the original either (only its method is);
+    // imports the author left textually unused are not ours to judge.
+    assert!(stripped.contains("use std::io::Write;"), "{}", stripped);
+}
+
+#[test]
+fn group_imports_are_pruned_name_by_name() {
+    let source = r#"
+use crate::defs::{ghost_bound, LIMIT};
+
+verus! {
+
+proof fn check(x: u32)
+    requires
+        ghost_bound(x),
+{
+}
+
+fn clamp(x: u32) -> u32 {
+    if x > LIMIT { LIMIT } else { x }
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("ghost_bound"), "{}", stripped);
+    // The sole survivor loses the braces along with its sibling.
+    assert!(stripped.contains("use crate::defs::LIMIT;"), "{}", stripped);
+}
+
+#[test]
+fn glob_imports_need_a_known_spec_module() {
+    let source = r#"
+use crate::spec_defs::*;
+
+verus! {
+
+fn f() -> u32 {
+    1
+}
+
+} // verus!
+"#;
+    // A glob hides what it provides, so by default it survives.
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(stripped.contains("use crate::spec_defs::*;"), "{}", stripped);
+
+    let config =
+        Config { known_spec_modules: vec!["spec_defs".to_string()], ..Config::default() };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(!stripped.contains("spec_defs"), "{}", stripped);
+}
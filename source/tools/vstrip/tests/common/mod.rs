@@ -0,0 +1,18 @@
+//! Helpers shared by the integration tests.
+//!
+//! Test files that need disk fixtures declare `mod common;` and pull in
+//! [`scratch`]. The per-file tree builders (`scratch_tree`, `scratch_crate`,
+//! ...) stay local to their suites — every suite lays out a different
+//! fixture — but build on the same empty directory from here.
+
+use std::fs;
+use std::path::PathBuf;
+
+/// A fresh scratch directory under the system temp dir, unique per test
+/// name and process; whatever a previous run left behind is removed first.
+pub fn scratch(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("vstrip-{}-{}", name, std::process::id()));
+    fs::remove_dir_all(&dir).ok();
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
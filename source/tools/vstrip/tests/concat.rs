@@ -0,0 +1,96 @@
+//! Recursive output to stdout: per-file markers, lexicographic order, and
+//! `--as-modules` wrapping the concatenation into one reviewable file.
+
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::process::Command;
+
+fn source(name: &str) -> String {
+    format!("verus! {{\n\nspec fn s_{name}() -> int {{ 1 }}\n\npub fn {name}() {{}}\n\n}} // verus!\n")
+}
+
+#[test]
+fn recursive_stdout_gets_markers_in_lexicographic_order() {
+    let dir = scratch("concat-markers");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("b.rs"), source("b")).unwrap();
+    fs::write(dir.join("a.rs"), source("a")).unwrap();
+    fs::write(dir.join("sub/c.rs"), source("c")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg("--recursive")
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    let markers: Vec<&str> =
+        stdout.lines().filter(|line| line.starts_with("// ====")).collect();
+    assert_eq!(
+        markers,
+        vec!["// ==== a.rs ====", "// ==== b.rs ====", "// ==== sub/c.rs ===="],
+        "{}",
+        stdout
+    );
+    // Marker lines are comments, so the whole concatenation stays Rust.
+    syn::parse_file(&stdout).unwrap();
+}
+
+#[test]
+fn repeated_runs_produce_byte_identical_stdout() {
+    let dir = scratch("concat-deterministic");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("m.rs"), source("m")).unwrap();
+    fs::write(dir.join("a.rs"), source("a")).unwrap();
+    fs::write(dir.join("z.rs"), source("z")).unwrap();
+    fs::write(dir.join("sub/n.rs"), source("n")).unwrap();
+
+    let run = || {
+        let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+            .arg("--recursive")
+            .arg(&dir)
+            .output()
+            .unwrap();
+        assert!(output.status.success());
+        output.stdout
+    };
+    // Lexicographic processing order is promised independent of what order
+    // the filesystem lists the directory in, so concatenated artifacts are
+    // reproducible.
+    assert_eq!(run(), run());
+}
+
+#[test]
+fn as_modules_wraps_each_file_by_its_path() {
+    let dir = scratch("concat-modules");
+    fs::create_dir_all(dir.join("math")).unwrap();
+    fs::write(dir.join("lib.rs"), source("top")).unwrap();
+    fs::write(dir.join("math/proofs.rs"), source("deep")).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--recursive", "--as-modules"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    let stdout = String::from_utf8_lossy(&output.stdout);
+    // The crate root contributes no module of its own; nested files nest.
+    assert!(stdout.contains("pub fn top()"), "{}", stdout);
+    assert!(stdout.contains("pub mod math {"), "{}", stdout);
+    assert!(stdout.contains("pub mod proofs {"), "{}", stdout);
+    assert!(!stdout.contains("pub mod lib"), "{}", stdout);
+    syn::parse_file(&stdout).unwrap();
+}
+
+#[test]
+fn as_modules_needs_a_stdout_mode() {
+    let err = vstrip::ConfigBuilder::new("src")
+        .recursive()
+        .in_place()
+        .as_modules()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("as_modules"), "{}", err);
+}
@@ -0,0 +1,193 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use vstrip::config::{
+    discover_project_config, merge_config, ApiDiffFormat, EmptyBodyPolicy, PartialConfig,
+};
+use vstrip::Config;
+
+#[test]
+fn overlay_fields_win_and_unset_fields_inherit() {
+    let base = Config {
+        input: PathBuf::from("src"),
+        recursive: true,
+        empty_body: EmptyBodyPolicy::Todo,
+        ..Config::default()
+    };
+    let overlay = PartialConfig {
+        empty_body: Some(EmptyBodyPolicy::Unreachable),
+        check: Some(true),
+        ..PartialConfig::default()
+    };
+    let merged = merge_config(&base, &overlay);
+    assert_eq!(merged.empty_body, EmptyBodyPolicy::Unreachable);
+    assert!(merged.check);
+    // Unset fields inherit from the base.
+    assert!(merged.recursive);
+    assert_eq!(merged.input, PathBuf::from("src"));
+    // The method form is the same operation.
+    assert_eq!(base.merge(&overlay).empty_body, EmptyBodyPolicy::Unreachable);
+}
+
+#[test]
+fn layers_merge_with_later_layers_winning() {
+    let project = PartialConfig {
+        spec_as_comments: Some(true),
+        empty_body: Some(EmptyBodyPolicy::Todo),
+        ..PartialConfig::default()
+    };
+    let command_line = PartialConfig {
+        empty_body: Some(EmptyBodyPolicy::Error),
+        api_diff: Some(ApiDiffFormat::Json),
+        ..PartialConfig::default()
+    };
+    let effective = project.merge(&command_line).apply_to(&Config::default());
+    assert!(effective.spec_as_comments);
+    assert_eq!(effective.empty_body, EmptyBodyPolicy::Error);
+    assert_eq!(effective.api_diff, Some(ApiDiffFormat::Json));
+}
+
+#[test]
+fn builder_setters_populate_the_config() {
+    let config = vstrip::ConfigBuilder::new("src")
+        .recursive()
+        .in_place()
+        .empty_body(EmptyBodyPolicy::Todo)
+        .extra_verus_derive("MyGhostDerive")
+        .verbosity(2)
+        .build()
+        .unwrap();
+    assert_eq!(config.input, PathBuf::from("src"));
+    assert!(config.recursive);
+    assert!(config.in_place);
+    assert_eq!(config.empty_body, EmptyBodyPolicy::Todo);
+    assert_eq!(config.extra_verus_derives, vec!["MyGhostDerive".to_string()]);
+    assert_eq!(config.verbosity, 2);
+}
+
+#[test]
+fn builder_default_matches_config_default() {
+    let built = vstrip::ConfigBuilder::default().input("lib.rs").build().unwrap();
+    let plain = Config { input: PathBuf::from("lib.rs"), ..Config::default() };
+    assert_eq!(built.in_place, plain.in_place);
+    assert_eq!(built.recursive, plain.recursive);
+    assert_eq!(built.empty_body, plain.empty_body);
+    assert_eq!(built.output, plain.output);
+    assert_eq!(built.follow_includes, plain.follow_includes);
+}
+
+#[test]
+fn toml_text_parses_into_a_layer() {
+    let layer = PartialConfig::from_toml(
+        "spec_as_comments = true\n\
+         recursive = true\n\
+         empty_body = \"todo\"\n\
+         exclude_globs = [\"target/**\"]\n",
+    )
+    .unwrap();
+    assert_eq!(layer.spec_as_comments, Some(true));
+    assert_eq!(layer.recursive, Some(true));
+    assert_eq!(layer.empty_body, Some(EmptyBodyPolicy::Todo));
+    assert_eq!(layer.exclude_globs, Some(vec!["target/**".to_string()]));
+    // Omitted keys stay unset, so they inherit when layered.
+    assert_eq!(layer.check, None);
+}
+
+#[test]
+fn toml_rejects_unknown_keys_and_bad_values() {
+    let err = PartialConfig::from_toml("spec_as_commets = true\n").unwrap_err();
+    assert!(err.to_string().contains("spec_as_commets"), "{}", err);
+    let err = PartialConfig::from_toml("empty_body = \"panic\"\n").unwrap_err();
+    assert!(err.to_string().contains("empty-body policy"), "{}", err);
+}
+
+#[test]
+fn from_toml_file_resolves_against_the_defaults() {
+    let dir = scratch("config-toml-file");
+    let path = dir.join("vstrip.toml");
+    fs::write(&path, "input = \"src\"\nrecursive = true\n").unwrap();
+    let config = Config::from_toml_file(&path).unwrap();
+    assert_eq!(config.input, PathBuf::from("src"));
+    assert!(config.recursive);
+    assert_eq!(config.empty_body, EmptyBodyPolicy::Error);
+}
+
+#[test]
+fn project_config_is_discovered_in_an_ancestor() {
+    let root = scratch("config-discover");
+    fs::write(root.join(".vstrip.toml"), "keep_empty_items = true\n").unwrap();
+    fs::create_dir_all(root.join("src/deep")).unwrap();
+    fs::write(root.join("src/deep/lib.rs"), "fn f() {}\n").unwrap();
+
+    let layer = discover_project_config(&root.join("src/deep/lib.rs")).unwrap().unwrap();
+    assert_eq!(layer.keep_empty_items, Some(true));
+    // Parse failures name the file they came from.
+    fs::write(root.join(".vstrip.toml"), "keep_empty_items = \"yes\"\n").unwrap();
+    let err = discover_project_config(&root.join("src/deep")).unwrap_err();
+    assert!(err.to_string().contains(".vstrip.toml"), "{}", err);
+}
+
+#[test]
+fn cli_flags_override_the_discovered_project_file() {
+    let root = scratch("config-cli-override");
+    fs::write(root.join(".vstrip.toml"), "empty_body = \"todo\"\n").unwrap();
+    // A value-returning function whose whole body is proof code: the
+    // empty-body policy decides between todo!() and refusing.
+    let source = "verus! {\n\nfn always_true() -> (b: bool)\n    ensures b,\n{\n    proof {\n        assert(true);\n    }\n}\n\n} // verus!\n";
+    fs::write(root.join("lib.rs"), source).unwrap();
+
+    // Without a flag the project file's policy applies.
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg(root.join("lib.rs"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("todo!()"));
+
+    // An explicit flag wins over the file.
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--empty-body", "error"])
+        .arg(root.join("lib.rs"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("always_true"));
+}
+
+#[test]
+fn validate_checks_directly_built_configs() {
+    let config = Config {
+        input: PathBuf::from("src/lib.rs"),
+        in_place: true,
+        output: Some(PathBuf::from("out.rs")),
+        ..Config::default()
+    };
+    assert!(matches!(config.validate(), Err(vstrip::StripError::ConfigError(_))));
+    // `process` runs the same checks at entry, so a hand-assembled invalid
+    // config fails before any file is touched.
+    let err = vstrip::process(&config).unwrap_err();
+    assert!(err.to_string().contains("mutually exclusive"), "{}", err);
+
+    let ok = Config { input: PathBuf::from("src/lib.rs"), ..Config::default() };
+    ok.validate().unwrap();
+}
+
+#[test]
+fn builder_rejects_contradictory_combinations() {
+    assert!(matches!(
+        vstrip::ConfigBuilder::default().build(),
+        Err(vstrip::StripError::ConfigError(_))
+    ));
+    assert!(matches!(
+        vstrip::ConfigBuilder::new("src").in_place().output("out.rs").build(),
+        Err(vstrip::StripError::ConfigError(_))
+    ));
+    assert!(matches!(
+        vstrip::ConfigBuilder::new("src").follow_includes().output("out.rs").build(),
+        Err(vstrip::StripError::ConfigError(_))
+    ));
+}
@@ -0,0 +1,103 @@
+//! `--count`: spec-density metrics instead of stripping.
+
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use vstrip::count::count_source;
+
+const FIXTURE: &str = r#"verus! {
+
+spec fn square(x: int) -> int {
+    x * x
+}
+
+proof fn square_nonneg(x: int)
+    ensures square(x) >= 0,
+{
+}
+
+struct Counter {
+    count: u64,
+    ghost history: Seq<int>,
+}
+
+fn bump(c: &mut Counter) {
+    let ghost old_count = c.count;
+    proof {
+        assert(c.count >= 0);
+    }
+    c.count = c.count + 1;
+}
+
+} // verus!
+"#;
+
+#[test]
+fn every_spec_construct_class_is_counted_by_line_extent() {
+    let counts = count_source(FIXTURE, Path::new("fixture.rs")).unwrap();
+    assert_eq!(counts.spec_fns, 1);
+    assert_eq!(counts.proof_fns, 1);
+    // The assert inside the proof block is part of the block, not a second
+    // construct.
+    assert_eq!(counts.proof_blocks, 1);
+    // The ghost field and the ghost local.
+    assert_eq!(counts.ghost_decls, 2);
+    assert_eq!(counts.exec_fns, 1);
+    // spec fn: 3 lines, proof fn: 4, proof block: 3, ghost field and ghost
+    // local: 1 each.
+    assert_eq!(counts.spec_lines, 12);
+    assert_eq!(counts.exec_lines, FIXTURE.lines().count() - counts.spec_lines);
+}
+
+#[test]
+fn count_runs_report_per_file_and_in_total() {
+    let dir = scratch("count-report");
+    fs::write(dir.join("a.rs"), FIXTURE).unwrap();
+    fs::write(dir.join("b.rs"), "pub fn plain() -> u32 { 7 }\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--count", "--recursive"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("a.rs"), "{}", stdout);
+    assert!(stdout.contains("b.rs"), "{}", stdout);
+    assert!(stdout.contains("total"), "{}", stdout);
+    assert!(stdout.contains("spec-to-exec line ratio"), "{}", stdout);
+    // Nothing was stripped or written.
+    assert_eq!(fs::read_to_string(dir.join("a.rs")).unwrap(), FIXTURE);
+}
+
+#[test]
+fn message_format_json_turns_the_report_into_an_array() {
+    let dir = scratch("count-json");
+    fs::write(dir.join("a.rs"), FIXTURE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--count", "--recursive", "--message-format=json"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let report: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let files = report.as_array().unwrap();
+    assert_eq!(files.len(), 1);
+    assert_eq!(files[0]["counts"]["spec_fns"], 1);
+    assert_eq!(files[0]["counts"]["spec_lines"], 12);
+}
+
+#[test]
+fn count_excludes_the_stripping_modes() {
+    let err = vstrip::ConfigBuilder::new("src/lib.rs")
+        .count()
+        .check()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("count"), "{}", err);
+}
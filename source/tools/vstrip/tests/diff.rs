@@ -0,0 +1,114 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::Path;
+use std::process::Command;
+
+use vstrip::diff::unified_diff;
+use vstrip::{strip_source, Config};
+
+const SOURCE: &str = "use vstd::prelude::*;\n\nverus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+#[test]
+fn identical_texts_produce_no_diff() {
+    assert!(unified_diff("fn f() {}\n", "fn f() {}\n", Path::new("a.rs")).is_none());
+}
+
+#[test]
+fn diffs_carry_headers_hunks_and_signed_lines() {
+    let original = "fn a() {}\nfn b() {}\nfn c() {}\nfn d() {}\n";
+    let stripped = "fn a() {}\nfn b() {}\nfn c2() {}\nfn d() {}\n";
+    let rendered = unified_diff(original, stripped, Path::new("src/lib.rs")).unwrap();
+    assert!(rendered.starts_with("--- src/lib.rs\n+++ src/lib.rs (stripped)\n"));
+    assert!(rendered.contains("@@ -1,4 +1,4 @@"));
+    assert!(rendered.contains("-fn c() {}\n"));
+    assert!(rendered.contains("+fn c2() {}\n"));
+    // Unchanged lines appear as context, prefixed with a space.
+    assert!(rendered.contains(" fn b() {}\n"));
+}
+
+#[test]
+fn distant_changes_land_in_separate_hunks() {
+    let keep: String = (0..20).map(|i| format!("fn k{}() {{}}\n", i)).collect();
+    let original = format!("fn top() {{}}\n{}fn bottom() {{}}\n", keep);
+    let stripped = format!("fn top2() {{}}\n{}fn bottom2() {{}}\n", keep);
+    let rendered = unified_diff(&original, &stripped, Path::new("a.rs")).unwrap();
+    assert_eq!(rendered.matches("@@ -").count(), 2);
+    assert!(rendered.contains("-fn top() {}\n"));
+    assert!(rendered.contains("+fn bottom2() {}\n"));
+}
+
+#[test]
+fn diff_mode_prints_the_diff_and_fails_when_files_would_change() {
+    let dir = scratch("diff-changed");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg("--diff")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!("--- {}", path.display())));
+    assert!(stdout.contains("+++"));
+    assert!(stdout.contains("@@"));
+    assert!(stdout.contains("-spec fn s() -> int { 1 }"));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("1 file(s) would be changed by stripping"));
+}
+
+#[test]
+fn diff_mode_is_silent_and_succeeds_on_already_stripped_files() {
+    let dir = scratch("diff-clean");
+    let path = dir.join("lib.rs");
+    // A file that is its own stripped output: strip once and diff the result.
+    let stripped = strip_source(SOURCE, &Config::default()).unwrap();
+    fs::write(&path, stripped).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg("--diff")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success());
+    assert!(output.stdout.is_empty());
+}
+
+#[test]
+fn diff_composes_with_check() {
+    let dir = scratch("diff-check");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    // --diff subsumes --check, so passing both shows the diff and still
+    // exits non-zero; nothing is written either way.
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--diff", "--check"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("@@"));
+    assert_eq!(fs::read_to_string(&path).unwrap(), SOURCE);
+}
+
+#[test]
+fn recursive_diff_covers_every_changed_file() {
+    let dir = scratch("diff-recursive");
+    fs::write(dir.join("a.rs"), SOURCE).unwrap();
+    fs::write(dir.join("b.rs"), strip_source(SOURCE, &Config::default()).unwrap()).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--diff", "--recursive"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!("--- {}", dir.join("a.rs").display())));
+    assert!(!stdout.contains(&format!("--- {}", dir.join("b.rs").display())));
+}
@@ -0,0 +1,112 @@
+//! Error accumulation across directory runs, and `--fail-fast` to stop at
+//! the first failing file instead.
+
+mod common;
+use common::scratch;
+
+use std::fs;
+
+use vstrip::reporter::SilentReporter;
+use vstrip::{process_with_reporter, Config, StripError};
+
+const SOURCE: &str = "verus! {\n\nspec fn s(x: int) -> int { x }\n\npub fn f() {}\n\n} // verus!\n";
+
+#[test]
+fn sequential_failures_accumulate_into_multiple_errors() {
+    let dir = scratch("failfast-accumulate");
+    fs::write(dir.join("a_bad.rs"), "fn broken(\n").unwrap();
+    fs::write(dir.join("m_good.rs"), SOURCE).unwrap();
+    fs::write(dir.join("z_bad.rs"), "struct also broken\n").unwrap();
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    match err {
+        StripError::MultipleErrors(failures) => {
+            let paths: Vec<String> =
+                failures.iter().map(|(path, _)| path.display().to_string()).collect();
+            assert_eq!(paths.len(), 2, "{:?}", paths);
+            assert!(paths[0].ends_with("a_bad.rs"), "{:?}", paths);
+            assert!(paths[1].ends_with("z_bad.rs"), "{:?}", paths);
+            assert!(matches!(failures[0].1, StripError::ParseError { .. }));
+        }
+        other => panic!("expected MultipleErrors, got {:?}", other),
+    }
+    // The failures did not stop the good file from being rewritten.
+    assert!(!fs::read_to_string(dir.join("m_good.rs")).unwrap().contains("spec fn"));
+}
+
+#[test]
+fn multiple_errors_display_names_each_file() {
+    let dir = scratch("failfast-display");
+    fs::write(dir.join("a_bad.rs"), "fn broken(\n").unwrap();
+    fs::write(dir.join("z_bad.rs"), "struct also broken\n").unwrap();
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        ..Config::default()
+    };
+    let rendered =
+        process_with_reporter(&config, &SilentReporter).unwrap_err().to_string();
+    assert!(rendered.contains("2 file(s) failed:"), "{}", rendered);
+    assert!(rendered.contains("a_bad.rs"), "{}", rendered);
+    assert!(rendered.contains("z_bad.rs"), "{}", rendered);
+}
+
+#[test]
+fn fail_fast_stops_at_the_first_failure() {
+    let dir = scratch("failfast-stop");
+    fs::write(dir.join("a_bad.rs"), "fn broken(\n").unwrap();
+    fs::write(dir.join("z_good.rs"), SOURCE).unwrap();
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        fail_fast: true,
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    // The first error comes back bare, as a single-file run would return it.
+    assert!(matches!(err, StripError::ParseError { .. }), "{:?}", err);
+    // Entries are processed in sorted order, so the later file was never
+    // touched.
+    assert!(fs::read_to_string(dir.join("z_good.rs")).unwrap().contains("spec fn"));
+}
+
+#[test]
+fn fail_fast_excludes_parallel_jobs() {
+    let dir = scratch("failfast-jobs");
+    let config = Config {
+        input: dir.clone(),
+        recursive: true,
+        check: true,
+        fail_fast: true,
+        parallel_jobs: Some(2),
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    assert!(err.to_string().contains("fail_fast"), "{}", err);
+}
+
+#[test]
+fn the_cli_flag_stops_the_walk() {
+    let dir = scratch("failfast-cli");
+    fs::write(dir.join("a_bad.rs"), "fn broken(\n").unwrap();
+    fs::write(dir.join("z_good.rs"), SOURCE).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--in-place", "--recursive", "--fail-fast"])
+        .arg(&dir)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    // The bare first error, not the end-of-run accumulation.
+    assert!(stderr.contains("parse error"), "{}", stderr);
+    assert!(!stderr.contains("file(s) failed"), "{}", stderr);
+    assert!(fs::read_to_string(dir.join("z_good.rs")).unwrap().contains("spec fn"));
+}
@@ -0,0 +1,103 @@
+//! `--files-from`: processing exactly the files a manifest lists instead of
+//! walking a directory.
+
+mod common;
+use common::scratch;
+
+use std::fs;
+
+use vstrip::reporter::SilentReporter;
+use vstrip::{process_with_reporter, Config, StripError};
+
+const SOURCE: &str = "verus! {\n\nspec fn s(x: int) -> int { x }\n\npub fn f() {}\n\n} // verus!\n";
+
+#[test]
+fn only_the_listed_files_are_processed() {
+    let dir = scratch("filesfrom-listed");
+    fs::create_dir_all(dir.join("sub")).unwrap();
+    fs::write(dir.join("a.rs"), SOURCE).unwrap();
+    fs::write(dir.join("b.rs"), SOURCE).unwrap();
+    fs::write(dir.join("sub/c.rs"), SOURCE).unwrap();
+    // Relative entries resolve against the manifest's own directory, with
+    // blank lines and comments ignored.
+    fs::write(dir.join("files.txt"), "# response file\n\na.rs\nsub/c.rs\n").unwrap();
+    let config = Config {
+        files_from: Some(dir.join("files.txt")),
+        in_place: true,
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+    assert!(!fs::read_to_string(dir.join("a.rs")).unwrap().contains("spec fn"));
+    assert!(!fs::read_to_string(dir.join("sub/c.rs")).unwrap().contains("spec fn"));
+    // The unlisted file was never touched.
+    assert!(fs::read_to_string(dir.join("b.rs")).unwrap().contains("spec fn"));
+}
+
+#[test]
+fn missing_entries_do_not_stop_the_rest() {
+    let dir = scratch("filesfrom-missing");
+    fs::write(dir.join("good.rs"), SOURCE).unwrap();
+    fs::write(dir.join("files.txt"), "absent.rs\ngood.rs\n").unwrap();
+    let config = Config {
+        files_from: Some(dir.join("files.txt")),
+        in_place: true,
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    match err {
+        StripError::MultipleErrors(failures) => {
+            assert_eq!(failures.len(), 1, "{:?}", failures);
+            assert!(failures[0].0.ends_with("absent.rs"), "{:?}", failures);
+            assert!(matches!(failures[0].1, StripError::IoError { .. }));
+        }
+        other => panic!("expected MultipleErrors, got {:?}", other),
+    }
+    assert!(!fs::read_to_string(dir.join("good.rs")).unwrap().contains("spec fn"));
+}
+
+#[test]
+fn fail_fast_makes_a_missing_entry_fatal() {
+    let dir = scratch("filesfrom-failfast");
+    fs::write(dir.join("z_good.rs"), SOURCE).unwrap();
+    fs::write(dir.join("files.txt"), "absent.rs\nz_good.rs\n").unwrap();
+    let config = Config {
+        files_from: Some(dir.join("files.txt")),
+        in_place: true,
+        fail_fast: true,
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    assert!(matches!(err, StripError::IoError { .. }), "{:?}", err);
+    assert!(fs::read_to_string(dir.join("z_good.rs")).unwrap().contains("spec fn"));
+}
+
+#[test]
+fn files_from_excludes_a_directory_walk() {
+    let err = vstrip::ConfigBuilder::default()
+        .files_from("files.txt")
+        .recursive()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("files_from"), "{}", err);
+}
+
+#[test]
+fn the_manifest_can_come_from_stdin() {
+    use std::io::Write;
+
+    let dir = scratch("filesfrom-stdin");
+    fs::write(dir.join("a.rs"), SOURCE).unwrap();
+
+    let mut child = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--files-from", "-", "--in-place"])
+        .stdin(std::process::Stdio::piped())
+        .spawn()
+        .unwrap();
+    // Entries from stdin resolve against the working directory, so an
+    // absolute path is the portable choice here.
+    let manifest = format!("{}\n", dir.join("a.rs").display());
+    child.stdin.take().unwrap().write_all(manifest.as_bytes()).unwrap();
+    let status = child.wait().unwrap();
+    assert!(status.success());
+    assert!(!fs::read_to_string(dir.join("a.rs")).unwrap().contains("spec fn"));
+}
@@ -0,0 +1,102 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use vstrip::reporter::{EventContext, Reporter, SilentReporter};
+use vstrip::{process_with_reporter, Config, Level};
+
+/// Captures only the summary line, for asserting the skip count.
+struct SummaryReporter {
+    summary: Mutex<Option<String>>,
+}
+
+impl SummaryReporter {
+    fn new() -> SummaryReporter {
+        SummaryReporter { summary: Mutex::new(None) }
+    }
+
+    fn summary(&self) -> String {
+        self.summary.lock().unwrap().clone().unwrap()
+    }
+}
+
+impl Reporter for SummaryReporter {
+    fn event(&self, _level: Level, message: &str, context: &EventContext<'_>) {
+        if context.kind == "summary" {
+            *self.summary.lock().unwrap() = Some(message.to_string());
+        }
+    }
+}
+
+const SOURCE: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+/// A tree with the shapes the filters are meant to separate: hand-written
+/// sources, an integration-test directory, and generated files at two depths.
+fn scratch_tree(name: &str) -> PathBuf {
+    let dir = scratch(name);
+    fs::create_dir_all(dir.join("src")).unwrap();
+    fs::create_dir_all(dir.join("tests")).unwrap();
+    fs::write(dir.join("src/lib.rs"), SOURCE).unwrap();
+    fs::write(dir.join("src/generated_tables.rs"), SOURCE).unwrap();
+    fs::write(dir.join("tests/integration.rs"), SOURCE).unwrap();
+    fs::write(dir.join("generated_top.rs"), SOURCE).unwrap();
+    dir
+}
+
+fn is_stripped(path: PathBuf) -> bool {
+    !fs::read_to_string(path).unwrap().contains("spec fn")
+}
+
+#[test]
+fn exclude_patterns_skip_tests_and_generated_files() {
+    let dir = scratch_tree("filters-exclude");
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        exclude_globs: vec!["tests/**".to_string(), "**/generated_*.rs".to_string()],
+        ..Config::default()
+    };
+    let reporter = SummaryReporter::new();
+    process_with_reporter(&config, &reporter).unwrap();
+    assert!(is_stripped(dir.join("src/lib.rs")));
+    assert!(!is_stripped(dir.join("tests/integration.rs")));
+    assert!(!is_stripped(dir.join("src/generated_tables.rs")));
+    assert!(!is_stripped(dir.join("generated_top.rs")));
+    assert_eq!(reporter.summary(), "1 file(s) processed, 0 error(s), 3 skipped, 0 ignored");
+}
+
+#[test]
+fn include_patterns_limit_the_walk_and_exclude_wins() {
+    let dir = scratch_tree("filters-include");
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        include_globs: vec!["src/**".to_string()],
+        exclude_globs: vec!["**/generated_*.rs".to_string()],
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+    assert!(is_stripped(dir.join("src/lib.rs")));
+    // Matched by the include pattern, but exclude wins.
+    assert!(!is_stripped(dir.join("src/generated_tables.rs")));
+    assert!(!is_stripped(dir.join("tests/integration.rs")));
+}
+
+#[test]
+fn invalid_patterns_are_a_configuration_error() {
+    let dir = scratch_tree("filters-invalid");
+    let config = Config {
+        input: dir,
+        check: true,
+        recursive: true,
+        exclude_globs: vec!["tests/[".to_string()],
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    assert!(err.to_string().contains("invalid glob pattern"));
+}
@@ -0,0 +1,24 @@
+verus! {
+
+pub fn compute(x: u32) -> (r: u32)
+    ensures
+        r >= x,
+{
+    let ghost base = x as int;
+    calc! {
+        (<=)
+        base;
+        (<=) { }
+        base + 1;
+    };
+    let bound = if calc! { (==) 1int; (==) { } 1int; } == () { x } else { x + 1 };
+    let _witness = calc! {
+        (==)
+        base;
+        (==) { }
+        base;
+    };
+    bound
+}
+
+} // verus!
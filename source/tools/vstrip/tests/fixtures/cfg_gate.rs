@@ -0,0 +1,37 @@
+use vstd::prelude::*;
+
+verus! {
+
+spec fn cap_spec(c: u64) -> int {
+    c as int
+}
+
+proof fn lemma_cap(c: u64)
+    requires
+        c < 1000,
+    ensures
+        cap_spec(c) < 1000,
+{
+    assert((c as int) < 1000);
+}
+
+struct Meter {
+    used: u64,
+    ghost budget: int,
+}
+
+fn charge(m: &mut Meter, amount: u64) -> (total: u64)
+    requires
+        old(m).used < 1000,
+    ensures
+        total >= amount,
+{
+    proof {
+        assert(old(m).used < 1000);
+    }
+    assert(amount < 1000);
+    m.used = m.used + amount;
+    m.used
+}
+
+} // verus!
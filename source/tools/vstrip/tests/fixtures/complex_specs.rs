@@ -0,0 +1,41 @@
+use std::fmt;
+
+verus! {
+
+spec fn spec_len(v: Seq<int>) -> int {
+    v.len() as int
+}
+
+pub struct Counter {
+    pub count: u64,
+    pub ghost history: Seq<int>,
+}
+
+pub fn increment(c: &mut Counter)
+    requires
+        old(c).count < 1000,
+    ensures
+        c.count == old(c).count + 1,
+{
+    c.count = c.count + 1;
+}
+
+proof fn lemma_increment_monotone(a: int, b: int)
+    requires
+        a <= b,
+    ensures
+        a <= b + 1,
+{
+}
+
+impl Counter {
+    pub fn reset(&mut self) {
+        self.count = 0;
+    }
+
+    spec fn well_formed(&self) -> bool {
+        self.count < 1000
+    }
+}
+
+} // verus!
@@ -0,0 +1,34 @@
+use vstd::prelude::*;
+
+verus! {
+
+pub struct Counter {
+    pub count: usize,
+}
+
+impl Counter {
+    pub closed spec fn spec_len(&self) -> int {
+        self.count as int
+    }
+
+    pub fn len(&self) -> (n: usize)
+        ensures
+            n == self.spec_len(),
+    {
+        self.count
+    }
+}
+
+} // verus!
+
+// A second impl block that defines `len` again; in the original code the two
+// blocks were distinguished by configuration that stripping removes.
+verus! {
+
+impl Counter {
+    pub fn len(&self) -> usize {
+        self.count
+    }
+}
+
+} // verus!
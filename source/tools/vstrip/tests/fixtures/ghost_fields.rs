@@ -0,0 +1,10 @@
+// Ghost fields: stripping removes `history` from `Account`'s constructor.
+
+verus! {
+
+pub struct Account {
+    pub balance: u64,
+    pub ghost history: Seq<int>,
+}
+
+} // verus!
@@ -0,0 +1,18 @@
+// Ghost parameters: stripping drops `g`, changing `incr`'s arity.
+
+verus! {
+
+pub fn incr(x: u32, g: Ghost<int>) -> (r: u32)
+    requires
+        x < 1000,
+    ensures
+        r == x + 1,
+{
+    x + 1
+}
+
+pub fn plain(x: u32) -> u32 {
+    x
+}
+
+} // verus!
@@ -0,0 +1,19 @@
+verus! {
+
+fn pick() -> Ghost<int> {
+    Ghost(3)
+}
+
+fn split(v: u32) -> (Ghost<int>, u32) {
+    (Ghost(v as int), v)
+}
+
+fn explicit(v: u32) -> Tracked<u32> {
+    return Tracked(v);
+}
+
+fn all_ghost() -> (Ghost<int>, Tracked<int>) {
+    (Ghost(1), Tracked(2))
+}
+
+} // verus!
@@ -0,0 +1,54 @@
+verus! {
+
+pub fn sum_to(n: u32) -> (total: u32)
+    requires
+        n < 1000,
+    ensures
+        total < 1000 * 1000,
+{
+    let mut total: u32 = 0;
+    let mut i: u32 = 0;
+    while i < n
+        invariant
+            i <= n,
+            total == i * (i + 1) / 2,
+        decreases n - i,
+    {
+        i = i + 1;
+        total = total + i;
+    }
+    total
+}
+
+pub fn drain(v: &mut Vec<u32>) {
+    loop
+        invariant_except_break
+            old(v).len() >= v.len(),
+        invariant
+            true,
+        ensures
+            v.len() == 0,
+        decreases v.len(),
+    {
+        if v.len() == 0 {
+            break;
+        }
+        v.pop();
+    }
+}
+
+pub fn count_evens(v: &Vec<u32>) -> (evens: usize) {
+    let mut evens: usize = 0;
+    for k in iter: 0..v.len()
+        invariant
+            evens <= k,
+            iter.end == v.len(),
+    {
+        if v[k] % 2 == 0 {
+            evens = evens + 1;
+        }
+    }
+    evens
+}
+
+} // verus!
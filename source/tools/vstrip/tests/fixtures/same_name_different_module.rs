@@ -0,0 +1,37 @@
+use vstd::prelude::*;
+
+verus! {
+
+pub mod reader {
+    pub fn len() -> usize {
+        1
+    }
+}
+
+pub mod writer {
+    pub fn len() -> usize {
+        2
+    }
+}
+
+pub struct Left {
+    pub x: usize,
+}
+
+pub struct Right {
+    pub y: usize,
+}
+
+impl Left {
+    pub fn get(&self) -> usize {
+        self.x
+    }
+}
+
+impl Right {
+    pub fn get(&self) -> usize {
+        self.y
+    }
+}
+
+} // verus!
@@ -0,0 +1,22 @@
+// Spec and proof consts: only the plain `const` survives stripping.
+
+verus! {
+
+spec const SPEC_LIMIT: int = 100;
+
+proof const WITNESS: nat = 0;
+
+exec const CAP: u64
+    ensures
+        CAP == 7,
+{
+    7
+}
+
+const LIMIT: u64 = 100;
+
+fn check(x: u64) -> bool {
+    x < LIMIT
+}
+
+} // verus!
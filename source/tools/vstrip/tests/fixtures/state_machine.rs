@@ -0,0 +1,25 @@
+verus! {
+
+state_machine! {
+    Counter {
+        fields {
+            pub count: int,
+        }
+
+        init! {
+            initialize() {
+                init count = 0;
+            }
+        }
+    }
+}
+
+spec fn bound() -> int {
+    100
+}
+
+pub fn unrelated() -> u32 {
+    1
+}
+
+} // verus!
@@ -0,0 +1,60 @@
+use vstrip::ghost_usage::{collect_ghost_type_usages, GhostTypeKind};
+
+const FIXTURE: &str = r#"
+verus! {
+
+spec fn spec_only(s: Seq<int>) -> int {
+    s.len() as int
+}
+
+pub struct Account {
+    pub balance: u64,
+    pub ghost history: Seq<int>,
+    pub audit: Map<u64, nat>,
+}
+
+pub fn deposit(account: &mut Account, amount: u64, g: Ghost<int>) -> (total: nat) {
+    let running: int = 0;
+    let ghost snapshot: Seq<int> = Seq::empty();
+    0
+}
+
+} // verus!
+"#;
+
+#[test]
+fn reports_exec_positions_only() {
+    let report = collect_ghost_type_usages(FIXTURE).unwrap();
+    let found: Vec<(&str, GhostTypeKind)> = report
+        .locations
+        .iter()
+        .map(|(context, _, kind)| (context.as_str(), *kind))
+        .collect();
+    assert_eq!(
+        found,
+        vec![
+            ("struct Account: field audit", GhostTypeKind::Map),
+            ("struct Account: field audit", GhostTypeKind::Nat),
+            ("fn deposit: parameter g", GhostTypeKind::Ghost),
+            ("fn deposit: parameter g", GhostTypeKind::Int),
+            ("fn deposit: return type", GhostTypeKind::Nat),
+            ("fn deposit: let running", GhostTypeKind::Int),
+        ]
+    );
+}
+
+#[test]
+fn line_numbers_match_the_source() {
+    let report = collect_ghost_type_usages(FIXTURE).unwrap();
+    let (_, line, kind) = &report.locations[0];
+    assert_eq!(*kind, GhostTypeKind::Map);
+    // `pub audit: Map<u64, nat>` sits on line 11 of the fixture; the
+    // preprocessor keeps every newline, so the span survives unwrapping.
+    assert_eq!(*line, 11);
+}
+
+#[test]
+fn clean_sources_produce_empty_reports() {
+    let report = collect_ghost_type_usages("pub fn double(x: u32) -> u32 { 2 * x }").unwrap();
+    assert!(report.locations.is_empty());
+}
@@ -0,0 +1,68 @@
+//! Granular entry points: stripping one item, block, or expression at a
+//! time, as an IDE code action would.
+
+use quote::ToTokens;
+use verus_syn::{Block, Expr, Item};
+use vstrip::config::AssertStrategy;
+use vstrip::{strip_block, strip_expr, strip_item, Config, ConfigBuilder, StripDisposition};
+
+#[test]
+fn spec_fn_items_are_marked_for_removal() {
+    let mut item: Item = verus_syn::parse_quote! {
+        spec fn square(x: int) -> int { x * x }
+    };
+    assert_eq!(strip_item(&mut item, &Config::default()), StripDisposition::Remove);
+}
+
+#[test]
+fn exec_items_are_rewritten_in_place_and_kept() {
+    let mut item: Item = verus_syn::parse_quote! {
+        fn double(x: u32) -> u32
+            requires
+                x < 1000,
+        {
+            2 * x
+        }
+    };
+    assert_eq!(strip_item(&mut item, &Config::default()), StripDisposition::Keep);
+    let rendered = item.into_token_stream().to_string();
+    assert!(!rendered.contains("requires"), "{}", rendered);
+    assert!(rendered.contains("fn double"), "{}", rendered);
+}
+
+#[test]
+fn blocks_lose_proof_statements_but_are_always_kept() {
+    let mut block: Block = verus_syn::parse_quote! {
+        {
+            let ghost saved = x;
+            assert(x < 10);
+            x + 1
+        }
+    };
+    assert_eq!(strip_block(&mut block, &Config::default()), StripDisposition::Keep);
+    assert_eq!(block.stmts.len(), 1, "{}", block.into_token_stream());
+}
+
+#[test]
+fn proof_expressions_are_marked_for_removal() {
+    let mut expr: Expr = verus_syn::parse_quote!(assert(x < 10));
+    assert_eq!(strip_expr(&mut expr, &Config::default()), StripDisposition::Remove);
+
+    // Under a converting assert strategy the same expression is kept, as a
+    // runtime check.
+    let config = ConfigBuilder::new("src/lib.rs")
+        .assert_strategy(AssertStrategy::DebugAssert)
+        .build()
+        .unwrap();
+    let mut expr: Expr = verus_syn::parse_quote!(assert(x < 10));
+    assert_eq!(strip_expr(&mut expr, &config), StripDisposition::Keep);
+    assert!(expr.into_token_stream().to_string().contains("debug_assert"));
+}
+
+#[test]
+fn surviving_expressions_are_rewritten_in_place() {
+    let mut expr: Expr = verus_syn::parse_quote!(v@.len());
+    assert_eq!(strip_expr(&mut expr, &Config::default()), StripDisposition::Keep);
+    let rendered = expr.into_token_stream().to_string();
+    assert!(!rendered.contains('@'), "{}", rendered);
+}
@@ -0,0 +1,56 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::Path;
+
+use vstrip::{process, Config, StripError};
+
+const PARENT: &str = "verus! {\n\nspec fn parent_spec() -> int { 1 }\n\npub fn parent_exec() -> u32 { 1 }\n\n} // verus!\n\ninclude!(\"child.rs\");\n";
+const CHILD: &str = "verus! {\n\nspec fn child_spec() -> int { 2 }\n\npub fn child_exec() -> u32 { 2 }\n\n} // verus!\n";
+
+fn in_place_config(input: &Path, follow_includes: bool) -> Config {
+    Config {
+        input: input.to_path_buf(),
+        in_place: true,
+        follow_includes,
+        ..Config::default()
+    }
+}
+
+#[test]
+fn includes_are_followed_only_when_enabled() {
+    let dir = scratch("includes");
+    let parent = dir.join("parent.rs");
+    fs::write(&parent, PARENT).unwrap();
+    fs::write(dir.join("child.rs"), CHILD).unwrap();
+
+    process(&in_place_config(&parent, false)).unwrap();
+    assert!(!fs::read_to_string(&parent).unwrap().contains("parent_spec"));
+    assert!(fs::read_to_string(dir.join("child.rs")).unwrap().contains("child_spec"));
+
+    process(&in_place_config(&parent, true)).unwrap();
+    let child = fs::read_to_string(dir.join("child.rs")).unwrap();
+    assert!(!child.contains("child_spec"));
+    assert!(child.contains("child_exec"));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn include_cycles_are_reported() {
+    let dir = scratch("include-cycle");
+    fs::write(dir.join("a.rs"), "include!(\"b.rs\");\n").unwrap();
+    fs::write(dir.join("b.rs"), "include!(\"a.rs\");\n").unwrap();
+
+    match process(&in_place_config(&dir.join("a.rs"), true)) {
+        Err(StripError::IncludeCycle(chain)) => {
+            assert!(chain.len() >= 3);
+            let rendered = format!("{}", StripError::IncludeCycle(chain));
+            assert!(rendered.contains("cycle"), "unexpected message: {}", rendered);
+        }
+        other => panic!("expected IncludeCycle, got {:?}", other.map(|_| ())),
+    }
+
+    fs::remove_dir_all(&dir).ok();
+}
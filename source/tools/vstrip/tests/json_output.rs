@@ -0,0 +1,74 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::process::Command;
+
+const SOURCE: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\nproof fn p() {\n}\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+fn vstrip(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_vstrip")).args(args).output().unwrap()
+}
+
+#[test]
+fn json_check_runs_emit_one_entry_per_file() {
+    let dir = scratch("json-ok");
+    fs::write(dir.join("a.rs"), SOURCE).unwrap();
+    fs::write(dir.join("b.rs"), "pub fn plain() {}\n").unwrap();
+
+    let output = vstrip(&["--check", "--json", "--recursive", dir.to_str().unwrap()]);
+    // a.rs would be stripped, so the check itself fails; the diagnostics are
+    // still printed in full first.
+    assert!(!output.status.success());
+    let parsed: serde_json::Value =
+        serde_json::from_slice(&output.stdout).expect("stdout is a JSON array");
+    let entries = parsed.as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    for entry in entries {
+        assert_eq!(entry["status"], "ok");
+        assert!(entry["warnings"].as_array().unwrap().is_empty());
+        assert!(entry.get("error").is_none());
+    }
+    let a = entries.iter().find(|e| e["file"].as_str().unwrap().ends_with("a.rs")).unwrap();
+    // One spec fn and one proof fn were removed.
+    assert_eq!(a["stripped"], 2);
+    let b = entries.iter().find(|e| e["file"].as_str().unwrap().ends_with("b.rs")).unwrap();
+    assert_eq!(b["stripped"], 0);
+}
+
+#[test]
+fn json_entries_carry_error_status_and_message() {
+    let dir = scratch("json-err");
+    fs::write(dir.join("bad.rs"), "fn broken(\n").unwrap();
+
+    let output = vstrip(&["--check", "--json", "--recursive", dir.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = parsed.as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["status"], "error");
+    assert!(entries[0]["error"].as_str().unwrap().contains("parse error"));
+}
+
+#[test]
+fn json_single_file_runs_also_emit_an_array() {
+    let dir = scratch("json-single");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    let output = vstrip(&["--check", "--json", path.to_str().unwrap()]);
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    assert_eq!(parsed.as_array().unwrap().len(), 1);
+}
+
+#[test]
+fn json_without_a_free_stdout_is_rejected() {
+    let dir = scratch("json-stdout");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+
+    let output = vstrip(&["--json", path.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--json"));
+}
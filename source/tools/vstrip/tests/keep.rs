@@ -0,0 +1,106 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+
+use vstrip::{strip_source_detailed, Config};
+
+// One of every construct class the keep_* selections govern.
+const FIXTURE: &str = r#"
+verus! {
+
+spec fn width_spec(w: u32) -> int {
+    w as int
+}
+
+struct Frame {
+    width: u32,
+    ghost history: Seq<int>,
+}
+
+impl Frame {
+    proof fn lemma_width(&self) {
+    }
+
+    fn widen(&mut self, x: u32) -> (r: u32)
+        requires
+            old(self).width < 1000,
+        ensures
+            r >= x,
+    {
+        proof {
+            assert(old(self).width < 1000);
+        }
+        assert(x < 1000);
+        self.width = self.width + x;
+        self.width
+    }
+}
+
+} // verus!
+"#;
+
+#[test]
+fn keeping_spec_fns_and_signature_specs_strips_only_the_rest() {
+    let config =
+        Config { keep_spec_fns: true, keep_signature_specs: true, ..Config::default() };
+    let result = strip_source_detailed(FIXTURE, &config).unwrap();
+    // The specification surface survives for later re-verification...
+    assert!(result.output.contains("spec fn width_spec"), "{}", result.output);
+    assert!(result.output.contains("proof fn lemma_width"), "{}", result.output);
+    assert!(result.output.contains("requires"), "{}", result.output);
+    assert!(result.output.contains("ensures"), "{}", result.output);
+    // ...while the other classes are stripped as usual.
+    assert!(!result.output.contains("proof {"), "{}", result.output);
+    assert!(!result.output.contains("assert"), "{}", result.output);
+    assert!(!result.output.contains("ghost"), "{}", result.output);
+    // Nothing kept is counted as removed; the dropped ghost field is.
+    let removed: Vec<String> =
+        result.stripped_items.iter().map(ToString::to_string).collect();
+    assert_eq!(removed, vec!["ghost field Frame::history"]);
+    assert_eq!(result.stats.spec_fns, 0);
+    assert_eq!(result.stats.proof_fns, 0);
+    assert_eq!(result.stats.requires_clauses, 0);
+    assert_eq!(result.stats.ensures_clauses, 0);
+    assert_eq!(result.stats.ghost_fields, 1);
+    assert!(result.warnings.is_empty());
+}
+
+#[test]
+fn keeping_proof_blocks_and_ghost_fields_strips_only_the_rest() {
+    let config =
+        Config { keep_proof_blocks: true, keep_ghost_fields: true, ..Config::default() };
+    let result = strip_source_detailed(FIXTURE, &config).unwrap();
+    // The proof block survives verbatim, including the assert inside it.
+    assert!(result.output.contains("proof {"), "{}", result.output);
+    assert!(result.output.contains("old(self).width < 1000"), "{}", result.output);
+    assert!(result.output.contains("ghost history"), "{}", result.output);
+    // The bare assert and the spec surface go as usual.
+    assert!(!result.output.contains("x < 1000"), "{}", result.output);
+    assert!(!result.output.contains("spec fn"), "{}", result.output);
+    assert!(!result.output.contains("requires"), "{}", result.output);
+    assert_eq!(result.stats.proof_blocks, 0);
+    assert_eq!(result.stats.ghost_fields, 0);
+    assert_eq!(result.stats.assert_assume_exprs, 1);
+    assert_eq!(result.stats.spec_fns, 1);
+    assert_eq!(result.stats.proof_fns, 1);
+}
+
+#[test]
+fn keep_flags_are_rejected_alongside_verify_output() {
+    assert!(matches!(
+        vstrip::ConfigBuilder::new("src/lib.rs").keep_spec_fns().verify_output().build(),
+        Err(vstrip::StripError::ConfigError(_))
+    ));
+
+    let dir = scratch("keep-cli");
+    let path = dir.join("lib.rs");
+    fs::write(&path, FIXTURE).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg("--keep-ghost-fields")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    assert!(String::from_utf8(output.stdout).unwrap().contains("ghost history"));
+}
@@ -0,0 +1,67 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::process::Command;
+
+use vstrip::{strip_source_detailed, Config, StrippedItemKind};
+
+const FIXTURE: &str = include_str!("fixtures/complex_specs.rs");
+
+#[test]
+fn detailed_results_name_every_removed_item_kind() {
+    let result = strip_source_detailed(FIXTURE, &Config::default()).unwrap();
+    let removed: Vec<String> =
+        result.stripped_items.iter().map(ToString::to_string).collect();
+    // Top-level functions are recorded before the walk descends into the
+    // surviving items, hence the order.
+    assert_eq!(
+        removed,
+        vec![
+            "spec fn spec_len",
+            "proof fn lemma_increment_monotone",
+            "ghost field Counter::history",
+            "spec fn well_formed",
+        ]
+    );
+    assert_eq!(result.stripped_items[2].kind, StrippedItemKind::GhostField);
+    assert_eq!(result.stripped_items[2].line, 11);
+}
+
+#[test]
+fn list_removed_prints_names_under_the_file() {
+    let dir = scratch("list-removed");
+    let path = dir.join("lib.rs");
+    fs::write(&path, FIXTURE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--check", "--list-removed"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    // --check exits non-zero because stripping would change the file; the
+    // listing is printed all the same.
+    assert!(!output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains(&format!("{}:", path.display())), "{}", stdout);
+    assert!(stdout.contains("  spec fn spec_len (line 5)"), "{}", stdout);
+    assert!(stdout.contains("  ghost field Counter::history (line 11)"), "{}", stdout);
+    assert!(stdout.contains("  proof fn lemma_increment_monotone (line 23)"), "{}", stdout);
+    assert!(stdout.contains("  spec fn well_formed (line 36)"), "{}", stdout);
+}
+
+#[test]
+fn list_removed_refuses_to_share_stdout_with_output() {
+    let dir = scratch("list-removed-stdout");
+    let path = dir.join("lib.rs");
+    fs::write(&path, FIXTURE).unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg("--list-removed")
+        .arg(&path)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("--list-removed"), "{}", stderr);
+}
@@ -0,0 +1,91 @@
+//! End-to-end tests for `--message-format json`: errors and warnings as one
+//! JSON object per stderr line, for editor integration.
+
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::process::Command;
+
+fn json_lines(stderr: &[u8]) -> Vec<serde_json::Value> {
+    String::from_utf8_lossy(stderr)
+        .lines()
+        .map(|line| {
+            serde_json::from_str(line)
+                .unwrap_or_else(|e| panic!("stderr line is not JSON ({}): {}", e, line))
+        })
+        .collect()
+}
+
+#[test]
+fn warnings_print_as_json_lines() {
+    let dir = scratch("msgfmt-warn");
+    let file = dir.join("lib.rs");
+    fs::write(
+        &file,
+        "verus! {\n\nfn f(x: u32, credit: Tracked<int>) -> u32 { x }\n\n} // verus!\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--check", "--message-format=json"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    let lines = json_lines(&output.stderr);
+    let warning = lines
+        .iter()
+        .find(|l| l["level"] == "warning")
+        .unwrap_or_else(|| panic!("no warning line in {:?}", lines));
+    assert_eq!(warning["code"], "ghost-param-dropped");
+    assert_eq!(warning["line"], 3);
+    assert_eq!(warning["column"], 14);
+    assert!(warning["path"].as_str().unwrap().ends_with("lib.rs"));
+    assert!(warning["message"].as_str().unwrap().contains("credit"));
+}
+
+#[test]
+fn parse_errors_carry_line_and_column() {
+    let dir = scratch("msgfmt-parse");
+    let file = dir.join("broken.rs");
+    fs::write(&file, "fn broken( {\n").unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--check", "--message-format=json"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let lines = json_lines(&output.stderr);
+    let error = lines
+        .iter()
+        .find(|l| l["code"] == "parse-error")
+        .unwrap_or_else(|| panic!("no parse-error line in {:?}", lines));
+    assert_eq!(error["level"], "error");
+    assert!(error["line"].is_u64(), "{:?}", error);
+    assert!(error["column"].is_u64(), "{:?}", error);
+    assert!(error["path"].as_str().unwrap().ends_with("broken.rs"));
+    // The structured fields carry the location, so the message does not
+    // repeat the path:line:column prefix of the text rendering.
+    assert!(!error["message"].as_str().unwrap().contains("broken.rs"));
+}
+
+#[test]
+fn text_remains_the_default() {
+    let dir = scratch("msgfmt-text");
+    let file = dir.join("lib.rs");
+    fs::write(
+        &file,
+        "verus! {\n\nfn f(x: u32, credit: Tracked<int>) -> u32 { x }\n\n} // verus!\n",
+    )
+    .unwrap();
+
+    let output = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--check"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("warning: "), "{}", stderr);
+    assert!(!stderr.contains("\"level\""), "{}", stderr);
+}
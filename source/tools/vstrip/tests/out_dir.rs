@@ -0,0 +1,84 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+
+use vstrip::reporter::SilentReporter;
+use vstrip::{process_with_reporter, Config, ConfigBuilder};
+
+const SOURCE: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+/// A nested tree with sources at three depths and one non-Rust asset.
+fn scratch_tree(name: &str) -> PathBuf {
+    let dir = scratch(name);
+    fs::create_dir_all(dir.join("input/a/b")).unwrap();
+    fs::write(dir.join("input/top.rs"), SOURCE).unwrap();
+    fs::write(dir.join("input/a/one.rs"), SOURCE).unwrap();
+    fs::write(dir.join("input/a/b/two.rs"), SOURCE).unwrap();
+    fs::write(dir.join("input/a/data.txt"), "not rust\n").unwrap();
+    dir
+}
+
+#[test]
+fn out_dir_mirrors_the_input_tree() {
+    let dir = scratch_tree("outdir-mirror");
+    let config = Config {
+        input: dir.join("input"),
+        out_dir: Some(dir.join("stripped")),
+        recursive: true,
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+
+    for relative in ["top.rs", "a/one.rs", "a/b/two.rs"] {
+        let mirrored = fs::read_to_string(dir.join("stripped").join(relative)).unwrap();
+        assert!(!mirrored.contains("spec fn"), "{} was not stripped", relative);
+        // The originals are untouched.
+        assert_eq!(fs::read_to_string(dir.join("input").join(relative)).unwrap(), SOURCE);
+    }
+    // Without --copy-assets, non-.rs files stay behind.
+    assert!(!dir.join("stripped/a/data.txt").exists());
+}
+
+#[test]
+fn copy_assets_brings_non_rs_files_along() {
+    let dir = scratch_tree("outdir-assets");
+    let config = Config {
+        input: dir.join("input"),
+        out_dir: Some(dir.join("stripped")),
+        copy_assets: true,
+        recursive: true,
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+
+    assert_eq!(fs::read_to_string(dir.join("stripped/a/data.txt")).unwrap(), "not rust\n");
+}
+
+#[test]
+fn out_dir_inside_the_input_directory_is_refused() {
+    let dir = scratch_tree("outdir-nested");
+    let config = Config {
+        input: dir.join("input"),
+        out_dir: Some(dir.join("input/stripped")),
+        recursive: true,
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    assert!(err.to_string().contains("inside the input directory"), "{}", err);
+    // Nothing was processed or written.
+    assert!(!dir.join("input/stripped/top.rs").exists());
+}
+
+#[test]
+fn builder_requires_recursive_for_out_dir() {
+    let err = ConfigBuilder::new("src").out_dir("stripped").build().unwrap_err();
+    assert!(err.to_string().contains("recursive"), "{}", err);
+}
+
+#[test]
+fn builder_requires_out_dir_for_copy_assets() {
+    let err = ConfigBuilder::new("src").recursive().copy_assets().build().unwrap_err();
+    assert!(err.to_string().contains("out_dir"), "{}", err);
+}
@@ -0,0 +1,138 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+use std::process::Command;
+
+use vstrip::package::discover;
+
+fn vstrip(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_vstrip")).args(args).output().unwrap()
+}
+
+const LIB: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 1 }\n\n} // verus!\n";
+
+/// A workspace with an `app` package that exercises every discovery rule:
+/// declared and conventional targets, module following (including `#[path]`
+/// out of `src/`), a path dependency, and a stray file belonging to nobody.
+fn fixture(name: &str) -> PathBuf {
+    let ws = scratch(name);
+    fs::create_dir_all(ws.join("app/src/bin")).unwrap();
+    fs::create_dir_all(ws.join("app/extra")).unwrap();
+    fs::create_dir_all(ws.join("app/tests")).unwrap();
+    fs::create_dir_all(ws.join("util/src")).unwrap();
+    fs::write(
+        ws.join("app/Cargo.toml"),
+        "[package]\n\
+         name = \"app\"\n\n\
+         [[bin]]\n\
+         name = \"tool\"\n\
+         path = \"src/bin/tool.rs\"\n\n\
+         [dependencies]\n\
+         util = { path = \"../util\" }\n\
+         serde = \"1.0\"\n",
+    )
+    .unwrap();
+    fs::write(
+        ws.join("app/src/lib.rs"),
+        "mod helpers;\n\
+         #[path = \"../extra/generated.rs\"]\n\
+         mod generated;\n\
+         mod platform {\n    mod imp;\n}\n",
+    )
+    .unwrap();
+    fs::create_dir_all(ws.join("app/src/platform")).unwrap();
+    fs::write(ws.join("app/src/helpers.rs"), LIB).unwrap();
+    fs::write(ws.join("app/src/platform/imp.rs"), LIB).unwrap();
+    fs::write(ws.join("app/extra/generated.rs"), LIB).unwrap();
+    fs::write(ws.join("app/src/bin/tool.rs"), "fn main() {}\n").unwrap();
+    fs::write(ws.join("app/tests/integration.rs"), LIB).unwrap();
+    fs::write(ws.join("util/Cargo.toml"), "[package]\nname = \"util\"\n").unwrap();
+    fs::write(ws.join("util/src/lib.rs"), LIB).unwrap();
+    // Not reachable from any target or manifest; must be left alone.
+    fs::write(ws.join("stray.rs"), LIB).unwrap();
+    ws
+}
+
+#[test]
+fn discover_finds_targets_modules_and_path_dependencies() {
+    let ws = fixture("package-discover");
+    let packages = discover(&ws.join("app")).unwrap();
+    assert_eq!(packages.len(), 2);
+    assert_eq!(packages[0].name, "app");
+    assert_eq!(packages[1].name, "util");
+
+    let app_root = fs::canonicalize(ws.join("app")).unwrap();
+    let relative: Vec<String> = packages[0]
+        .files
+        .iter()
+        .map(|f| {
+            fs::canonicalize(f)
+                .unwrap()
+                .strip_prefix(&app_root)
+                .unwrap()
+                .display()
+                .to_string()
+        })
+        .collect();
+    for expected in [
+        "src/lib.rs",
+        "src/helpers.rs",
+        "src/platform/imp.rs",
+        "extra/generated.rs",
+        "src/bin/tool.rs",
+        "tests/integration.rs",
+    ] {
+        assert!(relative.contains(&expected.to_string()), "{} missing from {:?}", expected, relative);
+    }
+    assert!(!relative.iter().any(|f| f.contains("stray")), "{:?}", relative);
+    assert_eq!(packages[1].files, vec![packages[1].root.join("src/lib.rs")]);
+}
+
+#[test]
+fn package_mode_mirrors_each_package_under_out_dir() {
+    let ws = fixture("package-out-dir");
+    let out = ws.join("out");
+    let output = vstrip(&[
+        "--package",
+        ws.join("app").to_str().unwrap(),
+        "--out-dir",
+        out.to_str().unwrap(),
+    ]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    // The path dependency lies outside the package root, so both packages
+    // mirror from their common ancestor.
+    for written in ["app/src/helpers.rs", "app/extra/generated.rs", "util/src/lib.rs"] {
+        let text = fs::read_to_string(out.join(written)).unwrap();
+        assert!(!text.contains("spec fn"), "{}: {}", written, text);
+    }
+    // The stray file was neither stripped nor copied.
+    assert!(fs::read_to_string(ws.join("stray.rs")).unwrap().contains("spec fn"));
+    assert!(!out.join("stray.rs").exists());
+}
+
+#[test]
+fn missing_and_malformed_manifests_are_clear_errors() {
+    let dir = scratch("package-errors");
+    let output = vstrip(&["--package", dir.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("Cargo.toml"));
+
+    fs::write(dir.join("Cargo.toml"), "[package\nname =\n").unwrap();
+    let output = vstrip(&["--package", dir.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("malformed manifest"), "{}", stderr);
+
+    // A declared target that does not exist is a manifest problem too.
+    fs::write(
+        dir.join("Cargo.toml"),
+        "[package]\nname = \"broken\"\n\n[[bin]]\nname = \"gone\"\npath = \"src/gone.rs\"\n",
+    )
+    .unwrap();
+    let output = vstrip(&["--package", dir.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("src/gone.rs"), "{}", stderr);
+}
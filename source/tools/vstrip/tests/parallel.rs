@@ -0,0 +1,125 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::sync::Mutex;
+
+use vstrip::reporter::{EventContext, Reporter, SilentReporter};
+use vstrip::{process_with_reporter, Config, Level};
+
+/// Records every event's kind and message; order is not asserted, since
+/// parallel workers interleave.
+struct CapturingReporter {
+    events: Mutex<Vec<(&'static str, String)>>,
+}
+
+impl CapturingReporter {
+    fn new() -> CapturingReporter {
+        CapturingReporter { events: Mutex::new(Vec::new()) }
+    }
+
+    fn messages_of(&self, kind: &str) -> Vec<String> {
+        self.events
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(k, _)| *k == kind)
+            .map(|(_, m)| m.clone())
+            .collect()
+    }
+}
+
+impl Reporter for CapturingReporter {
+    fn event(&self, _level: Level, message: &str, context: &EventContext<'_>) {
+        self.events.lock().unwrap().push((context.kind, message.to_string()));
+    }
+}
+
+const SOURCE: &str = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+#[test]
+fn parallel_runs_process_every_file() {
+    let dir = scratch("parallel-ok");
+    for i in 0..6 {
+        fs::write(dir.join(format!("m{}.rs", i)), SOURCE).unwrap();
+    }
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        parallel_jobs: Some(2),
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+    for i in 0..6 {
+        let stripped = fs::read_to_string(dir.join(format!("m{}.rs", i))).unwrap();
+        assert!(!stripped.contains("spec fn"));
+        assert!(stripped.contains("pub fn f()"));
+    }
+}
+
+#[test]
+fn parallel_failures_accumulate_without_short_circuiting() {
+    let dir = scratch("parallel-err");
+    fs::write(dir.join("good_a.rs"), SOURCE).unwrap();
+    fs::write(dir.join("bad.rs"), "fn broken(\n").unwrap();
+    fs::write(dir.join("good_b.rs"), SOURCE).unwrap();
+    let config = Config {
+        input: dir.clone(),
+        in_place: true,
+        recursive: true,
+        parallel_jobs: Some(0),
+        ..Config::default()
+    };
+    let reporter = CapturingReporter::new();
+    let result = process_with_reporter(&config, &reporter);
+    let rendered = result.unwrap_err().to_string();
+    assert!(rendered.contains("1 file(s) failed:"), "{}", rendered);
+    assert!(rendered.contains("bad.rs"), "{}", rendered);
+    // The failure did not stop the other files from being rewritten.
+    assert!(!fs::read_to_string(dir.join("good_a.rs")).unwrap().contains("spec fn"));
+    assert!(!fs::read_to_string(dir.join("good_b.rs")).unwrap().contains("spec fn"));
+    assert_eq!(reporter.messages_of("file-error").len(), 1);
+    assert_eq!(reporter.messages_of("summary"), vec!["2 file(s) processed, 1 error(s), 0 skipped, 0 ignored"]);
+}
+
+#[test]
+fn jobs_and_cache_are_mutually_exclusive() {
+    let dir = scratch("parallel-cache");
+    let config = Config {
+        input: dir.clone(),
+        recursive: true,
+        check: true,
+        parallel_jobs: Some(2),
+        cache: Some(dir.join("cache.json")),
+        ..Config::default()
+    };
+    let err = process_with_reporter(&config, &SilentReporter).unwrap_err();
+    assert!(err.to_string().contains("--cache"));
+}
+
+#[test]
+fn parallel_check_leaves_files_alone_and_counts_exactly() {
+    let dir = scratch("parallel-check");
+    for i in 0..6 {
+        fs::write(dir.join(format!("m{}.rs", i)), SOURCE).unwrap();
+    }
+    let config = Config {
+        input: dir.clone(),
+        check: true,
+        recursive: true,
+        parallel_jobs: Some(3),
+        ..Config::default()
+    };
+    let reporter = CapturingReporter::new();
+    // Every file contains a spec fn, so check mode reports them all as
+    // would-be-stripped and the run fails.
+    let err = process_with_reporter(&config, &reporter).unwrap_err();
+    assert!(err.to_string().contains("6 file(s) would be changed"), "{}", err);
+    assert_eq!(reporter.messages_of("check-would-strip").len(), 6);
+    assert_eq!(reporter.messages_of("summary"), vec!["6 file(s) processed, 0 error(s), 0 skipped, 0 ignored"]);
+    for i in 0..6 {
+        // Check mode writes nothing, from any thread.
+        assert_eq!(fs::read_to_string(dir.join(format!("m{}.rs", i))).unwrap(), SOURCE);
+    }
+}
@@ -0,0 +1,114 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+
+use vstrip::reporter::SilentReporter;
+use vstrip::{process_with_reporter, Config, ConfigBuilder};
+
+const PROOFS_ONLY: &str =
+    "verus! {\n\nspec fn one() -> int { 1 }\n\nproof fn lemma_one() {\n    assert(one() == 1);\n}\n\n} // verus!\n";
+
+/// A crate with a two-level module tree whose leaves hold only proof code:
+/// `lib.rs` declares `math` (file form) and `geo` (directory form), and each
+/// of those declares a proofs-only leaf.
+fn scratch_crate(name: &str) -> PathBuf {
+    let dir = scratch(name);
+    fs::create_dir_all(dir.join("src/math")).unwrap();
+    fs::create_dir_all(dir.join("src/geo")).unwrap();
+    fs::write(
+        dir.join("src/lib.rs"),
+        "mod geo;\nmod math;\n\npub fn entry() -> u32 {\n    math::double(2)\n}\n",
+    )
+    .unwrap();
+    fs::write(
+        dir.join("src/math.rs"),
+        "mod proofs;\n\npub fn double(x: u32) -> u32 {\n    x * 2\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/math/proofs.rs"), PROOFS_ONLY).unwrap();
+    fs::write(
+        dir.join("src/geo/mod.rs"),
+        "mod lemmas;\n\npub fn area(w: u32, h: u32) -> u32 {\n    w * h\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/geo/lemmas.rs"), PROOFS_ONLY).unwrap();
+    dir
+}
+
+#[test]
+fn emptied_files_and_their_mod_decls_are_removed() {
+    let dir = scratch_crate("remove-empty");
+    let config = Config {
+        input: dir.join("src"),
+        in_place: true,
+        recursive: true,
+        remove_empty: true,
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+
+    assert!(!dir.join("src/math/proofs.rs").exists());
+    assert!(!dir.join("src/geo/lemmas.rs").exists());
+    let math = fs::read_to_string(dir.join("src/math.rs")).unwrap();
+    assert!(!math.contains("mod proofs"), "{}", math);
+    assert!(math.contains("pub fn double"), "{}", math);
+    let geo = fs::read_to_string(dir.join("src/geo/mod.rs")).unwrap();
+    assert!(!geo.contains("mod lemmas"), "{}", geo);
+    // The modules that kept real code stay declared.
+    let lib = fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+    assert!(lib.contains("mod math;"), "{}", lib);
+    assert!(lib.contains("mod geo;"), "{}", lib);
+}
+
+#[test]
+fn out_dir_mirrors_lose_their_emptied_files() {
+    let dir = scratch_crate("remove-empty-outdir");
+    let config = Config {
+        input: dir.join("src"),
+        out_dir: Some(dir.join("stripped")),
+        recursive: true,
+        remove_empty: true,
+        ..Config::default()
+    };
+    process_with_reporter(&config, &SilentReporter).unwrap();
+
+    assert!(!dir.join("stripped/math/proofs.rs").exists());
+    let math = fs::read_to_string(dir.join("stripped/math.rs")).unwrap();
+    assert!(!math.contains("mod proofs"), "{}", math);
+    // The originals keep both the file and its declaration.
+    assert!(dir.join("src/math/proofs.rs").exists());
+    let original = fs::read_to_string(dir.join("src/math.rs")).unwrap();
+    assert!(original.contains("mod proofs;"), "{}", original);
+}
+
+#[test]
+fn pub_mod_re_exports_are_warned_about_not_broken() {
+    let dir = scratch_crate("remove-empty-pub");
+    fs::write(
+        dir.join("src/lib.rs"),
+        "mod geo;\nmod math;\npub mod api;\n\npub fn entry() -> u32 {\n    math::double(2)\n}\n",
+    )
+    .unwrap();
+    fs::write(dir.join("src/api.rs"), PROOFS_ONLY).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--recursive", "--in-place", "--remove-empty"])
+        .arg(dir.join("src"))
+        .output()
+        .unwrap();
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("pub mod api"), "{}", stderr);
+    // The re-exported module survives, empty, with its declaration.
+    assert!(dir.join("src/api.rs").exists());
+    let lib = fs::read_to_string(dir.join("src/lib.rs")).unwrap();
+    assert!(lib.contains("pub mod api;"), "{}", lib);
+}
+
+#[test]
+fn builder_requires_a_write_mode_for_remove_empty() {
+    let err = ConfigBuilder::new("src").recursive().remove_empty().build().unwrap_err();
+    assert!(err.to_string().contains("in_place or out_dir"), "{}", err);
+}
@@ -0,0 +1,162 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use vstrip::reporter::{EventContext, Reporter};
+use vstrip::{process_with_reporter, Config, Level};
+
+/// Records (level, kind) for every event, in order.
+struct CapturingReporter {
+    events: Mutex<Vec<(Level, &'static str)>>,
+}
+
+impl CapturingReporter {
+    fn new() -> CapturingReporter {
+        CapturingReporter { events: Mutex::new(Vec::new()) }
+    }
+
+    fn kinds(&self) -> Vec<(Level, &'static str)> {
+        self.events.lock().unwrap().clone()
+    }
+}
+
+impl Reporter for CapturingReporter {
+    fn event(&self, level: Level, _message: &str, context: &EventContext<'_>) {
+        self.events.lock().unwrap().push((level, context.kind));
+    }
+}
+
+fn recursive_in_place(input: PathBuf) -> Config {
+    Config { input, in_place: true, recursive: true, ..Config::default() }
+}
+
+#[test]
+fn directory_runs_emit_the_expected_event_sequence() {
+    let dir = scratch("reporter-ok");
+    fs::write(dir.join("lib.rs"), "verus! {\n\nspec fn s() -> int { 1 }\n\nfn e() {}\n\n} // verus!\n").unwrap();
+
+    let reporter = CapturingReporter::new();
+    process_with_reporter(&recursive_in_place(dir), &reporter).unwrap();
+
+    assert_eq!(
+        reporter.kinds(),
+        vec![
+            (Level::Info, "file-start"),
+            (Level::Debug, "strip-report"),
+            (Level::Debug, "stripped-item"),
+            (Level::Info, "summary"),
+        ],
+    );
+}
+
+#[test]
+fn per_file_failures_become_error_events() {
+    let dir = scratch("reporter-err");
+    fs::write(dir.join("broken.rs"), "fn incomplete(\n").unwrap();
+
+    let reporter = CapturingReporter::new();
+    let result = process_with_reporter(&recursive_in_place(dir), &reporter);
+
+    assert!(result.is_err());
+    assert_eq!(
+        reporter.kinds(),
+        vec![
+            (Level::Info, "file-start"),
+            (Level::Error, "file-error"),
+            (Level::Info, "summary"),
+        ],
+    );
+}
+
+#[test]
+fn check_runs_report_clean_files() {
+    let dir = scratch("reporter-check");
+    fs::write(dir.join("ok.rs"), "fn e() {}\n").unwrap();
+    let config = Config { check: true, ..recursive_in_place(dir) };
+
+    let reporter = CapturingReporter::new();
+    process_with_reporter(&config, &reporter).unwrap();
+
+    assert_eq!(
+        reporter.kinds(),
+        vec![
+            (Level::Info, "file-start"),
+            (Level::Debug, "strip-report"),
+            (Level::Info, "check-clean"),
+            (Level::Info, "summary"),
+        ],
+    );
+}
+
+#[test]
+fn stripped_item_events_name_the_item_and_its_line() {
+    let source = "verus! {\n\nspec fn s() -> int { 1 }\n\nfn e() {}\n\n} // verus!\n";
+    let result = vstrip::strip_source_detailed(source, &Config::default()).unwrap();
+    assert_eq!(result.stripped_items.len(), 1);
+    assert_eq!(result.stripped_items[0].name, "s");
+    // Unwrapping verus! {} preserves line numbers, so the span is the
+    // original file's.
+    assert_eq!(result.stripped_items[0].line, 3);
+
+    let dir = scratch("reporter-item-lines");
+    let path = dir.join("lib.rs");
+    fs::write(&path, source).unwrap();
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--check", "-vv"])
+        .arg(&path)
+        .output()
+        .unwrap();
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains(&format!("{}:3: removed spec fn s", path.display())), "{}", stderr);
+}
+
+#[test]
+fn quiet_silences_warnings_but_not_errors() {
+    let dir = scratch("reporter-quiet");
+    let path = dir.join("lib.rs");
+    // Dropping a ghost parameter warns; --quiet swallows the warning.
+    fs::write(&path, "verus! {\n\nfn f(x: u32, credit: Tracked<int>) -> u32 { x }\n\n} // verus!\n")
+        .unwrap();
+    let run = |extra: &[&str]| {
+        let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+            .args(extra)
+            .args(["--in-place"])
+            .arg(&path)
+            .output()
+            .unwrap();
+        String::from_utf8(output.stderr).unwrap()
+    };
+    assert!(run(&[]).contains("warning:"));
+    fs::write(&path, "verus! {\n\nfn f(x: u32, credit: Tracked<int>) -> u32 { x }\n\n} // verus!\n")
+        .unwrap();
+    assert!(run(&["--quiet"]).is_empty());
+
+    // Errors still come through.
+    fs::write(&path, "fn incomplete(\n").unwrap();
+    assert!(run(&["--quiet"]).contains("error:"));
+}
+
+#[test]
+fn check_runs_flag_files_stripping_would_change() {
+    let dir = scratch("reporter-check-dirty");
+    fs::write(dir.join("dirty.rs"), "verus! {\n\nspec fn s() -> int { 1 }\n\nfn e() {}\n\n} // verus!\n").unwrap();
+    let config = Config { check: true, ..recursive_in_place(dir) };
+
+    let reporter = CapturingReporter::new();
+    let err = process_with_reporter(&config, &reporter).unwrap_err();
+
+    assert!(err.to_string().contains("would be changed"), "{}", err);
+    assert_eq!(
+        reporter.kinds(),
+        vec![
+            (Level::Info, "file-start"),
+            (Level::Debug, "strip-report"),
+            (Level::Debug, "stripped-item"),
+            (Level::Warn, "check-would-strip"),
+            (Level::Info, "summary"),
+        ],
+    );
+}
@@ -0,0 +1,53 @@
+use vstrip::rewrap::{rewrap_source, UNMATCHED_MARKER};
+use vstrip::{strip_source, Config};
+
+const ORIGINAL: &str = include_str!("fixtures/complex_specs.rs");
+
+#[test]
+fn rewrapped_output_mirrors_the_original_layout() {
+    let stripped = strip_source(ORIGINAL, &Config::default()).unwrap();
+    let rewrapped = rewrap_source(&stripped, ORIGINAL).unwrap();
+
+    // The leading import stays outside the wrapper, everything else inside.
+    let use_pos = rewrapped.find("use std::fmt;").unwrap();
+    let open_pos = rewrapped.find("verus! {").unwrap();
+    let close_pos = rewrapped.find("} // verus!").unwrap();
+    assert!(use_pos < open_pos);
+
+    // Surviving items appear in the original's order, inside the wrapper.
+    let struct_pos = rewrapped.find("pub struct Counter").unwrap();
+    let fn_pos = rewrapped.find("pub fn increment").unwrap();
+    let impl_pos = rewrapped.find("impl Counter").unwrap();
+    assert!(open_pos < struct_pos);
+    assert!(struct_pos < fn_pos);
+    assert!(fn_pos < impl_pos);
+    assert!(impl_pos < close_pos);
+
+    // The only differences from the original are the removed spec constructs.
+    assert!(!rewrapped.contains("spec_len"));
+    assert!(!rewrapped.contains("lemma_increment_monotone"));
+    assert!(!rewrapped.contains("well_formed"));
+    assert!(!rewrapped.contains(UNMATCHED_MARKER));
+}
+
+#[test]
+fn items_missing_from_the_original_are_appended_with_a_marker() {
+    let mut stripped = strip_source(ORIGINAL, &Config::default()).unwrap();
+    stripped.push_str("\nfn brand_new() {}\n");
+    let rewrapped = rewrap_source(&stripped, ORIGINAL).unwrap();
+
+    let marker_pos = rewrapped.find(UNMATCHED_MARKER).unwrap();
+    let new_pos = rewrapped.find("fn brand_new").unwrap();
+    let close_pos = rewrapped.find("} // verus!").unwrap();
+    assert!(close_pos < marker_pos);
+    assert!(marker_pos < new_pos);
+}
+
+#[test]
+fn originals_without_a_wrapper_are_just_reordered() {
+    let original = "fn b() {}\nfn a() {}\n";
+    let stripped = "fn a() {}\nfn b() {}\n";
+    let rewrapped = rewrap_source(stripped, original).unwrap();
+    assert!(!rewrapped.contains("verus!"));
+    assert!(rewrapped.find("fn b").unwrap() < rewrapped.find("fn a").unwrap());
+}
@@ -0,0 +1,82 @@
+//! Self-hosting regression: strip the SQL example shipped in this repository
+//! and check the output against plain `rustc`, with no vstd on the path.
+//!
+//! The example is stripped with [`strip_files`] so ghost parameters and
+//! ghost return elements dropped in one file have their call sites rewritten
+//! in the others, and the result must *compile*. When stripping regresses —
+//! a new construct survives, a call-site rewrite stops firing — the rustc
+//! gate here fails before any downstream consumer sees broken output.
+#![cfg(feature = "example-regression")]
+
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use vstrip::{strip_file, strip_files, Config};
+
+fn examples_dir() -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR")).join("../../../examples")
+}
+
+fn strip_into(src: &Path, dst: &Path) {
+    let stripped = strip_file(src, &Config::default())
+        .unwrap_or_else(|e| panic!("stripping {} failed: {}", src.display(), e));
+    fs::write(dst, stripped).unwrap();
+}
+
+#[test]
+fn stripped_sql_example_has_no_verus_residue_in_text() {
+    let dir = scratch("selfhost-text");
+
+    let sql = dir.join("sql.rs");
+    strip_into(&examples_dir().join("sql.rs"), &sql);
+    let text = fs::read_to_string(&sql).unwrap();
+    assert!(!text.contains("use vstd"), "vstd import survived stripping");
+    assert!(!text.contains("verus!"));
+    assert!(!text.contains("proof"));
+
+    let model = dir.join("relational_model.rs");
+    strip_into(&examples_dir().join("sql_spec/relational_model.rs"), &model);
+    let text = fs::read_to_string(&model).unwrap();
+    assert!(!text.contains("tracked struct"), "data mode survived stripping");
+    assert!(!text.contains("use vstd"));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn stripped_sql_example_compiles_without_vstd() {
+    let dir = scratch("selfhost");
+    fs::create_dir_all(dir.join("sql_spec")).unwrap();
+
+    let examples = examples_dir();
+    let mut paths = vec![examples.join("sql.rs")];
+    for entry in fs::read_dir(examples.join("sql_spec")).unwrap() {
+        let path = entry.unwrap().path();
+        if path.extension().is_some_and(|e| e == "rs") {
+            paths.push(path);
+        }
+    }
+    let results = strip_files(&paths, &Config::default())
+        .unwrap_or_else(|e| panic!("stripping the SQL example failed: {}", e));
+    for (path, result) in paths.iter().zip(results) {
+        let relative = path.strip_prefix(&examples).unwrap();
+        fs::write(dir.join(relative), result.output).unwrap();
+    }
+
+    let output = Command::new("rustc")
+        .args(["--edition=2021", "--crate-type", "lib", "--emit=metadata", "sql.rs"])
+        .current_dir(&dir)
+        .output()
+        .expect("rustc not found");
+    assert!(
+        output.status.success(),
+        "stripped SQL example no longer compiles:\n{}",
+        String::from_utf8_lossy(&output.stderr)
+    );
+
+    fs::remove_dir_all(&dir).ok();
+}
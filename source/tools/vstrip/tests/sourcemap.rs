@@ -0,0 +1,127 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+
+use vstrip::sourcemap::sidecar_path;
+use vstrip::{strip_source_detailed, Config};
+
+const SOURCE: &str = "\
+verus! {
+
+spec fn ghost_only(x: int) -> int {
+    x + 1
+}
+
+pub fn first() -> u32 {
+    1
+}
+
+proof fn lemma_gone() {
+}
+
+pub fn second() -> u32 {
+    2
+}
+
+} // verus!
+";
+
+#[test]
+fn anchors_point_at_identifiers_in_the_output() {
+    let result = strip_source_detailed(SOURCE, &Config::default()).unwrap();
+    let map = &result.source_map;
+    assert_eq!(map.entries.len(), 2);
+    for entry in &map.entries {
+        assert!(entry.output_byte_offset < result.output.len());
+    }
+    assert!(result.output[map.entries[0].output_byte_offset..].starts_with("first"));
+    assert!(result.output[map.entries[1].output_byte_offset..].starts_with("second"));
+    // `pub fn first` is on line 7 of the original, `pub fn second` on line 14.
+    assert_eq!(map.entries[0].original_line, 7);
+    assert_eq!(map.entries[1].original_line, 14);
+}
+
+#[test]
+fn positions_resolve_to_the_nearest_preceding_anchor() {
+    let result = strip_source_detailed(SOURCE, &Config::default()).unwrap();
+    let map = &result.source_map;
+    let second_offset = map.entries[1].output_byte_offset;
+    assert_eq!(map.original_position_for(second_offset + 10), Some((14, 7)));
+    assert_eq!(
+        map.original_position_for(map.entries[0].output_byte_offset),
+        Some((7, 7)),
+    );
+    assert_eq!(map.original_position_for(0), None);
+}
+
+#[test]
+fn in_place_runs_write_a_sourcemap_sidecar() {
+    let dir = scratch("sourcemap-sidecar");
+    let path = dir.join("lib.rs");
+    fs::write(&path, SOURCE).unwrap();
+    let config = Config {
+        input: path.clone(),
+        in_place: true,
+        emit_source_map: true,
+        ..Config::default()
+    };
+    vstrip::process(&config).unwrap();
+    let sidecar = sidecar_path(&path);
+    assert_eq!(sidecar, dir.join("lib.rs.sourcemap.json"));
+    let json = fs::read_to_string(&sidecar).unwrap();
+    let parsed: serde_json::Value = serde_json::from_str(&json).unwrap();
+    let entries = parsed["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["original_line"], 7);
+    assert!(entries[0]["output_byte_offset"].is_u64());
+}
+
+#[test]
+fn line_maps_collapse_anchors_to_lines() {
+    let result = strip_source_detailed(SOURCE, &Config::default()).unwrap();
+    let map = result.source_map.to_line_map(&result.output);
+    assert_eq!(map.entries.len(), 2);
+    // `pub fn first` opens the output, and its identifier stood on line 7
+    // of the annotated original.
+    assert_eq!(map.entries[0].output_line, 1);
+    assert_eq!(map.entries[0].original_line, 7);
+    assert_eq!(map.entries[1].original_line, 14);
+    assert!(map.entries[1].output_line > map.entries[0].output_line);
+}
+
+#[test]
+fn recursive_runs_write_one_line_map_document() {
+    let dir = scratch("sourcemap-linemap");
+    fs::write(dir.join("a.rs"), SOURCE).unwrap();
+    fs::write(dir.join("b.rs"), SOURCE).unwrap();
+    let map_path = dir.join("lines.json");
+    let config = Config {
+        input: dir.clone(),
+        recursive: true,
+        in_place: true,
+        line_map: Some(map_path.clone()),
+        ..Config::default()
+    };
+    vstrip::process(&config).unwrap();
+    let parsed: serde_json::Value =
+        serde_json::from_str(&fs::read_to_string(&map_path).unwrap()).unwrap();
+    let tables = parsed.as_object().unwrap();
+    assert_eq!(tables.len(), 2);
+    let entries =
+        tables[&dir.join("a.rs").display().to_string()]["entries"].as_array().unwrap();
+    assert_eq!(entries.len(), 2);
+    assert_eq!(entries[0]["output_line"], 1);
+    assert_eq!(entries[0]["original_line"], 7);
+}
+
+#[test]
+fn line_maps_need_written_output() {
+    let err = vstrip::ConfigBuilder::new("src")
+        .recursive()
+        .check()
+        .line_map("lines.json")
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("line_map"), "{}", err);
+}
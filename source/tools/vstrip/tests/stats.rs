@@ -0,0 +1,120 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::process::Command;
+
+use vstrip::{strip_source_detailed, Config};
+
+// One of everything the statistics count.
+const FIXTURE: &str = r#"
+verus! {
+
+spec fn square(x: int) -> int { x * x }
+
+proof fn lemma_square_nonneg(x: int)
+    ensures square(x) >= 0,
+{
+}
+
+struct Counter {
+    value: u32,
+    ghost history: Seq<int>,
+}
+
+fn bump(c: &mut Counter, credit: Tracked<int>) -> (r: u32)
+    requires old(c).value < 1000,
+    ensures r == old(c).value + 1,
+{
+    let ghost before = c.value;
+    proof {
+        assert(before < 1000);
+    }
+    assert(c.value < 1000);
+    let mut i: u32 = 0;
+    while i < c.value
+        invariant i <= c.value,
+        decreases c.value - i,
+    {
+        i = i + 1;
+    }
+    c.value = c.value + 1;
+    c.value
+}
+
+} // verus!
+"#;
+
+#[test]
+fn every_category_is_counted() {
+    let result = strip_source_detailed(FIXTURE, &Config::default()).unwrap();
+    let stats = result.stats;
+    assert_eq!(stats.spec_fns, 1);
+    assert_eq!(stats.proof_fns, 1);
+    assert_eq!(stats.requires_clauses, 1);
+    assert_eq!(stats.ensures_clauses, 1);
+    assert_eq!(stats.ghost_locals, 1);
+    assert_eq!(stats.ghost_fields, 1);
+    // The proof block is removed whole; the assert inside it is not counted
+    // on top.
+    assert_eq!(stats.proof_blocks, 1);
+    assert_eq!(stats.assert_assume_exprs, 1);
+    assert_eq!(stats.ghost_params, 1);
+    assert_eq!(stats.loop_clauses, 2);
+}
+
+#[test]
+fn removed_spec_fn_clauses_are_not_double_counted() {
+    // The lemma's ensures clause vanishes with the lemma itself and must not
+    // inflate the clause counters.
+    let source = "verus! {\n\nproof fn lemma()\n    ensures true,\n{\n}\n\n} // verus!\n";
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert_eq!(result.stats.proof_fns, 1);
+    assert_eq!(result.stats.ensures_clauses, 0);
+}
+
+fn vstrip(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_vstrip")).args(args).output().unwrap()
+}
+
+#[test]
+fn stats_json_is_an_array_of_per_file_objects() {
+    let dir = scratch("stats-json");
+    let path = dir.join("lib.rs");
+    fs::write(&path, FIXTURE).unwrap();
+
+    // --check exits non-zero for a file stripping would change; the report is
+    // printed regardless.
+    let output = vstrip(&["--check", "--stats=json", path.to_str().unwrap()]);
+    let parsed: serde_json::Value = serde_json::from_slice(&output.stdout).unwrap();
+    let entries = parsed.as_array().unwrap();
+    assert_eq!(entries.len(), 1);
+    assert_eq!(entries[0]["counts"]["spec_fns"], 1);
+    assert_eq!(entries[0]["counts"]["ghost_params"], 1);
+    assert_eq!(entries[0]["bytes_before"].as_u64().unwrap(), FIXTURE.len() as u64);
+    assert!(entries[0]["bytes_after"].as_u64().unwrap() < FIXTURE.len() as u64);
+}
+
+#[test]
+fn stats_text_prints_an_aggregate_table() {
+    let dir = scratch("stats-text");
+    fs::write(dir.join("a.rs"), FIXTURE).unwrap();
+    fs::write(dir.join("b.rs"), FIXTURE).unwrap();
+
+    let output = vstrip(&["--check", "--stats", "--recursive", dir.to_str().unwrap()]);
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(stdout.contains("files"), "{}", stdout);
+    let spec_row = stdout.lines().find(|l| l.starts_with("spec fns")).unwrap();
+    assert!(spec_row.ends_with('2'), "{}", spec_row);
+}
+
+#[test]
+fn stats_without_a_free_stdout_is_rejected() {
+    let dir = scratch("stats-stdout");
+    let path = dir.join("lib.rs");
+    fs::write(&path, FIXTURE).unwrap();
+
+    let output = vstrip(&["--stats", path.to_str().unwrap()]);
+    assert!(!output.status.success());
+    assert!(String::from_utf8(output.stderr).unwrap().contains("--stats"));
+}
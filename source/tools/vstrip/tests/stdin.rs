@@ -0,0 +1,48 @@
+use std::io::Write;
+use std::process::{Command, Stdio};
+
+const SOURCE: &str = "use vstd::prelude::*;\n\nverus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() -> u32 { 2 }\n\n} // verus!\n";
+
+fn run_with_stdin(args: &[&str], input: &str) -> std::process::Output {
+    let mut child = Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(args)
+        .stdin(Stdio::piped())
+        .stdout(Stdio::piped())
+        .stderr(Stdio::piped())
+        .spawn()
+        .unwrap();
+    child.stdin.as_mut().unwrap().write_all(input.as_bytes()).unwrap();
+    child.wait_with_output().unwrap()
+}
+
+#[test]
+fn dash_reads_stdin_and_strips_to_stdout() {
+    let output = run_with_stdin(&["-"], SOURCE);
+    assert!(output.status.success());
+    let stdout = String::from_utf8(output.stdout).unwrap();
+    assert!(!stdout.contains("spec fn"));
+    assert!(!stdout.contains("verus!"));
+    assert!(stdout.contains("pub fn f()"));
+}
+
+#[test]
+fn stdin_rejects_file_oriented_modes() {
+    let in_place = run_with_stdin(&["--in-place", "-"], SOURCE);
+    assert!(!in_place.status.success());
+    let stderr = String::from_utf8(in_place.stderr).unwrap();
+    assert!(stderr.contains("--in-place"));
+    assert!(stderr.contains("stdin"));
+
+    let recursive = run_with_stdin(&["--recursive", "-"], SOURCE);
+    assert!(!recursive.status.success());
+    let stderr = String::from_utf8(recursive.stderr).unwrap();
+    assert!(stderr.contains("--recursive"));
+}
+
+#[test]
+fn parse_errors_from_stdin_name_the_stream() {
+    let output = run_with_stdin(&["-"], "fn broken(\n");
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("<stdin>"));
+}
@@ -0,0 +1,92 @@
+mod common;
+use common::scratch;
+
+use std::fmt::Write as _;
+use std::fs;
+
+use vstrip::{
+    strip_file_streaming, strip_file_to_writer, strip_source_streaming, strip_source_to_writer,
+    Config,
+};
+
+/// Generate a large source file of `n` verified functions, the shape that
+/// auto-generated Verus code tends to take.
+fn generate_source(n: usize) -> String {
+    let mut source = String::from("verus! {\n\n");
+    for i in 0..n {
+        writeln!(
+            source,
+            "pub fn f{i}(x: u32) -> (r: u32)\n    requires x < 1000,\n    ensures r == x,\n{{\n    let ghost g: int = x as int;\n    proof {{ assert(g >= 0); }}\n    x\n}}\n"
+        )
+        .unwrap();
+    }
+    source.push_str("} // verus!\n");
+    source
+}
+
+#[test]
+fn streaming_matches_in_memory_stripping() {
+    let source = generate_source(2000);
+    let mut streamed: Vec<u8> = Vec::new();
+    strip_source_streaming(source.as_bytes(), &mut streamed, &Config::default()).unwrap();
+    let streamed = String::from_utf8(streamed).unwrap();
+    let in_memory = vstrip::strip_source(&source, &Config::default()).unwrap();
+    assert_eq!(streamed, in_memory);
+    assert!(streamed.contains("pub fn f0"));
+    assert!(streamed.contains("pub fn f1999"));
+    assert!(!streamed.contains("ghost"));
+    assert!(!streamed.contains("requires"));
+}
+
+#[test]
+fn file_to_file_streaming() {
+    let dir = scratch("streaming");
+    let input = dir.join("big.rs");
+    let output = dir.join("big-stripped.rs");
+    fs::write(&input, generate_source(500)).unwrap();
+
+    strip_file_streaming(&input, &output, &Config::default()).unwrap();
+    let stripped = fs::read_to_string(&output).unwrap();
+    assert!(stripped.contains("pub fn f499"));
+    assert!(!stripped.contains("proof"));
+
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn writer_variants_match_the_string_returning_api() {
+    let source = generate_source(200);
+    let mut via_writer: Vec<u8> = Vec::new();
+    strip_source_to_writer(&source, &Config::default(), &mut via_writer).unwrap();
+    let in_memory = vstrip::strip_source(&source, &Config::default()).unwrap();
+    assert_eq!(String::from_utf8(via_writer).unwrap(), in_memory);
+
+    let dir = scratch("to-writer");
+    let input = dir.join("big.rs");
+    fs::write(&input, &source).unwrap();
+    let mut from_file: Vec<u8> = Vec::new();
+    strip_file_to_writer(&input, &Config::default(), &mut from_file).unwrap();
+    assert_eq!(String::from_utf8(from_file).unwrap(), in_memory);
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn file_to_writer_errors_name_the_file() {
+    let dir = scratch("writer-err");
+    let input = dir.join("broken.rs");
+    fs::write(&input, "fn broken(\n").unwrap();
+
+    let mut out: Vec<u8> = Vec::new();
+    let err = strip_file_to_writer(&input, &Config::default(), &mut out).unwrap_err();
+    assert!(err.to_string().contains("broken.rs"), "{}", err);
+    assert!(out.is_empty(), "nothing may be written on error");
+    fs::remove_dir_all(&dir).ok();
+}
+
+#[test]
+fn streaming_surfaces_parse_errors() {
+    let mut out: Vec<u8> = Vec::new();
+    let result = strip_source_streaming("fn broken(".as_bytes(), &mut out, &Config::default());
+    assert!(matches!(result, Err(vstrip::StripError::ParseError { .. })));
+    assert!(out.is_empty(), "nothing may be written on error");
+}
@@ -0,0 +1,75 @@
+//! `--strict`: Verus-only constructs the visitor has no handling for become
+//! errors instead of warnings passed through into uncompilable output.
+
+mod common;
+use common::scratch;
+
+use vstrip::{strip_source, strip_source_detailed, Config, StripError, Warning};
+
+const FIXTURE: &str = include_str!("fixtures/state_machine.rs");
+
+#[test]
+fn unhandled_macros_warn_by_default() {
+    let result = strip_source_detailed(FIXTURE, &Config::default()).unwrap();
+    // The invocation survives verbatim; the warning names it and where it is.
+    assert!(result.output.contains("state_machine!"), "{}", result.output);
+    let warning = result
+        .warnings
+        .iter()
+        .find(|w| matches!(w, Warning::UnknownVerusConstruct { .. }))
+        .expect("expected an unknown-construct warning");
+    match warning {
+        Warning::UnknownVerusConstruct { item_name, line, .. } => {
+            assert!(item_name.contains("state_machine"), "{}", item_name);
+            assert_eq!(*line, 3);
+        }
+        _ => unreachable!(),
+    }
+}
+
+#[test]
+fn strict_makes_unhandled_macros_fatal() {
+    let config = Config { strict: true, ..Config::default() };
+    let err = strip_source(FIXTURE, &config).unwrap_err();
+    match err {
+        StripError::UnsupportedConstructs(constructs) => {
+            assert_eq!(constructs.len(), 1, "{:?}", constructs);
+        }
+        other => panic!("expected UnsupportedConstructs, got {:?}", other),
+    }
+}
+
+#[test]
+fn the_error_names_the_construct_and_its_location() {
+    let config = Config { strict: true, ..Config::default() };
+    let rendered = strip_source(FIXTURE, &config).unwrap_err().to_string();
+    assert!(rendered.contains("state_machine"), "{}", rendered);
+    assert!(rendered.contains("3:1"), "{}", rendered);
+    assert!(rendered.contains("--strict"), "{}", rendered);
+}
+
+#[test]
+fn strict_passes_code_without_unhandled_constructs() {
+    let source = "verus! {\n\nspec fn s() -> int { 1 }\n\npub fn f() {}\n\n} // verus!\n";
+    let config = Config { strict: true, ..Config::default() };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(stripped.contains("pub fn f"));
+}
+
+#[test]
+fn the_cli_flag_fails_the_run() {
+    use std::fs;
+
+    let dir = scratch("strict-cli");
+    fs::write(dir.join("machine.rs"), FIXTURE).unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .arg("--check")
+        .arg("--strict")
+        .arg(dir.join("machine.rs"))
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("state_machine"), "{}", stderr);
+}
@@ -0,0 +1,1078 @@
+use vstrip::{strip_source, Config, StripError};
+
+#[test]
+fn ghost_and_tracked_locals_are_stripped() {
+    let source = r#"
+verus! {
+
+fn f() -> u32 {
+    let ghost g: int = 5;
+    let tracked t = m.tracked_remove(0);
+    let y: u32 = 5;
+    y
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("ghost"));
+    assert!(!stripped.contains("tracked"));
+    assert!(stripped.contains("let y: u32 = 5;"));
+}
+
+#[test]
+fn ghost_and_tracked_destructuring_locals_are_stripped() {
+    // `let Ghost(x) = ...` binds the contents of a wrapper value without the
+    // `ghost` keyword; the binding is a plain tuple-struct pattern, so it has
+    // to be recognized by shape rather than by modifier.
+    let source = r#"
+verus! {
+
+fn f(v: Ghost<int>, m: Tracked<Map<int, int>>) -> u32 {
+    let Ghost(g) = v;
+    let Tracked(t) = m;
+    let ghost keyword_form: int = 5;
+    let y: u32 = 5;
+    y
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("Ghost(g)"), "{}", stripped);
+    assert!(!stripped.contains("Tracked(t)"), "{}", stripped);
+    assert!(!stripped.contains("keyword_form"), "{}", stripped);
+    assert!(stripped.contains("let y: u32 = 5;"), "{}", stripped);
+}
+
+#[test]
+fn proof_let_is_not_a_recognized_form() {
+    // Verus spells ghost locals `let ghost x = ...;`; there is no `proof let`
+    // statement modifier, and verus_syn rejects it outright. Pin that down so
+    // a future grammar change shows up as a test failure rather than silently
+    // leaking unstripped ghost code.
+    let source = r#"
+verus! {
+
+fn f() -> u32 {
+    proof let x: int = 5;
+    let y: u32 = 5;
+    y
+}
+
+} // verus!
+"#;
+    match strip_source(source, &Config::default()) {
+        Err(StripError::ParseError { .. }) => {}
+        other => panic!("expected a parse error for `proof let`, got {:?}", other.map(|_| ())),
+    }
+}
+
+mod empty_body {
+    use vstrip::config::EmptyBodyPolicy;
+    use vstrip::{strip_source, Config, StripError};
+
+    // An exec function whose whole body is proof code; after stripping,
+    // `fn always_true() -> bool { }` is not valid Rust.
+    const FIXTURE: &str = r#"
+verus! {
+
+fn always_true() -> (b: bool)
+    ensures b,
+{
+    proof {
+        assert(true);
+    }
+}
+
+} // verus!
+"#;
+
+    #[test]
+    fn error_policy_names_the_function() {
+        match strip_source(FIXTURE, &Config::default()) {
+            Err(StripError::EmptyBodies(functions)) => {
+                assert_eq!(functions, ["always_true"]);
+            }
+            other => panic!("expected EmptyBodies, got {:?}", other.map(|_| ())),
+        }
+    }
+
+    #[test]
+    fn todo_policy_fills_the_body() {
+        let config = Config { empty_body: EmptyBodyPolicy::Todo, ..Config::default() };
+        let stripped = strip_source(FIXTURE, &config).unwrap();
+        assert!(stripped.contains("todo!()"));
+        assert!(!stripped.contains("assert"));
+    }
+
+    #[test]
+    fn unreachable_policy_fills_the_body() {
+        let config = Config { empty_body: EmptyBodyPolicy::Unreachable, ..Config::default() };
+        let stripped = strip_source(FIXTURE, &config).unwrap();
+        assert!(stripped.contains("unreachable!()"));
+    }
+
+    #[test]
+    fn unit_returning_functions_are_left_empty() {
+        let source = "verus! {\n\nfn nop() {\n    proof {\n        assert(true);\n    }\n}\n\n} // verus!\n";
+        let stripped = strip_source(source, &Config::default()).unwrap();
+        assert!(stripped.contains("fn nop()"));
+        assert!(!stripped.contains("todo!"));
+    }
+
+    // Trait defaults whose whole bodies are proof code: a unit-returning one
+    // and a bool-returning one that the empty-body policy would reject.
+    const TRAIT_FIXTURE: &str = r#"
+verus! {
+
+trait Checked {
+    fn audit(&self) {
+        proof {
+            assert(true);
+        }
+    }
+
+    fn valid(&self) -> (b: bool)
+        ensures b,
+    {
+        proof {
+            assert(true);
+        }
+    }
+}
+
+} // verus!
+"#;
+
+    #[test]
+    fn trait_defaults_follow_the_empty_body_policy() {
+        match strip_source(TRAIT_FIXTURE, &Config::default()) {
+            Err(StripError::EmptyBodies(functions)) => {
+                assert_eq!(functions, ["valid"]);
+            }
+            other => panic!("expected EmptyBodies, got {:?}", other.map(|_| ())),
+        }
+        let config = Config { empty_body: EmptyBodyPolicy::Todo, ..Config::default() };
+        let stripped = strip_source(TRAIT_FIXTURE, &config).unwrap();
+        assert!(stripped.contains("todo!()"));
+        // The unit-returning default keeps its (empty) body.
+        assert!(stripped.contains("fn audit(&self) {"));
+    }
+
+    #[test]
+    fn drop_empty_trait_defaults_leaves_bare_declarations() {
+        let config = Config { drop_empty_trait_defaults: true, ..Config::default() };
+        let stripped = strip_source(TRAIT_FIXTURE, &config).unwrap();
+        assert!(stripped.contains("fn audit(&self);"));
+        assert!(stripped.contains("fn valid(&self) -> bool;"));
+        assert!(!stripped.contains("todo!"));
+    }
+}
+
+#[test]
+fn attributes_only_keeps_ghost_code() {
+    let source = r#"
+verus! {
+
+#[verifier::external_body]
+fn trusted(x: u32) -> (r: u32)
+    ensures r == x,
+{
+    let ghost g: int = x as int;
+    x
+}
+
+spec fn sp(x: int) -> int {
+    x + 1
+}
+
+} // verus!
+"#;
+    let cleaned = vstrip::strip_verus_attributes_only(source, &Config::default()).unwrap();
+    assert!(!cleaned.contains("verifier"));
+    assert!(cleaned.contains("ensures"));
+    assert!(cleaned.contains("let ghost g"));
+    assert!(cleaned.contains("spec fn sp"));
+}
+
+#[test]
+fn verifier_attributes_are_stripped_by_default() {
+    let source = r#"
+verus! {
+
+#[verifier::external_body]
+fn trusted(x: u32) -> u32 {
+    x
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("verifier"), "{}", stripped);
+    assert!(stripped.contains("fn trusted"));
+
+    // Opting out keeps the attribute for tooling that still reads it.
+    let keep = Config { strip_verifier_attrs: false, ..Config::default() };
+    let kept = strip_source(source, &keep).unwrap();
+    assert!(kept.contains("#[verifier::external_body]"), "{}", kept);
+}
+
+#[test]
+fn trailing_semicolon_after_verus_block_is_consumed() {
+    let unwrapped = vstrip::preprocess::unwrap_verus_macros("verus! { fn foo() {} };\n");
+    assert!(!unwrapped.contains(';'), "stray semicolon in {:?}", unwrapped);
+    assert!(unwrapped.contains("fn foo() {}"));
+
+    // The semicolon is part of the macro statement only when it directly
+    // follows the block; unrelated semicolons survive.
+    let unwrapped = vstrip::preprocess::unwrap_verus_macros("verus! { fn foo() {} }\nuse a::b;\n");
+    assert_eq!(unwrapped.matches(';').count(), 1);
+}
+
+#[test]
+fn spec_and_proof_consts_are_removed() {
+    let source = include_str!("fixtures/spec_const.rs");
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("SPEC_LIMIT"), "{}", stripped);
+    assert!(!stripped.contains("WITNESS"), "{}", stripped);
+    assert!(stripped.contains("const LIMIT: u64 = 100;"), "{}", stripped);
+    assert!(stripped.contains("fn check"), "{}", stripped);
+    // An exec const survives, but its `ensures` clause is Verus-only syntax
+    // and goes the way of a signature's; the block initializer stays.
+    assert!(stripped.contains("const CAP: u64"), "{}", stripped);
+    assert!(!stripped.contains("ensures"), "{}", stripped);
+    assert!(!stripped.contains("exec"), "{}", stripped);
+    // What is left is plain Rust.
+    syn::parse_file(&stripped).unwrap();
+}
+
+#[test]
+fn broadcast_lemmas_are_removed_in_both_spellings() {
+    // The modern form is a mode (`broadcast proof fn`, removed as a proof
+    // fn); legacy code spelled the same thing as a verifier attribute on a
+    // plain `fn`, in path or list form, which only the attribute check
+    // catches.
+    let source = r#"
+verus! {
+
+broadcast proof fn my_lemma(x: u64)
+    ensures
+        x < u64::MAX || x == u64::MAX,
+{
+}
+
+#[verifier::broadcast_forall]
+#[verifier::external_body]
+fn legacy_axiom() {}
+
+#[verifier(broadcast_forall)]
+fn older_axiom() {}
+
+fn keep() {}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("my_lemma"), "{}", stripped);
+    assert!(!stripped.contains("axiom"), "{}", stripped);
+    assert!(!stripped.contains("broadcast"), "{}", stripped);
+    assert!(stripped.contains("fn keep"), "{}", stripped);
+}
+
+#[test]
+fn verus_blocks_are_unwrapped_at_every_module_depth() {
+    // One `verus!` invocation per nesting level: file root, a module, and a
+    // module inside that module. The flat scan must unwrap all three while
+    // the enclosing `mod` braces stay put.
+    let source = r#"
+verus! {
+
+spec fn top_spec(x: int) -> int { x }
+
+fn top() {}
+
+} // verus!
+
+mod outer {
+    verus! {
+
+    spec fn outer_spec(x: int) -> int { x }
+
+    fn in_outer() {}
+
+    } // verus!
+
+    mod inner {
+        verus! {
+
+        spec fn inner_spec(x: int) -> int { x }
+
+        fn in_inner() {}
+
+        } // verus!
+    }
+}
+"#;
+    let unwrapped = vstrip::preprocess::unwrap_verus_macros(source);
+    // The invocations are gone; the `// verus!` comments are untouched text
+    // and deliberately stay (the preprocessor blanks tokens in place).
+    assert!(!unwrapped.contains("verus! {"), "{:?}", unwrapped);
+
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // The module structure survives and each level's exec fn with it.
+    assert!(stripped.contains("mod outer"), "{}", stripped);
+    assert!(stripped.contains("mod inner"), "{}", stripped);
+    for survivor in ["fn top()", "fn in_outer()", "fn in_inner()"] {
+        assert!(stripped.contains(survivor), "`{}` missing from:\n{}", survivor, stripped);
+    }
+    assert!(!stripped.contains("spec"), "{}", stripped);
+}
+
+#[test]
+fn raw_strings_are_opaque_to_the_preprocessor() {
+    // The raw string embeds a plain `"` — the case where treating `"` as the
+    // only string delimiter ends the literal early and exposes the fake
+    // `verus! {` (and its unbalanced brace) to the scanner.
+    let source =
+        "const FAKE: &str = r#\"a \"quoted\" verus! { x\"#;\nverus! { fn foo() {} }\n";
+    let unwrapped = vstrip::preprocess::unwrap_verus_macros(source);
+    assert!(unwrapped.contains("r#\"a \"quoted\" verus! { x\"#"), "{:?}", unwrapped);
+    assert!(unwrapped.contains("fn foo() {}"), "{:?}", unwrapped);
+    assert!(!unwrapped.contains("verus! { fn"), "{:?}", unwrapped);
+}
+
+#[test]
+fn raw_strings_inside_a_block_do_not_derail_brace_matching() {
+    // An unbalanced `}` and an embedded `"` inside a raw string must not
+    // close the surrounding `verus!` block early.
+    let source = "verus! {\n\nfn f() -> &'static str {\n    r#\"} \"quote\" }\"#\n}\n\n} // verus!\n";
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(stripped.contains(r##"r#"} "quote" }"#"##), "{}", stripped);
+}
+
+#[test]
+fn parse_errors_point_at_original_positions() {
+    // The bad token shares a line with the `verus! {` opener — the case
+    // where deleted (rather than blanked) wrapper tokens would shift its
+    // column. The preprocessor preserves positions, so the parser's span is
+    // already an original-file position.
+    let source = "verus! { fn 123() {} }\n";
+    match strip_source(source, &Config::default()) {
+        Err(StripError::ParseError { source: e, .. }) => {
+            let start = e.span().start();
+            assert_eq!((start.line, start.column), (1, 12), "{}", e);
+        }
+        other => panic!("expected a parse error, got {:?}", other.map(|_| ())),
+    }
+}
+
+#[test]
+fn axiom_fns_are_removed_in_both_spellings() {
+    // `axiom fn` parses as `FnMode::ProofAxiom`; the attribute spelling rides
+    // on an ordinary `proof fn`. Both must disappear from the output. If a
+    // future verus_syn changes how axioms are represented, this fixture is
+    // the canary.
+    let source = r#"
+verus! {
+
+axiom fn keyword_axiom(x: int)
+    ensures x == x;
+
+#[verifier::proof_axiom]
+proof fn attribute_axiom(x: int)
+    ensures x == x;
+
+fn survivor() -> u32 {
+    7
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("keyword_axiom"));
+    assert!(!stripped.contains("attribute_axiom"));
+    assert!(!stripped.contains("axiom"));
+    assert!(stripped.contains("fn survivor"));
+}
+
+#[test]
+fn axiom_keyword_parses_as_proof_axiom_mode() {
+    // Companion representation check for the fixture above: the keyword form
+    // really does come back as `FnMode::ProofAxiom`, not as some new variant
+    // the strip visitor has never heard of.
+    let file = verus_syn::parse_file("axiom fn ax(x: int);\n").unwrap();
+    let [verus_syn::Item::Fn(func)] = &file.items[..] else {
+        panic!("expected a single fn item");
+    };
+    assert!(matches!(func.sig.mode, verus_syn::FnMode::ProofAxiom(_)));
+}
+
+#[test]
+fn verification_support_imports_are_removed() {
+    let source = r#"
+#[allow(unused_imports)]
+use vstd::prelude::*;
+use std::collections::HashMap;
+
+verus! {
+
+use vstd::seq_lib::*;
+
+fn f() -> u32 {
+    1
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("vstd"));
+    assert!(stripped.contains("use std::collections::HashMap;"));
+    assert!(stripped.contains("fn f"));
+}
+
+#[test]
+fn data_modes_are_cleared_from_surviving_items() {
+    let source = r#"
+verus! {
+
+pub struct Cap {
+    pub ghost id: int,
+    pub serial: u64,
+}
+
+ghost enum Phase {
+    Idle,
+    Running,
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("tracked"));
+    assert!(!stripped.contains("ghost"));
+    assert!(stripped.contains("pub struct Cap"));
+    assert!(stripped.contains("pub serial: u64"));
+    // A `ghost enum` declares a verification-only type: it is removed
+    // whole, not kept with its mode marker cleared.
+    assert!(!stripped.contains("enum Phase"));
+}
+
+#[test]
+fn strip_report_counts_open_and_closed_spec_fns() {
+    let source = r#"
+verus! {
+
+pub open spec fn visible(x: int) -> int {
+    x + 1
+}
+
+pub closed spec fn hidden(x: int) -> int {
+    x + 2
+}
+
+spec fn unmarked(x: int) -> int {
+    x + 3
+}
+
+proof fn lemma_something() {
+}
+
+fn exec_survivor() -> u32 {
+    4
+}
+
+} // verus!
+"#;
+    let (stripped, report) =
+        vstrip::strip_source_with_report(source, &Config::default()).unwrap();
+    assert!(stripped.contains("exec_survivor"));
+    assert_eq!(report.open_spec_fns_removed, 1);
+    // `closed` is explicit opacity; an unmarked spec fn's body is just as
+    // invisible, so both land in the closed tally.
+    assert_eq!(report.closed_spec_fns_removed, 2);
+    assert_eq!(report.to_string(), "removed 1 open and 2 closed spec fn(s)");
+}
+
+#[test]
+fn assert_by_is_stripped_in_every_statement_position() {
+    // `assert(..) by { .. }` is an `Expr::Assert` wherever it appears; every
+    // nested block goes through the same `visit_block_mut` retain pass, so
+    // loop bodies, `if` branches, and `match` arms all strip alike.
+    let source = r#"
+verus! {
+
+fn f(n: u32) -> u32 {
+    let mut i: u32 = 0;
+    while i < n {
+        assert(i <= n) by {
+            assert(true);
+        };
+        i = i + 1;
+    }
+    if i == n {
+        assert(i == n) by {
+            assert(true);
+        };
+        let j = i;
+        assert(j == i) by {
+            assert(true);
+        };
+        match j {
+            0 => {
+                assert(j == 0) by {
+                    assert(true);
+                };
+                0
+            }
+            _ => j,
+        }
+    } else {
+        0
+    }
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("assert"));
+    assert!(stripped.contains("while i < n"));
+    assert!(stripped.contains("let j = i;"));
+    assert!(stripped.contains("match j"));
+    assert!(stripped.contains("_ => j,"));
+}
+
+#[test]
+fn verus_only_derives_are_filtered_from_derive_lists() {
+    let source = r#"
+verus! {
+
+#[derive(PartialEq, Eq, Structural, Clone)]
+enum Mode {
+    Fast,
+    Careful,
+}
+
+#[derive(Structural)]
+struct Marker {
+    pub id: u64,
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(stripped.contains("#[derive(PartialEq, Eq, Clone)]"));
+    // A list that held only Verus derives leaves no attribute behind.
+    assert!(!stripped.contains("Structural"));
+    assert!(!stripped.contains("#[derive()]"));
+    assert!(stripped.contains("struct Marker"));
+}
+
+#[test]
+fn cfg_attr_wrapped_derives_get_the_same_treatment() {
+    let source = r#"
+verus! {
+
+#[cfg_attr(feature = "extras", derive(Structural, Clone))]
+struct Wrapped {
+    pub id: u64,
+}
+
+#[cfg_attr(test, derive(Structural))]
+struct OnlyGhostDerive {
+    pub id: u64,
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(stripped.contains(r#"#[cfg_attr(feature = "extras", derive(Clone))]"#));
+    // The second cfg_attr carried nothing but the removed derive.
+    assert!(!stripped.contains("cfg_attr(test"));
+    assert!(!stripped.contains("Structural"));
+}
+
+#[test]
+fn the_verus_derive_list_is_configurable() {
+    let source = r#"
+verus! {
+
+#[derive(Clone, MyGhostDerive)]
+struct Configured {
+    pub id: u64,
+}
+
+} // verus!
+"#;
+    let config = Config {
+        extra_verus_derives: vec!["MyGhostDerive".to_string()],
+        ..Config::default()
+    };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(stripped.contains("#[derive(Clone)]"));
+    assert!(!stripped.contains("MyGhostDerive"));
+}
+
+const NESTED_MODULES: &str = r#"
+verus! {
+
+mod outer {
+    spec fn outer_spec() -> int {
+        1
+    }
+
+    pub fn outer_exec() -> u32 {
+        1
+    }
+
+    mod inner {
+        spec fn inner_spec() -> int {
+            2
+        }
+
+        proof fn inner_lemma() {
+        }
+
+        pub fn inner_exec() -> u32 {
+            2
+        }
+    }
+
+    mod all_spec {
+        spec fn gone() -> int {
+            3
+        }
+    }
+}
+
+} // verus!
+"#;
+
+#[test]
+fn spec_items_are_removed_at_every_module_depth() {
+    let stripped = strip_source(NESTED_MODULES, &Config::default()).unwrap();
+    assert!(stripped.contains("mod outer"));
+    assert!(stripped.contains("pub fn outer_exec"));
+    assert!(stripped.contains("mod inner"));
+    assert!(stripped.contains("pub fn inner_exec"));
+    assert!(!stripped.contains("spec"));
+    assert!(!stripped.contains("outer_spec"));
+    assert!(!stripped.contains("inner_spec"));
+    assert!(!stripped.contains("inner_lemma"));
+    // `all_spec` lost its entire contents and goes the way of any other
+    // empty shell.
+    assert!(!stripped.contains("all_spec"));
+}
+
+#[test]
+fn emptied_nested_modules_are_kept_on_request() {
+    let config = Config { keep_empty_items: true, ..Config::default() };
+    let stripped = strip_source(NESTED_MODULES, &config).unwrap();
+    assert!(stripped.contains("mod all_spec"));
+    assert!(!stripped.contains("gone"));
+}
+
+#[test]
+fn items_nested_in_function_bodies_are_filtered() {
+    let source = r#"
+verus! {
+
+fn host() -> u32 {
+    spec fn helper() -> int {
+        1
+    }
+    fn local_exec() -> u32 {
+        6
+    }
+    local_exec() + 1
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(stripped.contains("fn host"));
+    assert!(stripped.contains("fn local_exec"));
+    assert!(!stripped.contains("helper"));
+}
+
+#[test]
+fn impls_emptied_by_stripping_disappear() {
+    let source = r#"
+verus! {
+
+struct Counter(u32);
+
+impl Counter {
+    spec fn view(self) -> int {
+        self.0 as int
+    }
+
+    proof fn lemma_view_bounded(self) {
+    }
+}
+
+struct Gauge(u32);
+
+impl Gauge {
+    spec fn view(self) -> int {
+        self.0 as int
+    }
+
+    pub fn get(&self) -> u32 {
+        self.0
+    }
+}
+
+fn host() -> u32 {
+    struct Local(u32);
+    impl Local {
+        spec fn view(self) -> int {
+            self.0 as int
+        }
+    }
+    0
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // `impl Counter` lost every member, so the hollow block goes with them;
+    // `impl Gauge` keeps its exec method and stays.
+    assert!(!stripped.contains("impl Counter"), "{}", stripped);
+    assert!(stripped.contains("impl Gauge"), "{}", stripped);
+    assert!(stripped.contains("pub fn get"), "{}", stripped);
+    // The same holds for an impl nested inside a function body.
+    assert!(!stripped.contains("impl Local"), "{}", stripped);
+    assert!(stripped.contains("struct Local"), "{}", stripped);
+}
+
+#[test]
+fn emptied_impls_are_kept_on_request() {
+    let source = r#"
+verus! {
+
+struct Counter(u32);
+
+impl Counter {
+    spec fn view(self) -> int {
+        self.0 as int
+    }
+}
+
+} // verus!
+"#;
+    let config = Config { keep_empty_items: true, ..Config::default() };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(stripped.contains("impl Counter"), "{}", stripped);
+    assert!(!stripped.contains("view"), "{}", stripped);
+}
+
+const SPEC_ONLY_TRAIT: &str = r#"
+verus! {
+
+trait SpecCompare {
+    spec fn spec_compare(&self, other: &Self) -> bool;
+}
+
+struct Value(u32);
+
+impl SpecCompare for Value {
+    spec fn spec_compare(&self, other: &Self) -> bool {
+        self.0 == other.0
+    }
+}
+
+pub fn get(v: &Value) -> u32 {
+    v.0
+}
+
+} // verus!
+"#;
+
+#[test]
+fn emptied_traits_survive_by_default() {
+    // The trait is still crate API after its spec declarations are gone, so
+    // it and its (now empty) impl survive a default strip.
+    let stripped = strip_source(SPEC_ONLY_TRAIT, &Config::default()).unwrap();
+    assert!(stripped.contains("trait SpecCompare"), "{}", stripped);
+    assert!(stripped.contains("impl SpecCompare for Value"), "{}", stripped);
+    assert!(!stripped.contains("spec_compare"), "{}", stripped);
+}
+
+#[test]
+fn remove_empty_traits_deletes_the_trait_and_its_impls() {
+    let config = Config { remove_empty_traits: true, ..Config::default() };
+    let stripped = strip_source(SPEC_ONLY_TRAIT, &config).unwrap();
+    assert!(!stripped.contains("SpecCompare"), "{}", stripped);
+    assert!(stripped.contains("struct Value"), "{}", stripped);
+    assert!(stripped.contains("pub fn get"), "{}", stripped);
+}
+
+#[test]
+fn remove_empty_traits_excludes_keep_empty_items() {
+    let err = vstrip::ConfigBuilder::new("src/lib.rs")
+        .keep_empty_items()
+        .remove_empty_traits()
+        .build()
+        .unwrap_err();
+    assert!(err.to_string().contains("keep_empty_items"), "{}", err);
+}
+
+#[test]
+fn detailed_results_list_each_removed_item() {
+    let source = r#"
+verus! {
+
+spec fn width(x: int) -> int {
+    x + 1
+}
+
+proof fn lemma_width_positive() {
+}
+
+fn survivor() -> u32 {
+    7
+}
+
+} // verus!
+"#;
+    let result = vstrip::strip_source_detailed(source, &Config::default()).unwrap();
+    // The plain entry point is a thin wrapper over the detailed one.
+    assert_eq!(result.output, strip_source(source, &Config::default()).unwrap());
+    let rendered: Vec<String> =
+        result.stripped_items.iter().map(ToString::to_string).collect();
+    assert_eq!(rendered, vec!["spec fn width", "proof fn lemma_width_positive"]);
+    assert_eq!(
+        result.stripped_items[0].kind,
+        vstrip::StrippedItemKind::SpecFn,
+    );
+    assert!(result.warnings.is_empty());
+    assert_eq!(result.report.closed_spec_fns_removed, 1);
+}
+
+#[test]
+fn stripping_is_idempotent_on_tricky_fixtures() {
+    // The string literal is the historically risky part: a second pass must
+    // not mistake `verus! {` inside it for a macro wrapper and eat the brace.
+    let source = r#"
+verus! {
+
+spec fn s(x: int) -> int {
+    x + 1
+}
+
+fn banner() -> &'static str {
+    "wrapped in verus! { ... } at build time"
+}
+
+pub fn f(x: u32) -> (r: u32)
+    requires
+        x < 1000,
+    ensures
+        r == 2 * x,
+{
+    2 * x
+}
+
+} // verus!
+"#;
+    let config = Config { check_idempotent: true, spec_as_comments: true, ..Config::default() };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(stripped.contains("wrapped in verus! { ... }"));
+    // Belt and braces: the fixed point holds outside the built-in check too.
+    assert_eq!(strip_source(&stripped, &config).unwrap(), stripped);
+}
+
+#[test]
+fn idempotency_failures_name_the_diverging_line() {
+    let err = StripError::IdempotencyError {
+        first: "fn a() {}\nfn b() {}\n".to_string(),
+        second: "fn a() {}\nfn c() {}\n".to_string(),
+    };
+    let rendered = err.to_string();
+    assert!(rendered.contains("not idempotent"));
+    assert!(rendered.contains("line 2"));
+}
+
+#[test]
+fn view_expressions_collapse_to_their_base() {
+    let source = r#"
+verus! {
+
+fn f(v: Vec<u32>) -> usize {
+    let n = v@.len() as usize;
+    n
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains('@'));
+    assert!(stripped.contains("v.len()"));
+}
+
+#[test]
+fn ghost_subexpressions_become_unit() {
+    let source = r#"
+verus! {
+
+fn f(x: u32) -> u32 {
+    let pair = (proof { assert(x < 10); }, x + 1);
+    pair.1
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("proof"));
+    assert!(!stripped.contains("assert"));
+    assert!(stripped.contains("((), x + 1)"));
+}
+
+#[test]
+fn proof_tuple_projections_fold_to_the_real_value() {
+    let source = r#"
+verus! {
+
+fn f(x: u32) -> u32 {
+    let y = (proof { assert(x < 10); }, x + 1).1;
+    y
+}
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    assert!(!stripped.contains("proof"));
+    assert!(stripped.contains("let y = x + 1;"));
+}
+
+#[test]
+fn tuple_projections_with_side_effects_are_left_alone() {
+    let source = r#"
+verus! {
+
+fn f(x: u32) -> u32 {
+    (bump_counter(), x).1
+}
+
+fn bump_counter() -> u32 { 0 }
+
+} // verus!
+"#;
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // Only all-unit siblings may be folded away; a real call stays put.
+    assert!(stripped.contains("(bump_counter(), x).1"));
+}
+
+#[test]
+fn loop_annotations_are_stripped_from_all_loop_forms() {
+    let source = include_str!("fixtures/loop_annotations.rs");
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // The loops themselves survive; only the verification clauses go.
+    assert!(stripped.contains("while i < n {"), "{}", stripped);
+    assert!(stripped.contains("loop {"), "{}", stripped);
+    // The iterator name (`for k in iter: ...`) exists only for the clauses
+    // and goes out with them.
+    assert!(stripped.contains("for k in 0..v.len() {"), "{}", stripped);
+    for keyword in
+        ["invariant", "invariant_except_break", "ensures", "decreases", "requires", "iter"]
+    {
+        assert!(!stripped.contains(keyword), "`{}` leaked into:\n{}", keyword, stripped);
+    }
+    // What is left is plain Rust.
+    syn::parse_file(&stripped).unwrap();
+
+    let result = vstrip::strip_source_detailed(source, &Config::default()).unwrap();
+    // Three on the while, four on the loop, two on the for.
+    assert_eq!(result.stats.loop_clauses, 9);
+}
+
+#[test]
+fn spec_as_comments_covers_decreases_and_loop_annotations() {
+    let source = r#"
+verus! {
+
+fn countdown(n: u32) -> u32
+    requires
+        n < 1000,
+    decreases n,
+{
+    let mut i: u32 = n;
+    while 0 < i
+        invariant
+            i <= n,
+        decreases i,
+    {
+        i = i - 1;
+    }
+    i
+}
+
+} // verus!
+"#;
+    let config = Config { spec_as_comments: true, ..Config::default() };
+    let stripped = strip_source(source, &config).unwrap();
+    assert!(stripped.contains("/// requires n < 1000"), "{}", stripped);
+    assert!(stripped.contains("/// decreases n"), "{}", stripped);
+    // Loop annotations land as doc comments on the loop itself.
+    assert!(stripped.contains("/// invariant i <= n"), "{}", stripped);
+    assert!(stripped.contains("/// decreases i"), "{}", stripped);
+    assert!(stripped.contains("while 0 < i {"), "{}", stripped);
+}
+
+#[test]
+fn spec_as_comments_changes_the_output_of_strip_source() {
+    // Guards the config threading from `strip_source` down into the visitor:
+    // with the flag the removed clauses come back as doc comments, without
+    // it they vanish entirely.
+    let source = r#"
+verus! {
+
+fn double(x: u32) -> u32
+    requires
+        x < 1000,
+{
+    2 * x
+}
+
+} // verus!
+"#;
+    let plain = strip_source(source, &Config::default()).unwrap();
+    let commented =
+        strip_source(source, &Config { spec_as_comments: true, ..Config::default() }).unwrap();
+    assert_ne!(plain, commented);
+    assert!(!plain.contains("/// requires"), "{}", plain);
+    assert!(commented.contains("/// requires x < 1000"), "{}", commented);
+}
+
+#[test]
+fn ghost_wrappers_are_unwrapped_from_return_types() {
+    let source = include_str!("fixtures/ghost_return.rs");
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // A bare wrapper becomes its inner type, and the body's value loses its
+    // constructor to match, in both trailing and `return` position.
+    assert!(stripped.contains("fn pick() -> int"), "{}", stripped);
+    assert!(stripped.contains("return v;"), "{}", stripped);
+    // Wrapper elements of a returned tuple are removed; a single survivor
+    // loses the tuple entirely, and no survivors leave unit.
+    assert!(stripped.contains("fn split(v: u32) -> u32"), "{}", stripped);
+    assert!(stripped.contains("fn all_ghost() -> ()"), "{}", stripped);
+    assert!(!stripped.contains("Ghost"), "{}", stripped);
+    assert!(!stripped.contains("Tracked"), "{}", stripped);
+}
+
+#[test]
+fn calc_macros_collapse_in_expression_position() {
+    let source = include_str!("fixtures/calc_in_expressions.rs");
+    let stripped = strip_source(source, &Config::default()).unwrap();
+    // The bare statement goes through the statement filter; the `if`
+    // condition and `let` initializer collapse to `()` in place.
+    assert!(!stripped.contains("calc"), "{}", stripped);
+    assert!(stripped.contains("if () == () { x } else { x + 1 }"), "{}", stripped);
+    assert!(stripped.contains("let _witness = ();"), "{}", stripped);
+    syn::parse_file(&stripped).unwrap();
+
+    let result = vstrip::strip_source_detailed(source, &Config::default()).unwrap();
+    // One statement plus two expression positions.
+    assert_eq!(result.stats.proof_blocks, 3);
+}
@@ -0,0 +1,40 @@
+use vstrip::{strip_source_to_syn_file, Config};
+
+#[test]
+fn stripped_output_parses_as_standard_syn() {
+    let source = r#"
+use vstd::prelude::*;
+
+verus! {
+
+spec fn spec_double(x: int) -> int {
+    2 * x
+}
+
+fn double(x: u32) -> (r: u32)
+    requires
+        x < 1000,
+    ensures
+        r == spec_double(x as int),
+{
+    assert(x + x < 2000);
+    x + x
+}
+
+} // verus!
+"#;
+    let file = strip_source_to_syn_file(source, &Config::default()).unwrap();
+    // The spec fn and the vstd import are gone — stripped output must
+    // compile without vstd on the path — leaving only the exec fn.
+    assert_eq!(file.items.len(), 1);
+    let has_double = file.items.iter().any(|item| match item {
+        syn::Item::Fn(func) => func.sig.ident == "double",
+        _ => false,
+    });
+    assert!(has_double);
+    let has_spec = file.items.iter().any(|item| match item {
+        syn::Item::Fn(func) => func.sig.ident == "spec_double",
+        _ => false,
+    });
+    assert!(!has_spec);
+}
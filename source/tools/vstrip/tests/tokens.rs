@@ -0,0 +1,54 @@
+//! The token-level API: `strip_tokens` for proc-macro and build-script use.
+
+use std::path::Path;
+
+use quote::quote;
+use vstrip::{strip_tokens, Config, StripError};
+
+#[test]
+fn quote_input_loses_its_ghost_code() {
+    let tokens = quote! {
+        spec fn double(x: int) -> int { x * 2 }
+
+        proof fn double_monotone(x: int, y: int)
+            ensures x <= y ==> double(x) <= double(y),
+        {
+        }
+
+        fn run(x: u64) -> u64 {
+            proof {
+                assert(x < u64::MAX);
+            }
+            x
+        }
+    };
+    let stripped = strip_tokens(tokens, &Config::default()).unwrap();
+    let rendered = stripped.to_string();
+    assert!(!rendered.contains("double"), "{}", rendered);
+    assert!(!rendered.contains("proof"), "{}", rendered);
+    assert!(!rendered.contains("assert"), "{}", rendered);
+    assert!(rendered.contains("fn run"), "{}", rendered);
+}
+
+#[test]
+fn stripped_tokens_parse_as_plain_rust() {
+    let tokens = quote! {
+        fn f(x: u32, credit: Ghost<int>) -> u32 {
+            let ghost saved = x;
+            x + 1
+        }
+    };
+    let stripped = strip_tokens(tokens, &Config::default()).unwrap();
+    // The result is consumable by ordinary syn, no Verus extensions left.
+    let file: syn::File = syn::parse2(stripped).unwrap();
+    assert_eq!(file.items.len(), 1);
+}
+
+#[test]
+fn bad_tokens_surface_as_parse_errors() {
+    let err = strip_tokens(quote! { fn }, &Config::default()).unwrap_err();
+    match err {
+        StripError::ParseError { path, .. } => assert_eq!(path, Path::new("<tokens>")),
+        other => panic!("expected ParseError, got {:?}", other),
+    }
+}
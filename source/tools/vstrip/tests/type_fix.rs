@@ -0,0 +1,56 @@
+use vstrip::type_fix::auto_fix_type_errors;
+use vstrip::Config;
+
+fn aggressive() -> Config {
+    Config { aggressive_type_fixing: true, ..Config::default() }
+}
+
+#[test]
+fn mathematical_integers_become_machine_integers() {
+    let source = "fn scale(x: int, y: nat) -> int { x }\n";
+    let fixed = auto_fix_type_errors(source, &aggressive()).unwrap();
+    assert!(fixed.contains("x: i64"));
+    assert!(fixed.contains("y: u64"));
+    assert!(fixed.contains("-> i64"));
+    assert!(!fixed.contains("int"));
+    assert!(!fixed.contains("nat"));
+}
+
+#[test]
+fn ghost_wrappers_are_unwrapped() {
+    let source = "struct S { g: Ghost<u64>, t: Tracked<bool>, nested: Ghost<int> }\n";
+    let fixed = auto_fix_type_errors(source, &aggressive()).unwrap();
+    assert!(fixed.contains("g: u64"));
+    assert!(fixed.contains("t: bool"));
+    // The unwrapped inner type is itself fixed.
+    assert!(fixed.contains("nested: i64"));
+    assert!(!fixed.contains("Ghost"));
+    assert!(!fixed.contains("Tracked"));
+}
+
+#[test]
+fn vstd_path_prefixes_are_stripped() {
+    let source = "fn take(s: vstd::seq::Seq<int>) -> vstd::map::Map<u64, u64> { s }\n";
+    let fixed = auto_fix_type_errors(source, &aggressive()).unwrap();
+    assert!(fixed.contains("s: Seq<i64>"));
+    assert!(fixed.contains("-> Map<u64, u64>"));
+    assert!(!fixed.contains("vstd"));
+}
+
+#[test]
+fn verus_trait_bounds_are_removed() {
+    let source = "fn show<T: View + Clone, U>(t: T, u: U) where U: Structural {}\n";
+    let fixed = auto_fix_type_errors(source, &aggressive()).unwrap();
+    assert!(fixed.contains("T: Clone"));
+    assert!(!fixed.contains("View"));
+    assert!(!fixed.contains("Structural"));
+    // The where clause held only a removed bound, so it disappears entirely.
+    assert!(!fixed.contains("where"));
+}
+
+#[test]
+fn nothing_changes_without_the_opt_in() {
+    let source = "fn scale(x: int) -> Ghost<int> { x }\n";
+    let fixed = auto_fix_type_errors(source, &Config::default()).unwrap();
+    assert_eq!(fixed, source);
+}
@@ -0,0 +1,34 @@
+use std::path::Path;
+
+use vstrip::{strip_source, Config, StripError};
+
+fn fixture(name: &str) -> String {
+    let path = Path::new(env!("CARGO_MANIFEST_DIR")).join("tests/fixtures").join(name);
+    std::fs::read_to_string(&path).unwrap_or_else(|e| panic!("{}: {}", path.display(), e))
+}
+
+#[test]
+fn duplicate_impl_method_is_reported() {
+    let source = fixture("duplicate_impl_method.rs");
+    let err = strip_source(&source, &Config::default())
+        .expect_err("stripping should report the duplicate `len`");
+    match err {
+        StripError::DuplicateItems(dups) => {
+            assert_eq!(dups.len(), 1, "expected exactly one duplicate: {:?}", dups);
+            assert_eq!(dups[0].name, "len");
+            assert!(dups[0].self_ty.as_deref().unwrap_or("").contains("Counter"));
+            assert!(dups[0].first_line < dups[0].second_line);
+        }
+        other => panic!("expected DuplicateItems, got: {}", other),
+    }
+}
+
+#[test]
+fn same_name_in_different_modules_is_benign() {
+    let source = fixture("same_name_different_module.rs");
+    let stripped = strip_source(&source, &Config::default())
+        .expect("same name in different namespaces must not be flagged");
+    // Both modules and both impls survive.
+    assert_eq!(stripped.matches("fn len").count(), 2);
+    assert_eq!(stripped.matches("fn get").count(), 2);
+}
@@ -0,0 +1,77 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::process::Command;
+
+use vstrip::{strip_source, Config, ConfigBuilder, StripError};
+
+// The `is` operator has no plain-Rust spelling, and the stripper leaves it
+// alone in exec code, so the stripped output re-parses only under verus_syn.
+const LEAKY: &str = r#"
+verus! {
+
+fn has_value(x: Option<u32>) -> bool {
+    x is Some
+}
+
+} // verus!
+"#;
+
+#[test]
+fn leaked_verus_syntax_fails_verification() {
+    let config = Config { verify_output: true, ..Config::default() };
+    let err = strip_source(LEAKY, &config).unwrap_err();
+    match &err {
+        StripError::InvalidOutput { snippet, .. } => {
+            assert!(snippet.contains("is Some"), "{}", snippet);
+        }
+        other => panic!("expected InvalidOutput, got {:?}", other),
+    }
+    assert!(err.to_string().contains("not valid Rust"), "{}", err);
+}
+
+#[test]
+fn clean_output_passes_verification() {
+    let source = r#"
+verus! {
+
+fn double(x: u32) -> (r: u32)
+    requires x < 1000,
+{
+    proof { assert(x < 1000); }
+    x * 2
+}
+
+} // verus!
+"#;
+    let config = Config { verify_output: true, ..Config::default() };
+    let output = strip_source(source, &config).unwrap();
+    assert!(output.contains("fn double"));
+}
+
+#[test]
+fn verification_does_not_apply_to_attribute_only_runs() {
+    // Attribute-only output deliberately keeps ghost code, so verifying it
+    // would reject every interesting input.
+    let err =
+        ConfigBuilder::new("src/lib.rs").verify_output().attributes_only().build().unwrap_err();
+    assert!(err.to_string().contains("attributes_only"), "{}", err);
+}
+
+fn vstrip(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_vstrip")).args(args).output().unwrap()
+}
+
+#[test]
+fn failed_verification_leaves_the_original_untouched() {
+    let dir = scratch("verify-in-place");
+    let path = dir.join("lib.rs");
+    fs::write(&path, LEAKY).unwrap();
+
+    let output = vstrip(&["--in-place", "--verify-output", path.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("not valid Rust"), "{}", stderr);
+    assert_eq!(fs::read_to_string(&path).unwrap(), LEAKY);
+}
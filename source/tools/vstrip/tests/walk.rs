@@ -0,0 +1,135 @@
+mod common;
+use common::scratch;
+
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+
+use vstrip::walk::walk;
+
+/// A little repository: a gitignored `target/`, a hidden directory, a
+/// negated ignore pattern, and a nested `.ignore` file.
+fn fixture(name: &str) -> PathBuf {
+    let root = scratch(name);
+    fs::write(root.join(".gitignore"), "/target\n*.tmp.rs\n!keep.tmp.rs\n").unwrap();
+    fs::create_dir_all(root.join("src")).unwrap();
+    fs::write(root.join("src/lib.rs"), "fn lib() {}\n").unwrap();
+    fs::create_dir_all(root.join("target")).unwrap();
+    fs::write(root.join("target/generated.rs"), "this is not rust\n").unwrap();
+    fs::create_dir_all(root.join(".hidden")).unwrap();
+    fs::write(root.join(".hidden/secret.rs"), "fn secret() {}\n").unwrap();
+    fs::write(root.join("scratch.tmp.rs"), "fn scratch() {}\n").unwrap();
+    fs::write(root.join("keep.tmp.rs"), "fn keep() {}\n").unwrap();
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("sub/.ignore"), "local.rs\n").unwrap();
+    fs::write(root.join("sub/local.rs"), "fn local() {}\n").unwrap();
+    fs::write(root.join("sub/real.rs"), "fn real() {}\n").unwrap();
+    root
+}
+
+fn rust_files(root: &Path, no_ignore: bool) -> Vec<String> {
+    let walked = walk(root, false, no_ignore).unwrap();
+    let mut names: Vec<String> = walked
+        .files
+        .iter()
+        .filter(|p| p.extension().is_some_and(|ext| ext == "rs"))
+        .map(|p| p.strip_prefix(root).unwrap().display().to_string())
+        .collect();
+    names.sort();
+    names
+}
+
+#[test]
+fn ignore_rules_and_hidden_directories_prune_the_walk() {
+    let root = fixture("walk-ignores");
+    // `!keep.tmp.rs` wins over `*.tmp.rs`; `target/` and `.hidden/` are
+    // pruned whole; `sub/.ignore` applies below `sub` only.
+    assert_eq!(rust_files(&root, false), vec!["keep.tmp.rs", "src/lib.rs", "sub/real.rs"]);
+
+    let walked = walk(&root, false, false).unwrap();
+    // target, .hidden (one each, pruned as directories), scratch.tmp.rs,
+    // and sub/local.rs.
+    assert_eq!(walked.ignored, 4);
+}
+
+#[test]
+fn no_ignore_restores_the_exhaustive_walk() {
+    let root = fixture("walk-no-ignore");
+    assert_eq!(
+        rust_files(&root, true),
+        vec![
+            ".hidden/secret.rs",
+            "keep.tmp.rs",
+            "scratch.tmp.rs",
+            "src/lib.rs",
+            "sub/local.rs",
+            "sub/real.rs",
+            "target/generated.rs",
+        ],
+    );
+    assert_eq!(walk(&root, false, true).unwrap().ignored, 0);
+}
+
+#[test]
+fn git_info_exclude_prunes_the_walk_with_lowest_precedence() {
+    let root = scratch("walk-git-exclude");
+    fs::create_dir_all(root.join(".git/info")).unwrap();
+    fs::write(root.join(".git/info/exclude"), "scratch_*.rs\nlocal.rs\n").unwrap();
+    // The checked-in .gitignore outranks the private excludes.
+    fs::write(root.join(".gitignore"), "!local.rs\n").unwrap();
+    fs::write(root.join("lib.rs"), "fn lib() {}\n").unwrap();
+    fs::write(root.join("scratch_one.rs"), "fn scratch() {}\n").unwrap();
+    fs::write(root.join("local.rs"), "fn local() {}\n").unwrap();
+
+    assert_eq!(rust_files(&root, false), vec!["lib.rs", "local.rs"]);
+    assert_eq!(
+        rust_files(&root, true),
+        vec!["lib.rs", "local.rs", "scratch_one.rs"],
+    );
+}
+
+#[test]
+fn verusignore_files_prune_the_walk_like_gitignore() {
+    let root = scratch("walk-verusignore");
+    // Glob patterns, one per line, with comments; committed to the
+    // repository instead of repeated via --exclude.
+    fs::write(root.join(".verusignore"), "# generated code\nvendor/\n*_gen.rs\n").unwrap();
+    fs::write(root.join("lib.rs"), "fn lib() {}\n").unwrap();
+    fs::write(root.join("types_gen.rs"), "fn generated() {}\n").unwrap();
+    fs::create_dir_all(root.join("vendor")).unwrap();
+    fs::write(root.join("vendor/dep.rs"), "fn dep() {}\n").unwrap();
+    // A nested file applies below its own directory, like a nested .ignore.
+    fs::create_dir_all(root.join("sub")).unwrap();
+    fs::write(root.join("sub/.verusignore"), "local.rs\n").unwrap();
+    fs::write(root.join("sub/local.rs"), "fn local() {}\n").unwrap();
+    fs::write(root.join("sub/real.rs"), "fn real() {}\n").unwrap();
+
+    assert_eq!(rust_files(&root, false), vec!["lib.rs", "sub/real.rs"]);
+    // --no-ignore disregards it with the rest.
+    assert_eq!(
+        rust_files(&root, true),
+        vec!["lib.rs", "sub/local.rs", "sub/real.rs", "types_gen.rs", "vendor/dep.rs"],
+    );
+}
+
+fn vstrip(args: &[&str]) -> std::process::Output {
+    Command::new(env!("CARGO_BIN_EXE_vstrip")).args(args).output().unwrap()
+}
+
+#[test]
+fn recursive_runs_skip_ignored_trees_and_report_the_count() {
+    let root = fixture("walk-recursive");
+
+    // target/generated.rs is not Rust, but the walk never reaches it; the
+    // fixture's surviving files are already stripped, so --check passes.
+    let output = vstrip(&["--check", "--recursive", "-v", root.to_str().unwrap()]);
+    assert!(output.status.success(), "{}", String::from_utf8_lossy(&output.stderr));
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("4 ignored"), "{}", stderr);
+
+    // --no-ignore walks into target/ and trips over the generated file.
+    let output = vstrip(&["--check", "--recursive", "--no-ignore", root.to_str().unwrap()]);
+    assert!(!output.status.success());
+    let stderr = String::from_utf8(output.stderr).unwrap();
+    assert!(stderr.contains("generated.rs"), "{}", stderr);
+}
@@ -0,0 +1,205 @@
+use vstrip::{strip_source_detailed, Config, ConfigBuilder, StripError, Warning};
+
+#[test]
+fn dropped_ghost_params_are_warned_about() {
+    let source = r#"
+verus! {
+
+fn f(x: u32, credit: Tracked<int>) -> u32 { x }
+
+} // verus!
+"#;
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert_eq!(
+        result.warnings,
+        vec![Warning::GhostParamDropped {
+            fn_name: "f".to_string(),
+            param_name: "credit".to_string(),
+            line: 4,
+            column: 14,
+        }],
+    );
+    assert_eq!(result.warnings[0].kind(), "ghost-param-dropped");
+    assert_eq!(result.warnings[0].line(), Some(4));
+}
+
+#[test]
+fn open_spec_fns_are_warned_about_but_closed_ones_are_not() {
+    let source = r#"
+verus! {
+
+pub open spec fn published(x: int) -> int { x }
+
+spec fn internal(x: int) -> int { x }
+
+fn keep() {}
+
+} // verus!
+"#;
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert_eq!(
+        result.warnings,
+        vec![Warning::SpecFnDropped { fn_name: "published".to_string(), line: 4, column: 18 }],
+    );
+}
+
+#[test]
+fn files_of_pure_spec_code_are_flagged() {
+    let source = r#"
+verus! {
+
+spec fn only(x: int) -> int { x }
+
+} // verus!
+"#;
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert!(result.output.trim().is_empty());
+    assert!(result
+        .warnings
+        .iter()
+        .any(|w| matches!(w, Warning::OnlySpecCode { .. })));
+}
+
+#[test]
+fn unwrapped_ghost_return_types_are_not_flagged() {
+    // The visitor rewrites `-> Ghost<T>` to `-> T` itself, so nothing is
+    // left to warn about.
+    let source = r#"
+verus! {
+
+fn produce() -> Ghost<int> { Ghost(1) }
+
+} // verus!
+"#;
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert!(result.output.contains("-> int"), "{}", result.output);
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+}
+
+#[test]
+fn deny_warnings_makes_visitor_warnings_fatal() {
+    let source = r#"
+verus! {
+
+pub open spec fn published(x: int) -> int { x }
+
+fn keep() {}
+
+} // verus!
+"#;
+    let config = ConfigBuilder::new("src/lib.rs").deny_warnings().build().unwrap();
+    let err = strip_source_detailed(source, &config).unwrap_err();
+    match err {
+        StripError::WarningsDenied(warnings) => {
+            assert_eq!(
+                warnings,
+                vec![Warning::SpecFnDropped {
+                    fn_name: "published".to_string(),
+                    line: 4,
+                    column: 18,
+                }],
+            );
+        }
+        other => panic!("expected WarningsDenied, got {:?}", other),
+    }
+    // The same input passes untouched without the flag.
+    assert!(strip_source_detailed(source, &Config::default()).is_ok());
+}
+
+#[test]
+fn deny_warnings_makes_emptied_files_fatal() {
+    let source = r#"
+verus! {
+
+spec fn only(x: int) -> int { x }
+
+} // verus!
+"#;
+    let config = ConfigBuilder::new("src/lib.rs").deny_warnings().build().unwrap();
+    let err = strip_source_detailed(source, &config).unwrap_err();
+    let rendered = err.to_string();
+    assert!(rendered.contains("nothing but spec/proof code"), "{}", rendered);
+    assert!(rendered.contains("--deny-warnings"), "{}", rendered);
+}
+
+#[test]
+fn deny_warnings_exits_nonzero_from_the_cli() {
+    let dir = std::env::temp_dir()
+        .join(format!("vstrip-deny-warnings-{}", std::process::id()));
+    std::fs::remove_dir_all(&dir).ok();
+    std::fs::create_dir_all(&dir).unwrap();
+    let file = dir.join("lib.rs");
+    std::fs::write(
+        &file,
+        "verus! {\n\nfn f(x: u32, credit: Tracked<int>) -> u32 { x }\n\n} // verus!\n",
+    )
+    .unwrap();
+
+    let output = std::process::Command::new(env!("CARGO_BIN_EXE_vstrip"))
+        .args(["--check", "--deny-warnings"])
+        .arg(&file)
+        .output()
+        .unwrap();
+    assert!(!output.status.success());
+    let stderr = String::from_utf8_lossy(&output.stderr);
+    assert!(stderr.contains("ghost parameter `credit`"), "{}", stderr);
+}
+
+#[test]
+fn exec_calls_to_removed_spec_fns_are_warned_about() {
+    let source = r#"
+verus! {
+
+spec fn limit_ok(x: u64) -> bool { x < 10 }
+
+fn check(x: u64) -> bool {
+    limit_ok(x)
+}
+
+} // verus!
+"#;
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert_eq!(
+        result.warnings,
+        vec![Warning::DanglingCall { fn_name: "limit_ok".to_string(), line: 7, column: 5 }],
+    );
+    assert_eq!(result.warnings[0].kind(), "dangling-call");
+    // The broken call site is still in the output for the user to fix.
+    assert!(result.output.contains("limit_ok(x)"), "{}", result.output);
+}
+
+#[test]
+fn calls_removed_along_with_their_proof_context_do_not_dangle() {
+    // The only call to the lemma sits in a proof block that stripping removes
+    // too, so nothing in the surviving code references it.
+    let source = r#"
+verus! {
+
+proof fn lemma() {}
+
+fn run() -> u32 {
+    proof {
+        lemma();
+    }
+    1
+}
+
+} // verus!
+"#;
+    let result = strip_source_detailed(source, &Config::default()).unwrap();
+    assert!(result.warnings.is_empty(), "{:?}", result.warnings);
+}
+
+#[test]
+fn warnings_serialize_as_kind_and_message() {
+    let warning = Warning::GhostParamDropped {
+        fn_name: "f".to_string(),
+        param_name: "credit".to_string(),
+        line: 4,
+        column: 14,
+    };
+    let json = serde_json::to_value(&warning).unwrap();
+    assert_eq!(json["kind"], "ghost-param-dropped");
+    assert!(json["message"].as_str().unwrap().contains("credit"));
+    assert_eq!(json.as_object().unwrap().len(), 2);
+}